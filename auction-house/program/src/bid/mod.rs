@@ -1,5 +1,5 @@
-//! Create both private and public bids.
-//! A private bid is a bid on a specific NFT *held by a specific person*. A public bid is a bid on a specific NFT *regardless of who holds it*.
+//! Create private, public, collection and delegated bids.
+//! A private bid is a bid on a specific NFT *held by a specific person*. A public bid is a bid on a specific NFT *regardless of who holds it*. A collection bid is a bid on any verified member of a collection, for a given quantity. A delegated bid is a private bid paid at settlement out of the buyer's own token account rather than the escrow.
 
 use anchor_lang::{
     prelude::*,
@@ -8,6 +8,7 @@ use anchor_lang::{
 };
 use anchor_spl::token::{Mint, Token, TokenAccount};
 use solana_program::program_memory::sol_memset;
+use spl_token::instruction::approve;
 
 use crate::{
     constants::*, errors::AuctionHouseError, utils::*, AuctionHouse, Auctioneer, AuthorityScope,
@@ -32,7 +33,8 @@ pub struct PublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Validated in bid_logic.
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Box<Account<'info, TokenAccount>>,
 
     /// CHECK: Validated in public_bid_logic.
@@ -102,8 +104,8 @@ pub struct PublicBuy<'info> {
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn public_bid(
-    ctx: Context<PublicBuy>,
+pub fn public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, PublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -113,7 +115,7 @@ pub fn public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -135,6 +137,7 @@ pub fn public_bid(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -156,7 +159,8 @@ pub struct AuctioneerPublicBuy<'info> {
     /// CHECK: Validated in public_bid_logic.
     transfer_authority: UncheckedAccount<'info>,
 
-    treasury_mint: Box<Account<'info, Mint>>,
+    /// CHECK: Validated in auctioneer_bid_logic.
+    treasury_mint: UncheckedAccount<'info>,
 
     token_account: Box<Account<'info, TokenAccount>>,
 
@@ -242,8 +246,8 @@ pub struct AuctioneerPublicBuy<'info> {
 
 /// Create a bid on a specific SPL token.
 /// Public bids are specific to the token itself, rather than the auction, and remain open indefinitely until either the user closes it or the requirements for the bid are met and it is matched with a counter bid and closed as a transaction.
-pub fn auctioneer_public_bid(
-    ctx: Context<AuctioneerPublicBuy>,
+pub fn auctioneer_public_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, AuctioneerPublicBuy<'info>>,
     trade_state_bump: u8,
     escrow_payment_bump: u8,
     buyer_price: u64,
@@ -253,7 +257,7 @@ pub fn auctioneer_public_bid(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -277,6 +281,7 @@ pub fn auctioneer_public_bid(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -301,8 +306,9 @@ pub struct Buy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in bid_logic.
     /// Auction House instance treasury mint account.
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -412,6 +418,7 @@ pub fn private_bid<'info>(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -436,8 +443,9 @@ pub struct AuctioneerBuy<'info> {
     /// SPL token account transfer authority.
     transfer_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in auctioneer_bid_logic.
     /// Auction House instance treasury mint account.
-    treasury_mint: Box<Account<'info, Mint>>,
+    treasury_mint: UncheckedAccount<'info>,
 
     /// SPL token account.
     token_account: Box<Account<'info, TokenAccount>>,
@@ -537,7 +545,7 @@ pub fn auctioneer_private_bid<'info>(
         ctx.accounts.wallet.to_owned(),
         ctx.accounts.payment_account.to_owned(),
         ctx.accounts.transfer_authority.to_owned(),
-        *ctx.accounts.treasury_mint.to_owned(),
+        ctx.accounts.treasury_mint.to_owned(),
         *ctx.accounts.token_account.to_owned(),
         ctx.accounts.metadata.to_owned(),
         ctx.accounts.escrow_payment_account.to_owned(),
@@ -561,6 +569,7 @@ pub fn auctioneer_private_bid<'info>(
         *ctx.bumps
             .get("buyer_trade_state")
             .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?,
+        ctx.remaining_accounts,
     )
 }
 
@@ -570,7 +579,7 @@ pub fn bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -588,6 +597,7 @@ pub fn bid_logic<'info>(
     public: bool,
     escrow_canonical_bump: u8,
     trade_state_canonical_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     // If it has an auctioneer authority delegated must use auctioneer_* handler.
     if (auction_house.scopes[AuthorityScope::PublicBuy as usize] || !public)
@@ -629,7 +639,13 @@ pub fn bid_logic<'info>(
         &seeds,
     )?;
 
+    assert_treasury_mint(&treasury_mint)?;
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let treasury_token_program = get_treasury_token_program(
+        &treasury_mint,
+        &token_program.to_account_info(),
+        remaining_accounts,
+    )?;
 
     let auction_house_key = auction_house.key();
     let wallet_key = wallet.key();
@@ -643,7 +659,7 @@ pub fn bid_logic<'info>(
         &escrow_payment_account,
         &system_program,
         &fee_payer,
-        &token_program,
+        &treasury_token_program,
         &treasury_mint,
         &auction_house.to_account_info(),
         &rent,
@@ -679,30 +695,14 @@ pub fn bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
-
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
-                .checked_sub(escrow_payment_loaded.amount)
-                .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
-            )?;
-        }
+        top_up_token_escrow(
+            &treasury_token_program,
+            &payment_account,
+            &escrow_payment_account,
+            &treasury_mint,
+            &transfer_authority,
+            buyer_price,
+        )?;
     }
     assert_metadata_valid(&metadata, &token_account)?;
 
@@ -768,7 +768,7 @@ pub fn auctioneer_bid_logic<'info>(
     wallet: Signer<'info>,
     payment_account: UncheckedAccount<'info>,
     transfer_authority: UncheckedAccount<'info>,
-    treasury_mint: Account<'info, Mint>,
+    treasury_mint: UncheckedAccount<'info>,
     token_account: Account<'info, TokenAccount>,
     metadata: UncheckedAccount<'info>,
     escrow_payment_account: UncheckedAccount<'info>,
@@ -788,6 +788,7 @@ pub fn auctioneer_bid_logic<'info>(
     public: bool,
     escrow_canonical_bump: u8,
     trade_state_canonical_bump: u8,
+    remaining_accounts: &[AccountInfo<'info>],
 ) -> Result<()> {
     if !auction_house.has_auctioneer {
         return Err(AuctionHouseError::NoAuctioneerProgramSet.into());
@@ -831,7 +832,13 @@ pub fn auctioneer_bid_logic<'info>(
         &seeds,
     )?;
 
+    assert_treasury_mint(&treasury_mint)?;
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let treasury_token_program = get_treasury_token_program(
+        &treasury_mint,
+        &token_program.to_account_info(),
+        remaining_accounts,
+    )?;
 
     let auction_house_key = auction_house.key();
     let wallet_key = wallet.key();
@@ -845,7 +852,7 @@ pub fn auctioneer_bid_logic<'info>(
         &escrow_payment_account,
         &system_program,
         &fee_payer,
-        &token_program,
+        &treasury_token_program,
         &treasury_mint,
         &auction_house.to_account_info(),
         &rent,
@@ -881,30 +888,14 @@ pub fn auctioneer_bid_logic<'info>(
             )?;
         }
     } else {
-        let escrow_payment_loaded: spl_token::state::Account =
-            assert_initialized(&escrow_payment_account)?;
-
-        if escrow_payment_loaded.amount < buyer_price {
-            let diff = buyer_price
-                .checked_sub(escrow_payment_loaded.amount)
-                .ok_or(AuctionHouseError::NumericalOverflow)?;
-            invoke(
-                &spl_token::instruction::transfer(
-                    &token_program.key(),
-                    &payment_account.key(),
-                    &escrow_payment_account.key(),
-                    &transfer_authority.key(),
-                    &[],
-                    diff,
-                )?,
-                &[
-                    transfer_authority.to_account_info(),
-                    payment_account.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    token_program.to_account_info(),
-                ],
-            )?;
-        }
+        top_up_token_escrow(
+            &treasury_token_program,
+            &payment_account,
+            &escrow_payment_account,
+            &treasury_mint,
+            &transfer_authority,
+            buyer_price,
+        )?;
     }
     assert_metadata_valid(&metadata, &token_account)?;
 
@@ -963,3 +954,502 @@ pub fn auctioneer_bid_logic<'info>(
     // Allow The same bid to be sent with no issues
     Ok(())
 }
+
+/// Accounts for the [`collection_bid` handler](fn.collection_bid.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    quantity: u64
+)]
+pub struct CollectionBuy<'info> {
+    wallet: Signer<'info>,
+
+    /// CHECK: Validated in collection_bid.
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in collection_bid.
+    transfer_authority: UncheckedAccount<'info>,
+
+    treasury_mint: Box<Account<'info, Mint>>,
+
+    /// Mint of the collection NFT whose verified members the bid is for.
+    collection_mint: Box<Account<'info, Mint>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            wallet.key().as_ref()
+        ],
+        bump
+    )]
+    escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Verified with has_one constraint on auction house account.
+    authority: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION_BID.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            collection_mint.key().as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            quantity.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a bid for `quantity` tokens of a collection at `buyer_price` each.
+/// Any seller holding a verified member of the collection can fill it with [`execute_collection_sale`](../execute_sale/fn.execute_collection_sale.html) until the quantity runs out, so the escrow is funded for all of them up front.
+/// The trade state stores the bump followed by the quantity left to fill.
+pub fn collection_bid(
+    ctx: Context<CollectionBuy>,
+    trade_state_bump: u8,
+    escrow_payment_bump: u8,
+    buyer_price: u64,
+    quantity: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let payment_account = &ctx.accounts.payment_account;
+    let transfer_authority = &ctx.accounts.transfer_authority;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let collection_mint = &ctx.accounts.collection_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::PublicBuy as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let escrow_canonical_bump = *ctx
+        .bumps
+        .get("escrow_payment_account")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (escrow_canonical_bump != escrow_payment_bump)
+        || (trade_state_canonical_bump != trade_state_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    if quantity == 0 {
+        return Err(AuctionHouseError::InvalidCollectionBidQuantity.into());
+    }
+
+    let total_price = buyer_price
+        .checked_mul(quantity)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    let wallet_key = wallet.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+    create_program_token_account_if_not_present(
+        escrow_payment_account,
+        system_program,
+        &fee_payer,
+        &token_program.to_account_info(),
+        &treasury_mint.to_account_info(),
+        &auction_house.to_account_info(),
+        rent,
+        &escrow_signer_seeds,
+        fee_seeds,
+        is_native,
+    )?;
+    if is_native {
+        assert_keys_equal(wallet.key(), payment_account.key())?;
+
+        let required = total_price
+            .checked_add(rent.minimum_balance(escrow_payment_account.data_len()))
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        if escrow_payment_account.lamports() < required {
+            let diff = required
+                .checked_sub(escrow_payment_account.lamports())
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+            invoke(
+                &system_instruction::transfer(
+                    &payment_account.key(),
+                    &escrow_payment_account.key(),
+                    diff,
+                ),
+                &[
+                    payment_account.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+            )?;
+        }
+    } else {
+        let escrow_payment_loaded: spl_token::state::Account =
+            assert_initialized(escrow_payment_account)?;
+
+        if escrow_payment_loaded.amount < total_price {
+            let diff = total_price
+                .checked_sub(escrow_payment_loaded.amount)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            invoke(
+                &spl_token::instruction::transfer(
+                    &token_program.key(),
+                    &payment_account.key(),
+                    &escrow_payment_account.key(),
+                    &transfer_authority.key(),
+                    &[],
+                    diff,
+                )?,
+                &[
+                    transfer_authority.to_account_info(),
+                    payment_account.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    token_program.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
+    let ts_info = buyer_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        let collection_key = collection_mint.key();
+        create_or_allocate_account_raw(
+            crate::id(),
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            COLLECTION_BID_TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                COLLECTION_BID.as_bytes(),
+                wallet_key.as_ref(),
+                auction_house_key.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                collection_key.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &quantity.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+        let mut data = ts_info.try_borrow_mut_data()?;
+        data[0] = trade_state_bump;
+        data[1..COLLECTION_BID_TRADE_STATE_SIZE].copy_from_slice(&quantity.to_le_bytes());
+    }
+    // Allow The same bid to be sent with no issues
+    Ok(())
+}
+
+/// Accounts for the [`delegated_bid` handler](fn.delegated_bid.html).
+#[derive(Accounts)]
+#[instruction(
+    trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct DelegatedBuy<'info> {
+    wallet: Signer<'info>,
+
+    /// CHECK: Validated in delegated_bid.
+    /// Buyer treasury mint ATA the bid is paid from at settlement.
+    #[account(mut)]
+    payment_account: UncheckedAccount<'info>,
+
+    treasury_mint: Box<Account<'info, Mint>>,
+
+    token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated in delegated_bid.
+    metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Verified with has_one constraint on auction house account.
+    authority: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump = auction_house.bump,
+        has_one = authority,
+        has_one = treasury_mint,
+        has_one = auction_house_fee_account
+    )]
+    auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump = auction_house.fee_payer_bump
+    )]
+    auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_BID.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            treasury_mint.key().as_ref(),
+            token_account.mint.as_ref(),
+            buyer_price.to_le_bytes().as_ref(),
+            token_size.to_le_bytes().as_ref()
+        ],
+        bump
+    )]
+    buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Ledger of the allowance the payment account owes to open delegated bids.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_ALLOWANCE.as_bytes(),
+            payment_account.key().as_ref()
+        ],
+        bump
+    )]
+    delegated_allowance: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds = [PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    program_as_signer: UncheckedAccount<'info>,
+
+    token_program: Program<'info, Token>,
+    system_program: Program<'info, System>,
+    rent: Sysvar<'info, Rent>,
+}
+
+/// Create a private bid paid straight out of the buyer's treasury mint ATA instead of the escrow.
+/// Opening the bid raises the allowance the ATA delegates to the program as signer by `buyer_price`, so no funds move until [`execute_delegated_sale`](../execute_sale/fn.execute_delegated_sale.html) settles it.
+/// The delegated allowance ledger records what the payment account's open delegated bids are owed, and the ATA delegates exactly that total.
+pub fn delegated_bid(
+    ctx: Context<DelegatedBuy>,
+    trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let payment_account = &ctx.accounts.payment_account;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let delegated_allowance = &ctx.accounts.delegated_allowance;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let rent = &ctx.accounts.rent;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Buy as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let trade_state_canonical_bump = *ctx
+        .bumps
+        .get("buyer_trade_state")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let delegated_allowance_bump = *ctx
+        .bumps
+        .get("delegated_allowance")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    if (trade_state_canonical_bump != trade_state_bump)
+        || (program_as_signer_canonical_bump != program_as_signer_bump)
+    {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    // Lamports cannot be delegated, so native treasuries keep using the escrow.
+    if treasury_mint.key() == spl_token::native_mint::id() {
+        return Err(AuctionHouseError::DelegatedBidNativeTreasury.into());
+    }
+
+    assert_is_ata(payment_account, &wallet.key(), &treasury_mint.key())?;
+    assert_metadata_valid(metadata, token_account)?;
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+    let (fee_payer, fee_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let ts_info = buyer_trade_state.to_account_info();
+    if ts_info.data_is_empty() {
+        // The program as signer is the one delegate of the ATA across the buyer's delegated bids
+        // in every auction house, so each bid adds its price to what the others are owed.
+        let ledger = delegated_allowance.to_account_info();
+        let allowance = get_delegated_allowance(&ledger)?
+            .checked_add(buyer_price)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        invoke(
+            &approve(
+                &token_program.key(),
+                &payment_account.key(),
+                &program_as_signer.key(),
+                &wallet.key(),
+                &[],
+                allowance,
+            )?,
+            &[
+                token_program.to_account_info(),
+                payment_account.to_account_info(),
+                program_as_signer.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+
+        if ledger.data_is_empty() {
+            let payment_account_key = payment_account.key();
+            create_or_allocate_account_raw(
+                crate::id(),
+                &ledger,
+                &rent.to_account_info(),
+                system_program,
+                &fee_payer,
+                DELEGATED_ALLOWANCE_SIZE,
+                fee_seeds,
+                &[
+                    PREFIX.as_bytes(),
+                    DELEGATED_ALLOWANCE.as_bytes(),
+                    payment_account_key.as_ref(),
+                    &[delegated_allowance_bump],
+                ],
+            )?;
+            ledger.try_borrow_mut_data()?[0] = delegated_allowance_bump;
+        }
+        set_delegated_allowance(&ledger, allowance)?;
+
+        let wallet_key = wallet.key();
+        let token_account_key = token_account.key();
+        create_or_allocate_account_raw(
+            crate::id(),
+            &ts_info,
+            &rent.to_account_info(),
+            system_program,
+            &fee_payer,
+            TRADE_STATE_SIZE,
+            fee_seeds,
+            &[
+                PREFIX.as_bytes(),
+                DELEGATED_BID.as_bytes(),
+                wallet_key.as_ref(),
+                auction_house_key.as_ref(),
+                token_account_key.as_ref(),
+                auction_house.treasury_mint.as_ref(),
+                token_account.mint.as_ref(),
+                &buyer_price.to_le_bytes(),
+                &token_size.to_le_bytes(),
+                &[trade_state_bump],
+            ],
+        )?;
+        sol_memset(
+            *ts_info.try_borrow_mut_data()?,
+            trade_state_bump,
+            TRADE_STATE_SIZE,
+        );
+    }
+    // Allow The same bid to be sent with no issues
+    Ok(())
+}
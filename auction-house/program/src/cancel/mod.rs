@@ -1,5 +1,10 @@
-use anchor_lang::{prelude::*, solana_program::program::invoke, AnchorDeserialize};
+use anchor_lang::{
+    prelude::*,
+    solana_program::{program::invoke, program_option::COption},
+    AnchorDeserialize,
+};
 use solana_program::program_memory::sol_memset;
+use spl_token::instruction::approve;
 
 use crate::{constants::*, errors::*, utils::*, AuctionHouse, AuthorityScope, *};
 
@@ -258,3 +263,478 @@ fn cancel_logic<'info>(
 
     Ok(())
 }
+
+/// Accounts for the [`cancel_collection_bid` handler](auction_house/fn.cancel_collection_bid.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, quantity: u64)]
+pub struct CancelCollectionBid<'info> {
+    /// CHECK: Validated as a signer in cancel_collection_bid.
+    /// Buyer wallet account.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Seed of the trade state.
+    /// Mint of the collection NFT the bid is for.
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as a signer in cancel_collection_bid.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Collection bid trade state PDA account to be canceled.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION_BID.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            collection_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &quantity.to_le_bytes()
+        ],
+        bump = trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub trade_state: UncheckedAccount<'info>,
+}
+
+/// Cancel a collection bid by transferring all lamports from its trade state account to the fee payer and zeroing its data.
+/// Funds left in escrow stay there until withdrawn.
+pub fn cancel_collection_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelCollectionBid<'info>>,
+    _buyer_price: u64,
+    _quantity: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let trade_state = &ctx.accounts.trade_state;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(AuctionHouseError::NoValidSignerPresent.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    sol_memset(
+        *trade_state.try_borrow_mut_data()?,
+        0,
+        COLLECTION_BID_TRADE_STATE_SIZE,
+    );
+
+    Ok(())
+}
+
+/// Accounts for the [`cancel_dutch_auction` handler](auction_house/fn.cancel_dutch_auction.html).
+#[derive(Accounts)]
+#[instruction(token_size: u64)]
+pub struct CancelDutchAuction<'info> {
+    /// CHECK: Validated as a signer in cancel_dutch_auction.
+    /// Seller wallet account, which receives the listing config rent.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// SPL token account containing the listed token.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Validated as a signer in cancel_dutch_auction.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Listing config PDA account of the Dutch auction.
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [
+            PREFIX.as_bytes(),
+            LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Cancel a Dutch-auction listing by closing its listing config and revoking the program as the token delegate.
+pub fn cancel_dutch_auction<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelDutchAuction<'info>>,
+    _token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(AuctionHouseError::NoValidSignerPresent.into());
+    }
+
+    if token_account.owner == wallet.key() && wallet.is_signer {
+        invoke(
+            &revoke(
+                &token_program.key(),
+                &token_account.key(),
+                &wallet.key(),
+                &[],
+            )
+            .unwrap(),
+            &[
+                token_program.to_account_info(),
+                token_account.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Accounts for the [`cancel_bundle_listing` handler](auction_house/fn.cancel_bundle_listing.html).
+#[derive(Accounts)]
+pub struct CancelBundleListing<'info> {
+    /// CHECK: Validated as a signer in cancel_bundle_listing.
+    /// Seller wallet account, which receives the bundle listing rent.
+    #[account(mut)]
+    pub wallet: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bundle listing seeds.
+    /// Mint of the first token in the bundle, which keys the bundle listing.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated as a signer in cancel_bundle_listing.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Bundle listing PDA account.
+    #[account(
+        mut,
+        close = wallet,
+        seeds = [
+            PREFIX.as_bytes(),
+            BUNDLE_LISTING.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump = bundle_listing.bump
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Cancel a bundle listing by closing it and, when the seller signs, revoking the program as delegate of the token accounts passed in remaining accounts.
+pub fn cancel_bundle_listing<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelBundleListing<'info>>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    if !wallet.to_account_info().is_signer && !authority.to_account_info().is_signer {
+        return Err(AuctionHouseError::NoValidSignerPresent.into());
+    }
+
+    if wallet.is_signer {
+        for token_account in ctx.remaining_accounts {
+            if !bundle_listing
+                .items
+                .iter()
+                .any(|item| item.token_account == token_account.key())
+            {
+                return Err(AuctionHouseError::BundleAccountMismatch.into());
+            }
+            invoke(
+                &revoke(
+                    &token_program.key(),
+                    &token_account.key(),
+                    &wallet.key(),
+                    &[],
+                )
+                .unwrap(),
+                &[
+                    token_program.to_account_info(),
+                    token_account.clone(),
+                    wallet.to_account_info(),
+                ],
+            )?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Accounts for the [`cancel_delegated_bid` handler](auction_house/fn.cancel_delegated_bid.html).
+#[derive(Accounts)]
+#[instruction(buyer_price: u64, token_size: u64)]
+pub struct CancelDelegatedBid<'info> {
+    /// Buyer wallet account.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Validated in cancel_delegated_bid.
+    /// Buyer treasury mint ATA delegated to the program as signer.
+    #[account(mut)]
+    pub payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Seed of the trade state.
+    /// SPL token account the bid is for.
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Seed of the trade state.
+    /// Token mint account of the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Verified with has_one constraint on auction house account.
+    /// Auction House instance authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Ledger of the allowance the payment account owes to open delegated bids.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_ALLOWANCE.as_bytes(),
+            payment_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub delegated_allowance: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Delegated bid trade state PDA account to be canceled.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_BID.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump = trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+}
+
+/// Cancel a delegated bid by closing its trade state and lowering the allowance of the payment account by the bid price.
+/// Only the buyer can cancel, since the allowance is theirs to lower. The allowance is revoked once no other delegated bid needs it.
+pub fn cancel_delegated_bid<'info>(
+    ctx: Context<'_, '_, '_, 'info, CancelDelegatedBid<'info>>,
+    buyer_price: u64,
+    _token_size: u64,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let payment_account = &ctx.accounts.payment_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let delegated_allowance = &ctx.accounts.delegated_allowance;
+    let trade_state = &ctx.accounts.trade_state;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let token_program = &ctx.accounts.token_program;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Cancel as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, _) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let payment_account_data =
+        assert_is_ata(payment_account, &wallet.key(), &auction_house.treasury_mint)?;
+    let ledger = delegated_allowance.to_account_info();
+    let allowance = get_delegated_allowance(&ledger)?.saturating_sub(buyer_price);
+    if !ledger.data_is_empty() {
+        set_delegated_allowance(&ledger, allowance)?;
+    }
+    if payment_account_data.delegate == COption::Some(program_as_signer.key()) {
+        let ix = if allowance > 0 {
+            approve(
+                &token_program.key(),
+                &payment_account.key(),
+                &program_as_signer.key(),
+                &wallet.key(),
+                &[],
+                allowance,
+            )
+        } else {
+            revoke(
+                &token_program.key(),
+                &payment_account.key(),
+                &wallet.key(),
+                &[],
+            )
+        }?;
+        invoke(
+            &ix,
+            &[
+                token_program.to_account_info(),
+                payment_account.to_account_info(),
+                program_as_signer.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+    }
+
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    sol_memset(*trade_state.try_borrow_mut_data()?, 0, TRADE_STATE_SIZE);
+
+    Ok(())
+}
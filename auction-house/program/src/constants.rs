@@ -6,7 +6,42 @@ pub const PURCHASE_RECEIPT_PREFIX: &str = "purchase_receipt";
 pub const BID_RECEIPT_PREFIX: &str = "bid_receipt";
 pub const LISTING_RECEIPT_PREFIX: &str = "listing_receipt";
 pub const AUCTIONEER: &str = "auctioneer";
+pub const COLLECTION_BID: &str = "collection_bid";
+pub const LISTING_CONFIG: &str = "listing_config";
+pub const BUNDLE_LISTING: &str = "bundle_listing";
+pub const DELEGATED_BID: &str = "delegated_bid";
+pub const DELEGATED_ALLOWANCE: &str = "delegated_allowance";
 pub const TRADE_STATE_SIZE: usize = 1;
+pub const COLLECTION_BID_TRADE_STATE_SIZE: usize = 1 +     // bump
+8                                                           // remaining quantity
+;
+pub const DELEGATED_ALLOWANCE_SIZE: usize = 1 +             // bump
+8                                                           // allowance owed to open delegated bids
+;
+pub const LISTING_CONFIG_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // seller
+32 +                                                        // auction house instance
+32 +                                                        // token account
+32 +                                                        // token mint
+8 +                                                         // token size
+8 +                                                         // start price
+8 +                                                         // end price
+8 +                                                         // start time
+8 +                                                         // end time
+1 + 8 +                                                     // price decay
+1 +                                                         // bump
+64                                                          // padding
+;
+pub const MAX_BUNDLE_ITEMS: usize = 8;
+pub const BUNDLE_LISTING_SIZE: usize = 8 +                  // Anchor discriminator/sighash
+32 +                                                        // seller
+32 +                                                        // auction house instance
+8 +                                                         // price
+4 +                                                         // items vec length
+MAX_BUNDLE_ITEMS * (32 + 32 + 2) +                          // items: token account, token mint, royalty weight
+1 +                                                         // bump
+64                                                          // padding
+;
 pub const MAX_NUM_SCOPES: usize = 7;
 pub const AUCTIONEER_SIZE: usize = 8 +                      // Anchor discriminator/sighash
 32 +                                                        // Auctioneer authority
@@ -31,8 +31,9 @@ pub struct Deposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in deposit_logic.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -109,7 +110,12 @@ pub fn deposit<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    deposit_logic(ctx.accounts, escrow_payment_bump, amount)
+    deposit_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 /// Accounts for the [`deposit` handler](auction_house/fn.deposit.html).
@@ -141,8 +147,9 @@ pub struct AuctioneerDeposit<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in deposit_logic.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in deposit_logic.
     /// Auction House instance authority account.
@@ -227,13 +234,20 @@ pub fn auctioneer_deposit<'info>(
 
     let mut accounts: Deposit<'info> = (*ctx.accounts).clone().into();
 
-    deposit_logic(&mut accounts, escrow_payment_bump, amount)
+    deposit_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 #[allow(clippy::needless_lifetimes)]
 /// Deposit `amount` into the escrow payment account for your specific wallet.
+/// Token-2022 treasuries need the Token-2022 program in `remaining_accounts`, and the escrow receives `amount` net of the mint's transfer fee.
 fn deposit_logic<'info>(
     accounts: &mut Deposit<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
     escrow_payment_bump: u8,
     amount: u64,
 ) -> Result<()> {
@@ -273,13 +287,16 @@ fn deposit_logic<'info>(
         &seeds,
     )?;
 
+    assert_treasury_mint(treasury_mint)?;
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
+    let treasury_token_program =
+        get_treasury_token_program(treasury_mint, token_program, remaining_accounts)?;
 
     create_program_token_account_if_not_present(
         escrow_payment_account,
         system_program,
         &fee_payer,
-        token_program,
+        &treasury_token_program,
         treasury_mint,
         &auction_house.to_account_info(),
         rent,
@@ -289,22 +306,15 @@ fn deposit_logic<'info>(
     )?;
 
     if !is_native {
-        assert_is_ata(payment_account, &wallet.key(), &treasury_mint.key())?;
-        invoke(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &payment_account.key(),
-                &escrow_payment_account.key(),
-                &transfer_authority.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                payment_account.to_account_info(),
-                token_program.to_account_info(),
-                transfer_authority.to_account_info(),
-            ],
+        assert_is_treasury_ata(payment_account, &wallet.key(), treasury_mint)?;
+        transfer_treasury_tokens(
+            &treasury_token_program,
+            payment_account,
+            escrow_payment_account,
+            treasury_mint,
+            transfer_authority,
+            &[],
+            amount,
         )?;
     } else {
         assert_keys_equal(payment_account.key(), wallet.key())?;
@@ -177,4 +177,56 @@ pub enum AuctionHouseError {
     // 6043
     #[msg("Insufficient funds in escrow account to purchase.")]
     InsufficientFunds,
+
+    // 6044
+    #[msg("The token is not a verified member of the bid collection.")]
+    CollectionNotVerified,
+
+    // 6045
+    #[msg("The collection bid has no quantity left to fill.")]
+    CollectionBidFilled,
+
+    // 6046
+    #[msg("Collection bids must be for a quantity of at least one.")]
+    InvalidCollectionBidQuantity,
+
+    // 6047
+    #[msg("Listing config must decline from a non-zero start price over a non-empty period.")]
+    InvalidListingConfig,
+
+    // 6048
+    #[msg("The Dutch auction has not started yet.")]
+    ListingNotStarted,
+
+    // 6049
+    #[msg("The bid is below the current price of the listing.")]
+    BidBelowCurrentPrice,
+
+    // 6050
+    #[msg("The Token-2022 program must be passed in remaining accounts for a Token-2022 treasury mint.")]
+    MissingTreasuryTokenProgram,
+
+    // 6051
+    #[msg("Bundles must list between two and eight tokens.")]
+    InvalidBundleSize,
+
+    // 6052
+    #[msg("Bundles need one weight per token and at least one non-zero weight.")]
+    InvalidBundleWeights,
+
+    // 6053
+    #[msg("The accounts passed do not match the tokens of the bundle listing.")]
+    BundleAccountMismatch,
+
+    // 6054
+    #[msg("The buyer price does not match the price of the bundle listing.")]
+    BundlePriceMismatch,
+
+    // 6055
+    #[msg("Delegated bids need an SPL treasury mint.")]
+    DelegatedBidNativeTreasury,
+
+    // 6056
+    #[msg("The buyer payment account does not hold or delegate enough to fill the bid.")]
+    InsufficientDelegatedFunds,
 }
@@ -1,11 +1,12 @@
 use crate::{constants::*, errors::*, utils::*, AuctionHouse, Auctioneer, AuthorityScope, *};
 use anchor_lang::{
     prelude::*,
-    solana_program::{program::invoke, program_pack::Pack},
+    solana_program::{program::invoke, program_option::COption, program_pack::Pack},
     AnchorDeserialize,
 };
 use solana_program::program_memory::sol_memset;
 use spl_token::state::Account as SplAccount;
+use std::collections::BTreeMap;
 
 /// Accounts for the [`execute_sale` handler](auction_house/fn.execute_sale.html).
 #[derive(Accounts)]
@@ -1198,6 +1199,9 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         ah_seeds
     };
 
+    let treasury_token_program =
+        get_treasury_token_program(treasury_mint, &token_clone, remaining_accounts)?;
+
     let buyer_leftover_after_royalties = pay_creator_fees(
         &mut remaining_accounts.iter(),
         &metadata_clone,
@@ -1206,7 +1210,7 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         &fee_payer_clone,
         treasury_mint,
         &ata_clone,
-        &token_clone,
+        &treasury_token_program,
         &sys_clone,
         &rent_clone,
         &signer_seeds_for_royalties,
@@ -1219,7 +1223,9 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
         auction_house,
         &treasury_clone,
         &escrow_clone,
-        &token_clone,
+        &auction_house_clone,
+        treasury_mint,
+        &treasury_token_program,
         &sys_clone,
         &signer_seeds_for_royalties,
         price,
@@ -1238,17 +1244,17 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
                 treasury_mint.to_account_info(),
                 fee_payer.to_account_info(),
                 ata_program.to_account_info(),
-                token_program.to_account_info(),
+                treasury_token_program.clone(),
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 fee_payer_seeds,
             )?;
         }
 
-        let seller_rec_acct = assert_is_ata(
+        let seller_rec_acct = assert_is_treasury_ata(
             &seller_payment_receipt_account.to_account_info(),
             &seller.key(),
-            &treasury_mint.key(),
+            treasury_mint,
         )?;
 
         // make sure you cant get rugged
@@ -1256,23 +1262,20 @@ fn auctioneer_execute_sale_logic<'c, 'info>(
             return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
         }
 
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
-            &[&ah_seeds],
+        let seller_received = transfer_treasury_tokens(
+            &treasury_token_program,
+            &escrow_payment_account.to_account_info(),
+            &seller_payment_receipt_account.to_account_info(),
+            treasury_mint,
+            &auction_house.to_account_info(),
+            &ah_seeds,
+            buyer_leftover_after_royalties_and_house_fee,
         )?;
+        msg!(
+            "Seller proceeds of {} paid, {} received by the seller",
+            buyer_leftover_after_royalties_and_house_fee,
+            seller_received
+        );
     } else {
         assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
         invoke_signed(
@@ -1410,53 +1413,28 @@ fn execute_sale_logic<'c, 'info>(
     let token_account = &accounts.token_account;
     let token_mint = &accounts.token_mint;
     let metadata = &accounts.metadata;
-    let treasury_mint = &accounts.treasury_mint;
-    let seller_payment_receipt_account = &accounts.seller_payment_receipt_account;
-    let buyer_receipt_token_account = &accounts.buyer_receipt_token_account;
-    let escrow_payment_account = &accounts.escrow_payment_account;
     let authority = &accounts.authority;
     let auction_house = &accounts.auction_house;
     let auction_house_fee_account = &accounts.auction_house_fee_account;
-    let auction_house_treasury = &accounts.auction_house_treasury;
     let buyer_trade_state = &accounts.buyer_trade_state;
     let seller_trade_state = &accounts.seller_trade_state;
     let free_trade_state = &accounts.free_trade_state;
     let token_program = &accounts.token_program;
-    let system_program = &accounts.system_program;
-    let ata_program = &accounts.ata_program;
     let program_as_signer = &accounts.program_as_signer;
-    let rent = &accounts.rent;
-
-    let metadata_clone = metadata.to_account_info();
-    let escrow_clone = escrow_payment_account.to_account_info();
-    let auction_house_clone = auction_house.to_account_info();
-    let ata_clone = ata_program.to_account_info();
-    let token_clone = token_program.to_account_info();
-    let sys_clone = system_program.to_account_info();
-    let rent_clone = rent.to_account_info();
-    let treasury_clone = auction_house_treasury.to_account_info();
-    let authority_clone = authority.to_account_info();
-    let buyer_receipt_clone = buyer_receipt_token_account.to_account_info();
-    let token_account_clone = token_account.to_account_info();
-
-    let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
-    if buyer_price == 0 && !authority_clone.is_signer && !seller.is_signer {
+    if buyer_price == 0 && !authority.is_signer && !seller.is_signer {
         return Err(
             AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
         );
     }
 
-    let token_account_mint = get_mint_from_token_account(&token_account_clone)?;
-
-    assert_keys_equal(token_mint.key(), token_account_mint)?;
-    let delegate = get_delegate_from_token_account(&token_account_clone)?;
-    if let Some(d) = delegate {
-        assert_keys_equal(program_as_signer.key(), d)?;
-    } else {
-        msg!("No delegate detected on token account.");
-        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
-    };
+    assert_sale_token_accounts(
+        seller,
+        token_account,
+        token_mint,
+        metadata,
+        program_as_signer,
+    )?;
 
     let buyer_ts_data = &mut buyer_trade_state.try_borrow_mut_data()?;
     let seller_ts_data = &mut seller_trade_state.try_borrow_mut_data()?;
@@ -1540,209 +1518,31 @@ fn execute_sale_logic<'c, 'info>(
         auction_house_fee_account.to_account_info(),
         &seeds,
     )?;
-    let fee_payer_clone = fee_payer.to_account_info();
 
-    assert_is_ata(
+    settle_sale(
+        remaining_accounts,
+        &buyer.to_account_info(),
+        &seller.to_account_info(),
         &token_account.to_account_info(),
-        &seller.key(),
-        &token_account_mint,
-    )?;
-    assert_derivation(
-        &mpl_token_metadata::id(),
+        &token_mint.to_account_info(),
         &metadata.to_account_info(),
-        &[
-            mpl_token_metadata::state::PREFIX.as_bytes(),
-            mpl_token_metadata::id().as_ref(),
-            token_account_mint.as_ref(),
-        ],
-    )?;
-
-    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
-    // token.  This is intended to cover the migration from pre-rent-exemption checked accounts to
-    // rent-exemption checked accounts.  The fee payer makes up the shortfall up to the amount of
-    // rent for an empty account.
-    if is_native {
-        let rent_shortfall = verify_withdrawal(escrow_payment_account.to_account_info(), price)?;
-        if rent_shortfall > 0 {
-            invoke_signed(
-                &system_instruction::transfer(
-                    fee_payer.key,
-                    escrow_payment_account.key,
-                    rent_shortfall,
-                ),
-                &[
-                    fee_payer.to_account_info(),
-                    escrow_payment_account.to_account_info(),
-                    system_program.to_account_info(),
-                ],
-                &[fee_payer_seeds],
-            )?;
-        }
-    }
-
-    if metadata.data_is_empty() {
-        return Err(AuctionHouseError::MetadataDoesntExist.into());
-    }
-
-    let auction_house_key = auction_house.key();
-    let wallet_key = buyer.key();
-    let escrow_signer_seeds = [
-        PREFIX.as_bytes(),
-        auction_house_key.as_ref(),
-        wallet_key.as_ref(),
-        &[escrow_payment_bump],
-    ];
-
-    let ah_seeds = [
-        PREFIX.as_bytes(),
-        auction_house.creator.as_ref(),
-        auction_house.treasury_mint.as_ref(),
-        &[auction_house.bump],
-    ];
-
-    // with the native account, the escrow is its own owner,
-    // whereas with token, it is the auction house that is owner.
-    let signer_seeds_for_royalties = if is_native {
-        escrow_signer_seeds
-    } else {
-        ah_seeds
-    };
-
-    let buyer_leftover_after_royalties = pay_creator_fees(
-        &mut remaining_accounts.iter(),
-        &metadata_clone,
-        &escrow_clone,
-        &auction_house_clone,
-        &fee_payer_clone,
-        treasury_mint,
-        &ata_clone,
-        &token_clone,
-        &sys_clone,
-        &rent_clone,
-        &signer_seeds_for_royalties,
-        fee_payer_seeds,
-        price,
-        is_native,
-    )?;
-
-    let auction_house_fee_paid = pay_auction_house_fees(
+        &accounts.treasury_mint.to_account_info(),
+        &accounts.escrow_payment_account.to_account_info(),
+        &accounts.seller_payment_receipt_account.to_account_info(),
+        &accounts.buyer_receipt_token_account.to_account_info(),
         auction_house,
-        &treasury_clone,
-        &escrow_clone,
-        &token_clone,
-        &sys_clone,
-        &signer_seeds_for_royalties,
+        &accounts.auction_house_treasury.to_account_info(),
+        &fee_payer,
+        fee_payer_seeds,
+        &token_program.to_account_info(),
+        &accounts.system_program.to_account_info(),
+        &accounts.ata_program.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &accounts.rent.to_account_info(),
+        escrow_payment_bump,
+        program_as_signer_bump,
         price,
-        is_native,
-    )?;
-
-    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
-        .checked_sub(auction_house_fee_paid)
-        .ok_or(AuctionHouseError::NumericalOverflow)?;
-
-    if !is_native {
-        if seller_payment_receipt_account.data_is_empty() {
-            make_ata(
-                seller_payment_receipt_account.to_account_info(),
-                seller.to_account_info(),
-                treasury_mint.to_account_info(),
-                fee_payer.to_account_info(),
-                ata_program.to_account_info(),
-                token_program.to_account_info(),
-                system_program.to_account_info(),
-                rent.to_account_info(),
-                fee_payer_seeds,
-            )?;
-        }
-
-        let seller_rec_acct = assert_is_ata(
-            &seller_payment_receipt_account.to_account_info(),
-            &seller.key(),
-            &treasury_mint.key(),
-        )?;
-
-        // make sure you cant get rugged
-        if seller_rec_acct.delegate.is_some() {
-            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
-        }
-
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &seller_payment_receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                buyer_leftover_after_royalties_and_house_fee,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
-            &[&ah_seeds],
-        )?;
-    } else {
-        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
-        invoke_signed(
-            &system_instruction::transfer(
-                escrow_payment_account.key,
-                seller_payment_receipt_account.key,
-                buyer_leftover_after_royalties_and_house_fee,
-            ),
-            &[
-                escrow_payment_account.to_account_info(),
-                seller_payment_receipt_account.to_account_info(),
-                system_program.to_account_info(),
-            ],
-            &[&escrow_signer_seeds],
-        )?;
-    }
-
-    if buyer_receipt_token_account.data_is_empty() {
-        make_ata(
-            buyer_receipt_token_account.to_account_info(),
-            buyer.to_account_info(),
-            token_mint.to_account_info(),
-            fee_payer.to_account_info(),
-            ata_program.to_account_info(),
-            token_program.to_account_info(),
-            system_program.to_account_info(),
-            rent.to_account_info(),
-            fee_payer_seeds,
-        )?;
-    }
-
-    let buyer_rec_acct = assert_is_ata(&buyer_receipt_clone, &buyer.key(), &token_mint.key())?;
-
-    // make sure you cant get rugged
-    if buyer_rec_acct.delegate.is_some() {
-        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
-    }
-
-    let program_as_signer_seeds = [
-        PREFIX.as_bytes(),
-        SIGNER.as_bytes(),
-        &[program_as_signer_bump],
-    ];
-
-    invoke_signed(
-        &spl_token::instruction::transfer(
-            token_program.key,
-            &token_account.key(),
-            &buyer_receipt_token_account.key(),
-            &program_as_signer.key(),
-            &[],
-            size,
-        )?,
-        &[
-            token_account.to_account_info(),
-            buyer_receipt_clone,
-            program_as_signer.to_account_info(),
-            token_clone,
-        ],
-        &[&program_as_signer_seeds],
+        size,
     )?;
 
     if token_account_data.amount == 0 {
@@ -1796,3 +1596,1615 @@ fn execute_sale_logic<'c, 'info>(
 
     Ok(())
 }
+
+/// Accounts for the [`execute_collection_sale` handler](auction_house/fn.execute_collection_sale.html).
+#[derive(Accounts)]
+#[instruction(
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    quantity: u64
+)]
+pub struct ExecuteCollectionSale<'info> {
+    /// CHECK: Validated in execute_collection_sale.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Seed of the buyer trade state, matched against the verified collection of the metadata.
+    /// Mint of the collection NFT the bid is for.
+    pub collection_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_collection_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer trade state PDA account encoding the collection bid and the quantity left to fill.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            COLLECTION_BID.as_bytes(),
+            buyer.key().as_ref(),
+            auction_house.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            collection_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &quantity.to_le_bytes()
+        ],
+        bump = buyer_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding a sell order of a single token at the bid price.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &1u64.to_le_bytes()
+        ],
+        bump = seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &1u64.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Fill one token of a collection bid with a verified member of the collection listed by the seller at the bid price.
+/// The buyer trade state is closed once its quantity is filled.
+pub fn execute_collection_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteCollectionSale<'info>>,
+    escrow_payment_bump: u8,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    _quantity: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let token_account = &ctx.accounts.token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let metadata = &ctx.accounts.metadata;
+    let collection_mint = &ctx.accounts.collection_mint;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let free_trade_state = &ctx.accounts.free_trade_state;
+    let token_program = &ctx.accounts.token_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    assert_canonical_bumps(
+        &ctx.bumps,
+        &[
+            ("escrow_payment_account", escrow_payment_bump),
+            ("free_trade_state", free_trade_state_bump),
+            ("program_as_signer", program_as_signer_bump),
+        ],
+    )?;
+
+    if buyer_price == 0 {
+        return Err(
+            AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
+        );
+    }
+
+    assert_sale_token_accounts(
+        seller,
+        token_account,
+        token_mint,
+        metadata,
+        program_as_signer,
+    )?;
+    assert_verified_collection_member(&metadata.to_account_info(), &collection_mint.key())?;
+
+    let remaining = {
+        let buyer_ts_data = buyer_trade_state.try_borrow_data()?;
+        if buyer_ts_data.len() != COLLECTION_BID_TRADE_STATE_SIZE || buyer_ts_data[0] == 0 {
+            return Err(AuctionHouseError::BuyerTradeStateNotValid.into());
+        }
+        u64::from_le_bytes(
+            buyer_ts_data[1..COLLECTION_BID_TRADE_STATE_SIZE]
+                .try_into()
+                .map_err(|_| AuctionHouseError::BuyerTradeStateNotValid)?,
+        )
+    };
+    if remaining == 0 {
+        return Err(AuctionHouseError::CollectionBidFilled.into());
+    }
+    if seller_trade_state.data_is_empty() || seller_trade_state.try_borrow_data()?[0] == 0 {
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+
+    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    if token_account_data.amount < 1 {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet_to_use.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    settle_sale(
+        ctx.remaining_accounts,
+        &buyer.to_account_info(),
+        &seller.to_account_info(),
+        &token_account.to_account_info(),
+        &token_mint.to_account_info(),
+        &metadata.to_account_info(),
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.escrow_payment_account.to_account_info(),
+        &ctx.accounts
+            .seller_payment_receipt_account
+            .to_account_info(),
+        &ctx.accounts.buyer_receipt_token_account.to_account_info(),
+        auction_house,
+        &ctx.accounts.auction_house_treasury.to_account_info(),
+        &fee_payer,
+        fee_payer_seeds,
+        &token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.ata_program.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &ctx.accounts.rent.to_account_info(),
+        escrow_payment_bump,
+        program_as_signer_bump,
+        buyer_price,
+        1,
+    )?;
+
+    close_trade_state(&seller_trade_state.to_account_info(), &fee_payer)?;
+    if free_trade_state.lamports() > 0 {
+        close_trade_state(&free_trade_state.to_account_info(), &fee_payer)?;
+    }
+
+    let remaining = remaining
+        .checked_sub(1)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if remaining == 0 {
+        close_trade_state(&buyer_trade_state.to_account_info(), &fee_payer)?;
+    } else {
+        buyer_trade_state.try_borrow_mut_data()?[1..COLLECTION_BID_TRADE_STATE_SIZE]
+            .copy_from_slice(&remaining.to_le_bytes());
+    }
+
+    Ok(())
+}
+
+/// Accounts for the [`execute_dutch_auction_sale` handler](auction_house/fn.execute_dutch_auction_sale.html).
+#[derive(Accounts)]
+#[instruction(
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct ExecuteDutchAuctionSale<'info> {
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_dutch_auction_sale.
+    /// Buyer trade state PDA account encoding the public or private bid.
+    #[account(mut)]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// Listing config of the declining-price listing, closed to the seller by the sale.
+    #[account(
+        mut,
+        close = seller,
+        seeds = [
+            PREFIX.as_bytes(),
+            LISTING_CONFIG.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            token_mint.key().as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump = listing_config.bump
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Match a bid of `buyer_price` with a declining-price listing whose current price, computed from the `Clock`, is at or below it.
+/// The sale settles at the current price; the rest of the bid stays in the buyer escrow.
+pub fn execute_dutch_auction_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteDutchAuctionSale<'info>>,
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let token_account = &ctx.accounts.token_account;
+    let token_mint = &ctx.accounts.token_mint;
+    let metadata = &ctx.accounts.metadata;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let listing_config = &ctx.accounts.listing_config;
+    let token_program = &ctx.accounts.token_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    assert_canonical_bumps(
+        &ctx.bumps,
+        &[
+            ("escrow_payment_account", escrow_payment_bump),
+            ("program_as_signer", program_as_signer_bump),
+        ],
+    )?;
+
+    let price = listing_config.current_price(Clock::get()?.unix_timestamp)?;
+    if buyer_price < price {
+        return Err(AuctionHouseError::BidBelowCurrentPrice.into());
+    }
+
+    assert_sale_token_accounts(
+        seller,
+        token_account,
+        token_mint,
+        metadata,
+        program_as_signer,
+    )?;
+
+    let ts_bump = match buyer_trade_state.try_borrow_data()?.first() {
+        Some(bump) if *bump != 0 => *bump,
+        _ => return Err(AuctionHouseError::BuyerTradeStateNotValid.into()),
+    };
+    assert_valid_trade_state(
+        &buyer.key(),
+        auction_house,
+        buyer_price,
+        token_size,
+        buyer_trade_state,
+        &token_mint.key(),
+        &token_account.key(),
+        ts_bump,
+    )?;
+
+    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    if token_account_data.amount < token_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet_to_use.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    settle_sale(
+        ctx.remaining_accounts,
+        &buyer.to_account_info(),
+        &seller.to_account_info(),
+        &token_account.to_account_info(),
+        &token_mint.to_account_info(),
+        &metadata.to_account_info(),
+        &ctx.accounts.treasury_mint.to_account_info(),
+        &ctx.accounts.escrow_payment_account.to_account_info(),
+        &ctx.accounts
+            .seller_payment_receipt_account
+            .to_account_info(),
+        &ctx.accounts.buyer_receipt_token_account.to_account_info(),
+        auction_house,
+        &ctx.accounts.auction_house_treasury.to_account_info(),
+        &fee_payer,
+        fee_payer_seeds,
+        &token_program.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        &ctx.accounts.ata_program.to_account_info(),
+        &program_as_signer.to_account_info(),
+        &ctx.accounts.rent.to_account_info(),
+        escrow_payment_bump,
+        program_as_signer_bump,
+        price,
+        token_size,
+    )?;
+
+    close_trade_state(&buyer_trade_state.to_account_info(), &fee_payer)?;
+
+    Ok(())
+}
+
+/// Accounts for the [`execute_bundle_sale` handler](auction_house/fn.execute_bundle_sale.html).
+#[derive(Accounts)]
+#[instruction(escrow_payment_bump: u8, program_as_signer_bump: u8, buyer_price: u64)]
+pub struct ExecuteBundleSale<'info> {
+    /// Buyer user wallet account, which agrees to the sale by signing.
+    #[account(mut)]
+    pub buyer: Signer<'info>,
+
+    /// CHECK: Validated by the bundle listing seeds.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the bundle listing seeds.
+    /// Mint of the first token in the bundle, which keys the bundle listing.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated by the auction house constraints.
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer escrow payment account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            buyer.key().as_ref()
+        ],
+        bump
+    )]
+    pub escrow_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Seller SOL or SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_bundle_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// Bundle listing of the tokens sold, closed to the seller by the sale.
+    #[account(
+        mut,
+        close = seller,
+        seeds = [
+            PREFIX.as_bytes(),
+            BUNDLE_LISTING.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump = bundle_listing.bump
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Buy every token of a bundle listing in one sale, paying the royalties of each token on its weighted share of the bundle price.
+/// For each token, in listing order, remaining accounts hold its token account, mint, metadata and buyer receipt token account, followed by its creator accounts.
+pub fn execute_bundle_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer;
+    let seller = &ctx.accounts.seller;
+    let treasury_mint = &ctx.accounts.treasury_mint;
+    let escrow_payment_account = &ctx.accounts.escrow_payment_account;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let bundle_listing = &ctx.accounts.bundle_listing;
+    let token_program = &ctx.accounts.token_program;
+    let system_program = &ctx.accounts.system_program;
+    let ata_program = &ctx.accounts.ata_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+    let rent = &ctx.accounts.rent.to_account_info();
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    assert_canonical_bumps(
+        &ctx.bumps,
+        &[
+            ("escrow_payment_account", escrow_payment_bump),
+            ("program_as_signer", program_as_signer_bump),
+        ],
+    )?;
+
+    if buyer_price != bundle_listing.price {
+        return Err(AuctionHouseError::BundlePriceMismatch.into());
+    }
+
+    if buyer_price == 0 && !authority.is_signer && !seller.is_signer {
+        return Err(
+            AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
+        );
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        buyer.to_account_info(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
+    // bundle.  The fee payer makes up the shortfall up to the amount of rent for an empty account.
+    if is_native {
+        let rent_shortfall =
+            verify_withdrawal(escrow_payment_account.to_account_info(), buyer_price)?;
+        if rent_shortfall > 0 {
+            invoke_signed(
+                &system_instruction::transfer(
+                    fee_payer.key,
+                    escrow_payment_account.key,
+                    rent_shortfall,
+                ),
+                &[
+                    fee_payer.to_account_info(),
+                    escrow_payment_account.to_account_info(),
+                    system_program.to_account_info(),
+                ],
+                &[fee_payer_seeds],
+            )?;
+        }
+    }
+
+    let wallet_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let treasury_token_program = get_treasury_token_program(
+        treasury_mint,
+        &token_program.to_account_info(),
+        ctx.remaining_accounts,
+    )?;
+
+    let item_prices = bundle_listing.item_prices()?;
+    let remaining_accounts = &mut ctx.remaining_accounts.iter();
+    let mut buyer_leftover_after_royalties: u64 = 0;
+    for (item, item_price) in bundle_listing.items.iter().zip(item_prices) {
+        let token_account = next_account_info(remaining_accounts)?;
+        let token_mint = next_account_info(remaining_accounts)?;
+        let metadata = next_account_info(remaining_accounts)?;
+        let buyer_receipt_token_account = next_account_info(remaining_accounts)?;
+
+        if token_account.key() != item.token_account || token_mint.key() != item.token_mint {
+            return Err(AuctionHouseError::BundleAccountMismatch.into());
+        }
+        assert_sale_token_accounts(
+            seller,
+            token_account,
+            token_mint,
+            metadata,
+            program_as_signer,
+        )?;
+        let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+        if token_account_data.amount < 1 {
+            return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+        }
+
+        let item_leftover = pay_creator_fees(
+            remaining_accounts,
+            metadata,
+            &escrow_payment_account.to_account_info(),
+            &auction_house.to_account_info(),
+            &fee_payer,
+            treasury_mint,
+            ata_program,
+            &treasury_token_program,
+            system_program,
+            rent,
+            &signer_seeds_for_royalties,
+            fee_payer_seeds,
+            item_price,
+            is_native,
+        )?;
+        buyer_leftover_after_royalties = buyer_leftover_after_royalties
+            .checked_add(item_leftover)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+        transfer_to_buyer(
+            &buyer.to_account_info(),
+            token_account,
+            token_mint,
+            buyer_receipt_token_account,
+            &fee_payer,
+            fee_payer_seeds,
+            token_program,
+            system_program,
+            ata_program,
+            program_as_signer,
+            rent,
+            program_as_signer_bump,
+            1,
+        )?;
+    }
+
+    let auction_house_fee_paid = pay_auction_house_fees(
+        auction_house,
+        &ctx.accounts.auction_house_treasury.to_account_info(),
+        &escrow_payment_account.to_account_info(),
+        &auction_house.to_account_info(),
+        treasury_mint,
+        &treasury_token_program,
+        system_program,
+        &signer_seeds_for_royalties,
+        buyer_price,
+        is_native,
+    )?;
+
+    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
+        .checked_sub(auction_house_fee_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    pay_seller(
+        seller,
+        treasury_mint,
+        escrow_payment_account,
+        &ctx.accounts.seller_payment_receipt_account,
+        &auction_house.to_account_info(),
+        &fee_payer,
+        fee_payer_seeds,
+        &treasury_token_program,
+        system_program,
+        ata_program,
+        rent,
+        &signer_seeds_for_royalties,
+        buyer_leftover_after_royalties_and_house_fee,
+    )
+}
+
+/// Accounts for the [`execute_delegated_sale` handler](auction_house/fn.execute_delegated_sale.html).
+#[derive(Accounts)]
+#[instruction(
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64
+)]
+pub struct ExecuteDelegatedSale<'info> {
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Buyer user wallet account.
+    #[account(mut)]
+    pub buyer: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Seller user wallet account.
+    #[account(mut)]
+    pub seller: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    ///Token account where the SPL token is stored.
+    #[account(mut)]
+    pub token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Token mint account for the SPL token.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    // cannot mark these as real Accounts or else we blow stack size limit
+    /// Auction House treasury mint account.
+    pub treasury_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Buyer treasury mint ATA delegated to the program as signer, which pays for the sale.
+    #[account(mut)]
+    pub buyer_payment_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Seller SPL account to receive payment at.
+    #[account(mut)]
+    pub seller_payment_receipt_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Buyer SPL token account to receive purchased item at.
+    #[account(mut)]
+    pub buyer_receipt_token_account: UncheckedAccount<'info>,
+
+    /// CHECK: Validated in execute_delegated_sale.
+    /// Auction House instance authority.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority,
+        has_one=treasury_mint,
+        has_one=auction_house_treasury,
+        has_one=auction_house_fee_account
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance fee account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            FEE_PAYER.as_bytes()
+        ],
+        bump=auction_house.fee_payer_bump
+    )]
+    pub auction_house_fee_account: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Auction House instance treasury account.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.key().as_ref(),
+            TREASURY.as_bytes()
+        ],
+        bump=auction_house.treasury_bump
+    )]
+    pub auction_house_treasury: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Buyer trade state PDA account encoding the delegated bid.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_BID.as_bytes(),
+            buyer.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump = buyer_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub buyer_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Ledger of the allowance the buyer payment account owes to open delegated bids.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            DELEGATED_ALLOWANCE.as_bytes(),
+            buyer_payment_account.key().as_ref()
+        ],
+        bump
+    )]
+    pub delegated_allowance: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Seller trade state PDA account encoding a sell order at the bid price.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &buyer_price.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump = seller_trade_state.to_account_info().data.borrow()[0]
+    )]
+    pub seller_trade_state: UncheckedAccount<'info>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    /// Free seller trade state PDA account encoding a free sell order.
+    #[account(
+        mut,
+        seeds = [
+            PREFIX.as_bytes(),
+            seller.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            auction_house.treasury_mint.as_ref(),
+            token_mint.key().as_ref(),
+            &0u64.to_le_bytes(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub free_trade_state: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub ata_program: Program<'info, AssociatedToken>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// Match a delegated bid with a listing at the bid price, pulling royalties, the auction house fee and the seller proceeds out of the buyer payment account through the program as signer.
+/// Fails with `InsufficientDelegatedFunds` if the account no longer holds the bid price or delegates what its open delegated bids are owed.
+pub fn execute_delegated_sale<'info>(
+    ctx: Context<'_, '_, '_, 'info, ExecuteDelegatedSale<'info>>,
+    free_trade_state_bump: u8,
+    program_as_signer_bump: u8,
+    buyer_price: u64,
+    token_size: u64,
+) -> Result<()> {
+    let buyer = &ctx.accounts.buyer.to_account_info();
+    let seller = &ctx.accounts.seller.to_account_info();
+    let token_account = &ctx.accounts.token_account.to_account_info();
+    let token_mint = &ctx.accounts.token_mint.to_account_info();
+    let metadata = &ctx.accounts.metadata.to_account_info();
+    let treasury_mint = &ctx.accounts.treasury_mint.to_account_info();
+    let buyer_payment_account = &ctx.accounts.buyer_payment_account.to_account_info();
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let auction_house_fee_account = &ctx.accounts.auction_house_fee_account;
+    let buyer_trade_state = &ctx.accounts.buyer_trade_state;
+    let delegated_allowance = &ctx.accounts.delegated_allowance.to_account_info();
+    let seller_trade_state = &ctx.accounts.seller_trade_state;
+    let free_trade_state = &ctx.accounts.free_trade_state;
+    let token_program = &ctx.accounts.token_program.to_account_info();
+    let system_program = &ctx.accounts.system_program.to_account_info();
+    let ata_program = &ctx.accounts.ata_program.to_account_info();
+    let program_as_signer = &ctx.accounts.program_as_signer.to_account_info();
+    let rent = &ctx.accounts.rent.to_account_info();
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::ExecuteSale as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    assert_canonical_bumps(
+        &ctx.bumps,
+        &[
+            ("free_trade_state", free_trade_state_bump),
+            ("program_as_signer", program_as_signer_bump),
+        ],
+    )?;
+
+    if treasury_mint.key() == spl_token::native_mint::id() {
+        return Err(AuctionHouseError::DelegatedBidNativeTreasury.into());
+    }
+
+    if buyer_price == 0 {
+        return Err(
+            AuctionHouseError::CannotMatchFreeSalesWithoutAuctionHouseOrSellerSignoff.into(),
+        );
+    }
+
+    assert_sale_token_accounts(
+        seller,
+        token_account,
+        token_mint,
+        metadata,
+        program_as_signer,
+    )?;
+
+    if buyer_trade_state.data_is_empty() || buyer_trade_state.try_borrow_data()?[0] == 0 {
+        return Err(AuctionHouseError::BuyerTradeStateNotValid.into());
+    }
+    if seller_trade_state.data_is_empty() || seller_trade_state.try_borrow_data()?[0] == 0 {
+        return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+    }
+
+    let token_account_data = SplAccount::unpack(&token_account.data.borrow())?;
+    if token_account_data.amount < token_size {
+        return Err(AuctionHouseError::NotEnoughTokensAvailableForPurchase.into());
+    }
+
+    // Funds are only checked now, so bids the buyer can no longer cover fail here rather than at bid time.
+    let buyer_payment_data =
+        assert_is_treasury_ata(buyer_payment_account, buyer.key, treasury_mint)?;
+    // The allowance is pooled across every delegated bid on the account, so it must still cover
+    // all of them, this one included; otherwise one bid could spend what was granted for others.
+    let outstanding = get_delegated_allowance(delegated_allowance)?;
+    if buyer_payment_data.delegate != COption::Some(program_as_signer.key())
+        || outstanding < buyer_price
+        || buyer_payment_data.delegated_amount < outstanding
+        || buyer_payment_data.amount < buyer_price
+    {
+        return Err(AuctionHouseError::InsufficientDelegatedFunds.into());
+    }
+
+    let auction_house_key = auction_house.key();
+    let seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        FEE_PAYER.as_bytes(),
+        &[auction_house.fee_payer_bump],
+    ];
+
+    let wallet_to_use = if buyer.is_signer { buyer } else { seller };
+
+    let (fee_payer, fee_payer_seeds) = get_fee_payer(
+        authority,
+        auction_house,
+        wallet_to_use.clone(),
+        auction_house_fee_account.to_account_info(),
+        &seeds,
+    )?;
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    let treasury_token_program =
+        get_treasury_token_program(treasury_mint, token_program, ctx.remaining_accounts)?;
+
+    let buyer_leftover_after_royalties = pay_creator_fees(
+        &mut ctx.remaining_accounts.iter(),
+        metadata,
+        buyer_payment_account,
+        program_as_signer,
+        &fee_payer,
+        treasury_mint,
+        ata_program,
+        &treasury_token_program,
+        system_program,
+        rent,
+        &program_as_signer_seeds,
+        fee_payer_seeds,
+        buyer_price,
+        false,
+    )?;
+
+    let auction_house_fee_paid = pay_auction_house_fees(
+        auction_house,
+        &ctx.accounts.auction_house_treasury.to_account_info(),
+        buyer_payment_account,
+        program_as_signer,
+        treasury_mint,
+        &treasury_token_program,
+        system_program,
+        &program_as_signer_seeds,
+        buyer_price,
+        false,
+    )?;
+
+    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
+        .checked_sub(auction_house_fee_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    pay_seller(
+        seller,
+        treasury_mint,
+        buyer_payment_account,
+        &ctx.accounts.seller_payment_receipt_account,
+        program_as_signer,
+        &fee_payer,
+        fee_payer_seeds,
+        &treasury_token_program,
+        system_program,
+        ata_program,
+        rent,
+        &program_as_signer_seeds,
+        buyer_leftover_after_royalties_and_house_fee,
+    )?;
+
+    transfer_to_buyer(
+        buyer,
+        token_account,
+        token_mint,
+        &ctx.accounts.buyer_receipt_token_account,
+        &fee_payer,
+        fee_payer_seeds,
+        token_program,
+        system_program,
+        ata_program,
+        program_as_signer,
+        rent,
+        program_as_signer_bump,
+        token_size,
+    )?;
+
+    close_trade_state(&seller_trade_state.to_account_info(), &fee_payer)?;
+    if free_trade_state.lamports() > 0 {
+        close_trade_state(&free_trade_state.to_account_info(), &fee_payer)?;
+    }
+    set_delegated_allowance(delegated_allowance, outstanding - buyer_price)?;
+    close_trade_state(&buyer_trade_state.to_account_info(), &fee_payer)?;
+
+    Ok(())
+}
+
+/// Check the bumps passed as instruction arguments are the canonical bumps Anchor derived for the named accounts.
+fn assert_canonical_bumps(bumps: &BTreeMap<String, u8>, expected: &[(&str, u8)]) -> Result<()> {
+    for (account, bump) in expected {
+        let canonical_bump = *bumps
+            .get(*account)
+            .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+        if canonical_bump != *bump {
+            return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+        }
+    }
+    Ok(())
+}
+
+/// Check the seller's token account is their ATA of `token_mint`, delegated to the program as signer,
+/// and that `metadata` is the existing token-metadata account of that mint.
+fn assert_sale_token_accounts(
+    seller: &AccountInfo,
+    token_account: &AccountInfo,
+    token_mint: &AccountInfo,
+    metadata: &AccountInfo,
+    program_as_signer: &AccountInfo,
+) -> Result<()> {
+    let token_account_mint = get_mint_from_token_account(token_account)?;
+    assert_keys_equal(token_mint.key(), token_account_mint)?;
+
+    match get_delegate_from_token_account(token_account)? {
+        Some(delegate) => assert_keys_equal(program_as_signer.key(), delegate)?,
+        None => {
+            msg!("No delegate detected on token account.");
+            return Err(AuctionHouseError::BothPartiesNeedToAgreeToSale.into());
+        }
+    }
+
+    assert_is_ata(token_account, &seller.key(), &token_account_mint)?;
+    assert_derivation(
+        &mpl_token_metadata::id(),
+        metadata,
+        &[
+            mpl_token_metadata::state::PREFIX.as_bytes(),
+            mpl_token_metadata::id().as_ref(),
+            token_account_mint.as_ref(),
+        ],
+    )?;
+    if metadata.data_is_empty() {
+        return Err(AuctionHouseError::MetadataDoesntExist.into());
+    }
+
+    Ok(())
+}
+
+/// Pay royalties and the auction house fee out of the buyer escrow and the rest of `price` to the seller, then transfer `size` tokens from the seller to the buyer.
+#[allow(clippy::too_many_arguments)]
+#[inline(never)]
+fn settle_sale<'info>(
+    remaining_accounts: &[AccountInfo<'info>],
+    buyer: &AccountInfo<'info>,
+    seller: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_mint: &AccountInfo<'info>,
+    metadata: &AccountInfo<'info>,
+    treasury_mint: &AccountInfo<'info>,
+    escrow_payment_account: &AccountInfo<'info>,
+    seller_payment_receipt_account: &AccountInfo<'info>,
+    buyer_receipt_token_account: &AccountInfo<'info>,
+    auction_house: &Account<'info, AuctionHouse>,
+    auction_house_treasury: &AccountInfo<'info>,
+    fee_payer: &AccountInfo<'info>,
+    fee_payer_seeds: &[&[u8]],
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    ata_program: &AccountInfo<'info>,
+    program_as_signer: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    escrow_payment_bump: u8,
+    program_as_signer_bump: u8,
+    price: u64,
+    size: u64,
+) -> Result<()> {
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    // For native purchases, verify that the amount in escrow is sufficient to actually purchase the
+    // token.  The fee payer makes up the shortfall up to the amount of rent for an empty account.
+    if is_native {
+        let rent_shortfall = verify_withdrawal(escrow_payment_account.clone(), price)?;
+        if rent_shortfall > 0 {
+            invoke_signed(
+                &system_instruction::transfer(
+                    fee_payer.key,
+                    escrow_payment_account.key,
+                    rent_shortfall,
+                ),
+                &[
+                    fee_payer.clone(),
+                    escrow_payment_account.clone(),
+                    system_program.clone(),
+                ],
+                &[fee_payer_seeds],
+            )?;
+        }
+    }
+
+    let auction_house_key = auction_house.key();
+    let wallet_key = buyer.key();
+    let escrow_signer_seeds = [
+        PREFIX.as_bytes(),
+        auction_house_key.as_ref(),
+        wallet_key.as_ref(),
+        &[escrow_payment_bump],
+    ];
+
+    let ah_seeds = [
+        PREFIX.as_bytes(),
+        auction_house.creator.as_ref(),
+        auction_house.treasury_mint.as_ref(),
+        &[auction_house.bump],
+    ];
+
+    // with the native account, the escrow is its own owner,
+    // whereas with token, it is the auction house that is owner.
+    let signer_seeds_for_royalties = if is_native {
+        escrow_signer_seeds
+    } else {
+        ah_seeds
+    };
+
+    let treasury_token_program =
+        get_treasury_token_program(treasury_mint, token_program, remaining_accounts)?;
+
+    let buyer_leftover_after_royalties = pay_creator_fees(
+        &mut remaining_accounts.iter(),
+        metadata,
+        escrow_payment_account,
+        &auction_house.to_account_info(),
+        fee_payer,
+        treasury_mint,
+        ata_program,
+        &treasury_token_program,
+        system_program,
+        rent,
+        &signer_seeds_for_royalties,
+        fee_payer_seeds,
+        price,
+        is_native,
+    )?;
+
+    let auction_house_fee_paid = pay_auction_house_fees(
+        auction_house,
+        auction_house_treasury,
+        escrow_payment_account,
+        &auction_house.to_account_info(),
+        treasury_mint,
+        &treasury_token_program,
+        system_program,
+        &signer_seeds_for_royalties,
+        price,
+        is_native,
+    )?;
+
+    let buyer_leftover_after_royalties_and_house_fee = buyer_leftover_after_royalties
+        .checked_sub(auction_house_fee_paid)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    pay_seller(
+        seller,
+        treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account,
+        &auction_house.to_account_info(),
+        fee_payer,
+        fee_payer_seeds,
+        &treasury_token_program,
+        system_program,
+        ata_program,
+        rent,
+        &signer_seeds_for_royalties,
+        buyer_leftover_after_royalties_and_house_fee,
+    )?;
+
+    transfer_to_buyer(
+        buyer,
+        token_account,
+        token_mint,
+        buyer_receipt_token_account,
+        fee_payer,
+        fee_payer_seeds,
+        token_program,
+        system_program,
+        ata_program,
+        program_as_signer,
+        rent,
+        program_as_signer_bump,
+        size,
+    )
+}
+
+/// Pay `amount` out of the buyer's payment account to the seller, creating the seller's treasury ATA if needed.
+/// `signer_seeds` sign for the escrow itself on native treasuries and for `payment_account_owner` otherwise.
+#[allow(clippy::too_many_arguments)]
+fn pay_seller<'info>(
+    seller: &AccountInfo<'info>,
+    treasury_mint: &AccountInfo<'info>,
+    payment_account: &AccountInfo<'info>,
+    seller_payment_receipt_account: &AccountInfo<'info>,
+    payment_account_owner: &AccountInfo<'info>,
+    fee_payer: &AccountInfo<'info>,
+    fee_payer_seeds: &[&[u8]],
+    treasury_token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    ata_program: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<()> {
+    let is_native = treasury_mint.key() == spl_token::native_mint::id();
+
+    if !is_native {
+        if seller_payment_receipt_account.data_is_empty() {
+            make_ata(
+                seller_payment_receipt_account.clone(),
+                seller.clone(),
+                treasury_mint.clone(),
+                fee_payer.clone(),
+                ata_program.clone(),
+                treasury_token_program.clone(),
+                system_program.clone(),
+                rent.clone(),
+                fee_payer_seeds,
+            )?;
+        }
+
+        let seller_rec_acct =
+            assert_is_treasury_ata(seller_payment_receipt_account, seller.key, treasury_mint)?;
+
+        // make sure you cant get rugged
+        if seller_rec_acct.delegate.is_some() {
+            return Err(AuctionHouseError::SellerATACannotHaveDelegate.into());
+        }
+
+        let seller_received = transfer_treasury_tokens(
+            treasury_token_program,
+            payment_account,
+            seller_payment_receipt_account,
+            treasury_mint,
+            payment_account_owner,
+            signer_seeds,
+            amount,
+        )?;
+        msg!(
+            "Seller proceeds of {} paid, {} received by the seller",
+            amount,
+            seller_received
+        );
+    } else {
+        assert_keys_equal(seller_payment_receipt_account.key(), seller.key())?;
+        invoke_signed(
+            &system_instruction::transfer(
+                payment_account.key,
+                seller_payment_receipt_account.key,
+                amount,
+            ),
+            &[
+                payment_account.clone(),
+                seller_payment_receipt_account.clone(),
+                system_program.clone(),
+            ],
+            &[signer_seeds],
+        )?;
+    }
+
+    Ok(())
+}
+
+/// Transfer `size` tokens from the seller to the buyer's ATA through the program delegate, creating the ATA if needed.
+#[allow(clippy::too_many_arguments)]
+fn transfer_to_buyer<'info>(
+    buyer: &AccountInfo<'info>,
+    token_account: &AccountInfo<'info>,
+    token_mint: &AccountInfo<'info>,
+    buyer_receipt_token_account: &AccountInfo<'info>,
+    fee_payer: &AccountInfo<'info>,
+    fee_payer_seeds: &[&[u8]],
+    token_program: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    ata_program: &AccountInfo<'info>,
+    program_as_signer: &AccountInfo<'info>,
+    rent: &AccountInfo<'info>,
+    program_as_signer_bump: u8,
+    size: u64,
+) -> Result<()> {
+    if buyer_receipt_token_account.data_is_empty() {
+        make_ata(
+            buyer_receipt_token_account.clone(),
+            buyer.clone(),
+            token_mint.clone(),
+            fee_payer.clone(),
+            ata_program.clone(),
+            token_program.clone(),
+            system_program.clone(),
+            rent.clone(),
+            fee_payer_seeds,
+        )?;
+    }
+
+    let buyer_rec_acct = assert_is_ata(buyer_receipt_token_account, buyer.key, token_mint.key)?;
+
+    // make sure you cant get rugged
+    if buyer_rec_acct.delegate.is_some() {
+        return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
+    }
+
+    let program_as_signer_seeds = [
+        PREFIX.as_bytes(),
+        SIGNER.as_bytes(),
+        &[program_as_signer_bump],
+    ];
+
+    invoke_signed(
+        &spl_token::instruction::transfer(
+            token_program.key,
+            token_account.key,
+            buyer_receipt_token_account.key,
+            program_as_signer.key,
+            &[],
+            size,
+        )?,
+        &[
+            token_account.clone(),
+            buyer_receipt_token_account.clone(),
+            program_as_signer.clone(),
+            token_program.clone(),
+        ],
+        &[&program_as_signer_seeds],
+    )?;
+
+    Ok(())
+}
+
+/// Move all lamports of a trade state to the fee payer and zero its data so it can be garbage collected.
+fn close_trade_state(trade_state: &AccountInfo, fee_payer: &AccountInfo) -> Result<()> {
+    let curr_lamp = trade_state.lamports();
+    **trade_state.lamports.borrow_mut() = 0;
+    **fee_payer.lamports.borrow_mut() = fee_payer
+        .lamports()
+        .checked_add(curr_lamp)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+
+    let mut data = trade_state.try_borrow_mut_data()?;
+    let len = data.len();
+    sol_memset(&mut data, 0, len);
+    Ok(())
+}
@@ -77,6 +77,7 @@ pub mod auction_house {
     }
 
     /// Withdraw `amount` from the Auction House Treasury Account to a provided destination account.
+    /// Token-2022 treasuries need the Token-2022 program in remaining accounts.
     pub fn withdraw_from_treasury<'info>(
         ctx: Context<'_, '_, '_, 'info, WithdrawFromTreasury<'info>>,
         amount: u64,
@@ -88,6 +89,7 @@ pub mod auction_house {
         let token_program = &ctx.accounts.token_program;
         let system_program = &ctx.accounts.system_program;
 
+        assert_treasury_mint(treasury_mint)?;
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
         let auction_house_seeds = [
             PREFIX.as_bytes(),
@@ -104,22 +106,16 @@ pub mod auction_house {
             &[auction_house.treasury_bump],
         ];
         if !is_native {
-            invoke_signed(
-                &spl_token::instruction::transfer(
-                    token_program.key,
-                    &auction_house_treasury.key(),
-                    &treasury_withdrawal_destination.key(),
-                    &auction_house.key(),
-                    &[],
-                    amount,
-                )?,
-                &[
-                    auction_house_treasury.to_account_info(),
-                    treasury_withdrawal_destination.to_account_info(),
-                    token_program.to_account_info(),
-                    auction_house.to_account_info(),
-                ],
-                &[&auction_house_seeds],
+            let treasury_token_program =
+                get_treasury_token_program(treasury_mint, token_program, ctx.remaining_accounts)?;
+            transfer_treasury_tokens(
+                &treasury_token_program,
+                auction_house_treasury,
+                treasury_withdrawal_destination,
+                treasury_mint,
+                &auction_house.to_account_info(),
+                &auction_house_seeds,
+                amount,
             )?;
         } else {
             invoke_signed(
@@ -141,6 +137,7 @@ pub mod auction_house {
     }
 
     /// Update Auction House values such as seller fee basis points, update authority, treasury account, etc.
+    /// Token-2022 treasuries need the Token-2022 program in remaining accounts.
     pub fn update_auction_house<'info>(
         ctx: Context<'_, '_, '_, 'info, UpdateAuctionHouse<'info>>,
         seller_fee_basis_points: Option<u16>,
@@ -159,7 +156,10 @@ pub mod auction_house {
         let system_program = &ctx.accounts.system_program;
         let ata_program = &ctx.accounts.ata_program;
         let rent = &ctx.accounts.rent;
+        assert_treasury_mint(treasury_mint)?;
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
+        let treasury_token_program =
+            get_treasury_token_program(treasury_mint, token_program, ctx.remaining_accounts)?;
 
         if let Some(sfbp) = seller_fee_basis_points {
             if sfbp > 10000 {
@@ -188,17 +188,17 @@ pub mod auction_house {
                     treasury_mint.to_account_info(),
                     payer.to_account_info(),
                     ata_program.to_account_info(),
-                    token_program.to_account_info(),
+                    treasury_token_program.clone(),
                     system_program.to_account_info(),
                     rent.to_account_info(),
                     &[],
                 )?;
             }
 
-            assert_is_ata(
-                &treasury_withdrawal_destination.to_account_info(),
+            assert_is_treasury_ata(
+                treasury_withdrawal_destination,
                 &treasury_withdrawal_destination_owner.key(),
-                &treasury_mint.key(),
+                treasury_mint,
             )?;
         } else {
             assert_keys_equal(
@@ -211,6 +211,7 @@ pub mod auction_house {
     }
 
     /// Create a new Auction House instance.
+    /// Token-2022 treasuries need the Token-2022 program in remaining accounts.
     pub fn create_auction_house<'info>(
         ctx: Context<'_, '_, '_, 'info, CreateAuctionHouse<'info>>,
         _bump: u8,
@@ -275,7 +276,10 @@ pub mod auction_house {
         auction_house.treasury_withdrawal_destination = treasury_withdrawal_destination.key();
         auction_house.fee_withdrawal_destination = fee_withdrawal_destination.key();
 
+        assert_treasury_mint(treasury_mint)?;
         let is_native = treasury_mint.key() == spl_token::native_mint::id();
+        let treasury_token_program =
+            get_treasury_token_program(treasury_mint, token_program, ctx.remaining_accounts)?;
 
         let ah_key = auction_house.key();
 
@@ -290,7 +294,7 @@ pub mod auction_house {
             auction_house_treasury,
             system_program,
             payer,
-            token_program,
+            &treasury_token_program,
             treasury_mint,
             &auction_house.to_account_info(),
             rent,
@@ -307,17 +311,17 @@ pub mod auction_house {
                     treasury_mint.to_account_info(),
                     payer.to_account_info(),
                     ata_program.to_account_info(),
-                    token_program.to_account_info(),
+                    treasury_token_program.clone(),
                     system_program.to_account_info(),
                     rent.to_account_info(),
                     &[],
                 )?;
             }
 
-            assert_is_ata(
-                &treasury_withdrawal_destination.to_account_info(),
+            assert_is_treasury_ata(
+                treasury_withdrawal_destination,
                 &treasury_withdrawal_destination_owner.key(),
-                &treasury_mint.key(),
+                treasury_mint,
             )?;
         } else {
             assert_keys_equal(
@@ -396,6 +400,40 @@ pub mod auction_house {
         )
     }
 
+    /// Create a collection bid by creating a `collection_buyer_trade_state` account for `quantity` verified members of a collection and funding the escrow with `buyer_price` for each of them.
+    pub fn collection_buy<'info>(
+        ctx: Context<'_, '_, '_, 'info, CollectionBuy<'info>>,
+        trade_state_bump: u8,
+        escrow_payment_bump: u8,
+        buyer_price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        collection_bid(
+            ctx,
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price,
+            quantity,
+        )
+    }
+
+    /// Create a private bid paid at settlement out of the buyer's treasury mint ATA, which delegates the bid price to the program instead of funding the escrow.
+    pub fn delegated_buy(
+        ctx: Context<DelegatedBuy>,
+        trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        delegated_bid(
+            ctx,
+            trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
     /// Cancel a bid or ask by revoking the token delegate, transferring all lamports from the trade state account to the fee payer, and setting the trade state account data to zero so it can be garbage collected.
     pub fn cancel<'info>(
         ctx: Context<'_, '_, '_, 'info, Cancel<'info>>,
@@ -414,6 +452,39 @@ pub mod auction_house {
         cancel::auctioneer_cancel(ctx, buyer_price, token_size)
     }
 
+    /// Cancel a collection bid, transferring all lamports from its trade state account to the fee payer.
+    pub fn cancel_collection_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelCollectionBid<'info>>,
+        buyer_price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        cancel::cancel_collection_bid(ctx, buyer_price, quantity)
+    }
+
+    /// Cancel a Dutch-auction listing, closing its listing config and revoking the token delegate.
+    pub fn cancel_dutch_auction<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelDutchAuction<'info>>,
+        token_size: u64,
+    ) -> Result<()> {
+        cancel::cancel_dutch_auction(ctx, token_size)
+    }
+
+    /// Cancel a bundle listing, closing it and revoking the token delegates.
+    pub fn cancel_bundle_listing<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelBundleListing<'info>>,
+    ) -> Result<()> {
+        cancel::cancel_bundle_listing(ctx)
+    }
+
+    /// Cancel a delegated bid as the buyer, closing its trade state and lowering their delegated allowance.
+    pub fn cancel_delegated_bid<'info>(
+        ctx: Context<'_, '_, '_, 'info, CancelDelegatedBid<'info>>,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        cancel::cancel_delegated_bid(ctx, buyer_price, token_size)
+    }
+
     /// Deposit `amount` into the escrow payment account for your specific wallet.
     pub fn deposit<'info>(
         ctx: Context<'_, '_, '_, 'info, Deposit<'info>>,
@@ -450,6 +521,74 @@ pub mod auction_house {
         )
     }
 
+    /// Fill one token of a collection bid with a verified member of the collection listed at the bid price.
+    pub fn execute_collection_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteCollectionSale<'info>>,
+        escrow_payment_bump: u8,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        quantity: u64,
+    ) -> Result<()> {
+        execute_sale::execute_collection_sale(
+            ctx,
+            escrow_payment_bump,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            quantity,
+        )
+    }
+
+    /// Execute a sale of a Dutch-auction listing at its current price to a bid at or above it.
+    pub fn execute_dutch_auction_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteDutchAuctionSale<'info>>,
+        escrow_payment_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::execute_dutch_auction_sale(
+            ctx,
+            escrow_payment_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
+    /// Execute the sale of every token in a bundle listing to a signing buyer, paying royalties per token.
+    pub fn execute_bundle_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteBundleSale<'info>>,
+        escrow_payment_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+    ) -> Result<()> {
+        execute_sale::execute_bundle_sale(
+            ctx,
+            escrow_payment_bump,
+            program_as_signer_bump,
+            buyer_price,
+        )
+    }
+
+    /// Execute a sale against a delegated bid, paying out of the buyer's token account rather than the escrow.
+    pub fn execute_delegated_sale<'info>(
+        ctx: Context<'_, '_, '_, 'info, ExecuteDelegatedSale<'info>>,
+        free_trade_state_bump: u8,
+        program_as_signer_bump: u8,
+        buyer_price: u64,
+        token_size: u64,
+    ) -> Result<()> {
+        execute_sale::execute_delegated_sale(
+            ctx,
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price,
+            token_size,
+        )
+    }
+
     pub fn execute_partial_sale<'info>(
         ctx: Context<'_, '_, '_, 'info, ExecutePartialSale<'info>>,
         escrow_payment_bump: u8,
@@ -546,6 +685,39 @@ pub mod auction_house {
         )
     }
 
+    /// List `token_size` tokens with a price declining from `start_price` to `end_price` between `start_time` and `end_time`, stored in a `listing_config` account.
+    pub fn dutch_auction_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, DutchAuctionSell<'info>>,
+        program_as_signer_bump: u8,
+        token_size: u64,
+        start_price: u64,
+        end_price: u64,
+        start_time: i64,
+        end_time: i64,
+        decay: PriceDecay,
+    ) -> Result<()> {
+        sell::dutch_auction_sell(
+            ctx,
+            program_as_signer_bump,
+            token_size,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            decay,
+        )
+    }
+
+    /// List up to eight tokens sold together for `price`, with `weights` splitting the price between them for royalties.
+    pub fn bundle_sell<'info>(
+        ctx: Context<'_, '_, '_, 'info, BundleSell<'info>>,
+        program_as_signer_bump: u8,
+        price: u64,
+        weights: Vec<u16>,
+    ) -> Result<()> {
+        sell::bundle_sell(ctx, program_as_signer_bump, price, weights)
+    }
+
     /// Withdraw `amount` from the escrow payment account for your specific wallet.
     pub fn withdraw<'info>(
         ctx: Context<'_, '_, '_, 'info, Withdraw<'info>>,
@@ -652,8 +824,9 @@ pub mod auction_house {
 #[derive(Accounts)]
 #[instruction(bump: u8, fee_payer_bump: u8, treasury_bump: u8)]
 pub struct CreateAuctionHouse<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Validated in create_auction_house.
+    /// Treasury mint account, either native SOL mint or a mint of either token program.
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
     #[account(mut)]
@@ -700,8 +873,9 @@ pub struct CreateAuctionHouse<'info> {
 /// Accounts for the [`update_auction_house` handler](auction_house/fn.update_auction_house.html).
 #[derive(Accounts)]
 pub struct UpdateAuctionHouse<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Validated in update_auction_house.
+    /// Treasury mint account, either native SOL mint or a mint of either token program.
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Key paying SOL fees for setting up the Auction House.
     pub payer: Signer<'info>,
@@ -740,8 +914,9 @@ pub struct UpdateAuctionHouse<'info> {
 /// Accounts for the [`withdraw_from_treasury` handler](auction_house/fn.withdraw_from_treasury.html).
 #[derive(Accounts)]
 pub struct WithdrawFromTreasury<'info> {
-    /// Treasury mint account, either native SOL mint or a SPL token mint.
-    pub treasury_mint: Account<'info, Mint>,
+    /// CHECK: Validated in withdraw_from_treasury.
+    /// Treasury mint account, either native SOL mint or a mint of either token program.
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// Authority key for the Auction House.
     pub authority: Signer<'info>,
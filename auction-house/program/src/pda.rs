@@ -96,6 +96,108 @@ pub fn find_public_bid_trade_state_address(
     )
 }
 
+/// Return collection bid trade state `Pubkey` address and bump seed.
+pub fn find_collection_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    collection: &Pubkey,
+    price: u64,
+    quantity: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            COLLECTION_BID.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            treasury_mint.as_ref(),
+            collection.as_ref(),
+            &price.to_le_bytes(),
+            &quantity.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return delegated bid trade state `Pubkey` address and bump seed.
+pub fn find_delegated_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            DELEGATED_BID.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return the `Pubkey` address and bump seed of the ledger of the allowance a payment account owes to open delegated bids.
+pub fn find_delegated_allowance_address(payment_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            DELEGATED_ALLOWANCE.as_bytes(),
+            payment_account.as_ref(),
+        ],
+        &id(),
+    )
+}
+
+/// Return Dutch-auction listing config `Pubkey` address and bump seed.
+pub fn find_listing_config_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    token_mint: &Pubkey,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            LISTING_CONFIG.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            token_mint.as_ref(),
+            &token_size.to_le_bytes(),
+        ],
+        &id(),
+    )
+}
+
+/// Return bundle listing `Pubkey` address and bump seed, keyed by the mint of the first token in the bundle.
+pub fn find_bundle_listing_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    first_token_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            BUNDLE_LISTING.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            first_token_mint.as_ref(),
+        ],
+        &id(),
+    )
+}
+
 /// Return bid receipt `Pubkey` address and bump seed.
 pub fn find_bid_receipt_address(trade_state: &Pubkey) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -452,3 +452,315 @@ fn sell_logic<'info>(
 
     Ok(())
 }
+
+/// Accounts for the [`dutch_auction_sell` handler](auction_house/fn.dutch_auction_sell.html).
+#[derive(Accounts)]
+#[instruction(program_as_signer_bump: u8, token_size: u64)]
+pub struct DutchAuctionSell<'info> {
+    /// User wallet account, which pays for the listing config.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// SPL token account containing token for sale.
+    #[account(mut)]
+    pub token_account: Box<Account<'info, TokenAccount>>,
+
+    /// CHECK: Verified through CPI
+    /// Metaplex metadata account decorating SPL mint account.
+    pub metadata: UncheckedAccount<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Listing config PDA account holding the price schedule of the listing.
+    #[account(
+        init,
+        payer = wallet,
+        space = LISTING_CONFIG_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            LISTING_CONFIG.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_account.key().as_ref(),
+            token_account.mint.as_ref(),
+            &token_size.to_le_bytes()
+        ],
+        bump
+    )]
+    pub listing_config: Box<Account<'info, ListingConfig>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// List `token_size` tokens at a price declining from `start_price` to `end_price` between `start_time` and `end_time`, approving the program as the token delegate.
+#[allow(clippy::too_many_arguments)]
+pub fn dutch_auction_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, DutchAuctionSell<'info>>,
+    program_as_signer_bump: u8,
+    token_size: u64,
+    start_price: u64,
+    end_price: u64,
+    start_time: i64,
+    end_time: i64,
+    decay: PriceDecay,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_account = &ctx.accounts.token_account;
+    let metadata = &ctx.accounts.metadata;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let listing_config = &mut ctx.accounts.listing_config;
+    let token_program = &ctx.accounts.token_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    if program_as_signer_canonical_bump != program_as_signer_bump {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    if auction_house.requires_sign_off && !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    let valid_decay = match decay {
+        PriceDecay::Linear => true,
+        PriceDecay::Stepwise { interval } => interval > 0,
+    };
+    if end_price == 0 || start_price < end_price || end_time <= start_time || !valid_decay {
+        return Err(AuctionHouseError::InvalidListingConfig.into());
+    }
+
+    assert_is_ata(
+        &token_account.to_account_info(),
+        &wallet.key(),
+        &token_account.mint,
+    )?;
+    assert_metadata_valid(metadata, token_account)?;
+
+    if token_size == 0 || token_size > token_account.amount {
+        return Err(AuctionHouseError::InvalidTokenAmount.into());
+    }
+
+    invoke(
+        &approve(
+            &token_program.key(),
+            &token_account.key(),
+            &program_as_signer.key(),
+            &wallet.key(),
+            &[],
+            token_size,
+        )
+        .unwrap(),
+        &[
+            token_program.to_account_info(),
+            token_account.to_account_info(),
+            program_as_signer.to_account_info(),
+            wallet.to_account_info(),
+        ],
+    )?;
+
+    listing_config.seller = wallet.key();
+    listing_config.auction_house = auction_house.key();
+    listing_config.token_account = token_account.key();
+    listing_config.token_mint = token_account.mint;
+    listing_config.token_size = token_size;
+    listing_config.start_price = start_price;
+    listing_config.end_price = end_price;
+    listing_config.start_time = start_time;
+    listing_config.end_time = end_time;
+    listing_config.decay = decay;
+    listing_config.bump = *ctx
+        .bumps
+        .get("listing_config")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
+
+/// Accounts for the [`bundle_sell` handler](auction_house/fn.bundle_sell.html).
+#[derive(Accounts)]
+pub struct BundleSell<'info> {
+    /// User wallet account, which pays for the bundle listing.
+    #[account(mut)]
+    pub wallet: Signer<'info>,
+
+    /// CHECK: Checked against the first token of the bundle in bundle_sell.
+    /// Mint of the first token in the bundle, which keys the bundle listing.
+    pub token_mint: UncheckedAccount<'info>,
+
+    /// CHECK: Verified through CPI
+    /// Auction House authority account.
+    pub authority: UncheckedAccount<'info>,
+
+    /// Auction House instance PDA account.
+    #[account(
+        seeds = [
+            PREFIX.as_bytes(),
+            auction_house.creator.as_ref(),
+            auction_house.treasury_mint.as_ref()
+        ],
+        bump=auction_house.bump,
+        has_one=authority
+    )]
+    pub auction_house: Box<Account<'info, AuctionHouse>>,
+
+    /// Bundle listing PDA account recording the tokens sold together and their price.
+    #[account(
+        init,
+        payer = wallet,
+        space = BUNDLE_LISTING_SIZE,
+        seeds = [
+            PREFIX.as_bytes(),
+            BUNDLE_LISTING.as_bytes(),
+            wallet.key().as_ref(),
+            auction_house.key().as_ref(),
+            token_mint.key().as_ref()
+        ],
+        bump
+    )]
+    pub bundle_listing: Box<Account<'info, BundleListing>>,
+
+    /// CHECK: Not dangerous. Account seeds checked in constraint.
+    #[account(seeds=[PREFIX.as_bytes(), SIGNER.as_bytes()], bump)]
+    pub program_as_signer: UncheckedAccount<'info>,
+
+    pub token_program: Program<'info, Token>,
+    pub system_program: Program<'info, System>,
+    pub rent: Sysvar<'info, Rent>,
+}
+
+/// List a bundle of tokens sold together for `price`, approving the program as the delegate of each token.
+/// The token account and metadata of every token are passed in pairs in remaining accounts, in the order of `weights`.
+/// As with single listings, a bundle listed at a price of 0 only sells when the auction house or the seller signs the sale.
+pub fn bundle_sell<'info>(
+    ctx: Context<'_, '_, '_, 'info, BundleSell<'info>>,
+    program_as_signer_bump: u8,
+    price: u64,
+    weights: Vec<u16>,
+) -> Result<()> {
+    let wallet = &ctx.accounts.wallet;
+    let token_mint = &ctx.accounts.token_mint;
+    let authority = &ctx.accounts.authority;
+    let auction_house = &ctx.accounts.auction_house;
+    let token_program = &ctx.accounts.token_program;
+    let program_as_signer = &ctx.accounts.program_as_signer;
+
+    // If it has an auctioneer authority delegated must use auctioneer_* handler.
+    if auction_house.has_auctioneer && auction_house.scopes[AuthorityScope::Sell as usize] {
+        return Err(AuctionHouseError::MustUseAuctioneerHandler.into());
+    }
+
+    let program_as_signer_canonical_bump = *ctx
+        .bumps
+        .get("program_as_signer")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+    if program_as_signer_canonical_bump != program_as_signer_bump {
+        return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
+    }
+
+    if auction_house.requires_sign_off && !authority.is_signer {
+        return Err(AuctionHouseError::CannotTakeThisActionWithoutAuctionHouseSignOff.into());
+    }
+
+    if weights.len() < 2 || weights.len() > MAX_BUNDLE_ITEMS {
+        return Err(AuctionHouseError::InvalidBundleSize.into());
+    }
+    if ctx.remaining_accounts.len() != weights.len() * 2 || weights.iter().all(|w| *w == 0) {
+        return Err(AuctionHouseError::InvalidBundleWeights.into());
+    }
+
+    let mut items: Vec<BundleItem> = Vec::with_capacity(weights.len());
+    for (accounts, weight) in ctx.remaining_accounts.chunks(2).zip(weights) {
+        let token_account_info = &accounts[0];
+        let metadata = &accounts[1];
+
+        let token_account: Account<TokenAccount> = Account::try_from(token_account_info)?;
+        assert_is_ata(token_account_info, &wallet.key(), &token_account.mint)?;
+        assert_derivation(
+            &mpl_token_metadata::id(),
+            metadata,
+            &[
+                mpl_token_metadata::state::PREFIX.as_bytes(),
+                mpl_token_metadata::id().as_ref(),
+                token_account.mint.as_ref(),
+            ],
+        )?;
+        if metadata.data_is_empty() {
+            return Err(AuctionHouseError::MetadataDoesntExist.into());
+        }
+        if token_account.amount < 1 {
+            return Err(AuctionHouseError::InvalidTokenAmount.into());
+        }
+        if items
+            .iter()
+            .any(|item| item.token_mint == token_account.mint)
+        {
+            return Err(AuctionHouseError::PublicKeysShouldBeUnique.into());
+        }
+
+        invoke(
+            &approve(
+                &token_program.key(),
+                &token_account_info.key(),
+                &program_as_signer.key(),
+                &wallet.key(),
+                &[],
+                1,
+            )
+            .unwrap(),
+            &[
+                token_program.to_account_info(),
+                token_account_info.clone(),
+                program_as_signer.to_account_info(),
+                wallet.to_account_info(),
+            ],
+        )?;
+
+        items.push(BundleItem {
+            token_account: token_account_info.key(),
+            token_mint: token_account.mint,
+            weight,
+        });
+    }
+    assert_keys_equal(items[0].token_mint, token_mint.key())?;
+
+    let bundle_listing = &mut ctx.accounts.bundle_listing;
+    bundle_listing.seller = wallet.key();
+    bundle_listing.auction_house = auction_house.key();
+    bundle_listing.price = price;
+    bundle_listing.items = items;
+    bundle_listing.bump = *ctx
+        .bumps
+        .get("bundle_listing")
+        .ok_or(AuctionHouseError::BumpSeedNotInHashMap)?;
+
+    Ok(())
+}
@@ -1,6 +1,6 @@
 use anchor_lang::{prelude::*, AnchorDeserialize, AnchorSerialize};
 
-use crate::constants::*;
+use crate::{constants::*, errors::AuctionHouseError};
 
 #[account]
 pub struct AuctionHouse {
@@ -41,3 +41,95 @@ pub enum AuthorityScope {
     Cancel = 5,
     Withdraw = 6,
 }
+
+/// How the price of a Dutch-auction listing declines between its start and end time.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub enum PriceDecay {
+    /// The price declines continuously with every second.
+    Linear,
+    /// The price declines once per `interval` seconds.
+    Stepwise { interval: i64 },
+}
+
+#[account]
+pub struct ListingConfig {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub token_account: Pubkey,
+    pub token_mint: Pubkey,
+    pub token_size: u64,
+    pub start_price: u64,
+    pub end_price: u64,
+    pub start_time: i64,
+    pub end_time: i64,
+    pub decay: PriceDecay,
+    pub bump: u8,
+}
+
+impl ListingConfig {
+    /// Price of the listing at unix timestamp `now`, which stays at `end_price` once `end_time` has passed.
+    pub fn current_price(&self, now: i64) -> Result<u64> {
+        if now < self.start_time {
+            return Err(AuctionHouseError::ListingNotStarted.into());
+        }
+        if now >= self.end_time {
+            return Ok(self.end_price);
+        }
+
+        let elapsed = now - self.start_time;
+        let elapsed = match self.decay {
+            PriceDecay::Linear => elapsed,
+            PriceDecay::Stepwise { interval } => elapsed - elapsed % interval,
+        };
+        let duration = self.end_time - self.start_time;
+        let drop = (self.start_price - self.end_price) as u128 * elapsed as u128 / duration as u128;
+
+        Ok(self.start_price - drop as u64)
+    }
+}
+
+/// A token of a bundle listing and its weight in splitting the bundle price for royalties.
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, Debug)]
+pub struct BundleItem {
+    pub token_account: Pubkey,
+    pub token_mint: Pubkey,
+    pub weight: u16,
+}
+
+#[account]
+pub struct BundleListing {
+    pub seller: Pubkey,
+    pub auction_house: Pubkey,
+    pub price: u64,
+    pub items: Vec<BundleItem>,
+    pub bump: u8,
+}
+
+impl BundleListing {
+    /// Share of the bundle price attributed to each item in proportion to its weight, with the rounding dust on the last item.
+    pub fn item_prices(&self) -> Result<Vec<u64>> {
+        let total_weight = self
+            .items
+            .iter()
+            .map(|item| item.weight as u128)
+            .sum::<u128>();
+        if total_weight == 0 {
+            return Err(AuctionHouseError::InvalidBundleWeights.into());
+        }
+
+        let mut remaining = self.price;
+        let mut prices = Vec::with_capacity(self.items.len());
+        for (i, item) in self.items.iter().enumerate() {
+            let item_price = if i == self.items.len() - 1 {
+                remaining
+            } else {
+                (self.price as u128 * item.weight as u128 / total_weight) as u64
+            };
+            remaining = remaining
+                .checked_sub(item_price)
+                .ok_or(AuctionHouseError::NumericalOverflow)?;
+            prices.push(item_price);
+        }
+        Ok(prices)
+    }
+}
@@ -5,19 +5,25 @@ use crate::{
 use anchor_lang::{
     prelude::*,
     solana_program::{
+        instruction::{AccountMeta, Instruction},
         program::invoke_signed,
         program_memory::sol_memcmp,
         program_option::COption,
         program_pack::{IsInitialized, Pack},
         pubkey::PUBKEY_BYTES,
-        system_instruction,
+        system_instruction, system_program, sysvar,
     },
 };
-use anchor_spl::token::{Mint, Token, TokenAccount};
+use anchor_spl::token::{Mint, TokenAccount};
 use arrayref::array_ref;
 use metaplex_token_metadata::state::Metadata;
+use mpl_token_metadata::state::TokenMetadataAccount;
 use spl_associated_token_account::get_associated_token_address;
-use spl_token::{instruction::initialize_account2, state::Account as SplAccount};
+use spl_token::state::Account as SplAccount;
+use spl_token_2022::{
+    extension::{transfer_fee::TransferFeeConfig, ExtensionType, StateWithExtensions},
+    state::{Account as Account2022, Mint as Mint2022},
+};
 use std::{convert::TryInto, slice::Iter};
 
 pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> Result<SplAccount> {
@@ -29,6 +35,57 @@ pub fn assert_is_ata(ata: &AccountInfo, wallet: &Pubkey, mint: &Pubkey) -> Resul
     Ok(ata_account)
 }
 
+/// Assert that `treasury_mint` is an initialized mint of either token program.
+pub fn assert_treasury_mint(treasury_mint: &AccountInfo) -> Result<()> {
+    if *treasury_mint.owner != spl_token::id() && *treasury_mint.owner != spl_token_2022::id() {
+        return err!(AuctionHouseError::IncorrectOwner);
+    }
+    let data = treasury_mint.try_borrow_data()?;
+    match StateWithExtensions::<Mint2022>::unpack(&data) {
+        Ok(mint) if mint.base.is_initialized => Ok(()),
+        _ => err!(AuctionHouseError::UninitializedAccount),
+    }
+}
+
+/// Unpack the base state of a token account of either token program. Token-2022 accounts share the legacy layout, followed by their extensions.
+pub fn unpack_token_account(account: &AccountInfo) -> Result<SplAccount> {
+    let data = account.try_borrow_data()?;
+    if data.len() < SplAccount::LEN {
+        return err!(AuctionHouseError::UninitializedAccount);
+    }
+    let token_account = SplAccount::unpack_from_slice(&data[..SplAccount::LEN])?;
+    if !token_account.is_initialized() {
+        return err!(AuctionHouseError::UninitializedAccount);
+    }
+    Ok(token_account)
+}
+
+/// Assert that `ata` is the associated token account of `wallet` for the treasury mint, under whichever token program owns the mint.
+pub fn assert_is_treasury_ata(
+    ata: &AccountInfo,
+    wallet: &Pubkey,
+    treasury_mint: &AccountInfo,
+) -> Result<SplAccount> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return assert_is_ata(ata, wallet, treasury_mint.key);
+    }
+
+    assert_owned_by(ata, &spl_token_2022::id())?;
+    let ata_account = unpack_token_account(ata)?;
+    assert_keys_equal(ata_account.owner, *wallet)?;
+    assert_keys_equal(ata_account.mint, *treasury_mint.key)?;
+    let (ata_key, _) = Pubkey::find_program_address(
+        &[
+            wallet.as_ref(),
+            spl_token_2022::id().as_ref(),
+            treasury_mint.key.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    );
+    assert_keys_equal(ata_key, *ata.key)?;
+    Ok(ata_account)
+}
+
 pub fn make_ata<'a>(
     ata: AccountInfo<'a>,
     wallet: AccountInfo<'a>,
@@ -48,12 +105,23 @@ pub fn make_ata<'a>(
         &[]
     };
 
+    // The token program is passed explicitly so the ATA can belong to either token program.
+    let create_ata = Instruction {
+        program_id: spl_associated_token_account::id(),
+        accounts: vec![
+            AccountMeta::new(*fee_payer.key, true),
+            AccountMeta::new(*ata.key, false),
+            AccountMeta::new_readonly(*wallet.key, false),
+            AccountMeta::new_readonly(*mint.key, false),
+            AccountMeta::new_readonly(system_program::id(), false),
+            AccountMeta::new_readonly(*token_program.key, false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: vec![],
+    };
+
     invoke_signed(
-        &spl_associated_token_account::create_associated_token_account(
-            fee_payer.key,
-            wallet.key,
-            mint.key,
-        ),
+        &create_ata,
         &[
             ata,
             wallet,
@@ -90,6 +158,19 @@ pub fn assert_metadata_valid<'a>(
     Ok(())
 }
 
+/// Assert that the metadata belongs to a verified member of `collection`.
+pub fn assert_verified_collection_member(
+    metadata_info: &AccountInfo,
+    collection: &Pubkey,
+) -> Result<()> {
+    let metadata: mpl_token_metadata::state::Metadata =
+        mpl_token_metadata::state::Metadata::from_account_info(metadata_info)?;
+    match metadata.collection {
+        Some(member_of) if member_of.verified && member_of.key == *collection => Ok(()),
+        _ => err!(AuctionHouseError::CollectionNotVerified),
+    }
+}
+
 pub fn get_fee_payer<'a, 'b>(
     authority: &UncheckedAccount,
     auction_house: &anchor_lang::prelude::Account<AuctionHouse>,
@@ -263,11 +344,135 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> Result<()> {
     }
 }
 
+/// Return the token program of the treasury mint. Token-2022 treasuries need the Token-2022 program passed in `remaining_accounts`, after any creator accounts.
+pub fn get_treasury_token_program<'a>(
+    treasury_mint: &AccountInfo<'a>,
+    token_program: &AccountInfo<'a>,
+    remaining_accounts: &[AccountInfo<'a>],
+) -> Result<AccountInfo<'a>> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return Ok(token_program.clone());
+    }
+    remaining_accounts
+        .iter()
+        .find(|account| *account.key == spl_token_2022::id())
+        .cloned()
+        .ok_or_else(|| AuctionHouseError::MissingTreasuryTokenProgram.into())
+}
+
+/// Transfer fee the treasury mint withholds when moving `amount` in the current epoch, zero for mints without the transfer-fee extension.
+pub fn get_transfer_fee(treasury_mint: &AccountInfo, amount: u64) -> Result<u64> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return Ok(0);
+    }
+    let data = treasury_mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint2022>::unpack(&data)?;
+    match mint.get_extension::<TransferFeeConfig>() {
+        Ok(transfer_fee_config) => Ok(transfer_fee_config
+            .calculate_epoch_fee(Clock::get()?.epoch, amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?),
+        Err(_) => Ok(0),
+    }
+}
+
+/// Move `amount` of the treasury mint with `transfer_checked`, which both token programs accept.
+/// Returns the amount the destination received once the mint's transfer fee is withheld.
+pub fn transfer_treasury_tokens<'a>(
+    token_program: &AccountInfo<'a>,
+    source: &AccountInfo<'a>,
+    destination: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    authority: &AccountInfo<'a>,
+    signer_seeds: &[&[u8]],
+    amount: u64,
+) -> Result<u64> {
+    let as_arr = [signer_seeds];
+    let seeds: &[&[&[u8]]] = if !signer_seeds.is_empty() {
+        &as_arr
+    } else {
+        &[]
+    };
+    let decimals = {
+        let data = treasury_mint.try_borrow_data()?;
+        StateWithExtensions::<Mint2022>::unpack(&data)?
+            .base
+            .decimals
+    };
+    let transfer_fee = get_transfer_fee(treasury_mint, amount)?;
+
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program.key,
+            source.key,
+            treasury_mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[
+            source.clone(),
+            treasury_mint.clone(),
+            destination.clone(),
+            authority.clone(),
+            token_program.clone(),
+        ],
+        seeds,
+    )?;
+
+    let received = amount
+        .checked_sub(transfer_fee)
+        .ok_or(AuctionHouseError::NumericalOverflow)?;
+    if transfer_fee > 0 {
+        msg!(
+            "Transferred {} to {}, {} withheld as transfer fee",
+            received,
+            destination.key,
+            transfer_fee
+        );
+    }
+    Ok(received)
+}
+
+/// Top up the token escrow to `amount` from `payment_account`.
+/// The escrow has to receive the whole shortfall, so for mints with a transfer fee the buyer deposits the fee on top first.
+pub fn top_up_token_escrow<'a>(
+    token_program: &AccountInfo<'a>,
+    payment_account: &AccountInfo<'a>,
+    escrow_payment_account: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
+    transfer_authority: &AccountInfo<'a>,
+    amount: u64,
+) -> Result<()> {
+    let escrow = unpack_token_account(escrow_payment_account)?;
+    if escrow.amount < amount {
+        let diff = amount
+            .checked_sub(escrow.amount)
+            .ok_or(AuctionHouseError::NumericalOverflow)?;
+        let received = transfer_treasury_tokens(
+            token_program,
+            payment_account,
+            escrow_payment_account,
+            treasury_mint,
+            transfer_authority,
+            &[],
+            diff,
+        )?;
+        if received < diff {
+            return err!(AuctionHouseError::InsufficientFunds);
+        }
+    }
+    Ok(())
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn pay_auction_house_fees<'a>(
     auction_house: &anchor_lang::prelude::Account<'a, AuctionHouse>,
     auction_house_treasury: &AccountInfo<'a>,
     escrow_payment_account: &AccountInfo<'a>,
+    payment_account_owner: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     token_program: &AccountInfo<'a>,
     system_program: &AccountInfo<'a>,
     signer_seeds: &[&[u8]],
@@ -281,23 +486,20 @@ pub fn pay_auction_house_fees<'a>(
         .checked_div(10000)
         .ok_or(AuctionHouseError::NumericalOverflow)? as u64;
     if !is_native {
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                escrow_payment_account.key,
-                auction_house_treasury.key,
-                &auction_house.key(),
-                &[],
-                total_fee,
-            )?,
-            &[
-                escrow_payment_account.clone(),
-                auction_house_treasury.clone(),
-                token_program.clone(),
-                auction_house.to_account_info(),
-            ],
-            &[signer_seeds],
+        let received = transfer_treasury_tokens(
+            token_program,
+            escrow_payment_account,
+            auction_house_treasury,
+            treasury_mint,
+            payment_account_owner,
+            signer_seeds,
+            total_fee,
         )?;
+        msg!(
+            "Auction house fee of {} paid, {} received by the treasury",
+            total_fee,
+            received
+        );
     } else {
         invoke_signed(
             &system_instruction::transfer(
@@ -316,12 +518,24 @@ pub fn pay_auction_house_fees<'a>(
     Ok(total_fee)
 }
 
+/// Size of a token account of the treasury mint, including the extensions Token-2022 requires for the mint.
+pub fn get_treasury_account_len(treasury_mint: &AccountInfo) -> Result<usize> {
+    if *treasury_mint.owner != spl_token_2022::id() {
+        return Ok(SplAccount::LEN);
+    }
+    let data = treasury_mint.try_borrow_data()?;
+    let mint = StateWithExtensions::<Mint2022>::unpack(&data)?;
+    let extensions =
+        ExtensionType::get_required_init_account_extensions(&mint.get_extension_types()?);
+    Ok(ExtensionType::get_account_len::<Account2022>(&extensions))
+}
+
 pub fn create_program_token_account_if_not_present<'a>(
     payment_account: &UncheckedAccount<'a>,
     system_program: &Program<'a, System>,
     fee_payer: &AccountInfo<'a>,
-    token_program: &Program<'a, Token>,
-    treasury_mint: &anchor_lang::prelude::Account<'a, Mint>,
+    token_program: &AccountInfo<'a>,
+    treasury_mint: &AccountInfo<'a>,
     owner: &AccountInfo<'a>,
     rent: &Sysvar<'a, Rent>,
     signer_seeds: &[&[u8]],
@@ -335,22 +549,22 @@ pub fn create_program_token_account_if_not_present<'a>(
             &rent.to_account_info(),
             system_program,
             fee_payer,
-            spl_token::state::Account::LEN,
+            get_treasury_account_len(treasury_mint)?,
             fee_seeds,
             signer_seeds,
         )?;
         msg!("This.");
         invoke_signed(
-            &initialize_account2(
+            &spl_token_2022::instruction::initialize_account2(
                 token_program.key,
                 &payment_account.key(),
-                &treasury_mint.key(),
+                treasury_mint.key,
                 &owner.key(),
             )
             .unwrap(),
             &[
-                token_program.to_account_info(),
-                treasury_mint.to_account_info(),
+                token_program.clone(),
+                treasury_mint.clone(),
                 payment_account.to_account_info(),
                 rent.to_account_info(),
                 owner.clone(),
@@ -419,29 +633,27 @@ pub fn pay_creator_fees<'a>(
                             fee_payer_seeds,
                         )?;
                     }
-                    assert_is_ata(
+                    assert_is_treasury_ata(
                         current_creator_token_account_info,
                         current_creator_info.key,
-                        &treasury_mint.key(),
+                        treasury_mint,
                     )?;
                     if creator_fee > 0 {
-                        invoke_signed(
-                            &spl_token::instruction::transfer(
-                                token_program.key,
-                                escrow_payment_account.key,
-                                current_creator_token_account_info.key,
-                                payment_account_owner.key,
-                                &[],
-                                creator_fee,
-                            )?,
-                            &[
-                                escrow_payment_account.clone(),
-                                current_creator_token_account_info.clone(),
-                                token_program.clone(),
-                                payment_account_owner.clone(),
-                            ],
-                            &[signer_seeds],
+                        let received = transfer_treasury_tokens(
+                            token_program,
+                            escrow_payment_account,
+                            current_creator_token_account_info,
+                            treasury_mint,
+                            payment_account_owner,
+                            signer_seeds,
+                            creator_fee,
                         )?;
+                        msg!(
+                            "Royalty of {} paid, {} received by creator {}",
+                            creator_fee,
+                            received,
+                            creator.address
+                        );
                     }
                 } else if creator_fee > 0 {
                     invoke_signed(
@@ -491,6 +703,23 @@ pub fn get_delegate_from_token_account(token_account_info: &AccountInfo) -> Resu
     }
 }
 
+/// Allowance a payment account owes to its open delegated bids, read from its ledger, or 0 before its first bid.
+pub fn get_delegated_allowance(ledger: &AccountInfo) -> Result<u64> {
+    // Ledger layout:   bump(1), allowance(8)
+    if ledger.data_is_empty() {
+        return Ok(0);
+    }
+    let data = ledger.try_borrow_data()?;
+    Ok(u64::from_le_bytes(*array_ref![data, 1, 8]))
+}
+
+/// Record the allowance a payment account owes to its open delegated bids in its ledger.
+pub fn set_delegated_allowance(ledger: &AccountInfo, allowance: u64) -> Result<()> {
+    let mut data = ledger.try_borrow_mut_data()?;
+    data[1..DELEGATED_ALLOWANCE_SIZE].copy_from_slice(&allowance.to_le_bytes());
+    Ok(())
+}
+
 /// Create account almost from scratch, lifted from
 /// <https://github.com/solana-labs/solana-program-library/blob/7d4873c61721aca25464d42cc5ef651a7923ca79/associated-token-account/program/src/processor.rs#L51-L98>
 #[inline(always)]
@@ -28,8 +28,9 @@ pub struct Withdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in withdraw_logic.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -108,7 +109,12 @@ pub fn withdraw<'info>(
         return Err(AuctionHouseError::BumpSeedNotInHashMap.into());
     }
 
-    withdraw_logic(ctx.accounts, escrow_payment_bump, amount)
+    withdraw_logic(
+        ctx.accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 /// Accounts for the [`auctioneer_withdraw` handler](auction_house/fn.auctioneer_withdraw.html).
@@ -137,8 +143,9 @@ pub struct AuctioneerWithdraw<'info> {
     )]
     pub escrow_payment_account: UncheckedAccount<'info>,
 
+    /// CHECK: Validated in withdraw_logic.
     /// Auction House instance treasury mint account.
-    pub treasury_mint: Box<Account<'info, Mint>>,
+    pub treasury_mint: UncheckedAccount<'info>,
 
     /// CHECK: Validated in withdraw_logic.
     /// Auction House instance authority account.
@@ -224,12 +231,19 @@ pub fn auctioneer_withdraw<'info>(
 
     let mut accounts: Withdraw<'info> = (*ctx.accounts).clone().into();
 
-    withdraw_logic(&mut accounts, escrow_payment_bump, amount)
+    withdraw_logic(
+        &mut accounts,
+        ctx.remaining_accounts,
+        escrow_payment_bump,
+        amount,
+    )
 }
 
 #[allow(clippy::needless_lifetimes)]
+/// Token-2022 treasuries need the Token-2022 program in `remaining_accounts`, and the receipt account receives `amount` net of the mint's transfer fee.
 fn withdraw_logic<'info>(
     accounts: &mut Withdraw<'info>,
+    remaining_accounts: &[AccountInfo<'info>],
     escrow_payment_bump: u8,
     amount: u64,
 ) -> Result<()> {
@@ -282,9 +296,12 @@ fn withdraw_logic<'info>(
         &seeds,
     )?;
 
+    assert_treasury_mint(treasury_mint)?;
     let is_native = treasury_mint.key() == spl_token::native_mint::id();
 
     if !is_native {
+        let treasury_token_program =
+            get_treasury_token_program(treasury_mint, token_program, remaining_accounts)?;
         if receipt_account.data_is_empty() {
             make_ata(
                 receipt_account.to_account_info(),
@@ -292,41 +309,28 @@ fn withdraw_logic<'info>(
                 treasury_mint.to_account_info(),
                 fee_payer.to_account_info(),
                 ata_program.to_account_info(),
-                token_program.to_account_info(),
+                treasury_token_program.clone(),
                 system_program.to_account_info(),
                 rent.to_account_info(),
                 fee_seeds,
             )?;
         }
 
-        let rec_acct = assert_is_ata(
-            &receipt_account.to_account_info(),
-            &wallet.key(),
-            &treasury_mint.key(),
-        )?;
+        let rec_acct = assert_is_treasury_ata(receipt_account, &wallet.key(), treasury_mint)?;
 
         // make sure you cant get rugged
         if rec_acct.delegate.is_some() {
             return Err(AuctionHouseError::BuyerATACannotHaveDelegate.into());
         }
 
-        assert_is_ata(receipt_account, &wallet.key(), &treasury_mint.key())?;
-        invoke_signed(
-            &spl_token::instruction::transfer(
-                token_program.key,
-                &escrow_payment_account.key(),
-                &receipt_account.key(),
-                &auction_house.key(),
-                &[],
-                amount,
-            )?,
-            &[
-                escrow_payment_account.to_account_info(),
-                receipt_account.to_account_info(),
-                token_program.to_account_info(),
-                auction_house.to_account_info(),
-            ],
-            &[&ah_seeds],
+        transfer_treasury_tokens(
+            &treasury_token_program,
+            escrow_payment_account,
+            receipt_account,
+            treasury_mint,
+            &auction_house.to_account_info(),
+            &ah_seeds,
+            amount,
         )?;
     } else {
         assert_keys_equal(receipt_account.key(), wallet.key())?;
@@ -0,0 +1,515 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::BundleListing;
+use mpl_testing_utils::solana::transfer;
+use mpl_token_metadata::state::Creator;
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+const PRICE: u64 = 1_000_000_000;
+const ROYALTY_BASIS_POINTS: u16 = 1000;
+
+/// Creates an NFT owned by `owner`, or by a new wallet, with `creator` taking all of its royalties.
+async fn create_bundle_item(
+    context: &mut ProgramTestContext,
+    owner: Option<&Keypair>,
+    creator: &Pubkey,
+) -> Metadata {
+    let mut item = Metadata::new();
+    match owner {
+        Some(owner) => item.token = Keypair::from_bytes(&owner.to_bytes()).unwrap(),
+        None => airdrop(context, &item.token.pubkey(), TEN_SOL)
+            .await
+            .unwrap(),
+    }
+    item.create(
+        context,
+        "Bundle".to_string(),
+        "BDL".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: *creator,
+            verified: false,
+            share: 100,
+        }]),
+        ROYALTY_BASIS_POINTS,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    item
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let first_creator = Keypair::new().pubkey();
+    let second_creator = Keypair::new().pubkey();
+    let first = create_bundle_item(&mut context, None, &first_creator).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &second_creator).await;
+
+    let (sell_acc, sell_tx) = bundle_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        PRICE,
+        vec![3, 1],
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let bundle_listing_account = context
+        .banks_client
+        .get_account(sell_acc.bundle_listing)
+        .await
+        .unwrap()
+        .unwrap();
+    let bundle_listing =
+        BundleListing::try_deserialize(&mut bundle_listing_account.data.as_ref()).unwrap();
+    assert_eq!(bundle_listing.price, PRICE);
+    assert_eq!(bundle_listing.items.len(), 2);
+    assert_eq!(bundle_listing.items[1].token_mint, second.mint.pubkey());
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &first, &buyer, PRICE);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let seller_before = context
+        .banks_client
+        .get_account(first.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, sale_tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        &[vec![first_creator], vec![second_creator]],
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    // Royalties are paid on each token's weighted share of the bundle price.
+    let first_royalty = PRICE * 3 / 4 * ROYALTY_BASIS_POINTS as u64 / 10000;
+    let second_royalty = PRICE / 4 * ROYALTY_BASIS_POINTS as u64 / 10000;
+    let first_creator_account = context
+        .banks_client
+        .get_account(first_creator)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(first_creator_account.lamports, first_royalty);
+    let second_creator_account = context
+        .banks_client
+        .get_account(second_creator)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(second_creator_account.lamports, second_royalty);
+
+    let seller_after = context
+        .banks_client
+        .get_account(first.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let house_fee = (ah.seller_fee_basis_points as u64 * PRICE) / 10000;
+    assert_eq!(
+        seller_before.lamports + PRICE - first_royalty - second_royalty - house_fee
+            + bundle_listing_account.lamports,
+        seller_after.lamports
+    );
+
+    for item in [&first, &second] {
+        let buyer_token_account =
+            get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey());
+        let buyer_token = Account::unpack_from_slice(
+            context
+                .banks_client
+                .get_account(buyer_token_account)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(buyer_token.amount, 1);
+    }
+
+    let bundle_listing = context
+        .banks_client
+        .get_account(sell_acc.bundle_listing)
+        .await
+        .unwrap();
+    assert!(bundle_listing.is_none());
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_rounding_dust_goes_to_last_item() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creators = [
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+        Keypair::new().pubkey(),
+    ];
+    let first = create_bundle_item(&mut context, None, &creators[0]).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &creators[1]).await;
+    let third = create_bundle_item(&mut context, Some(&first.token), &creators[2]).await;
+
+    let (sell_acc, sell_tx) = bundle_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second, &third],
+        PRICE,
+        vec![1, 1, 1],
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &first, &buyer, PRICE);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let bundle_listing_account = context
+        .banks_client
+        .get_account(sell_acc.bundle_listing)
+        .await
+        .unwrap()
+        .unwrap();
+    let seller_before = context
+        .banks_client
+        .get_account(first.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let (_, sale_tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second, &third],
+        &[vec![creators[0]], vec![creators[1]], vec![creators[2]]],
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    // The price does not split evenly in three, so the last item carries the extra lamport.
+    let item_price = PRICE / 3;
+    let last_item_price = PRICE - 2 * item_price;
+    assert_eq!(last_item_price, item_price + 1);
+    let expected_royalties = [item_price, item_price, last_item_price]
+        .map(|price| price * ROYALTY_BASIS_POINTS as u64 / 10000);
+    for (creator, expected_royalty) in creators.iter().zip(expected_royalties) {
+        let creator_account = context
+            .banks_client
+            .get_account(*creator)
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(creator_account.lamports, expected_royalty);
+    }
+
+    let seller_after = context
+        .banks_client
+        .get_account(first.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let house_fee = (ah.seller_fee_basis_points as u64 * PRICE) / 10000;
+    assert_eq!(
+        seller_before.lamports + PRICE - expected_royalties.iter().sum::<u64>() - house_fee
+            + bundle_listing_account.lamports,
+        seller_after.lamports
+    );
+}
+
+#[tokio::test]
+async fn execute_free_bundle_sale_without_sign_off_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new().pubkey();
+    let first = create_bundle_item(&mut context, None, &creator).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &creator).await;
+
+    let (_, sell_tx) = bundle_sell(&mut context, &ahkey, &ah, &[&first, &second], 0, vec![1, 1]);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Only the buyer signs, so neither the auction house nor the seller agreed to give the bundle away.
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        &[vec![creator], vec![creator]],
+        &buyer,
+        0,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, CANNOT_MATCH_FREE_SALES);
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_wrong_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new().pubkey();
+    let first = create_bundle_item(&mut context, None, &creator).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &creator).await;
+
+    let (_, sell_tx) = bundle_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        PRICE,
+        vec![1, 1],
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &first, &buyer, PRICE);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, sale_tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        &[vec![creator], vec![creator]],
+        &buyer,
+        PRICE / 2,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, BUNDLE_PRICE_MISMATCH);
+}
+
+#[tokio::test]
+async fn execute_bundle_sale_with_transferred_item_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new().pubkey();
+    let first = create_bundle_item(&mut context, None, &creator).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &creator).await;
+
+    let (_, sell_tx) = bundle_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        PRICE,
+        vec![1, 1],
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Moving the token out keeps the delegate on the now empty token account.
+    transfer(
+        &mut context,
+        &second.mint.pubkey(),
+        &second.token,
+        &Keypair::new(),
+    )
+    .await
+    .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (_, deposit_tx) = deposit(&mut context, &ahkey, &ah, &first, &buyer, PRICE);
+    context
+        .banks_client
+        .process_transaction(deposit_tx)
+        .await
+        .unwrap();
+
+    let (_, sale_tx) = execute_bundle_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        &[vec![creator], vec![creator]],
+        &buyer,
+        PRICE,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, NOT_ENOUGH_TOKENS_AVAIL_FOR_PURCHASE);
+}
+
+#[tokio::test]
+async fn bundle_sell_single_token_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let item = create_bundle_item(&mut context, None, &Keypair::new().pubkey()).await;
+
+    let (_, sell_tx) = bundle_sell(&mut context, &ahkey, &ah, &[&item], PRICE, vec![1]);
+    let err = context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, INVALID_BUNDLE_SIZE);
+}
+
+#[tokio::test]
+async fn cancel_bundle_listing_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let creator = Keypair::new().pubkey();
+    let first = create_bundle_item(&mut context, None, &creator).await;
+    let second = create_bundle_item(&mut context, Some(&first.token), &creator).await;
+
+    let (sell_acc, sell_tx) = bundle_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &[&first, &second],
+        PRICE,
+        vec![1, 1],
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let token_accounts: Vec<Pubkey> = [&first, &second]
+        .iter()
+        .map(|item| get_associated_token_address(&first.token.pubkey(), &item.mint.pubkey()))
+        .collect();
+    let mut accounts = mpl_auction_house::accounts::CancelBundleListing {
+        wallet: first.token.pubkey(),
+        token_mint: first.mint.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        bundle_listing: sell_acc.bundle_listing,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    for token_account in &token_accounts {
+        accounts.push(AccountMeta::new(*token_account, false));
+    }
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelBundleListing {}.data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&first.token.pubkey()),
+        &[&first.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let bundle_listing = context
+        .banks_client
+        .get_account(sell_acc.bundle_listing)
+        .await
+        .unwrap();
+    assert!(bundle_listing.is_none());
+
+    for token_account in token_accounts {
+        let token = Account::unpack_from_slice(
+            context
+                .banks_client
+                .get_account(token_account)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .as_slice(),
+        )
+        .unwrap();
+        assert!(token.delegate.is_none());
+    }
+}
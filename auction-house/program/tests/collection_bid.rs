@@ -0,0 +1,606 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::constants::COLLECTION_BID_TRADE_STATE_SIZE;
+use mpl_testing_utils::utils::MasterEditionV2;
+use mpl_token_metadata::state::Collection;
+use solana_program::program_pack::Pack;
+use spl_token::state::Account;
+
+const PRICE: u64 = 100_000_000;
+
+/// Creates a collection NFT with its master edition.
+async fn create_collection(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+    let collection = Metadata::new();
+    collection
+        .create(
+            context,
+            "Collection".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    let collection_edition = MasterEditionV2::new(&collection);
+    collection_edition
+        .create_v3(context, Some(0))
+        .await
+        .unwrap();
+
+    (collection, collection_edition)
+}
+
+/// Creates a member of `collection` held by a new wallet, `member.token`, verified if `verified`.
+async fn create_member(
+    context: &mut ProgramTestContext,
+    collection: &Metadata,
+    collection_edition: &MasterEditionV2,
+    verified: bool,
+) -> Metadata {
+    let member = Metadata::new();
+    airdrop(context, &member.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    member
+        .create(
+            context,
+            "Member".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            true,
+            1,
+        )
+        .await
+        .unwrap();
+    member
+        .update_v2(
+            context,
+            "Member".to_string(),
+            "COL".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            true,
+            Some(Collection {
+                verified: false,
+                key: collection.mint.pubkey(),
+            }),
+            None,
+        )
+        .await
+        .unwrap();
+
+    if verified {
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        member
+            .verify_collection(
+                context,
+                collection.pubkey,
+                payer,
+                collection.mint.pubkey(),
+                collection_edition.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+    }
+
+    member
+}
+
+/// Creates a collection NFT and a member of it held by `member.token`, verified if `verified`.
+async fn create_collection_member(
+    context: &mut ProgramTestContext,
+    verified: bool,
+) -> (Metadata, Metadata) {
+    let (collection, collection_edition) = create_collection(context).await;
+    let member = create_member(context, &collection, &collection_edition, verified).await;
+    (collection, member)
+}
+
+#[tokio::test]
+async fn execute_collection_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, member) = create_collection_member(&mut context, true).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &member, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        2,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    let escrow = context
+        .banks_client
+        .get_account(bid_acc.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(escrow.lamports >= 2 * PRICE);
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &member,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &member.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+        2,
+    );
+    let seller_before = context
+        .banks_client
+        .get_account(member.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let seller_after = context
+        .banks_client
+        .get_account(member.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let fee_minus: u64 = PRICE - ((ah.seller_fee_basis_points as u64 * PRICE) / 10000);
+    assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &member.mint.pubkey());
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+
+    // One of the two tokens is left to fill.
+    let trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(trade_state.data.len(), COLLECTION_BID_TRADE_STATE_SIZE);
+    assert_eq!(trade_state.data[1..], 1u64.to_le_bytes());
+
+    let seller_trade_state = context
+        .banks_client
+        .get_account(sell_acc.seller_trade_state)
+        .await
+        .unwrap();
+    assert!(seller_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_collection_sale_closes_filled_bid() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, member) = create_collection_member(&mut context, true).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &member, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &member,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &member.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_collection_sale_filled_by_several_sellers() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, collection_edition) = create_collection(&mut context).await;
+    let members = [
+        create_member(&mut context, &collection, &collection_edition, true).await,
+        create_member(&mut context, &collection, &collection_edition, true).await,
+    ];
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        2,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+
+    // Each seller fills one of the two tokens of the same bid.
+    let fee_minus: u64 = PRICE - ((ah.seller_fee_basis_points as u64 * PRICE) / 10000);
+    for (filled, member) in members.iter().enumerate() {
+        let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, member, PRICE, 1);
+        context
+            .banks_client
+            .process_transaction(sell_tx)
+            .await
+            .unwrap();
+
+        let seller_before = context
+            .banks_client
+            .get_account(member.token.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        let (_, sale_tx) = execute_collection_sale(
+            &mut context,
+            &ahkey,
+            &ah,
+            &authority,
+            member,
+            &collection.mint.pubkey(),
+            &buyer.pubkey(),
+            &member.token.pubkey(),
+            &sell_acc.seller_trade_state,
+            &bid_acc.buyer_trade_state,
+            PRICE,
+            2,
+        );
+        context
+            .banks_client
+            .process_transaction(sale_tx)
+            .await
+            .unwrap();
+
+        let seller_after = context
+            .banks_client
+            .get_account(member.token.pubkey())
+            .await
+            .unwrap()
+            .unwrap();
+        assert_eq!(seller_before.lamports + fee_minus, seller_after.lamports);
+
+        let trade_state = context
+            .banks_client
+            .get_account(bid_acc.buyer_trade_state)
+            .await
+            .unwrap();
+        if filled == 0 {
+            assert_eq!(trade_state.unwrap().data[1..], 1u64.to_le_bytes());
+        } else {
+            assert!(trade_state.is_none());
+        }
+    }
+
+    for member in &members {
+        let buyer_token_account =
+            get_associated_token_address(&buyer.pubkey(), &member.mint.pubkey());
+        let buyer_token = Account::unpack_from_slice(
+            context
+                .banks_client
+                .get_account(buyer_token_account)
+                .await
+                .unwrap()
+                .unwrap()
+                .data
+                .as_slice(),
+        )
+        .unwrap();
+        assert_eq!(buyer_token.amount, 1);
+    }
+}
+
+#[tokio::test]
+async fn execute_collection_sale_signed_by_authority_only() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, member) = create_collection_member(&mut context, true).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &member, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &member,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &member.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+        1,
+    );
+
+    // Neither the seller nor the buyer signs the sale.
+    let signers =
+        &sale_tx.message.account_keys[..sale_tx.message.header.num_required_signatures as usize];
+    assert_eq!(signers, &[authority.pubkey()]);
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    // The transfer used up the seller's delegation, so the program as signer keeps no allowance.
+    let seller_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(sell_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(seller_token.amount, 0);
+    assert!(seller_token.delegate.is_none());
+
+    let buyer_token_account = get_associated_token_address(&buyer.pubkey(), &member.mint.pubkey());
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+}
+
+#[tokio::test]
+async fn execute_collection_sale_unverified_member_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, member) = create_collection_member(&mut context, false).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &member, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_collection_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &member,
+        &collection.mint.pubkey(),
+        &buyer.pubkey(),
+        &member.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+        1,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, COLLECTION_NOT_VERIFIED);
+}
+
+#[tokio::test]
+async fn cancel_collection_bid_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (collection, _) = create_collection_member(&mut context, true).await;
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let (bid_acc, bid_tx) = collection_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &collection.mint.pubkey(),
+        &buyer,
+        PRICE,
+        3,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::CancelCollectionBid {
+        wallet: buyer.pubkey(),
+        collection_mint: collection.mint.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        trade_state: bid_acc.buyer_trade_state,
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelCollectionBid {
+            buyer_price: PRICE,
+            quantity: 3,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+}
@@ -23,6 +23,7 @@ pub use std::assert_eq;
 pub const HAS_ONE_CONSTRAINT_VIOLATION: u32 = 2001;
 pub const INVALID_SEEDS: u32 = 2006;
 pub const ACCOUNT_NOT_INITIALIZED: u32 = 3012;
+pub const CANNOT_MATCH_FREE_SALES: u32 = 6017;
 pub const MISSING_AUCTIONEER_SCOPE: u32 = 6029;
 pub const NO_AUCTIONEER_PROGRAM_SET: u32 = 6031;
 pub const TOO_MANY_SCOPES: u32 = 6032;
@@ -34,6 +35,12 @@ pub const PARTIAL_BUY_PRICE_MISMATCH: u32 = 6040;
 pub const MISSING_ELEMENTS_NEEDED_FOR_PARTIAL_BUY: u32 = 6038;
 pub const AUCTIONEER_ALREADY_DELEGATED: u32 = 6041;
 pub const INSUFFICIENT_FUNDS: u32 = 6043;
+pub const COLLECTION_NOT_VERIFIED: u32 = 6044;
+pub const BID_BELOW_CURRENT_PRICE: u32 = 6049;
+pub const INVALID_BUNDLE_SIZE: u32 = 6051;
+pub const BUNDLE_PRICE_MISMATCH: u32 = 6054;
+pub const DELEGATED_BID_NATIVE_TREASURY: u32 = 6055;
+pub const INSUFFICIENT_DELEGATED_FUNDS: u32 = 6056;
 
 pub const TEN_SOL: u64 = 10_000_000_000;
 pub const ONE_SOL: u64 = 1_000_000_000;
@@ -0,0 +1,496 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::pda::{
+    find_delegated_allowance_address, find_program_as_signer_address, find_trade_state_address,
+};
+use mpl_testing_utils::solana::{create_associated_token_account, get_token_account, mint_tokens};
+
+const PRICE: u64 = 100_000_000;
+
+/// Creates an NFT held by `nft.token` and a buyer holding `balance` of the treasury mint in their ATA.
+async fn create_nft_and_buyer(
+    context: &mut ProgramTestContext,
+    treasury_mint: &Pubkey,
+    balance: u64,
+) -> (Metadata, Keypair) {
+    let nft = Metadata::new();
+    airdrop(context, &nft.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    nft.create(
+        context,
+        "Name".to_string(),
+        "SYM".to_string(),
+        "uri".to_string(),
+        None,
+        10,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let payment_account = create_associated_token_account(context, &buyer, treasury_mint)
+        .await
+        .unwrap();
+    if balance > 0 {
+        let mint_authority = context.payer.pubkey();
+        mint_tokens(
+            context,
+            treasury_mint,
+            &payment_account,
+            balance,
+            &mint_authority,
+            None,
+        )
+        .await
+        .unwrap();
+    }
+
+    (nft, buyer)
+}
+
+/// Allowance the delegated allowance ledger of `payment_account` records as owed to open bids.
+async fn ledger_allowance(context: &mut ProgramTestContext, payment_account: &Pubkey) -> u64 {
+    let (ledger, _) = find_delegated_allowance_address(payment_account);
+    let account = context
+        .banks_client
+        .get_account(ledger)
+        .await
+        .unwrap()
+        .unwrap();
+    u64::from_le_bytes(account.data[1..9].try_into().unwrap())
+}
+
+#[tokio::test]
+async fn execute_delegated_sale_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_spl_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (nft, buyer) = create_nft_and_buyer(&mut context, &ah.treasury_mint, PRICE).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &nft, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (bid_acc, bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    // The bid only delegates the price, the funds stay with the buyer.
+    let (program_as_signer, _) = find_program_as_signer_address();
+    let payment_account = get_token_account(&mut context, &bid_acc.payment_account)
+        .await
+        .unwrap();
+    assert_eq!(payment_account.amount, PRICE);
+    assert_eq!(payment_account.delegated_amount, PRICE);
+    assert_eq!(
+        payment_account.delegate,
+        solana_program::program_option::COption::Some(program_as_signer)
+    );
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (sale_acc, sale_tx) = execute_delegated_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &nft,
+        &buyer.pubkey(),
+        &nft.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let payment_account = get_token_account(&mut context, &bid_acc.payment_account)
+        .await
+        .unwrap();
+    assert_eq!(payment_account.amount, 0);
+
+    let seller_payment = get_token_account(&mut context, &sale_acc.seller_payment_receipt_account)
+        .await
+        .unwrap();
+    let fee_minus: u64 = PRICE - ((ah.seller_fee_basis_points as u64 * PRICE) / 10000);
+    assert_eq!(seller_payment.amount, fee_minus);
+
+    let buyer_token = get_token_account(&mut context, &sale_acc.buyer_receipt_token_account)
+        .await
+        .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+
+    let trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_delegated_sale_insufficient_funds_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_spl_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (nft, buyer) = create_nft_and_buyer(&mut context, &ah.treasury_mint, PRICE / 2).await;
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &nft, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    // Bids are not funded up front, so the buyer can bid more than they hold.
+    let (bid_acc, bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_delegated_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &nft,
+        &buyer.pubkey(),
+        &nft.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, INSUFFICIENT_DELEGATED_FUNDS);
+}
+
+#[tokio::test]
+async fn delegated_bid_native_treasury_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (nft, buyer) = create_nft_and_buyer(&mut context, &ah.treasury_mint, 0).await;
+
+    let (_, bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, DELEGATED_BID_NATIVE_TREASURY);
+}
+
+#[tokio::test]
+async fn cancel_delegated_bid_lowers_allowance() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_spl_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (nft, buyer) = create_nft_and_buyer(&mut context, &ah.treasury_mint, 0).await;
+    let other_nft = Metadata::new();
+    airdrop(&mut context, &other_nft.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    other_nft
+        .create(
+            &mut context,
+            "Other".to_string(),
+            "SYM".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    // Both bids share the one allowance of the payment account.
+    let (bid_acc, bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+    let (_, other_bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &other_nft,
+        &other_nft.token.pubkey(),
+        &buyer,
+        2 * PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(other_bid_tx)
+        .await
+        .unwrap();
+
+    let payment_account = get_token_account(&mut context, &bid_acc.payment_account)
+        .await
+        .unwrap();
+    assert_eq!(payment_account.delegated_amount, 3 * PRICE);
+    assert_eq!(
+        ledger_allowance(&mut context, &bid_acc.payment_account).await,
+        3 * PRICE
+    );
+
+    let accounts = mpl_auction_house::accounts::CancelDelegatedBid {
+        wallet: buyer.pubkey(),
+        payment_account: bid_acc.payment_account,
+        token_account: bid_acc.token_account,
+        token_mint: nft.mint.pubkey(),
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        delegated_allowance: bid_acc.delegated_allowance,
+        trade_state: bid_acc.buyer_trade_state,
+        program_as_signer: bid_acc.program_as_signer,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelDelegatedBid {
+            buyer_price: PRICE,
+            token_size: 1,
+        }
+        .data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let payment_account = get_token_account(&mut context, &bid_acc.payment_account)
+        .await
+        .unwrap();
+    assert_eq!(payment_account.delegated_amount, 2 * PRICE);
+    assert_eq!(
+        ledger_allowance(&mut context, &bid_acc.payment_account).await,
+        2 * PRICE
+    );
+
+    let trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_delegated_sale_needs_allowance_for_every_open_bid() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_spl_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let (nft, buyer) = create_nft_and_buyer(&mut context, &ah.treasury_mint, 3 * PRICE).await;
+    let other_nft = Metadata::new();
+    airdrop(&mut context, &other_nft.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    other_nft
+        .create(
+            &mut context,
+            "Other".to_string(),
+            "SYM".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &nft, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let (bid_acc, bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(bid_tx)
+        .await
+        .unwrap();
+    let (_, other_bid_tx) = delegated_buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &other_nft,
+        &other_nft.token.pubkey(),
+        &buyer,
+        2 * PRICE,
+    );
+    context
+        .banks_client
+        .process_transaction(other_bid_tx)
+        .await
+        .unwrap();
+
+    // The buyer shrinks the pooled allowance to the price of one bid, leaving the other unbacked.
+    let approve = |amount| {
+        spl_token::instruction::approve(
+            &spl_token::id(),
+            &bid_acc.payment_account,
+            &bid_acc.program_as_signer,
+            &buyer.pubkey(),
+            &[],
+            amount,
+        )
+        .unwrap()
+    };
+    let approve_tx = Transaction::new_signed_with_payer(
+        &[approve(PRICE)],
+        Some(&buyer.pubkey()),
+        &[&buyer],
+        context.last_blockhash,
+    );
+    context
+        .banks_client
+        .process_transaction(approve_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (sale_acc, sale_tx) = execute_delegated_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &nft,
+        &buyer.pubkey(),
+        &nft.token.pubkey(),
+        &sell_acc.seller_trade_state,
+        &bid_acc.buyer_trade_state,
+        PRICE,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, INSUFFICIENT_DELEGATED_FUNDS);
+
+    // Restoring the allowance for both bids lets the sale through, which only spends its own share.
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &nft.token.pubkey(),
+        &ahkey,
+        &sale_acc.token_account,
+        &ah.treasury_mint,
+        &nft.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let sale_ix = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteDelegatedSale {
+            free_trade_state_bump,
+            program_as_signer_bump,
+            buyer_price: PRICE,
+            token_size: 1,
+        }
+        .data(),
+        accounts: sale_acc.to_account_metas(None),
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[approve(3 * PRICE), sale_ix],
+        Some(&authority.pubkey()),
+        &[&authority, &buyer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let payment_account = get_token_account(&mut context, &bid_acc.payment_account)
+        .await
+        .unwrap();
+    assert_eq!(payment_account.amount, 2 * PRICE);
+    assert_eq!(payment_account.delegated_amount, 2 * PRICE);
+    assert_eq!(
+        ledger_allowance(&mut context, &bid_acc.payment_account).await,
+        2 * PRICE
+    );
+}
@@ -0,0 +1,557 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::{ListingConfig, PriceDecay};
+use solana_program::program_pack::Pack;
+use solana_sdk::sysvar::clock::Clock;
+use spl_token::state::Account;
+
+const START_PRICE: u64 = 2_000_000_000;
+const END_PRICE: u64 = 1_000_000_000;
+
+async fn now(context: &mut ProgramTestContext) -> i64 {
+    context
+        .banks_client
+        .get_sysvar::<Clock>()
+        .await
+        .unwrap()
+        .unix_timestamp
+}
+
+async fn create_listed_token(context: &mut ProgramTestContext) -> Metadata {
+    let test_metadata = Metadata::new();
+    airdrop(context, &test_metadata.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    test_metadata
+        .create(
+            context,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            false,
+            1,
+        )
+        .await
+        .unwrap();
+    test_metadata
+}
+
+#[tokio::test]
+async fn execute_dutch_auction_sale_at_decayed_price() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_token(&mut context).await;
+
+    // The schedule has already run out, so the listing sells at its end price.
+    let timestamp = now(&mut context).await;
+    let (sell_acc, sell_tx) = dutch_auction_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        END_PRICE,
+        timestamp - 100,
+        timestamp - 1,
+        PriceDecay::Stepwise { interval: 10 },
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_acc.listing_config)
+        .await
+        .unwrap()
+        .unwrap();
+    let listing_config =
+        ListingConfig::try_deserialize(&mut listing_config_account.data.as_ref()).unwrap();
+    assert_eq!(listing_config.seller, test_metadata.token.pubkey());
+    assert_eq!(listing_config.start_price, START_PRICE);
+    assert_eq!(listing_config.end_price, END_PRICE);
+    assert_eq!(listing_config.decay, PriceDecay::Stepwise { interval: 10 });
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        START_PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_dutch_auction_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &bid_acc.buyer_trade_state,
+        START_PRICE,
+        1,
+    );
+    let seller_before = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let escrow_before = context
+        .banks_client
+        .get_account(bid_acc.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    // The seller is paid the end price and also gets the listing config rent back.
+    let seller_after = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let fee_minus: u64 = END_PRICE - ((ah.seller_fee_basis_points as u64 * END_PRICE) / 10000);
+    assert_eq!(
+        seller_before.lamports + fee_minus + listing_config_account.lamports,
+        seller_after.lamports
+    );
+
+    // The rest of the bid stays in escrow.
+    let escrow_after = context
+        .banks_client
+        .get_account(bid_acc.escrow_payment_account)
+        .await
+        .unwrap()
+        .unwrap();
+    assert_eq!(escrow_before.lamports - END_PRICE, escrow_after.lamports);
+
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+
+    let listing_config = context
+        .banks_client
+        .get_account(sell_acc.listing_config)
+        .await
+        .unwrap();
+    assert!(listing_config.is_none());
+    let buyer_trade_state = context
+        .banks_client
+        .get_account(bid_acc.buyer_trade_state)
+        .await
+        .unwrap();
+    assert!(buyer_trade_state.is_none());
+}
+
+#[tokio::test]
+async fn execute_dutch_auction_sale_at_stepwise_price() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_token(&mut context).await;
+
+    // 25 seconds into a 100 second schedule with 10 second steps, the price has dropped two steps.
+    let timestamp = now(&mut context).await;
+    let (sell_acc, sell_tx) = dutch_auction_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        END_PRICE,
+        timestamp - 25,
+        timestamp + 75,
+        PriceDecay::Stepwise { interval: 10 },
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+    let listing_config_account = context
+        .banks_client
+        .get_account(sell_acc.listing_config)
+        .await
+        .unwrap()
+        .unwrap();
+    let linear_price = START_PRICE - (START_PRICE - END_PRICE) * 25 / 100;
+    let stepwise_price = START_PRICE - (START_PRICE - END_PRICE) * 20 / 100;
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let mut bids = Vec::new();
+    for price in [linear_price, START_PRICE] {
+        let buyer = Keypair::new();
+        airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+            .await
+            .unwrap();
+        let ((bid_acc, _), buy_tx) = buy(
+            &mut context,
+            &ahkey,
+            &ah,
+            &test_metadata,
+            &test_metadata.token.pubkey(),
+            &buyer,
+            price,
+            1,
+        );
+        context
+            .banks_client
+            .process_transaction(buy_tx)
+            .await
+            .unwrap();
+        bids.push((buyer, bid_acc, price));
+    }
+
+    // The price holds until the next step, so a bid at the linear price is still too low.
+    let (buyer, bid_acc, price) = &bids[0];
+    let (_, sale_tx) = execute_dutch_auction_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &bid_acc.buyer_trade_state,
+        *price,
+        1,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, BID_BELOW_CURRENT_PRICE);
+
+    let (buyer, bid_acc, price) = &bids[1];
+    let (_, sale_tx) = execute_dutch_auction_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &bid_acc.buyer_trade_state,
+        *price,
+        1,
+    );
+    let seller_before = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    let seller_after = context
+        .banks_client
+        .get_account(test_metadata.token.pubkey())
+        .await
+        .unwrap()
+        .unwrap();
+    let fee_minus: u64 =
+        stepwise_price - ((ah.seller_fee_basis_points as u64 * stepwise_price) / 10000);
+    assert_eq!(
+        seller_before.lamports + fee_minus + listing_config_account.lamports,
+        seller_after.lamports
+    );
+}
+
+#[tokio::test]
+async fn execute_dutch_auction_sale_signed_by_authority_only() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_token(&mut context).await;
+
+    let timestamp = now(&mut context).await;
+    let (sell_acc, sell_tx) = dutch_auction_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        END_PRICE,
+        timestamp - 100,
+        timestamp - 1,
+        PriceDecay::Linear,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        END_PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_dutch_auction_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &bid_acc.buyer_trade_state,
+        END_PRICE,
+        1,
+    );
+
+    // Neither the seller nor the buyer signs the sale.
+    let signers =
+        &sale_tx.message.account_keys[..sale_tx.message.header.num_required_signatures as usize];
+    assert_eq!(signers, &[authority.pubkey()]);
+    context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap();
+
+    // The transfer used up the seller's delegation, so the program as signer keeps no allowance.
+    let seller_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(sell_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(seller_token.amount, 0);
+    assert!(seller_token.delegate.is_none());
+
+    let buyer_token_account =
+        get_associated_token_address(&buyer.pubkey(), &test_metadata.mint.pubkey());
+    let buyer_token = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(buyer_token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+}
+
+#[tokio::test]
+async fn execute_dutch_auction_sale_below_current_price_fails() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_token(&mut context).await;
+
+    // The price has barely started to decline from the start price.
+    let timestamp = now(&mut context).await;
+    let (_, sell_tx) = dutch_auction_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        END_PRICE,
+        timestamp,
+        timestamp + 86_400,
+        PriceDecay::Linear,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let buyer = Keypair::new();
+    airdrop(&mut context, &buyer.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    let ((bid_acc, _), buy_tx) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        &test_metadata.token.pubkey(),
+        &buyer,
+        END_PRICE,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(buy_tx)
+        .await
+        .unwrap();
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let (_, sale_tx) = execute_dutch_auction_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &test_metadata,
+        &buyer.pubkey(),
+        &test_metadata.token.pubkey(),
+        &bid_acc.buyer_trade_state,
+        END_PRICE,
+        1,
+    );
+    let err = context
+        .banks_client
+        .process_transaction(sale_tx)
+        .await
+        .unwrap_err();
+    assert_error!(err, BID_BELOW_CURRENT_PRICE);
+}
+
+#[tokio::test]
+async fn cancel_dutch_auction_success() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) = existing_auction_house_test_context(&mut context)
+        .await
+        .unwrap();
+    let test_metadata = create_listed_token(&mut context).await;
+
+    let timestamp = now(&mut context).await;
+    let (sell_acc, sell_tx) = dutch_auction_sell(
+        &mut context,
+        &ahkey,
+        &ah,
+        &test_metadata,
+        START_PRICE,
+        END_PRICE,
+        timestamp,
+        timestamp + 86_400,
+        PriceDecay::Linear,
+        1,
+    );
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let accounts = mpl_auction_house::accounts::CancelDutchAuction {
+        wallet: test_metadata.token.pubkey(),
+        token_account: sell_acc.token_account,
+        authority: ah.authority,
+        auction_house: ahkey,
+        listing_config: sell_acc.listing_config,
+        token_program: spl_token::id(),
+    }
+    .to_account_metas(None);
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CancelDutchAuction { token_size: 1 }.data(),
+        accounts,
+    };
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+
+    let listing_config = context
+        .banks_client
+        .get_account(sell_acc.listing_config)
+        .await
+        .unwrap();
+    assert!(listing_config.is_none());
+
+    let token_account = Account::unpack_from_slice(
+        context
+            .banks_client
+            .get_account(sell_acc.token_account)
+            .await
+            .unwrap()
+            .unwrap()
+            .data
+            .as_slice(),
+    )
+    .unwrap();
+    assert!(token_account.delegate.is_none());
+}
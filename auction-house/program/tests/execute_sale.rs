@@ -315,7 +315,7 @@ async fn execute_sale_wrong_token_account_owner_success() {
 
     println!("{:?}", err);
 
-    match err {
+    match TransportError::from(err) {
         TransportError::TransactionError(TransactionError::InstructionError(
             0,
             InstructionError::Custom(6000),
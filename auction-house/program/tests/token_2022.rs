@@ -0,0 +1,344 @@
+#![cfg(feature = "test-bpf")]
+pub mod common;
+pub mod utils;
+
+use common::*;
+use utils::setup_functions::*;
+
+use mpl_auction_house::pda::{
+    find_escrow_payment_address, find_program_as_signer_address, find_trade_state_address,
+};
+use mpl_testing_utils::solana::get_token_account;
+use mpl_token_metadata::state::Creator;
+use solana_sdk::{instruction::AccountMeta, sysvar};
+use spl_token_2022::{extension::StateWithExtensions, state::Account as Account2022};
+
+const PRICE: u64 = 1_000_000;
+const TRANSFER_FEE_BASIS_POINTS: u16 = 100;
+const ROYALTY_BASIS_POINTS: u16 = 1000;
+// Deposits pay the transfer fee on top, so the escrow nets exactly `PRICE`.
+const DEPOSIT: u64 = 1_010_102;
+
+/// Fee Token-2022 withholds from a transfer of `amount`, rounded up like the program does.
+fn transfer_fee(amount: u64) -> u64 {
+    (amount * TRANSFER_FEE_BASIS_POINTS as u64 + 9_999) / 10_000
+}
+
+/// The helpers build instructions for spl-token treasuries; Token-2022 treasuries also take the
+/// Token-2022 program in remaining accounts.
+fn with_token_2022(mut ix: Instruction) -> Instruction {
+    ix.accounts
+        .push(AccountMeta::new_readonly(spl_token_2022::id(), false));
+    ix
+}
+
+async fn process(context: &mut ProgramTestContext, ix: Instruction, signer: &Keypair) {
+    let tx = Transaction::new_signed_with_payer(
+        &[ix],
+        Some(&signer.pubkey()),
+        &[signer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await.unwrap();
+}
+
+async fn token_2022_amount(context: &mut ProgramTestContext, key: &Pubkey) -> u64 {
+    let account = context
+        .banks_client
+        .get_account(*key)
+        .await
+        .unwrap()
+        .unwrap();
+    StateWithExtensions::<Account2022>::unpack(&account.data)
+        .unwrap()
+        .base
+        .amount
+}
+
+/// Creates a buyer holding `DEPOSIT` of the treasury mint and deposits all of it into escrow.
+async fn create_funded_buyer(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+) -> (Keypair, Pubkey, Pubkey) {
+    let buyer = Keypair::new();
+    airdrop(context, &buyer.pubkey(), TEN_SOL).await.unwrap();
+    let payment_account =
+        create_token_2022_associated_token_account(context, &buyer.pubkey(), &ah.treasury_mint)
+            .await
+            .unwrap();
+    let mint_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+    process(
+        context,
+        spl_token_2022::instruction::mint_to(
+            &spl_token_2022::id(),
+            &ah.treasury_mint,
+            &payment_account,
+            &mint_authority.pubkey(),
+            &[],
+            DEPOSIT,
+        )
+        .unwrap(),
+        &mint_authority,
+    )
+    .await;
+
+    let (escrow, escrow_payment_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::Deposit {
+        wallet: buyer.pubkey(),
+        payment_account,
+        transfer_authority: buyer.pubkey(),
+        escrow_payment_account: escrow,
+        treasury_mint: ah.treasury_mint,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+    let deposit_ix = with_token_2022(Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Deposit {
+            escrow_payment_bump,
+            amount: DEPOSIT,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    });
+    process(context, deposit_ix, &buyer).await;
+
+    (buyer, payment_account, escrow)
+}
+
+#[tokio::test]
+async fn deposit_and_withdraw_net_of_transfer_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, _) =
+        existing_token_2022_auction_house_test_context(&mut context, TRANSFER_FEE_BASIS_POINTS)
+            .await
+            .unwrap();
+    let (buyer, payment_account, escrow) = create_funded_buyer(&mut context, &ahkey, &ah).await;
+
+    assert_eq!(DEPOSIT - transfer_fee(DEPOSIT), PRICE);
+    assert_eq!(token_2022_amount(&mut context, &escrow).await, PRICE);
+    assert_eq!(token_2022_amount(&mut context, &payment_account).await, 0);
+
+    let (_, escrow_payment_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let accounts = mpl_auction_house::accounts::Withdraw {
+        wallet: buyer.pubkey(),
+        receipt_account: payment_account,
+        escrow_payment_account: escrow,
+        treasury_mint: ah.treasury_mint,
+        authority: ah.authority,
+        auction_house: ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        rent: sysvar::rent::id(),
+    };
+    let withdraw_ix = with_token_2022(Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Withdraw {
+            escrow_payment_bump,
+            amount: PRICE,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    });
+    process(&mut context, withdraw_ix, &buyer).await;
+
+    assert_eq!(token_2022_amount(&mut context, &escrow).await, 0);
+    assert_eq!(
+        token_2022_amount(&mut context, &payment_account).await,
+        PRICE - transfer_fee(PRICE)
+    );
+}
+
+#[tokio::test]
+async fn execute_sale_pays_net_of_transfer_fee() {
+    let mut context = auction_house_program_test().start_with_context().await;
+    let (ah, ahkey, authority) =
+        existing_token_2022_auction_house_test_context(&mut context, TRANSFER_FEE_BASIS_POINTS)
+            .await
+            .unwrap();
+
+    let creator = Keypair::new();
+    let nft = Metadata::new();
+    airdrop(&mut context, &nft.token.pubkey(), TEN_SOL)
+        .await
+        .unwrap();
+    nft.create(
+        &mut context,
+        "Name".to_string(),
+        "SYM".to_string(),
+        "uri".to_string(),
+        Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]),
+        ROYALTY_BASIS_POINTS,
+        false,
+        1,
+    )
+    .await
+    .unwrap();
+    let (buyer, _, escrow) = create_funded_buyer(&mut context, &ahkey, &ah).await;
+
+    // The bundled associated token account program cannot create Token-2022 accounts, so the
+    // payees' accounts exist before the sale.
+    let creator_account = create_token_2022_associated_token_account(
+        &mut context,
+        &creator.pubkey(),
+        &ah.treasury_mint,
+    )
+    .await
+    .unwrap();
+    let seller_account = create_token_2022_associated_token_account(
+        &mut context,
+        &nft.token.pubkey(),
+        &ah.treasury_mint,
+    )
+    .await
+    .unwrap();
+
+    let ((sell_acc, _), sell_tx) = sell(&mut context, &ahkey, &ah, &nft, PRICE, 1);
+    context
+        .banks_client
+        .process_transaction(sell_tx)
+        .await
+        .unwrap();
+
+    let ((buy_acc, _), _) = buy(
+        &mut context,
+        &ahkey,
+        &ah,
+        &nft,
+        &nft.token.pubkey(),
+        &buyer,
+        PRICE,
+        1,
+    );
+    let (_, trade_state_bump) = find_trade_state_address(
+        &buyer.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &nft.mint.pubkey(),
+        PRICE,
+        1,
+    );
+    let (_, escrow_payment_bump) = find_escrow_payment_address(&ahkey, &buyer.pubkey());
+    let buy_ix = with_token_2022(Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::Buy {
+            trade_state_bump,
+            escrow_payment_bump,
+            buyer_price: PRICE,
+            token_size: 1,
+        }
+        .data(),
+        accounts: buy_acc.to_account_metas(None),
+    });
+    process(&mut context, buy_ix, &buyer).await;
+
+    airdrop(&mut context, &ah.auction_house_fee_account, TEN_SOL)
+        .await
+        .unwrap();
+    let ((mut sale_acc, _), _) = execute_sale(
+        &mut context,
+        &ahkey,
+        &ah,
+        &authority,
+        &nft,
+        &buyer.pubkey(),
+        &nft.token.pubkey(),
+        &sell_acc.token_account,
+        &sell_acc.seller_trade_state,
+        &buy_acc.buyer_trade_state,
+        1,
+        PRICE,
+    );
+    sale_acc.seller_payment_receipt_account = seller_account;
+    let (_, free_trade_state_bump) = find_trade_state_address(
+        &nft.token.pubkey(),
+        &ahkey,
+        &sell_acc.token_account,
+        &ah.treasury_mint,
+        &nft.mint.pubkey(),
+        0,
+        1,
+    );
+    let (_, program_as_signer_bump) = find_program_as_signer_address();
+    let mut accounts = sale_acc.to_account_metas(None);
+    accounts.push(AccountMeta::new(creator.pubkey(), false));
+    accounts.push(AccountMeta::new(creator_account, false));
+    let sale_ix = with_token_2022(Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteSale {
+            escrow_payment_bump,
+            _free_trade_state_bump: free_trade_state_bump,
+            program_as_signer_bump,
+            token_size: 1,
+            buyer_price: PRICE,
+        }
+        .data(),
+        accounts,
+    });
+    process(&mut context, sale_ix, &authority).await;
+
+    // Each payee receives its share less the fee withheld on the transfer.
+    let royalty = PRICE * ROYALTY_BASIS_POINTS as u64 / 10_000;
+    let house_fee = PRICE * ah.seller_fee_basis_points as u64 / 10_000;
+    let proceeds = PRICE - royalty - house_fee;
+    assert_eq!(token_2022_amount(&mut context, &escrow).await, 0);
+    assert_eq!(
+        token_2022_amount(&mut context, &creator_account).await,
+        royalty - transfer_fee(royalty)
+    );
+    assert_eq!(
+        token_2022_amount(&mut context, &ah.auction_house_treasury).await,
+        house_fee - transfer_fee(house_fee)
+    );
+    assert_eq!(
+        token_2022_amount(&mut context, &seller_account).await,
+        proceeds - transfer_fee(proceeds)
+    );
+
+    let buyer_token = get_token_account(&mut context, &sale_acc.buyer_receipt_token_account)
+        .await
+        .unwrap();
+    assert_eq!(buyer_token.amount, 1);
+
+    // Withdrawing from the treasury pays the fee again on the way out.
+    let treasury_balance = house_fee - transfer_fee(house_fee);
+    let accounts = mpl_auction_house::accounts::WithdrawFromTreasury {
+        treasury_mint: ah.treasury_mint,
+        authority: ah.authority,
+        treasury_withdrawal_destination: ah.treasury_withdrawal_destination,
+        auction_house_treasury: ah.auction_house_treasury,
+        auction_house: ahkey,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+    };
+    let withdraw_ix = with_token_2022(Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::WithdrawFromTreasury {
+            amount: treasury_balance,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    });
+    process(&mut context, withdraw_ix, &authority).await;
+
+    assert_eq!(
+        token_2022_amount(&mut context, &ah.auction_house_treasury).await,
+        0
+    );
+    assert_eq!(
+        token_2022_amount(&mut context, &ah.treasury_withdrawal_destination).await,
+        treasury_balance - transfer_fee(treasury_balance)
+    );
+}
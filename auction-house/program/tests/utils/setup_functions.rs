@@ -3,32 +3,50 @@ use std::io;
 use anchor_client::solana_sdk::{
     pubkey::Pubkey,
     signature::{Keypair, Signer},
-    system_program, sysvar,
+    system_instruction, system_program, sysvar,
 };
 use anchor_lang::*;
 use mpl_auction_house::{
     pda::{
         find_auction_house_address, find_auction_house_fee_account_address,
         find_auction_house_treasury_address, find_auctioneer_pda,
-        find_auctioneer_trade_state_address, find_bid_receipt_address, find_escrow_payment_address,
-        find_listing_receipt_address, find_program_as_signer_address,
+        find_auctioneer_trade_state_address, find_bid_receipt_address, find_bundle_listing_address,
+        find_collection_bid_trade_state_address, find_delegated_allowance_address,
+        find_delegated_bid_trade_state_address, find_escrow_payment_address,
+        find_listing_config_address, find_listing_receipt_address, find_program_as_signer_address,
         find_public_bid_trade_state_address, find_purchase_receipt_address,
         find_trade_state_address,
     },
-    AuctionHouse, AuthorityScope,
+    AuctionHouse, AuthorityScope, PriceDecay,
 };
 
-use mpl_testing_utils::{solana::airdrop, utils::Metadata};
+use mpl_testing_utils::{
+    solana::{airdrop, create_mint},
+    utils::Metadata,
+};
 use std::result::Result as StdResult;
 
 use mpl_token_metadata::pda::find_metadata_account;
 use solana_program_test::*;
-use solana_sdk::{instruction::Instruction, transaction::Transaction, transport::TransportError};
+use solana_sdk::{
+    account::{Account, AccountSharedData},
+    instruction::{AccountMeta, Instruction},
+    transaction::Transaction,
+    transport::TransportError,
+};
 use spl_associated_token_account::get_associated_token_address;
+use spl_token_2022::extension::{
+    transfer_fee::instruction::initialize_transfer_fee_config, ExtensionType, StateWithExtensions,
+};
 
 pub fn auction_house_program_test() -> ProgramTest {
     let mut program = ProgramTest::new("mpl_auction_house", mpl_auction_house::id(), None);
     program.add_program("mpl_token_metadata", mpl_token_metadata::id(), None);
+    program.add_program(
+        "spl_token_2022",
+        spl_token_2022::id(),
+        processor!(spl_token_2022::processor::Processor::process),
+    );
     program
 }
 
@@ -49,7 +67,12 @@ pub async fn create_auction_house(
     requires_sign_off: bool,
     can_change_sale_price: bool,
 ) -> StdResult<Pubkey, TransportError> {
-    let accounts = mpl_auction_house::accounts::CreateAuctionHouse {
+    let t_mint_owner = context
+        .banks_client
+        .get_account(*t_mint_key)
+        .await?
+        .map(|account| account.owner);
+    let mut accounts = mpl_auction_house::accounts::CreateAuctionHouse {
         treasury_mint: *t_mint_key,
         payer: payer_wallet.pubkey(),
         authority: payer_wallet.pubkey(),
@@ -65,6 +88,10 @@ pub async fn create_auction_house(
         rent: sysvar::rent::id(),
     }
     .to_account_metas(None);
+    // Token-2022 treasuries take the Token-2022 program in remaining accounts.
+    if t_mint_owner == Some(spl_token_2022::id()) {
+        accounts.push(AccountMeta::new_readonly(spl_token_2022::id(), false));
+    }
 
     let data = mpl_auction_house::instruction::CreateAuctionHouse {
         _bump: auction_house_key_bump,
@@ -94,6 +121,7 @@ pub async fn create_auction_house(
         .process_transaction(tx)
         .await
         .map(|_| *auction_house_key)
+        .map_err(Into::into)
 }
 
 pub fn deposit(
@@ -474,6 +502,127 @@ pub fn public_buy(
     )
 }
 
+pub fn collection_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    collection_mint: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+    quantity: u64,
+) -> (mpl_auction_house::accounts::CollectionBuy, Transaction) {
+    let (buyer_trade_state, trade_state_bump) = find_collection_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &ah.treasury_mint,
+        collection_mint,
+        sale_price,
+        quantity,
+    );
+    let (escrow, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::CollectionBuy {
+        wallet: buyer.pubkey(),
+        payment_account: buyer.pubkey(),
+        transfer_authority: buyer.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        collection_mint: *collection_mint,
+        escrow_payment_account: escrow,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::CollectionBuy {
+            trade_state_bump,
+            escrow_payment_bump: escrow_bump,
+            buyer_price: sale_price,
+            quantity,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
+pub fn delegated_buy(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    owner: &Pubkey,
+    buyer: &Keypair,
+    sale_price: u64,
+) -> (mpl_auction_house::accounts::DelegatedBuy, Transaction) {
+    let seller_token_account = get_associated_token_address(owner, &test_metadata.mint.pubkey());
+    let (buyer_trade_state, trade_state_bump) = find_delegated_bid_trade_state_address(
+        &buyer.pubkey(),
+        ahkey,
+        &seller_token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        sale_price,
+        1,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let payment_account = get_associated_token_address(&buyer.pubkey(), &ah.treasury_mint);
+    let (delegated_allowance, _) = find_delegated_allowance_address(&payment_account);
+
+    let accounts = mpl_auction_house::accounts::DelegatedBuy {
+        wallet: buyer.pubkey(),
+        payment_account,
+        treasury_mint: ah.treasury_mint,
+        token_account: seller_token_account,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        buyer_trade_state,
+        delegated_allowance,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: solana_program::system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::DelegatedBuy {
+            trade_state_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price: sale_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    (
+        accounts,
+        Transaction::new_signed_with_payer(
+            &[instruction],
+            Some(&buyer.pubkey()),
+            &[buyer],
+            context.last_blockhash,
+        ),
+    )
+}
+
 pub fn auctioneer_public_buy(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -653,6 +802,306 @@ pub fn execute_sale(
     ((execute_sale_accounts, print_purchase_receipt_accounts), tx)
 }
 
+pub fn execute_collection_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    collection_mint: &Pubkey,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+    quantity: u64,
+) -> (
+    mpl_auction_house::accounts::ExecuteCollectionSale,
+    Transaction,
+) {
+    let token_account = get_associated_token_address(seller, &test_metadata.mint.pubkey());
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+
+    let accounts = mpl_auction_house::accounts::ExecuteCollectionSale {
+        buyer: *buyer,
+        seller: *seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        collection_mint: *collection_mint,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: *buyer_trade_state,
+        seller_trade_state: *seller_trade_state,
+        free_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteCollectionSale {
+            escrow_payment_bump: escrow_bump,
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            quantity,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn execute_delegated_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+) -> (
+    mpl_auction_house::accounts::ExecuteDelegatedSale,
+    Transaction,
+) {
+    let token_account = get_associated_token_address(seller, &test_metadata.mint.pubkey());
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (free_trade_state, free_sts_bump) = find_trade_state_address(
+        seller,
+        ahkey,
+        &token_account,
+        &ah.treasury_mint,
+        &test_metadata.mint.pubkey(),
+        0,
+        1,
+    );
+    let buyer_payment_account = get_associated_token_address(buyer, &ah.treasury_mint);
+    let (delegated_allowance, _) = find_delegated_allowance_address(&buyer_payment_account);
+
+    let accounts = mpl_auction_house::accounts::ExecuteDelegatedSale {
+        buyer: *buyer,
+        seller: *seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        buyer_payment_account,
+        seller_payment_receipt_account: get_associated_token_address(seller, &ah.treasury_mint),
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: *buyer_trade_state,
+        delegated_allowance,
+        seller_trade_state: *seller_trade_state,
+        free_trade_state,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteDelegatedSale {
+            free_trade_state_bump: free_sts_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size: 1,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+pub fn execute_dutch_auction_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    authority: &Keypair,
+    test_metadata: &Metadata,
+    buyer: &Pubkey,
+    seller: &Pubkey,
+    buyer_trade_state: &Pubkey,
+    buyer_price: u64,
+    token_size: u64,
+) -> (
+    mpl_auction_house::accounts::ExecuteDutchAuctionSale,
+    Transaction,
+) {
+    let token_account = get_associated_token_address(seller, &test_metadata.mint.pubkey());
+    let buyer_token_account = get_associated_token_address(buyer, &test_metadata.mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (listing_config, _) = find_listing_config_address(
+        seller,
+        ahkey,
+        &token_account,
+        &test_metadata.mint.pubkey(),
+        token_size,
+    );
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, buyer);
+
+    let accounts = mpl_auction_house::accounts::ExecuteDutchAuctionSale {
+        buyer: *buyer,
+        seller: *seller,
+        token_account,
+        token_mint: test_metadata.mint.pubkey(),
+        metadata: test_metadata.pubkey,
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: *seller,
+        buyer_receipt_token_account: buyer_token_account,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        buyer_trade_state: *buyer_trade_state,
+        listing_config,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteDutchAuctionSale {
+            escrow_payment_bump: escrow_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+            token_size,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&authority.pubkey()),
+        &[authority],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+/// Buys every token of a bundle listed by the owner of the first token, with `creators[i]` the creators of `items[i]`.
+pub fn execute_bundle_sale(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    items: &[&Metadata],
+    creators: &[Vec<Pubkey>],
+    buyer: &Keypair,
+    buyer_price: u64,
+) -> (mpl_auction_house::accounts::ExecuteBundleSale, Transaction) {
+    let seller = items[0].token.pubkey();
+    let (bundle_listing, _) = find_bundle_listing_address(&seller, ahkey, &items[0].mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+    let (escrow_payment_account, escrow_bump) = find_escrow_payment_address(ahkey, &buyer.pubkey());
+
+    let accounts = mpl_auction_house::accounts::ExecuteBundleSale {
+        buyer: buyer.pubkey(),
+        seller,
+        token_mint: items[0].mint.pubkey(),
+        treasury_mint: ah.treasury_mint,
+        escrow_payment_account,
+        seller_payment_receipt_account: seller,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        auction_house_fee_account: ah.auction_house_fee_account,
+        auction_house_treasury: ah.auction_house_treasury,
+        bundle_listing,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        ata_program: spl_associated_token_account::id(),
+        program_as_signer,
+        rent: sysvar::rent::id(),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    for (item, item_creators) in items.iter().zip(creators) {
+        account_metas.push(AccountMeta::new(
+            get_associated_token_address(&seller, &item.mint.pubkey()),
+            false,
+        ));
+        account_metas.push(AccountMeta::new_readonly(item.mint.pubkey(), false));
+        account_metas.push(AccountMeta::new_readonly(item.pubkey, false));
+        account_metas.push(AccountMeta::new(
+            get_associated_token_address(&buyer.pubkey(), &item.mint.pubkey()),
+            false,
+        ));
+        for creator in item_creators {
+            account_metas.push(AccountMeta::new(*creator, false));
+        }
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::ExecuteBundleSale {
+            escrow_payment_bump: escrow_bump,
+            program_as_signer_bump: pas_bump,
+            buyer_price,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&buyer.pubkey()),
+        &[buyer],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
 pub fn auctioneer_execute_sale(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -1002,6 +1451,123 @@ pub fn sell(
     )
 }
 
+pub fn dutch_auction_sell(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    test_metadata: &Metadata,
+    start_price: u64,
+    end_price: u64,
+    start_time: i64,
+    end_time: i64,
+    decay: PriceDecay,
+    token_size: u64,
+) -> (mpl_auction_house::accounts::DutchAuctionSell, Transaction) {
+    let token =
+        get_associated_token_address(&test_metadata.token.pubkey(), &test_metadata.mint.pubkey());
+    let (listing_config, _) = find_listing_config_address(
+        &test_metadata.token.pubkey(),
+        ahkey,
+        &token,
+        &test_metadata.mint.pubkey(),
+        token_size,
+    );
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::DutchAuctionSell {
+        wallet: test_metadata.token.pubkey(),
+        token_account: token,
+        metadata: test_metadata.pubkey,
+        authority: ah.authority,
+        auction_house: *ahkey,
+        listing_config,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::DutchAuctionSell {
+            program_as_signer_bump: pas_bump,
+            token_size,
+            start_price,
+            end_price,
+            start_time,
+            end_time,
+            decay,
+        }
+        .data(),
+        accounts: accounts.to_account_metas(None),
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&test_metadata.token.pubkey()),
+        &[&test_metadata.token],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
+/// Lists `items`, all owned by the token keypair of the first one, as a bundle.
+pub fn bundle_sell(
+    context: &mut ProgramTestContext,
+    ahkey: &Pubkey,
+    ah: &AuctionHouse,
+    items: &[&Metadata],
+    price: u64,
+    weights: Vec<u16>,
+) -> (mpl_auction_house::accounts::BundleSell, Transaction) {
+    let seller = &items[0].token;
+    let (bundle_listing, _) =
+        find_bundle_listing_address(&seller.pubkey(), ahkey, &items[0].mint.pubkey());
+    let (program_as_signer, pas_bump) = find_program_as_signer_address();
+
+    let accounts = mpl_auction_house::accounts::BundleSell {
+        wallet: seller.pubkey(),
+        token_mint: items[0].mint.pubkey(),
+        authority: ah.authority,
+        auction_house: *ahkey,
+        bundle_listing,
+        program_as_signer,
+        token_program: spl_token::id(),
+        system_program: system_program::id(),
+        rent: sysvar::rent::id(),
+    };
+
+    let mut account_metas = accounts.to_account_metas(None);
+    for item in items {
+        account_metas.push(AccountMeta::new(
+            get_associated_token_address(&seller.pubkey(), &item.mint.pubkey()),
+            false,
+        ));
+        account_metas.push(AccountMeta::new_readonly(item.pubkey, false));
+    }
+
+    let instruction = Instruction {
+        program_id: mpl_auction_house::id(),
+        data: mpl_auction_house::instruction::BundleSell {
+            program_as_signer_bump: pas_bump,
+            price,
+            weights,
+        }
+        .data(),
+        accounts: account_metas,
+    };
+
+    let tx = Transaction::new_signed_with_payer(
+        &[instruction],
+        Some(&seller.pubkey()),
+        &[seller],
+        context.last_blockhash,
+    );
+
+    (accounts, tx)
+}
+
 pub fn auctioneer_sell(
     context: &mut ProgramTestContext,
     ahkey: &Pubkey,
@@ -1109,7 +1675,11 @@ pub async fn delegate_auctioneer(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub fn withdraw(
@@ -1236,11 +1806,144 @@ pub fn auctioneer_withdraw(
 
 pub async fn existing_auction_house_test_context(
     context: &mut ProgramTestContext,
+) -> StdResult<(AuctionHouse, Pubkey, Keypair), TransportError> {
+    auction_house_test_context_with_mint(context, &spl_token::native_mint::id()).await
+}
+
+/// Create an auction house whose treasury mint is a new SPL mint, with the context payer as mint authority.
+pub async fn existing_spl_auction_house_test_context(
+    context: &mut ProgramTestContext,
+) -> StdResult<(AuctionHouse, Pubkey, Keypair), TransportError> {
+    let treasury_mint = Keypair::new();
+    let mint_authority = context.payer.pubkey();
+    create_mint(context, &treasury_mint, &mint_authority, None).await?;
+    auction_house_test_context_with_mint(context, &treasury_mint.pubkey()).await
+}
+
+/// Create an auction house whose treasury mint is a new Token-2022 mint withholding
+/// `transfer_fee_basis_points` of every transfer, with the context payer as mint authority.
+pub async fn existing_token_2022_auction_house_test_context(
+    context: &mut ProgramTestContext,
+    transfer_fee_basis_points: u16,
+) -> StdResult<(AuctionHouse, Pubkey, Keypair), TransportError> {
+    let treasury_mint = Keypair::new();
+    let payer = context.payer.pubkey();
+    let len = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&[
+        ExtensionType::TransferFeeConfig,
+    ]);
+    let rent = context.banks_client.get_rent().await?;
+    let tx = Transaction::new_signed_with_payer(
+        &[
+            system_instruction::create_account(
+                &payer,
+                &treasury_mint.pubkey(),
+                rent.minimum_balance(len),
+                len as u64,
+                &spl_token_2022::id(),
+            ),
+            initialize_transfer_fee_config(
+                &spl_token_2022::id(),
+                &treasury_mint.pubkey(),
+                Some(&payer),
+                Some(&payer),
+                transfer_fee_basis_points,
+                u64::MAX,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_mint(
+                &spl_token_2022::id(),
+                &treasury_mint.pubkey(),
+                &payer,
+                None,
+                6,
+            )
+            .unwrap(),
+        ],
+        Some(&payer),
+        &[&context.payer, &treasury_mint],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+    create_token_2022_associated_token_account(context, &payer, &treasury_mint.pubkey()).await?;
+    auction_house_test_context_with_mint(context, &treasury_mint.pubkey()).await
+}
+
+/// Associated token account of `wallet` for a Token-2022 `mint`.
+pub fn get_token_2022_associated_token_address(wallet: &Pubkey, mint: &Pubkey) -> Pubkey {
+    Pubkey::find_program_address(
+        &[
+            wallet.as_ref(),
+            spl_token_2022::id().as_ref(),
+            mint.as_ref(),
+        ],
+        &spl_associated_token_account::id(),
+    )
+    .0
+}
+
+/// Create the associated token account of `wallet` for a Token-2022 `mint`. The associated token
+/// account program bundled with the test validator predates Token-2022, so the account is placed at
+/// its address directly and initialized by Token-2022, which adds the extensions the mint requires.
+pub async fn create_token_2022_associated_token_account(
+    context: &mut ProgramTestContext,
+    wallet: &Pubkey,
+    mint: &Pubkey,
+) -> StdResult<Pubkey, TransportError> {
+    let ata = get_token_2022_associated_token_address(wallet, mint);
+    let mint_account = context.banks_client.get_account(*mint).await?.unwrap();
+    let mint_state =
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(&mint_account.data).unwrap();
+    let extensions = ExtensionType::get_required_init_account_extensions(
+        &mint_state.get_extension_types().unwrap(),
+    );
+    let len = ExtensionType::get_account_len::<spl_token_2022::state::Account>(&extensions);
+    let rent = context.banks_client.get_rent().await?;
+    context.set_account(
+        &ata,
+        &AccountSharedData::from(Account {
+            lamports: rent.minimum_balance(len),
+            data: vec![0; len],
+            owner: spl_token_2022::id(),
+            executable: false,
+            rent_epoch: 0,
+        }),
+    );
+
+    let tx = Transaction::new_signed_with_payer(
+        &[spl_token_2022::instruction::initialize_account2(
+            &spl_token_2022::id(),
+            &ata,
+            mint,
+            wallet,
+        )
+        .unwrap()],
+        Some(&context.payer.pubkey()),
+        &[&context.payer],
+        context.last_blockhash,
+    );
+    context.banks_client.process_transaction(tx).await?;
+    Ok(ata)
+}
+
+async fn auction_house_test_context_with_mint(
+    context: &mut ProgramTestContext,
+    t_mint_key: &Pubkey,
 ) -> StdResult<(AuctionHouse, Pubkey, Keypair), TransportError> {
     let twd_key = context.payer.pubkey();
     let fwd_key = context.payer.pubkey();
-    let t_mint_key = spl_token::native_mint::id();
-    let tdw_ata = twd_key;
+    let t_mint_key = *t_mint_key;
+    let t_mint_owner = context
+        .banks_client
+        .get_account(t_mint_key)
+        .await?
+        .map(|account| account.owner);
+    let tdw_ata = if t_mint_key == spl_token::native_mint::id() {
+        twd_key
+    } else if t_mint_owner == Some(spl_token_2022::id()) {
+        get_token_2022_associated_token_address(&twd_key, &t_mint_key)
+    } else {
+        get_associated_token_address(&twd_key, &t_mint_key)
+    };
     let seller_fee_basis_points: u16 = 100;
     let authority = Keypair::new();
     airdrop(context, &authority.pubkey(), 10_000_000_000).await?;
@@ -139,6 +139,7 @@ pub async fn create_auction_house(
         .process_transaction(tx)
         .await
         .map(|_| *auction_house_key)
+        .map_err(Into::into)
 }
 
 pub fn deposit(
@@ -0,0 +1,51 @@
+//! PDAs of the Auction program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("auctxRXPeJoc4817jDhf4HbjnhEcr1cCXenosMhK5R8");
+
+pub const PREFIX: &str = "auction";
+pub const EXTENDED: &str = "extended";
+pub const METADATA: &str = "metadata";
+
+/// Auction of `resource`, which is the vault for auctions run by the Metaplex program.
+pub fn find_auction_address(resource: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), resource.as_ref()], &ID)
+}
+
+pub fn find_auction_extended_address(resource: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            resource.as_ref(),
+            EXTENDED.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_bidder_pot_address(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            auction.as_ref(),
+            bidder.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_bidder_metadata_address(auction: &Pubkey, bidder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            auction.as_ref(),
+            bidder.as_ref(),
+            METADATA.as_bytes(),
+        ],
+        &ID,
+    )
+}
@@ -0,0 +1,268 @@
+//! PDAs of the Auction House program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("hausS13jsjafwWwGqZTUQRmWyvyxn9EQpqMwV1PBBmk");
+
+pub const PREFIX: &str = "auction_house";
+pub const FEE_PAYER: &str = "fee_payer";
+pub const TREASURY: &str = "treasury";
+pub const SIGNER: &str = "signer";
+pub const PURCHASE_RECEIPT_PREFIX: &str = "purchase_receipt";
+pub const BID_RECEIPT_PREFIX: &str = "bid_receipt";
+pub const LISTING_RECEIPT_PREFIX: &str = "listing_receipt";
+pub const AUCTIONEER: &str = "auctioneer";
+pub const COLLECTION_BID: &str = "collection_bid";
+pub const DELEGATED_BID: &str = "delegated_bid";
+pub const DELEGATED_ALLOWANCE: &str = "delegated_allowance";
+pub const LISTING_CONFIG: &str = "listing_config";
+pub const BUNDLE_LISTING: &str = "bundle_listing";
+
+pub fn find_auction_house_address(authority: &Pubkey, treasury_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            authority.as_ref(),
+            treasury_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_auction_house_fee_account_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            FEE_PAYER.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_auction_house_treasury_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            auction_house.as_ref(),
+            TREASURY.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_escrow_payment_address(auction_house: &Pubkey, wallet: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX.as_bytes(), auction_house.as_ref(), wallet.as_ref()],
+        &ID,
+    )
+}
+
+pub fn find_program_as_signer_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), SIGNER.as_bytes()], &ID)
+}
+
+/// Trade state of a listing or private bid. Free trade states use a price of 0.
+pub fn find_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_public_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Trade state of a bid for `quantity` verified members of a collection at `price` each.
+pub fn find_collection_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    treasury_mint: &Pubkey,
+    collection: &Pubkey,
+    price: u64,
+    quantity: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            COLLECTION_BID.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            treasury_mint.as_ref(),
+            collection.as_ref(),
+            &price.to_le_bytes(),
+            &quantity.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Trade state of a bid paid at settlement out of the buyer's token account delegated to the program as signer.
+pub fn find_delegated_bid_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    price: u64,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            DELEGATED_BID.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &price.to_le_bytes(),
+            &token_size.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Ledger of the allowance a buyer's token account owes to its open delegated bids.
+pub fn find_delegated_allowance_address(payment_account: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            DELEGATED_ALLOWANCE.as_bytes(),
+            payment_account.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_listing_config_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    token_mint: &Pubkey,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            LISTING_CONFIG.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            token_mint.as_ref(),
+            &token_size.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Bundle listing, keyed by the mint of the first token in the bundle.
+pub fn find_bundle_listing_address(
+    seller: &Pubkey,
+    auction_house: &Pubkey,
+    first_token_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            BUNDLE_LISTING.as_bytes(),
+            seller.as_ref(),
+            auction_house.as_ref(),
+            first_token_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Trade state of a listing made through an auctioneer, which always uses `u64::MAX` as price.
+pub fn find_auctioneer_trade_state_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    find_trade_state_address(
+        wallet,
+        auction_house,
+        token_account,
+        treasury_mint,
+        token_mint,
+        u64::MAX,
+        token_size,
+    )
+}
+
+pub fn find_bid_receipt_address(trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[BID_RECEIPT_PREFIX.as_bytes(), trade_state.as_ref()], &ID)
+}
+
+pub fn find_listing_receipt_address(trade_state: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[LISTING_RECEIPT_PREFIX.as_bytes(), trade_state.as_ref()],
+        &ID,
+    )
+}
+
+pub fn find_purchase_receipt_address(
+    seller_trade_state: &Pubkey,
+    buyer_trade_state: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PURCHASE_RECEIPT_PREFIX.as_bytes(),
+            seller_trade_state.as_ref(),
+            buyer_trade_state.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Record of an auctioneer delegated on an auction house.
+pub fn find_auctioneer_pda(auction_house: &Pubkey, auctioneer_authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            AUCTIONEER.as_bytes(),
+            auction_house.as_ref(),
+            auctioneer_authority.as_ref(),
+        ],
+        &ID,
+    )
+}
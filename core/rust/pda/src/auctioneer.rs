@@ -0,0 +1,36 @@
+//! PDAs of the Auctioneer program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("neer8g6yJq2mQM6KbnViEDAD4gr3gRZyMMf4F2p3MEh");
+
+pub const LISTING_CONFIG: &str = "listing_config";
+pub const AUCTIONEER: &str = "auctioneer";
+
+pub fn find_listing_config_address(
+    wallet: &Pubkey,
+    auction_house: &Pubkey,
+    token_account: &Pubkey,
+    treasury_mint: &Pubkey,
+    token_mint: &Pubkey,
+    token_size: u64,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            LISTING_CONFIG.as_bytes(),
+            wallet.as_ref(),
+            auction_house.as_ref(),
+            token_account.as_ref(),
+            treasury_mint.as_ref(),
+            token_mint.as_ref(),
+            &token_size.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Authority the Auctioneer signs Auction House CPIs with. It is delegated on the auction house
+/// with [`crate::auction_house::find_auctioneer_pda`].
+pub fn find_auctioneer_authority_address(auction_house: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[AUCTIONEER.as_bytes(), auction_house.as_ref()], &ID)
+}
@@ -0,0 +1,48 @@
+//! PDAs of the Bubblegum program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("BGUMAp9Gq7iTEuizy4pqaxsTyUCBK68MDfK752saRPUY");
+
+pub const VOUCHER_PREFIX: &str = "voucher";
+pub const ASSET_PREFIX: &str = "asset";
+
+/// Tree config that authorizes changes to a merkle tree.
+pub fn find_tree_authority_address(merkle_slab: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_slab.as_ref()], &ID)
+}
+
+pub fn find_mint_authority_request_address(
+    merkle_slab: &Pubkey,
+    mint_authority: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[merkle_slab.as_ref(), mint_authority.as_ref()], &ID)
+}
+
+pub fn find_voucher_address(merkle_slab: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VOUCHER_PREFIX.as_bytes(),
+            merkle_slab.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Mint of a decompressed leaf.
+pub fn find_asset_address(merkle_slab: &Pubkey, nonce: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            ASSET_PREFIX.as_bytes(),
+            merkle_slab.as_ref(),
+            &nonce.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Mint authority of a decompressed leaf's mint.
+pub fn find_asset_mint_authority_address(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[mint.as_ref()], &ID)
+}
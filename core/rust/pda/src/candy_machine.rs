@@ -0,0 +1,17 @@
+//! PDAs of the Candy Machine program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("cndy3Z4yapfJBmL3ShUp5exZKqR3z33thTzeNMm2gRZ");
+
+pub const PREFIX: &str = "candy_machine";
+pub const COLLECTION: &str = "collection";
+
+/// Creator the candy machine signs minted metadata with.
+pub fn find_candy_machine_creator_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), candy_machine.as_ref()], &ID)
+}
+
+pub fn find_collection_pda_address(candy_machine: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[COLLECTION.as_bytes(), candy_machine.as_ref()], &ID)
+}
@@ -0,0 +1,66 @@
+//! PDAs of the Fixed Price Sale program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("SaLeTjyUa5wXHnGuewUSyJ5JWZaHwz3TxqUntCE9czo");
+
+pub const HOLDER_PREFIX: &str = "holder";
+pub const HISTORY_PREFIX: &str = "history";
+pub const VAULT_OWNER_PREFIX: &str = "mt_vault";
+pub const PAYOUT_TICKET_PREFIX: &str = "payout_ticket";
+pub const PRIMARY_METADATA_CREATORS_PREFIX: &str = "primary_creators";
+
+/// Owner of the vault holding the resource of a selling resource.
+pub fn find_vault_owner_address(resource_mint: &Pubkey, store: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            VAULT_OWNER_PREFIX.as_bytes(),
+            resource_mint.as_ref(),
+            store.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Owner of the treasury holder of a market.
+pub fn find_treasury_owner_address(
+    treasury_mint: &Pubkey,
+    selling_resource: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            HOLDER_PREFIX.as_bytes(),
+            treasury_mint.as_ref(),
+            selling_resource.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_trade_history_address(wallet: &Pubkey, market: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[HISTORY_PREFIX.as_bytes(), wallet.as_ref(), market.as_ref()],
+        &ID,
+    )
+}
+
+pub fn find_payout_ticket_address(market: &Pubkey, funder: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PAYOUT_TICKET_PREFIX.as_bytes(),
+            market.as_ref(),
+            funder.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_primary_metadata_creators_address(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PRIMARY_METADATA_CREATORS_PREFIX.as_bytes(),
+            metadata.as_ref(),
+        ],
+        &ID,
+    )
+}
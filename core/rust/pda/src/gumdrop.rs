@@ -0,0 +1,37 @@
+//! PDAs of the Gumdrop program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("gdrpGjVffourzkdDRrQmySw4aTHr8a3xmQzzxSwFD1a");
+
+pub const MERKLE_DISTRIBUTOR: &[u8] = b"MerkleDistributor";
+pub const WALLET: &[u8] = b"Wallet";
+pub const CLAIM_COUNT: &[u8] = b"ClaimCount";
+pub const CLAIM_STATUS: &[u8] = b"ClaimStatus";
+
+pub fn find_distributor_address(base: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[MERKLE_DISTRIBUTOR, base.as_ref()], &ID)
+}
+
+/// Wallet that holds the lamports and signs the CPIs of a distributor.
+pub fn find_distributor_wallet_address(distributor: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[WALLET, distributor.as_ref()], &ID)
+}
+
+pub fn find_claim_proof_address(distributor: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[&index.to_le_bytes(), distributor.as_ref()], &ID)
+}
+
+pub fn find_claim_status_address(distributor: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CLAIM_STATUS, &index.to_le_bytes(), distributor.as_ref()],
+        &ID,
+    )
+}
+
+pub fn find_claim_count_address(distributor: &Pubkey, index: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[CLAIM_COUNT, &index.to_le_bytes(), distributor.as_ref()],
+        &ID,
+    )
+}
@@ -0,0 +1,850 @@
+//! Off-chain derivation of the PDAs of every Metaplex program.
+//!
+//! Each program has a module with its program `ID`, its seed constants and a `find_*` function
+//! per PDA that mirrors the seeds the program checks. [`PDAS`] lists all of them by program and
+//! name, with typed arguments, for tooling that derives addresses from user input.
+
+pub mod auction;
+pub mod auction_house;
+pub mod auctioneer;
+pub mod bubblegum;
+pub mod candy_machine;
+pub mod fixed_price_sale;
+pub mod gumdrop;
+pub mod metaplex;
+pub mod nft_packs;
+pub mod token_entangler;
+pub mod token_metadata;
+pub mod token_vault;
+
+use {solana_program::pubkey::Pubkey, std::str::FromStr, thiserror::Error};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArgKind {
+    Pubkey,
+    U32,
+    U64,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Arg {
+    Pubkey(Pubkey),
+    U32(u32),
+    U64(u64),
+}
+
+impl ArgKind {
+    pub fn parse(&self, value: &str) -> Option<Arg> {
+        match self {
+            ArgKind::Pubkey => Pubkey::from_str(value).ok().map(Arg::Pubkey),
+            ArgKind::U32 => value.parse().ok().map(Arg::U32),
+            ArgKind::U64 => value.parse().ok().map(Arg::U64),
+        }
+    }
+}
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum DeriveError {
+    #[error("Unknown PDA {program} {name}")]
+    UnknownPda { program: String, name: String },
+
+    #[error("{program} {name} takes {expected} arguments, got {given}")]
+    WrongArgumentCount {
+        program: &'static str,
+        name: &'static str,
+        expected: usize,
+        given: usize,
+    },
+
+    #[error("Invalid {kind:?} for argument {arg}: {value}")]
+    InvalidArgument {
+        arg: &'static str,
+        kind: ArgKind,
+        value: String,
+    },
+}
+
+/// A PDA of one of the programs, derived from its arguments in order.
+pub struct PdaDefinition {
+    pub program: &'static str,
+    pub name: &'static str,
+    pub program_id: Pubkey,
+    pub args: &'static [(&'static str, ArgKind)],
+    find: fn(&[Arg]) -> (Pubkey, u8),
+}
+
+impl PdaDefinition {
+    pub fn find(&self, args: &[Arg]) -> Result<(Pubkey, u8), DeriveError> {
+        if args.len() != self.args.len() {
+            return Err(self.wrong_argument_count(args.len()));
+        }
+        for ((arg, kind), value) in self.args.iter().zip(args) {
+            let matches = matches!(
+                (kind, value),
+                (ArgKind::Pubkey, Arg::Pubkey(_))
+                    | (ArgKind::U32, Arg::U32(_))
+                    | (ArgKind::U64, Arg::U64(_))
+            );
+            if !matches {
+                return Err(DeriveError::InvalidArgument {
+                    arg,
+                    kind: *kind,
+                    value: format!("{:?}", value),
+                });
+            }
+        }
+        Ok((self.find)(args))
+    }
+
+    /// Parses the arguments from strings, pubkeys in base58 and integers in decimal.
+    pub fn derive(&self, args: &[&str]) -> Result<(Pubkey, u8), DeriveError> {
+        if args.len() != self.args.len() {
+            return Err(self.wrong_argument_count(args.len()));
+        }
+        let args = self
+            .args
+            .iter()
+            .zip(args)
+            .map(|((arg, kind), value)| {
+                kind.parse(value)
+                    .ok_or_else(|| DeriveError::InvalidArgument {
+                        arg,
+                        kind: *kind,
+                        value: value.to_string(),
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        self.find(&args)
+    }
+
+    fn wrong_argument_count(&self, given: usize) -> DeriveError {
+        DeriveError::WrongArgumentCount {
+            program: self.program,
+            name: self.name,
+            expected: self.args.len(),
+            given,
+        }
+    }
+}
+
+pub fn find_definition(program: &str, name: &str) -> Result<&'static PdaDefinition, DeriveError> {
+    PDAS.iter()
+        .find(|pda| pda.program == program && pda.name == name)
+        .ok_or_else(|| DeriveError::UnknownPda {
+            program: program.to_string(),
+            name: name.to_string(),
+        })
+}
+
+fn key(args: &[Arg], index: usize) -> &Pubkey {
+    match &args[index] {
+        Arg::Pubkey(key) => key,
+        _ => unreachable!(),
+    }
+}
+
+fn u32_arg(args: &[Arg], index: usize) -> u32 {
+    match args[index] {
+        Arg::U32(value) => value,
+        _ => unreachable!(),
+    }
+}
+
+fn u64_arg(args: &[Arg], index: usize) -> u64 {
+    match args[index] {
+        Arg::U64(value) => value,
+        _ => unreachable!(),
+    }
+}
+
+const PUBKEY: ArgKind = ArgKind::Pubkey;
+const U32: ArgKind = ArgKind::U32;
+const U64: ArgKind = ArgKind::U64;
+
+pub static PDAS: &[PdaDefinition] = &[
+    // Token Metadata
+    PdaDefinition {
+        program: "token-metadata",
+        name: "metadata",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY)],
+        find: |a| token_metadata::find_metadata_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "master-edition",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY)],
+        find: |a| token_metadata::find_master_edition_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "edition-marker",
+        program_id: token_metadata::ID,
+        args: &[("master_mint", PUBKEY), ("edition", U64)],
+        find: |a| token_metadata::find_edition_marker_account(key(a, 0), u64_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "use-authority-record",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY), ("authority", PUBKEY)],
+        find: |a| token_metadata::find_use_authority_account(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "collection-authority-record",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY), ("authority", PUBKEY)],
+        find: |a| token_metadata::find_collection_authority_account(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "burner",
+        program_id: token_metadata::ID,
+        args: &[],
+        find: |_| token_metadata::find_program_as_burner_account(),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "rule-set",
+        program_id: token_metadata::ID,
+        args: &[("authority", PUBKEY)],
+        find: |a| token_metadata::find_rule_set_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "update-delegate-record",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY), ("delegate", PUBKEY)],
+        find: |a| token_metadata::find_update_delegate_account(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "metadata-attributes",
+        program_id: token_metadata::ID,
+        args: &[("metadata", PUBKEY)],
+        find: |a| token_metadata::find_metadata_attributes_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "fungible-mint-authority",
+        program_id: token_metadata::ID,
+        args: &[("mint", PUBKEY)],
+        find: |a| token_metadata::find_fungible_mint_authority_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "creator-change-proposal",
+        program_id: token_metadata::ID,
+        args: &[("metadata", PUBKEY)],
+        find: |a| token_metadata::find_creator_change_proposal_account(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-metadata",
+        name: "metadata-history",
+        program_id: token_metadata::ID,
+        args: &[("metadata", PUBKEY)],
+        find: |a| token_metadata::find_metadata_history_account(key(a, 0)),
+    },
+    // Auction House
+    PdaDefinition {
+        program: "auction-house",
+        name: "auction-house",
+        program_id: auction_house::ID,
+        args: &[("authority", PUBKEY), ("treasury_mint", PUBKEY)],
+        find: |a| auction_house::find_auction_house_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "fee-account",
+        program_id: auction_house::ID,
+        args: &[("auction_house", PUBKEY)],
+        find: |a| auction_house::find_auction_house_fee_account_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "treasury",
+        program_id: auction_house::ID,
+        args: &[("auction_house", PUBKEY)],
+        find: |a| auction_house::find_auction_house_treasury_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "escrow-payment",
+        program_id: auction_house::ID,
+        args: &[("auction_house", PUBKEY), ("wallet", PUBKEY)],
+        find: |a| auction_house::find_escrow_payment_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "program-as-signer",
+        program_id: auction_house::ID,
+        args: &[],
+        find: |_| auction_house::find_program_as_signer_address(),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "trade-state",
+        program_id: auction_house::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("token_account", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("price", U64),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auction_house::find_trade_state_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                key(a, 4),
+                u64_arg(a, 5),
+                u64_arg(a, 6),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "public-bid-trade-state",
+        program_id: auction_house::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("price", U64),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auction_house::find_public_bid_trade_state_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                u64_arg(a, 4),
+                u64_arg(a, 5),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "collection-bid-trade-state",
+        program_id: auction_house::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("collection", PUBKEY),
+            ("price", U64),
+            ("quantity", U64),
+        ],
+        find: |a| {
+            auction_house::find_collection_bid_trade_state_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                u64_arg(a, 4),
+                u64_arg(a, 5),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "delegated-bid-trade-state",
+        program_id: auction_house::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("token_account", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("price", U64),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auction_house::find_delegated_bid_trade_state_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                key(a, 4),
+                u64_arg(a, 5),
+                u64_arg(a, 6),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "delegated-allowance",
+        program_id: auction_house::ID,
+        args: &[("payment_account", PUBKEY)],
+        find: |a| auction_house::find_delegated_allowance_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "listing-config",
+        program_id: auction_house::ID,
+        args: &[
+            ("seller", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("token_account", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auction_house::find_listing_config_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                u64_arg(a, 4),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "bundle-listing",
+        program_id: auction_house::ID,
+        args: &[
+            ("seller", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("first_token_mint", PUBKEY),
+        ],
+        find: |a| auction_house::find_bundle_listing_address(key(a, 0), key(a, 1), key(a, 2)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "auctioneer-trade-state",
+        program_id: auction_house::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("token_account", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auction_house::find_auctioneer_trade_state_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                key(a, 4),
+                u64_arg(a, 5),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "bid-receipt",
+        program_id: auction_house::ID,
+        args: &[("trade_state", PUBKEY)],
+        find: |a| auction_house::find_bid_receipt_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "listing-receipt",
+        program_id: auction_house::ID,
+        args: &[("trade_state", PUBKEY)],
+        find: |a| auction_house::find_listing_receipt_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "purchase-receipt",
+        program_id: auction_house::ID,
+        args: &[
+            ("seller_trade_state", PUBKEY),
+            ("buyer_trade_state", PUBKEY),
+        ],
+        find: |a| auction_house::find_purchase_receipt_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "auction-house",
+        name: "auctioneer",
+        program_id: auction_house::ID,
+        args: &[("auction_house", PUBKEY), ("auctioneer_authority", PUBKEY)],
+        find: |a| auction_house::find_auctioneer_pda(key(a, 0), key(a, 1)),
+    },
+    // Auctioneer
+    PdaDefinition {
+        program: "auctioneer",
+        name: "listing-config",
+        program_id: auctioneer::ID,
+        args: &[
+            ("wallet", PUBKEY),
+            ("auction_house", PUBKEY),
+            ("token_account", PUBKEY),
+            ("treasury_mint", PUBKEY),
+            ("token_mint", PUBKEY),
+            ("token_size", U64),
+        ],
+        find: |a| {
+            auctioneer::find_listing_config_address(
+                key(a, 0),
+                key(a, 1),
+                key(a, 2),
+                key(a, 3),
+                key(a, 4),
+                u64_arg(a, 5),
+            )
+        },
+    },
+    PdaDefinition {
+        program: "auctioneer",
+        name: "auctioneer-authority",
+        program_id: auctioneer::ID,
+        args: &[("auction_house", PUBKEY)],
+        find: |a| auctioneer::find_auctioneer_authority_address(key(a, 0)),
+    },
+    // Fixed Price Sale
+    PdaDefinition {
+        program: "fixed-price-sale",
+        name: "vault-owner",
+        program_id: fixed_price_sale::ID,
+        args: &[("resource_mint", PUBKEY), ("store", PUBKEY)],
+        find: |a| fixed_price_sale::find_vault_owner_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "fixed-price-sale",
+        name: "treasury-owner",
+        program_id: fixed_price_sale::ID,
+        args: &[("treasury_mint", PUBKEY), ("selling_resource", PUBKEY)],
+        find: |a| fixed_price_sale::find_treasury_owner_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "fixed-price-sale",
+        name: "trade-history",
+        program_id: fixed_price_sale::ID,
+        args: &[("wallet", PUBKEY), ("market", PUBKEY)],
+        find: |a| fixed_price_sale::find_trade_history_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "fixed-price-sale",
+        name: "payout-ticket",
+        program_id: fixed_price_sale::ID,
+        args: &[("market", PUBKEY), ("funder", PUBKEY)],
+        find: |a| fixed_price_sale::find_payout_ticket_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "fixed-price-sale",
+        name: "primary-metadata-creators",
+        program_id: fixed_price_sale::ID,
+        args: &[("metadata", PUBKEY)],
+        find: |a| fixed_price_sale::find_primary_metadata_creators_address(key(a, 0)),
+    },
+    // Candy Machine
+    PdaDefinition {
+        program: "candy-machine",
+        name: "creator",
+        program_id: candy_machine::ID,
+        args: &[("candy_machine", PUBKEY)],
+        find: |a| candy_machine::find_candy_machine_creator_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "candy-machine",
+        name: "collection",
+        program_id: candy_machine::ID,
+        args: &[("candy_machine", PUBKEY)],
+        find: |a| candy_machine::find_collection_pda_address(key(a, 0)),
+    },
+    // NFT Packs
+    PdaDefinition {
+        program: "nft-packs",
+        name: "program-authority",
+        program_id: nft_packs::ID,
+        args: &[],
+        find: |_| nft_packs::find_program_authority_address(),
+    },
+    PdaDefinition {
+        program: "nft-packs",
+        name: "pack-card",
+        program_id: nft_packs::ID,
+        args: &[("pack_set", PUBKEY), ("index", U32)],
+        find: |a| nft_packs::find_pack_card_address(key(a, 0), u32_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "nft-packs",
+        name: "pack-config",
+        program_id: nft_packs::ID,
+        args: &[("pack_set", PUBKEY)],
+        find: |a| nft_packs::find_pack_config_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "nft-packs",
+        name: "pack-voucher",
+        program_id: nft_packs::ID,
+        args: &[("pack_set", PUBKEY), ("index", U32)],
+        find: |a| nft_packs::find_pack_voucher_address(key(a, 0), u32_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "nft-packs",
+        name: "proving-process",
+        program_id: nft_packs::ID,
+        args: &[
+            ("pack_set", PUBKEY),
+            ("user_wallet", PUBKEY),
+            ("voucher_mint", PUBKEY),
+        ],
+        find: |a| nft_packs::find_proving_process_address(key(a, 0), key(a, 1), key(a, 2)),
+    },
+    // Gumdrop
+    PdaDefinition {
+        program: "gumdrop",
+        name: "distributor",
+        program_id: gumdrop::ID,
+        args: &[("base", PUBKEY)],
+        find: |a| gumdrop::find_distributor_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "gumdrop",
+        name: "distributor-wallet",
+        program_id: gumdrop::ID,
+        args: &[("distributor", PUBKEY)],
+        find: |a| gumdrop::find_distributor_wallet_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "gumdrop",
+        name: "claim-proof",
+        program_id: gumdrop::ID,
+        args: &[("distributor", PUBKEY), ("index", U64)],
+        find: |a| gumdrop::find_claim_proof_address(key(a, 0), u64_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "gumdrop",
+        name: "claim-status",
+        program_id: gumdrop::ID,
+        args: &[("distributor", PUBKEY), ("index", U64)],
+        find: |a| gumdrop::find_claim_status_address(key(a, 0), u64_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "gumdrop",
+        name: "claim-count",
+        program_id: gumdrop::ID,
+        args: &[("distributor", PUBKEY), ("index", U64)],
+        find: |a| gumdrop::find_claim_count_address(key(a, 0), u64_arg(a, 1)),
+    },
+    // Bubblegum
+    PdaDefinition {
+        program: "bubblegum",
+        name: "tree-authority",
+        program_id: bubblegum::ID,
+        args: &[("merkle_slab", PUBKEY)],
+        find: |a| bubblegum::find_tree_authority_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "bubblegum",
+        name: "mint-authority-request",
+        program_id: bubblegum::ID,
+        args: &[("merkle_slab", PUBKEY), ("mint_authority", PUBKEY)],
+        find: |a| bubblegum::find_mint_authority_request_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "bubblegum",
+        name: "voucher",
+        program_id: bubblegum::ID,
+        args: &[("merkle_slab", PUBKEY), ("nonce", U64)],
+        find: |a| bubblegum::find_voucher_address(key(a, 0), u64_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "bubblegum",
+        name: "asset",
+        program_id: bubblegum::ID,
+        args: &[("merkle_slab", PUBKEY), ("nonce", U64)],
+        find: |a| bubblegum::find_asset_address(key(a, 0), u64_arg(a, 1)),
+    },
+    PdaDefinition {
+        program: "bubblegum",
+        name: "asset-mint-authority",
+        program_id: bubblegum::ID,
+        args: &[("mint", PUBKEY)],
+        find: |a| bubblegum::find_asset_mint_authority_address(key(a, 0)),
+    },
+    // Token Entangler
+    PdaDefinition {
+        program: "token-entangler",
+        name: "entangled-pair",
+        program_id: token_entangler::ID,
+        args: &[("mint_a", PUBKEY), ("mint_b", PUBKEY)],
+        find: |a| token_entangler::find_entangled_pair_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-entangler",
+        name: "token-a-escrow",
+        program_id: token_entangler::ID,
+        args: &[("mint_a", PUBKEY), ("mint_b", PUBKEY)],
+        find: |a| token_entangler::find_token_a_escrow_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "token-entangler",
+        name: "token-b-escrow",
+        program_id: token_entangler::ID,
+        args: &[("mint_a", PUBKEY), ("mint_b", PUBKEY)],
+        find: |a| token_entangler::find_token_b_escrow_address(key(a, 0), key(a, 1)),
+    },
+    // Token Vault
+    PdaDefinition {
+        program: "token-vault",
+        name: "vault-authority",
+        program_id: token_vault::ID,
+        args: &[("vault", PUBKEY)],
+        find: |a| token_vault::find_vault_authority_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "token-vault",
+        name: "safety-deposit-box",
+        program_id: token_vault::ID,
+        args: &[("vault", PUBKEY), ("token_mint", PUBKEY)],
+        find: |a| token_vault::find_safety_deposit_box_address(key(a, 0), key(a, 1)),
+    },
+    // Auction
+    PdaDefinition {
+        program: "auction",
+        name: "auction",
+        program_id: auction::ID,
+        args: &[("resource", PUBKEY)],
+        find: |a| auction::find_auction_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction",
+        name: "auction-extended",
+        program_id: auction::ID,
+        args: &[("resource", PUBKEY)],
+        find: |a| auction::find_auction_extended_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "auction",
+        name: "bidder-pot",
+        program_id: auction::ID,
+        args: &[("auction", PUBKEY), ("bidder", PUBKEY)],
+        find: |a| auction::find_bidder_pot_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "auction",
+        name: "bidder-metadata",
+        program_id: auction::ID,
+        args: &[("auction", PUBKEY), ("bidder", PUBKEY)],
+        find: |a| auction::find_bidder_metadata_address(key(a, 0), key(a, 1)),
+    },
+    // Metaplex
+    PdaDefinition {
+        program: "metaplex",
+        name: "store",
+        program_id: metaplex::ID,
+        args: &[("admin", PUBKEY)],
+        find: |a| metaplex::find_store_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "store-config",
+        program_id: metaplex::ID,
+        args: &[("store", PUBKEY)],
+        find: |a| metaplex::find_store_config_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "whitelisted-creator",
+        program_id: metaplex::ID,
+        args: &[("store", PUBKEY), ("creator", PUBKEY)],
+        find: |a| metaplex::find_whitelisted_creator_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "auction-manager",
+        program_id: metaplex::ID,
+        args: &[("auction", PUBKEY)],
+        find: |a| metaplex::find_auction_manager_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "auction-winner-token-type-tracker",
+        program_id: metaplex::ID,
+        args: &[("auction_manager", PUBKEY)],
+        find: |a| metaplex::find_auction_winner_token_type_tracker_address(key(a, 0)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "safety-deposit-config",
+        program_id: metaplex::ID,
+        args: &[("auction_manager", PUBKEY), ("safety_deposit_box", PUBKEY)],
+        find: |a| metaplex::find_safety_deposit_config_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "prize-tracking-ticket",
+        program_id: metaplex::ID,
+        args: &[("auction_manager", PUBKEY), ("original_mint", PUBKEY)],
+        find: |a| metaplex::find_prize_tracking_ticket_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "auction-cache",
+        program_id: metaplex::ID,
+        args: &[("store", PUBKEY), ("auction", PUBKEY)],
+        find: |a| metaplex::find_auction_cache_address(key(a, 0), key(a, 1)),
+    },
+    PdaDefinition {
+        program: "metaplex",
+        name: "store-indexer",
+        program_id: metaplex::ID,
+        args: &[("store", PUBKEY), ("page", U64)],
+        find: |a| metaplex::find_store_indexer_address(key(a, 0), u64_arg(a, 1)),
+    },
+];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn definitions_are_unique() {
+        for (i, pda) in PDAS.iter().enumerate() {
+            assert!(
+                PDAS[i + 1..]
+                    .iter()
+                    .all(|other| (other.program, other.name) != (pda.program, pda.name)),
+                "duplicate definition {} {}",
+                pda.program,
+                pda.name
+            );
+        }
+    }
+
+    #[test]
+    fn derive_parses_arguments() {
+        let mint = Pubkey::new_unique();
+        let pda = find_definition("token-metadata", "edition-marker").unwrap();
+
+        assert_eq!(
+            pda.derive(&[&mint.to_string(), "500"]),
+            Ok(token_metadata::find_edition_marker_account(&mint, 500))
+        );
+        assert_eq!(
+            pda.derive(&[&mint.to_string()]),
+            Err(DeriveError::WrongArgumentCount {
+                program: "token-metadata",
+                name: "edition-marker",
+                expected: 2,
+                given: 1,
+            })
+        );
+        assert_eq!(
+            pda.derive(&[&mint.to_string(), "-1"]),
+            Err(DeriveError::InvalidArgument {
+                arg: "edition",
+                kind: ArgKind::U64,
+                value: "-1".to_string(),
+            })
+        );
+        assert!(matches!(
+            find_definition("token-metadata", "mystery"),
+            Err(DeriveError::UnknownPda { .. })
+        ));
+    }
+}
@@ -0,0 +1,112 @@
+//! PDAs of the Metaplex auction manager program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("p1exdMJcjVao65QdewkaZRUnU6VPSXhus9n2GzWfh98");
+
+pub const PREFIX: &str = "metaplex";
+pub const TOTALS: &str = "totals";
+pub const INDEX: &str = "index";
+pub const CACHE: &str = "cache";
+pub const CONFIG: &str = "config";
+
+pub fn find_store_address(admin: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), admin.as_ref()], &ID)
+}
+
+pub fn find_store_config_address(store: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            CONFIG.as_bytes(),
+            store.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_whitelisted_creator_address(store: &Pubkey, creator: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            store.as_ref(),
+            creator.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+/// Auction manager of `auction`. The program id is not part of the seeds.
+pub fn find_auction_manager_address(auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), auction.as_ref()], &ID)
+}
+
+pub fn find_auction_winner_token_type_tracker_address(auction_manager: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            auction_manager.as_ref(),
+            TOTALS.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_safety_deposit_config_address(
+    auction_manager: &Pubkey,
+    safety_deposit_box: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            auction_manager.as_ref(),
+            safety_deposit_box.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_prize_tracking_ticket_address(
+    auction_manager: &Pubkey,
+    original_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            auction_manager.as_ref(),
+            original_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_auction_cache_address(store: &Pubkey, auction: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            store.as_ref(),
+            auction.as_ref(),
+            CACHE.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_store_indexer_address(store: &Pubkey, page: u64) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            store.as_ref(),
+            INDEX.as_bytes(),
+            page.to_string().as_bytes(),
+        ],
+        &ID,
+    )
+}
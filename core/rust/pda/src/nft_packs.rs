@@ -0,0 +1,58 @@
+//! PDAs of the NFT Packs program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("packFeFNZzMfD9aVWL7QbGz1WcU7R9zpf6pvNsw2BLu");
+
+pub const PREFIX: &str = "packs";
+pub const PACK_CARD_PREFIX: &str = "card";
+pub const PACK_CONFIG_PREFIX: &str = "config";
+pub const PACK_VOUCHER_PREFIX: &str = "voucher";
+pub const PROVING_PROCESS_PREFIX: &str = "proving";
+
+/// Authority that owns the vouchers and cards of every pack set.
+pub fn find_program_authority_address() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref()], &ID)
+}
+
+pub fn find_pack_card_address(pack_set: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PACK_CARD_PREFIX.as_bytes(),
+            pack_set.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_pack_config_address(pack_set: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PACK_CONFIG_PREFIX.as_bytes(), pack_set.as_ref()], &ID)
+}
+
+pub fn find_pack_voucher_address(pack_set: &Pubkey, index: u32) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PACK_VOUCHER_PREFIX.as_bytes(),
+            pack_set.as_ref(),
+            &index.to_le_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_proving_process_address(
+    pack_set: &Pubkey,
+    user_wallet: &Pubkey,
+    voucher_mint: &Pubkey,
+) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PROVING_PROCESS_PREFIX.as_bytes(),
+            pack_set.as_ref(),
+            user_wallet.as_ref(),
+            voucher_mint.as_ref(),
+        ],
+        &ID,
+    )
+}
@@ -0,0 +1,41 @@
+//! PDAs of the Token Entangler program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("qntmGodpGkrM42mN68VCZHXnKqDCT8rdY23wFcXCLPd");
+
+pub const PREFIX: &str = "token_entangler";
+pub const ESCROW: &str = "escrow";
+pub const A_NAME: &str = "A";
+pub const B_NAME: &str = "B";
+
+/// Entangled pair of `mint_a` and `mint_b`. The reverse pair is this with the mints swapped.
+pub fn find_entangled_pair_address(mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), mint_a.as_ref(), mint_b.as_ref()], &ID)
+}
+
+pub fn find_token_a_escrow_address(mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            ESCROW.as_bytes(),
+            A_NAME.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_token_b_escrow_address(mint_a: &Pubkey, mint_b: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            mint_a.as_ref(),
+            mint_b.as_ref(),
+            ESCROW.as_bytes(),
+            B_NAME.as_bytes(),
+        ],
+        &ID,
+    )
+}
@@ -0,0 +1,154 @@
+//! PDAs of the Token Metadata program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("metaqbxxUerdq28cj1RbAWkYQm3ybzjb6a8bt518x1s");
+
+pub const PREFIX: &str = "metadata";
+pub const EDITION: &str = "edition";
+pub const USER: &str = "user";
+pub const BURN: &str = "burn";
+pub const COLLECTION_AUTHORITY: &str = "collection_authority";
+pub const RULE_SET: &str = "rule_set";
+pub const UPDATE_DELEGATE: &str = "update_delegate";
+pub const ATTRIBUTES: &str = "attributes";
+pub const MINT_AUTHORITY: &str = "mint_authority";
+pub const CREATOR_CHANGE: &str = "creator_change";
+pub const HISTORY: &str = "history";
+/// Number of editions tracked by one edition marker account.
+pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
+
+pub fn find_metadata_account(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), mint.as_ref()], &ID)
+}
+
+/// Master Edition of a mint, which is also where the Edition of a print lives.
+pub fn find_master_edition_account(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            EDITION.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+/// Edition marker of the master `mint` that tracks whether print `edition` exists.
+pub fn find_edition_marker_account(mint: &Pubkey, edition: u64) -> (Pubkey, u8) {
+    let marker_number = (edition / EDITION_MARKER_BIT_SIZE).to_string();
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            EDITION.as_bytes(),
+            marker_number.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_use_authority_account(mint: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            USER.as_bytes(),
+            authority.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_collection_authority_account(mint: &Pubkey, authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            COLLECTION_AUTHORITY.as_bytes(),
+            authority.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_program_as_burner_account() -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), BURN.as_bytes()], &ID)
+}
+
+pub fn find_rule_set_account(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            RULE_SET.as_bytes(),
+            authority.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_update_delegate_account(mint: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            UPDATE_DELEGATE.as_bytes(),
+            delegate.as_ref(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_metadata_attributes_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            metadata.as_ref(),
+            ATTRIBUTES.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_fungible_mint_authority_account(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            mint.as_ref(),
+            MINT_AUTHORITY.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_creator_change_proposal_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            metadata.as_ref(),
+            CREATOR_CHANGE.as_bytes(),
+        ],
+        &ID,
+    )
+}
+
+pub fn find_metadata_history_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            ID.as_ref(),
+            metadata.as_ref(),
+            HISTORY.as_bytes(),
+        ],
+        &ID,
+    )
+}
@@ -0,0 +1,20 @@
+//! PDAs of the Token Vault program.
+
+use solana_program::pubkey::Pubkey;
+
+solana_program::declare_id!("vau1zxA2LbssAUEF7Gpw91zMM1LvXrvpzJtmZ58rPsn");
+
+pub const PREFIX: &str = "vault";
+
+pub fn find_vault_authority_address(vault: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(&[PREFIX.as_bytes(), ID.as_ref(), vault.as_ref()], &ID)
+}
+
+/// Safety deposit box of `token_mint` in a vault. Unlike the vault authority, the program id is
+/// not part of the seeds.
+pub fn find_safety_deposit_box_address(vault: &Pubkey, token_mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[PREFIX.as_bytes(), vault.as_ref(), token_mint.as_ref()],
+        &ID,
+    )
+}
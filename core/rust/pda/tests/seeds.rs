@@ -0,0 +1,630 @@
+//! Compares every derivation with the seeds of the program itself, through the program's own
+//! helpers where it has them and its seed constants otherwise.
+
+use {mpl_pda::*, proptest::prelude::*, solana_program::pubkey::Pubkey};
+
+fn pubkey() -> impl Strategy<Value = Pubkey> {
+    any::<[u8; 32]>().prop_map(Pubkey::new_from_array)
+}
+
+fn config() -> ProptestConfig {
+    ProptestConfig::with_cases(64)
+}
+
+#[test]
+fn program_ids() {
+    assert_eq!(token_metadata::ID, mpl_token_metadata::id());
+    assert_eq!(auction_house::ID, mpl_auction_house::id());
+    assert_eq!(auctioneer::ID, mpl_auctioneer::id());
+    assert_eq!(fixed_price_sale::ID, mpl_fixed_price_sale::id());
+    assert_eq!(candy_machine::ID, mpl_candy_machine::id());
+    assert_eq!(nft_packs::ID, mpl_nft_packs::id());
+    assert_eq!(token_vault::ID, mpl_token_vault::id());
+    assert_eq!(auction::ID, mpl_auction::id());
+    assert_eq!(metaplex::ID, mpl_metaplex::id());
+    assert_eq!(gumdrop::ID, mpl_gumdrop::id());
+    assert_eq!(bubblegum::ID, mpl_bubblegum::id());
+    assert_eq!(token_entangler::ID, mpl_token_entangler::id());
+}
+
+#[test]
+fn every_definition_derives() {
+    for pda in PDAS {
+        let args: Vec<Arg> = pda
+            .args
+            .iter()
+            .map(|(_, kind)| match kind {
+                ArgKind::Pubkey => Arg::Pubkey(Pubkey::new_unique()),
+                ArgKind::U32 => Arg::U32(7),
+                ArgKind::U64 => Arg::U64(7),
+            })
+            .collect();
+        let (address, _) = pda.find(&args).unwrap();
+        assert!(!address.is_on_curve(), "{} {}", pda.program, pda.name);
+    }
+}
+
+mod token_metadata_seeds {
+    use {
+        super::*,
+        mpl_token_metadata::{pda, state::EDITION_MARKER_BIT_SIZE},
+    };
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn mint_accounts(mint in pubkey(), authority in pubkey(), edition in any::<u64>()) {
+            prop_assert_eq!(
+                token_metadata::find_metadata_account(&mint),
+                pda::find_metadata_account(&mint)
+            );
+            prop_assert_eq!(
+                token_metadata::find_master_edition_account(&mint),
+                pda::find_master_edition_account(&mint)
+            );
+            prop_assert_eq!(
+                token_metadata::find_edition_marker_account(&mint, edition),
+                pda::find_edition_account(&mint, (edition / EDITION_MARKER_BIT_SIZE).to_string())
+            );
+            prop_assert_eq!(
+                token_metadata::find_use_authority_account(&mint, &authority),
+                pda::find_use_authority_account(&mint, &authority)
+            );
+            prop_assert_eq!(
+                token_metadata::find_collection_authority_account(&mint, &authority),
+                pda::find_collection_authority_account(&mint, &authority)
+            );
+            prop_assert_eq!(
+                token_metadata::find_update_delegate_account(&mint, &authority),
+                pda::find_update_delegate_account(&mint, &authority)
+            );
+            prop_assert_eq!(
+                token_metadata::find_fungible_mint_authority_account(&mint),
+                pda::find_fungible_mint_authority_account(&mint)
+            );
+            prop_assert_eq!(
+                token_metadata::find_rule_set_account(&authority),
+                pda::find_rule_set_account(&authority)
+            );
+        }
+
+        #[test]
+        fn metadata_accounts(metadata in pubkey()) {
+            prop_assert_eq!(
+                token_metadata::find_metadata_attributes_account(&metadata),
+                pda::find_metadata_attributes_account(&metadata)
+            );
+            prop_assert_eq!(
+                token_metadata::find_creator_change_proposal_account(&metadata),
+                pda::find_creator_change_proposal_account(&metadata)
+            );
+            prop_assert_eq!(
+                token_metadata::find_metadata_history_account(&metadata),
+                pda::find_metadata_history_account(&metadata)
+            );
+        }
+    }
+
+    #[test]
+    fn burner() {
+        assert_eq!(
+            token_metadata::find_program_as_burner_account(),
+            pda::find_program_as_burner_account()
+        );
+    }
+}
+
+mod auction_house_seeds {
+    use {super::*, mpl_auction_house::pda};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn house_accounts(authority in pubkey(), treasury_mint in pubkey(), wallet in pubkey()) {
+            let (auction_house, _) = pda::find_auction_house_address(&authority, &treasury_mint);
+            prop_assert_eq!(
+                auction_house::find_auction_house_address(&authority, &treasury_mint).0,
+                auction_house
+            );
+            prop_assert_eq!(
+                auction_house::find_auction_house_fee_account_address(&auction_house),
+                pda::find_auction_house_fee_account_address(&auction_house)
+            );
+            prop_assert_eq!(
+                auction_house::find_auction_house_treasury_address(&auction_house),
+                pda::find_auction_house_treasury_address(&auction_house)
+            );
+            prop_assert_eq!(
+                auction_house::find_escrow_payment_address(&auction_house, &wallet),
+                pda::find_escrow_payment_address(&auction_house, &wallet)
+            );
+            prop_assert_eq!(
+                auction_house::find_auctioneer_pda(&auction_house, &wallet),
+                pda::find_auctioneer_pda(&auction_house, &wallet)
+            );
+        }
+
+        #[test]
+        fn trade_states(
+            keys in prop::array::uniform5(pubkey()),
+            price in any::<u64>(),
+            token_size in any::<u64>(),
+        ) {
+            let [wallet, house, token_account, treasury_mint, token_mint] = keys;
+            let (trade_state, _) = pda::find_trade_state_address(
+                &wallet, &house, &token_account, &treasury_mint, &token_mint, price, token_size,
+            );
+            prop_assert_eq!(
+                auction_house::find_trade_state_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, price, token_size,
+                ).0,
+                trade_state
+            );
+            prop_assert_eq!(
+                auction_house::find_public_bid_trade_state_address(
+                    &wallet, &house, &treasury_mint, &token_mint, price, token_size,
+                ),
+                pda::find_public_bid_trade_state_address(
+                    &wallet, &house, &treasury_mint, &token_mint, price, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auction_house::find_collection_bid_trade_state_address(
+                    &wallet, &house, &treasury_mint, &token_mint, price, token_size,
+                ),
+                pda::find_collection_bid_trade_state_address(
+                    &wallet, &house, &treasury_mint, &token_mint, price, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auction_house::find_delegated_bid_trade_state_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, price, token_size,
+                ),
+                pda::find_delegated_bid_trade_state_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, price, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auction_house::find_delegated_allowance_address(&token_account),
+                pda::find_delegated_allowance_address(&token_account)
+            );
+            prop_assert_eq!(
+                auction_house::find_listing_config_address(
+                    &wallet, &house, &token_account, &token_mint, token_size,
+                ),
+                pda::find_listing_config_address(
+                    &wallet, &house, &token_account, &token_mint, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auction_house::find_bundle_listing_address(&wallet, &house, &token_mint),
+                pda::find_bundle_listing_address(&wallet, &house, &token_mint)
+            );
+            prop_assert_eq!(
+                auction_house::find_auctioneer_trade_state_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, token_size,
+                ),
+                pda::find_auctioneer_trade_state_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auction_house::find_bid_receipt_address(&trade_state),
+                pda::find_bid_receipt_address(&trade_state)
+            );
+            prop_assert_eq!(
+                auction_house::find_listing_receipt_address(&trade_state),
+                pda::find_listing_receipt_address(&trade_state)
+            );
+            prop_assert_eq!(
+                auction_house::find_purchase_receipt_address(&trade_state, &wallet),
+                pda::find_purchase_receipt_address(&trade_state, &wallet)
+            );
+        }
+    }
+
+    #[test]
+    fn program_as_signer() {
+        assert_eq!(
+            auction_house::find_program_as_signer_address(),
+            pda::find_program_as_signer_address()
+        );
+    }
+}
+
+mod auctioneer_seeds {
+    use {super::*, mpl_auctioneer::pda};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn auctioneer_accounts(keys in prop::array::uniform5(pubkey()), token_size in any::<u64>()) {
+            let [wallet, house, token_account, treasury_mint, token_mint] = keys;
+            prop_assert_eq!(
+                auctioneer::find_listing_config_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, token_size,
+                ),
+                pda::find_listing_config_address(
+                    &wallet, &house, &token_account, &treasury_mint, &token_mint, token_size,
+                )
+            );
+            prop_assert_eq!(
+                auctioneer::find_auctioneer_authority_address(&house),
+                pda::find_auctioneer_authority_seeds(&house)
+            );
+        }
+    }
+}
+
+mod fixed_price_sale_seeds {
+    use {super::*, mpl_fixed_price_sale::utils::*};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn sale_accounts(a in pubkey(), b in pubkey()) {
+            let id = mpl_fixed_price_sale::id();
+            prop_assert_eq!(
+                fixed_price_sale::find_vault_owner_address(&a, &b),
+                Pubkey::find_program_address(&[VAULT_OWNER_PREFIX.as_bytes(), a.as_ref(), b.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                fixed_price_sale::find_treasury_owner_address(&a, &b),
+                Pubkey::find_program_address(&[HOLDER_PREFIX.as_bytes(), a.as_ref(), b.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                fixed_price_sale::find_trade_history_address(&a, &b),
+                Pubkey::find_program_address(&[HISTORY_PREFIX.as_bytes(), a.as_ref(), b.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                fixed_price_sale::find_payout_ticket_address(&a, &b),
+                Pubkey::find_program_address(&[PAYOUT_TICKET_PREFIX.as_bytes(), a.as_ref(), b.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                fixed_price_sale::find_primary_metadata_creators_address(&a),
+                Pubkey::find_program_address(
+                    &[PRIMARY_METADATA_CREATORS_PREFIX.as_bytes(), a.as_ref()],
+                    &id
+                )
+            );
+        }
+    }
+}
+
+mod candy_machine_seeds {
+    use {super::*, mpl_candy_machine::constants::PREFIX};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn candy_machine_accounts(candy_machine in pubkey()) {
+            let id = mpl_candy_machine::id();
+            prop_assert_eq!(
+                candy_machine::find_candy_machine_creator_address(&candy_machine),
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), candy_machine.as_ref()], &id)
+            );
+            // The collection PDA seed is inlined in the program's account constraints.
+            prop_assert_eq!(
+                candy_machine::find_collection_pda_address(&candy_machine),
+                Pubkey::find_program_address(&[b"collection", candy_machine.as_ref()], &id)
+            );
+        }
+    }
+}
+
+mod nft_packs_seeds {
+    use super::*;
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn pack_accounts(pack_set in pubkey(), wallet in pubkey(), mint in pubkey(), index in any::<u32>()) {
+            let id = mpl_nft_packs::id();
+            prop_assert_eq!(
+                nft_packs::find_pack_card_address(&pack_set, index),
+                mpl_nft_packs::find_pack_card_program_address(&id, &pack_set, index)
+            );
+            prop_assert_eq!(
+                nft_packs::find_pack_config_address(&pack_set),
+                mpl_nft_packs::find_pack_config_program_address(&id, &pack_set)
+            );
+            prop_assert_eq!(
+                nft_packs::find_pack_voucher_address(&pack_set, index),
+                mpl_nft_packs::find_pack_voucher_program_address(&id, &pack_set, index)
+            );
+            prop_assert_eq!(
+                nft_packs::find_proving_process_address(&pack_set, &wallet, &mint),
+                mpl_nft_packs::find_proving_process_program_address(&id, &pack_set, &wallet, &mint)
+            );
+        }
+    }
+
+    #[test]
+    fn program_authority() {
+        assert_eq!(
+            nft_packs::find_program_authority_address(),
+            mpl_nft_packs::find_program_authority(&mpl_nft_packs::id())
+        );
+    }
+}
+
+mod token_vault_seeds {
+    use {super::*, mpl_token_vault::state::PREFIX};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn vault_accounts(vault in pubkey(), mint in pubkey()) {
+            let id = mpl_token_vault::id();
+            prop_assert_eq!(
+                token_vault::find_vault_authority_address(&vault),
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), id.as_ref(), vault.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                token_vault::find_safety_deposit_box_address(&vault, &mint),
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), vault.as_ref(), mint.as_ref()], &id)
+            );
+        }
+    }
+}
+
+mod auction_seeds {
+    use {
+        super::*,
+        mpl_auction::{EXTENDED, PREFIX},
+    };
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn auction_accounts(resource in pubkey(), bidder in pubkey()) {
+            let id = mpl_auction::id();
+            let (auction, _) = auction::find_auction_address(&resource);
+            prop_assert_eq!(
+                auction,
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), id.as_ref(), resource.as_ref()], &id).0
+            );
+            prop_assert_eq!(
+                auction::find_auction_extended_address(&resource),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), resource.as_ref(), EXTENDED.as_bytes()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                auction::find_bidder_pot_address(&auction, &bidder),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), auction.as_ref(), bidder.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                auction::find_bidder_metadata_address(&auction, &bidder),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), auction.as_ref(), bidder.as_ref(), b"metadata"],
+                    &id
+                )
+            );
+        }
+    }
+}
+
+mod metaplex_seeds {
+    use {super::*, mpl_metaplex::state::*};
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn store_accounts(admin in pubkey(), other in pubkey(), page in any::<u64>()) {
+            let id = mpl_metaplex::id();
+            let (store, _) = metaplex::find_store_address(&admin);
+            prop_assert_eq!(
+                store,
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), id.as_ref(), admin.as_ref()], &id).0
+            );
+            prop_assert_eq!(
+                metaplex::find_store_config_address(&store),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), CONFIG.as_bytes(), store.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                metaplex::find_whitelisted_creator_address(&store, &other),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), store.as_ref(), other.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                metaplex::find_auction_cache_address(&store, &other),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), store.as_ref(), other.as_ref(), CACHE.as_bytes()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                metaplex::find_store_indexer_address(&store, page),
+                Pubkey::find_program_address(
+                    &[
+                        PREFIX.as_bytes(),
+                        id.as_ref(),
+                        store.as_ref(),
+                        INDEX.as_bytes(),
+                        page.to_string().as_bytes(),
+                    ],
+                    &id
+                )
+            );
+        }
+
+        #[test]
+        fn auction_manager_accounts(auction in pubkey(), other in pubkey()) {
+            let id = mpl_metaplex::id();
+            let (manager, _) = metaplex::find_auction_manager_address(&auction);
+            prop_assert_eq!(
+                manager,
+                Pubkey::find_program_address(&[PREFIX.as_bytes(), auction.as_ref()], &id).0
+            );
+            prop_assert_eq!(
+                metaplex::find_auction_winner_token_type_tracker_address(&manager),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), manager.as_ref(), TOTALS.as_bytes()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                metaplex::find_safety_deposit_config_address(&manager, &other),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), id.as_ref(), manager.as_ref(), other.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                metaplex::find_prize_tracking_ticket_address(&manager, &other),
+                metaplex::find_safety_deposit_config_address(&manager, &other)
+            );
+        }
+    }
+}
+
+mod gumdrop_seeds {
+    use {
+        super::*,
+        mpl_gumdrop::{CLAIM_COUNT, CLAIM_STATUS, MERKLE_DISTRIBUTOR, WALLET},
+    };
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn distributor_accounts(base in pubkey(), index in any::<u64>()) {
+            let id = mpl_gumdrop::id();
+            let (distributor, _) = gumdrop::find_distributor_address(&base);
+            prop_assert_eq!(
+                distributor,
+                Pubkey::find_program_address(&[MERKLE_DISTRIBUTOR, base.as_ref()], &id).0
+            );
+            prop_assert_eq!(
+                gumdrop::find_distributor_wallet_address(&distributor),
+                Pubkey::find_program_address(&[WALLET, distributor.as_ref()], &id)
+            );
+            // The claim proof has no prefix of its own.
+            prop_assert_eq!(
+                gumdrop::find_claim_proof_address(&distributor, index),
+                Pubkey::find_program_address(&[&index.to_le_bytes(), distributor.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                gumdrop::find_claim_status_address(&distributor, index),
+                Pubkey::find_program_address(
+                    &[CLAIM_STATUS, &index.to_le_bytes(), distributor.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                gumdrop::find_claim_count_address(&distributor, index),
+                Pubkey::find_program_address(
+                    &[CLAIM_COUNT, &index.to_le_bytes(), distributor.as_ref()],
+                    &id
+                )
+            );
+        }
+    }
+}
+
+mod bubblegum_seeds {
+    use {
+        super::*,
+        mpl_bubblegum::state::{ASSET_PREFIX, VOUCHER_PREFIX},
+    };
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn tree_accounts(merkle_slab in pubkey(), authority in pubkey(), nonce in any::<u64>()) {
+            let id = mpl_bubblegum::id();
+            // The tree authority and mint authority requests have no prefix of their own.
+            prop_assert_eq!(
+                bubblegum::find_tree_authority_address(&merkle_slab),
+                Pubkey::find_program_address(&[merkle_slab.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                bubblegum::find_mint_authority_request_address(&merkle_slab, &authority),
+                Pubkey::find_program_address(&[merkle_slab.as_ref(), authority.as_ref()], &id)
+            );
+            prop_assert_eq!(
+                bubblegum::find_voucher_address(&merkle_slab, nonce),
+                Pubkey::find_program_address(
+                    &[VOUCHER_PREFIX.as_bytes(), merkle_slab.as_ref(), &nonce.to_le_bytes()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                bubblegum::find_asset_address(&merkle_slab, nonce),
+                Pubkey::find_program_address(
+                    &[ASSET_PREFIX.as_bytes(), merkle_slab.as_ref(), &nonce.to_le_bytes()],
+                    &id
+                )
+            );
+        }
+    }
+}
+
+mod token_entangler_seeds {
+    use {
+        super::*,
+        mpl_token_entangler::{A_NAME, B_NAME, ESCROW, PREFIX},
+    };
+
+    proptest! {
+        #![proptest_config(config())]
+
+        #[test]
+        fn pair_accounts(mint_a in pubkey(), mint_b in pubkey()) {
+            let id = mpl_token_entangler::id();
+            prop_assert_eq!(
+                token_entangler::find_entangled_pair_address(&mint_a, &mint_b),
+                Pubkey::find_program_address(
+                    &[PREFIX.as_bytes(), mint_a.as_ref(), mint_b.as_ref()],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                token_entangler::find_token_a_escrow_address(&mint_a, &mint_b),
+                Pubkey::find_program_address(
+                    &[
+                        PREFIX.as_bytes(),
+                        mint_a.as_ref(),
+                        mint_b.as_ref(),
+                        ESCROW.as_bytes(),
+                        A_NAME.as_bytes(),
+                    ],
+                    &id
+                )
+            );
+            prop_assert_eq!(
+                token_entangler::find_token_b_escrow_address(&mint_a, &mint_b),
+                Pubkey::find_program_address(
+                    &[
+                        PREFIX.as_bytes(),
+                        mint_a.as_ref(),
+                        mint_b.as_ref(),
+                        ESCROW.as_bytes(),
+                        B_NAME.as_bytes(),
+                    ],
+                    &id
+                )
+            );
+        }
+    }
+}
@@ -16,7 +16,7 @@ macro_rules! assert_transport_error {
 #[macro_export]
 macro_rules! assert_error {
     ($error:expr, $matcher:expr) => {
-        match $error {
+        match TransportError::from($error) {
             TransportError::TransactionError(TransactionError::InstructionError(
                 0,
                 InstructionError::Custom(x),
@@ -29,7 +29,7 @@ macro_rules! assert_error {
 #[macro_export]
 macro_rules! assert_custom_error {
     ($error:expr, $matcher:pat) => {
-        match $error {
+        match TransportError::from($error) {
             TransportError::TransactionError(TransactionError::InstructionError(
                 0,
                 InstructionError::Custom(x),
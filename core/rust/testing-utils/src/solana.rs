@@ -124,7 +124,11 @@ pub async fn mint_tokens(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub async fn create_token_account(
@@ -157,7 +161,11 @@ pub async fn create_token_account(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub async fn create_mint(
@@ -191,7 +199,11 @@ pub async fn create_mint(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub async fn transfer(
@@ -220,5 +232,9 @@ pub async fn transfer(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
@@ -57,7 +57,11 @@ pub async fn mint_tokens(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub async fn create_token_account(
@@ -90,7 +94,11 @@ pub async fn create_token_account(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
 
 pub async fn create_mint(
@@ -124,5 +132,9 @@ pub async fn create_mint(
         context.last_blockhash,
     );
 
-    context.banks_client.process_transaction(tx).await
+    context
+        .banks_client
+        .process_transaction(tx)
+        .await
+        .map_err(Into::into)
 }
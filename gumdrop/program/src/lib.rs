@@ -33,8 +33,10 @@ fn verify_candy(candy_machine_program_account: &Pubkey) -> Result<()> {
     Ok(())
 }
 
-const CLAIM_COUNT: &[u8] = b"ClaimCount";
-const CLAIM_STATUS: &[u8] = b"ClaimStatus";
+pub const MERKLE_DISTRIBUTOR: &[u8] = b"MerkleDistributor";
+pub const WALLET: &[u8] = b"Wallet";
+pub const CLAIM_COUNT: &[u8] = b"ClaimCount";
+pub const CLAIM_STATUS: &[u8] = b"ClaimStatus";
 
 fn verify_temporal<'a>(
     distributor: &Account<'a, MerkleDistributor>,
@@ -188,7 +190,7 @@ pub mod gumdrop {
         );
 
         let seeds = [
-            b"MerkleDistributor".as_ref(),
+            MERKLE_DISTRIBUTOR.as_ref(),
             &distributor.base.to_bytes(),
             &[ctx.accounts.distributor.bump],
         ];
@@ -239,7 +241,7 @@ pub mod gumdrop {
         );
 
         let wallet_seeds = [
-            b"Wallet".as_ref(),
+            WALLET.as_ref(),
             &distributor.key().to_bytes(),
             &[_wallet_bump],
         ];
@@ -409,7 +411,7 @@ pub mod gumdrop {
         claim_status.claimant = ctx.accounts.payer.key();
 
         let seeds = [
-            b"MerkleDistributor".as_ref(),
+            MERKLE_DISTRIBUTOR.as_ref(),
             &distributor.base.to_bytes(),
             &[ctx.accounts.distributor.bump],
         ];
@@ -566,7 +568,7 @@ pub mod gumdrop {
             .ok_or(GumdropError::NumericalOverflow)?;
 
         let seeds = [
-            b"MerkleDistributor".as_ref(),
+            MERKLE_DISTRIBUTOR.as_ref(),
             &distributor.base.to_bytes(),
             &[ctx.accounts.distributor.bump],
         ];
@@ -681,7 +683,7 @@ pub mod gumdrop {
         wallet_bump: u8,
     ) -> Result<()> {
         let wallet_seeds = [
-            b"Wallet".as_ref(),
+            WALLET.as_ref(),
             &ctx.accounts.distributor.key().to_bytes(),
             &[wallet_bump],
         ];
@@ -763,7 +765,7 @@ fn issue_mint_nft<'info>(
     )?;
 
     let wallet_seeds = [
-        b"Wallet".as_ref(),
+        WALLET.as_ref(),
         &distributor.key().to_bytes(),
         &[wallet_bump],
     ];
@@ -850,7 +852,7 @@ pub struct NewDistributor<'info> {
     #[account(
     init,
     seeds = [
-    b"MerkleDistributor".as_ref(),
+    MERKLE_DISTRIBUTOR.as_ref(),
     base.key().to_bytes().as_ref()
     ],
     space = 8+97,
@@ -877,7 +879,7 @@ pub struct CloseDistributorTokenAccount<'info> {
     /// [MerkleDistributor].
     #[account(
     seeds = [
-    b"MerkleDistributor".as_ref(),
+    MERKLE_DISTRIBUTOR.as_ref(),
     base.key().to_bytes().as_ref()
     ],
     bump = _bump,
@@ -914,7 +916,7 @@ pub struct CloseDistributor<'info> {
     /// [MerkleDistributor].
     #[account(
     seeds = [
-    b"MerkleDistributor".as_ref(),
+    MERKLE_DISTRIBUTOR.as_ref(),
     base.key().to_bytes().as_ref()
     ],
     bump = _bump,
@@ -925,7 +927,7 @@ pub struct CloseDistributor<'info> {
 
     #[account(
     seeds = [
-    b"Wallet".as_ref(),
+    WALLET.as_ref(),
     distributor.key().to_bytes().as_ref()
     ],
     bump = _wallet_bump,
@@ -1046,7 +1048,7 @@ pub struct ClaimCandy<'info> {
     /// The [MerkleDistributor] wallet
     #[account(
     seeds = [
-    b"Wallet".as_ref(),
+    WALLET.as_ref(),
     distributor.key().to_bytes().as_ref()
     ],
     bump = _wallet_bump,
@@ -1230,7 +1232,7 @@ pub struct ClaimCandyProven<'info> {
     /// The [MerkleDistributor] wallet
     #[account(
     seeds = [
-    b"Wallet".as_ref(),
+    WALLET.as_ref(),
     distributor.key().to_bytes().as_ref()
     ],
     bump = wallet_bump,
@@ -1313,7 +1315,7 @@ pub struct RecoverUpdateAuthority<'info> {
     /// [MerkleDistributor].
     #[account(
     seeds = [
-    b"MerkleDistributor".as_ref(),
+    MERKLE_DISTRIBUTOR.as_ref(),
     base.key().to_bytes().as_ref()
     ],
     bump = _bump,
@@ -1323,7 +1325,7 @@ pub struct RecoverUpdateAuthority<'info> {
     /// The [MerkleDistributor] wallet
     #[account(
     seeds = [
-    b"Wallet".as_ref(),
+    WALLET.as_ref(),
     distributor.key().to_bytes().as_ref()
     ],
     bump = wallet_bump,
@@ -1484,4 +1486,4 @@ pub struct CandyMachineData {
     pub price: u64,
     pub items_available: u64,
     pub go_live_date: Option<i64>,
-}
\ No newline at end of file
+}
@@ -13,10 +13,10 @@ use anchor_spl::{
 
 anchor_lang::declare_id!("qntmGodpGkrM42mN68VCZHXnKqDCT8rdY23wFcXCLPd");
 
-const PREFIX: &str = "token_entangler";
-const ESCROW: &str = "escrow";
-const A_NAME: &str = "A";
-const B_NAME: &str = "B";
+pub const PREFIX: &str = "token_entangler";
+pub const ESCROW: &str = "escrow";
+pub const A_NAME: &str = "A";
+pub const B_NAME: &str = "B";
 #[program]
 pub mod token_entangler {
     use spl_token::amount_to_ui_amount;
@@ -0,0 +1,996 @@
+//! Bulk operations driven by a JSON or CSV manifest of mints.
+//!
+//! Instructions of consecutive manifest entries are packed into as few transactions as fit, and
+//! the entries of every confirmed transaction are appended to a checkpoint file, so a failed run
+//! resumes where it stopped when started again with the same manifest. Entries are checkpointed by
+//! a key that does not depend on their position, and entries whose change is already on chain, for
+//! example from a transaction that landed after its confirmation timed out, are recorded instead
+//! of being sent again.
+
+use {
+    clap::ArgMatches,
+    mpl_token_metadata::{
+        instruction::{
+            create_metadata_accounts_v3, set_and_verify_collection,
+            set_and_verify_sized_collection_item, update_metadata_accounts_v2, verify_collection,
+            verify_sized_collection_item, with_metadata_history,
+        },
+        pda::{find_master_edition_account, find_metadata_account, find_metadata_history_account},
+        state::{DataV2, Metadata, TokenMetadataAccount},
+    },
+    serde::{de::DeserializeOwned, Deserialize},
+    solana_clap_utils::input_parsers::pubkey_of,
+    solana_client::rpc_client::RpcClient,
+    solana_program::program_pack::Pack,
+    solana_sdk::{
+        hash::hashv,
+        instruction::Instruction,
+        packet::PACKET_DATA_SIZE,
+        pubkey::Pubkey,
+        signature::{keypair_from_seed, read_keypair_file, Keypair, Signer},
+        system_instruction::create_account,
+        transaction::Transaction,
+    },
+    spl_token::{instruction::initialize_mint, state::Mint},
+    std::{
+        collections::{HashMap, HashSet},
+        fmt::Debug,
+        fs::{self, OpenOptions},
+        io::Write,
+        str::FromStr,
+    },
+};
+
+/// Accounts fetched per `getMultipleAccounts` request.
+const FETCH_CHUNK_SIZE: usize = 100;
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct CreateEntry {
+    /// Existing mint, or a new mint is created with the payer as mint authority.
+    mint: Option<String>,
+    name: String,
+    symbol: String,
+    uri: String,
+    seller_fee_basis_points: Option<u16>,
+    is_mutable: Option<bool>,
+}
+
+impl CreateEntry {
+    /// Checkpoint key of the entry: its mint, or a hash of its name, symbol and uri.
+    fn key(&self) -> String {
+        match &self.mint {
+            Some(mint) => mint.clone(),
+            None => hashv(&[
+                self.name.as_bytes(),
+                self.symbol.as_bytes(),
+                self.uri.as_bytes(),
+            ])
+            .to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct UpdateEntry {
+    mint: String,
+    name: Option<String>,
+    symbol: Option<String>,
+    uri: Option<String>,
+    seller_fee_basis_points: Option<u16>,
+    new_update_authority: Option<String>,
+    primary_sale_happened: Option<bool>,
+    is_mutable: Option<bool>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+struct VerifyCollectionEntry {
+    mint: String,
+    /// Collection mint, defaults to the `--collection` argument.
+    collection: Option<String>,
+}
+
+/// The instructions of one manifest entry, sent together in the same transaction.
+struct PendingEntry {
+    id: String,
+    instructions: Vec<Instruction>,
+    signers: Vec<Keypair>,
+}
+
+/// Ids of the manifest entries already confirmed on chain, one per line.
+struct Checkpoint {
+    path: String,
+    done: HashSet<String>,
+}
+
+impl Checkpoint {
+    fn open(path: String) -> Checkpoint {
+        let done = fs::read_to_string(&path)
+            .map(|contents| contents.lines().map(str::to_owned).collect())
+            .unwrap_or_default();
+        Checkpoint { path, done }
+    }
+
+    fn contains(&self, id: &str) -> bool {
+        self.done.contains(id)
+    }
+
+    fn record(&mut self, ids: &[String]) {
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)
+            .expect("Could not open checkpoint file.");
+        for id in ids {
+            writeln!(file, "{}", id).expect("Could not write checkpoint file.");
+            self.done.insert(id.clone());
+        }
+    }
+}
+
+struct BulkOptions {
+    authority: Keypair,
+    checkpoint: Checkpoint,
+    dry_run: bool,
+    batch_size: usize,
+}
+
+impl BulkOptions {
+    fn from_matches(app_matches: &ArgMatches) -> BulkOptions {
+        let manifest = app_matches.value_of("manifest").unwrap();
+        let checkpoint = app_matches
+            .value_of("checkpoint")
+            .map(|val| val.to_owned())
+            .unwrap_or_else(|| format!("{}.checkpoint", manifest));
+        BulkOptions {
+            authority: read_keypair_file(
+                app_matches
+                    .value_of("update_authority")
+                    .unwrap_or_else(|| app_matches.value_of("keypair").unwrap()),
+            )
+            .unwrap(),
+            checkpoint: Checkpoint::open(checkpoint),
+            dry_run: app_matches.is_present("dry_run"),
+            batch_size: app_matches
+                .value_of("batch_size")
+                .map(|val| val.parse::<usize>().unwrap())
+                .unwrap_or(usize::MAX),
+        }
+    }
+}
+
+fn read_manifest<T: DeserializeOwned>(app_matches: &ArgMatches) -> Vec<T> {
+    let path = app_matches.value_of("manifest").unwrap();
+    let contents = fs::read_to_string(path).expect("Could not read manifest.");
+    parse_manifest(path, &contents)
+}
+
+/// Parses a CSV manifest if the path ends in `.csv`, otherwise a JSON array.
+fn parse_manifest<T: DeserializeOwned>(path: &str, contents: &str) -> Vec<T> {
+    if path.ends_with(".csv") {
+        csv::Reader::from_reader(contents.as_bytes())
+            .deserialize()
+            .map(|row| row.expect("Invalid manifest row."))
+            .collect()
+    } else {
+        serde_json::from_str(contents).expect("Invalid manifest.")
+    }
+}
+
+/// Checkpoint keys of the create entries, numbering repeated entries so each stays distinct.
+fn create_entry_ids(manifest: &[CreateEntry]) -> Vec<String> {
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+    manifest
+        .iter()
+        .map(|entry| {
+            let key = entry.key();
+            let occurrence = occurrences.entry(key.clone()).or_insert(0);
+            *occurrence += 1;
+            match *occurrence {
+                1 => key,
+                n => format!("{}#{}", key, n),
+            }
+        })
+        .collect()
+}
+
+/// Keypair of the new mint of a create entry, derived from the payer and the entry id so a run
+/// that is started again finds the mint created by an unrecorded transaction.
+fn new_mint_keypair(payer: &Keypair, id: &str) -> Keypair {
+    let seed = hashv(&[&payer.to_bytes(), id.as_bytes()]);
+    keypair_from_seed(seed.as_ref()).unwrap()
+}
+
+fn parse_pubkey(value: &str) -> Pubkey {
+    Pubkey::from_str(value).unwrap_or_else(|_| panic!("Invalid pubkey {:?}.", value))
+}
+
+/// Fetches the metadata accounts of the given mints, `None` for mints without metadata.
+fn fetch_metadata(client: &RpcClient, mints: &[Pubkey]) -> Vec<Option<Metadata>> {
+    let keys: Vec<Pubkey> = mints
+        .iter()
+        .map(|mint| find_metadata_account(mint).0)
+        .collect();
+    let mut metadata = Vec::with_capacity(keys.len());
+    for chunk in keys.chunks(FETCH_CHUNK_SIZE) {
+        for account in client.get_multiple_accounts(chunk).unwrap() {
+            metadata
+                .push(account.and_then(|account| Metadata::safe_deserialize(&account.data).ok()));
+        }
+    }
+    metadata
+}
+
+/// Passes the metadata history after the accounts of the instruction when the metadata has
+/// history enabled, so the change is recorded.
+pub(crate) fn with_history(instruction: Instruction, metadata: &Metadata) -> Instruction {
+    if metadata.history_enabled {
+        let (history, _) = find_metadata_history_account(&find_metadata_account(&metadata.mint).0);
+        with_metadata_history(instruction, history)
+    } else {
+        instruction
+    }
+}
+
+fn diff_field<T: Debug + PartialEq>(changes: &mut Vec<String>, field: &str, before: &T, after: &T) {
+    if before != after {
+        changes.push(format!("{}: {:?} -> {:?}", field, before, after));
+    }
+}
+
+fn fits_in_transaction(instructions: &[Instruction], payer: &Pubkey) -> bool {
+    let transaction = Transaction::new_with_payer(instructions, Some(payer));
+    bincode::serialized_size(&transaction).unwrap() <= PACKET_DATA_SIZE as u64
+}
+
+/// Groups consecutive entries into batches of at most `batch_size` entries whose instructions fit
+/// in a single transaction.
+fn pack_entries(
+    entries: Vec<PendingEntry>,
+    batch_size: usize,
+    payer: &Pubkey,
+) -> Vec<Vec<PendingEntry>> {
+    let mut batches = vec![];
+    let mut batch: Vec<PendingEntry> = vec![];
+    for entry in entries {
+        let mut instructions: Vec<Instruction> = batch
+            .iter()
+            .flat_map(|pending| pending.instructions.clone())
+            .collect();
+        instructions.extend(entry.instructions.clone());
+        if !batch.is_empty()
+            && (batch.len() >= batch_size || !fits_in_transaction(&instructions, payer))
+        {
+            batches.push(batch);
+            batch = vec![];
+        }
+        batch.push(entry);
+    }
+    if !batch.is_empty() {
+        batches.push(batch);
+    }
+    batches
+}
+
+/// Sends the entries packed into as few transactions as fit, recording each confirmed transaction
+/// in the checkpoint. Exits on the first failed transaction so the run can be resumed.
+fn send_packed(
+    client: &RpcClient,
+    payer: &Keypair,
+    options: &mut BulkOptions,
+    entries: Vec<PendingEntry>,
+) {
+    let total = entries.len();
+    let mut sent = 0;
+    for batch in pack_entries(entries, options.batch_size, &payer.pubkey()) {
+        sent += send_batch(client, payer, options, &batch);
+        println!("Sent {} / {}", sent, total);
+    }
+}
+
+fn send_batch(
+    client: &RpcClient,
+    payer: &Keypair,
+    options: &mut BulkOptions,
+    batch: &[PendingEntry],
+) -> usize {
+    let instructions: Vec<Instruction> = batch
+        .iter()
+        .flat_map(|pending| pending.instructions.clone())
+        .collect();
+    let mut signers: Vec<&Keypair> = vec![payer];
+    if options.authority.pubkey() != payer.pubkey() {
+        signers.push(&options.authority);
+    }
+    signers.extend(batch.iter().flat_map(|pending| pending.signers.iter()));
+
+    let mut transaction = Transaction::new_with_payer(&instructions, Some(&payer.pubkey()));
+    let recent_blockhash = client.get_latest_blockhash().unwrap();
+    transaction.sign(&signers, recent_blockhash);
+    if let Err(err) = client.send_and_confirm_transaction(&transaction) {
+        // The transaction may still have landed after its confirmation failed.
+        let landed = matches!(
+            client.get_signature_status(&transaction.signatures[0]),
+            Ok(Some(Ok(())))
+        );
+        if !landed {
+            println!(
+                "Transaction for {:?} failed: {}",
+                batch.iter().map(|pending| &pending.id).collect::<Vec<_>>(),
+                err
+            );
+            println!(
+                "Confirmed entries are recorded in {}, run the same command again to resume.",
+                options.checkpoint.path
+            );
+            std::process::exit(1);
+        }
+    }
+
+    let ids: Vec<String> = batch.iter().map(|pending| pending.id.clone()).collect();
+    options.checkpoint.record(&ids);
+    batch.len()
+}
+
+/// The update of one manifest entry, against the current state of its metadata.
+struct UpdatePlan {
+    current: DataV2,
+    data: DataV2,
+    new_update_authority: Option<Pubkey>,
+    primary_sale_happened: bool,
+    is_mutable: bool,
+    /// The changed fields, as printed for review, empty if the entry is already on chain.
+    changes: Vec<String>,
+}
+
+fn plan_update(entry: UpdateEntry, metadata: &Metadata) -> UpdatePlan {
+    let current = DataV2 {
+        name: metadata.data.name.trim_matches(char::from(0)).to_owned(),
+        symbol: metadata.data.symbol.trim_matches(char::from(0)).to_owned(),
+        uri: metadata.data.uri.trim_matches(char::from(0)).to_owned(),
+        seller_fee_basis_points: metadata.data.seller_fee_basis_points,
+        creators: metadata.data.creators.clone(),
+        collection: metadata.collection.clone(),
+        uses: metadata.uses.clone(),
+    };
+    let data = DataV2 {
+        name: entry.name.unwrap_or_else(|| current.name.clone()),
+        symbol: entry.symbol.unwrap_or_else(|| current.symbol.clone()),
+        uri: entry.uri.unwrap_or_else(|| current.uri.clone()),
+        seller_fee_basis_points: entry
+            .seller_fee_basis_points
+            .unwrap_or(current.seller_fee_basis_points),
+        ..current.clone()
+    };
+    let new_update_authority = entry.new_update_authority.as_deref().map(parse_pubkey);
+    let primary_sale_happened = entry
+        .primary_sale_happened
+        .unwrap_or(metadata.primary_sale_happened);
+    let is_mutable = entry.is_mutable.unwrap_or(metadata.is_mutable);
+
+    let mut changes = vec![];
+    diff_field(&mut changes, "name", &current.name, &data.name);
+    diff_field(&mut changes, "symbol", &current.symbol, &data.symbol);
+    diff_field(&mut changes, "uri", &current.uri, &data.uri);
+    diff_field(
+        &mut changes,
+        "seller_fee_basis_points",
+        &current.seller_fee_basis_points,
+        &data.seller_fee_basis_points,
+    );
+    diff_field(
+        &mut changes,
+        "update_authority",
+        &metadata.update_authority,
+        &new_update_authority.unwrap_or(metadata.update_authority),
+    );
+    diff_field(
+        &mut changes,
+        "primary_sale_happened",
+        &metadata.primary_sale_happened,
+        &primary_sale_happened,
+    );
+    diff_field(
+        &mut changes,
+        "is_mutable",
+        &metadata.is_mutable,
+        &is_mutable,
+    );
+
+    UpdatePlan {
+        current,
+        data,
+        new_update_authority,
+        primary_sale_happened,
+        is_mutable,
+        changes,
+    }
+}
+
+pub fn bulk_create(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
+    let mut options = BulkOptions::from_matches(app_matches);
+    let manifest: Vec<CreateEntry> = read_manifest(app_matches);
+    let manifest: Vec<(String, CreateEntry)> = create_entry_ids(&manifest)
+        .into_iter()
+        .zip(manifest)
+        .filter(|(id, _)| !options.checkpoint.contains(id))
+        .collect();
+    let mints: Vec<Pubkey> = manifest
+        .iter()
+        .map(|(id, entry)| match &entry.mint {
+            Some(mint) => parse_pubkey(mint),
+            None => new_mint_keypair(&payer, id).pubkey(),
+        })
+        .collect();
+    let existing = fetch_metadata(&client, &mints);
+    let program_key = mpl_token_metadata::id();
+    let mint_rent = client
+        .get_minimum_balance_for_rent_exemption(Mint::LEN)
+        .unwrap();
+
+    let mut pending = vec![];
+    for (((id, entry), mint_key), metadata) in manifest.into_iter().zip(mints).zip(existing) {
+        if metadata.is_some() {
+            println!("{}: metadata of mint {} already exists", id, mint_key);
+            if !options.dry_run {
+                options.checkpoint.record(&[id]);
+            }
+            continue;
+        }
+        let seller_fee_basis_points = entry.seller_fee_basis_points.unwrap_or(0);
+        let is_mutable = entry.is_mutable.unwrap_or(true);
+        if options.dry_run {
+            println!(
+                "{}: create mint {}, name {:?}, symbol {:?}, uri {:?}, seller_fee_basis_points {}, is_mutable {}",
+                id, mint_key, entry.name, entry.symbol, entry.uri, seller_fee_basis_points, is_mutable
+            );
+            continue;
+        }
+
+        let mut instructions = vec![];
+        let mut signers = vec![];
+        let mint_authority = match &entry.mint {
+            Some(_) => {
+                let mint_account = client
+                    .get_account(&mint_key)
+                    .expect("Could not find mint account.");
+                let mint =
+                    Mint::unpack(&mint_account.data).expect("Failed to deserialize Mint account.");
+                let mint_authority = mint.mint_authority.expect("Mint has no mint authority.");
+                if mint_authority != payer.pubkey() && mint_authority != options.authority.pubkey()
+                {
+                    panic!("Mint authority of {} is not a signer.", mint_key);
+                }
+                mint_authority
+            }
+            None => {
+                instructions.push(create_account(
+                    &payer.pubkey(),
+                    &mint_key,
+                    mint_rent,
+                    Mint::LEN as u64,
+                    &spl_token::id(),
+                ));
+                instructions.push(
+                    initialize_mint(
+                        &spl_token::id(),
+                        &mint_key,
+                        &payer.pubkey(),
+                        Some(&payer.pubkey()),
+                        0,
+                    )
+                    .unwrap(),
+                );
+                signers.push(new_mint_keypair(&payer, &id));
+                payer.pubkey()
+            }
+        };
+        println!("{}: creating metadata of mint {}", id, mint_key);
+
+        instructions.push(create_metadata_accounts_v3(
+            program_key,
+            find_metadata_account(&mint_key).0,
+            mint_key,
+            mint_authority,
+            payer.pubkey(),
+            options.authority.pubkey(),
+            entry.name,
+            entry.symbol,
+            entry.uri,
+            None,
+            seller_fee_basis_points,
+            true,
+            is_mutable,
+            None,
+            None,
+            None,
+        ));
+        pending.push(PendingEntry {
+            id,
+            instructions,
+            signers,
+        });
+    }
+
+    if !options.dry_run {
+        send_packed(&client, &payer, &mut options, pending);
+    }
+}
+
+pub fn bulk_update(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
+    let mut options = BulkOptions::from_matches(app_matches);
+    let manifest: Vec<UpdateEntry> = read_manifest(app_matches);
+    let manifest: Vec<UpdateEntry> = manifest
+        .into_iter()
+        .filter(|entry| !options.checkpoint.contains(&entry.mint))
+        .collect();
+    let mints: Vec<Pubkey> = manifest
+        .iter()
+        .map(|entry| parse_pubkey(&entry.mint))
+        .collect();
+    let program_key = mpl_token_metadata::id();
+
+    let mut pending = vec![];
+    for (entry, metadata) in manifest.into_iter().zip(fetch_metadata(&client, &mints)) {
+        let metadata = match metadata {
+            Some(metadata) => metadata,
+            None => {
+                println!("{}: no metadata found, skipping", entry.mint);
+                continue;
+            }
+        };
+
+        let mint = entry.mint.clone();
+        let UpdatePlan {
+            current,
+            data,
+            new_update_authority,
+            primary_sale_happened,
+            is_mutable,
+            changes,
+        } = plan_update(entry, &metadata);
+        if changes.is_empty() {
+            println!("{}: unchanged", mint);
+            if !options.dry_run {
+                options.checkpoint.record(&[mint]);
+            }
+            continue;
+        }
+        println!("{}: {}", mint, changes.join(", "));
+        if options.dry_run {
+            continue;
+        }
+
+        let instruction = update_metadata_accounts_v2(
+            program_key,
+            find_metadata_account(&metadata.mint).0,
+            options.authority.pubkey(),
+            new_update_authority,
+            (data != current).then_some(data),
+            (primary_sale_happened != metadata.primary_sale_happened)
+                .then_some(primary_sale_happened),
+            (is_mutable != metadata.is_mutable).then_some(is_mutable),
+        );
+        pending.push(PendingEntry {
+            id: mint,
+            instructions: vec![with_history(instruction, &metadata)],
+            signers: vec![],
+        });
+    }
+
+    if !options.dry_run {
+        send_packed(&client, &payer, &mut options, pending);
+    }
+}
+
+pub fn bulk_verify_collection(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
+    let mut options = BulkOptions::from_matches(app_matches);
+    let default_collection = pubkey_of(app_matches, "collection");
+    let manifest: Vec<VerifyCollectionEntry> = read_manifest(app_matches);
+    let manifest: Vec<VerifyCollectionEntry> = manifest
+        .into_iter()
+        .filter(|entry| !options.checkpoint.contains(&entry.mint))
+        .collect();
+    let mints: Vec<Pubkey> = manifest
+        .iter()
+        .map(|entry| parse_pubkey(&entry.mint))
+        .collect();
+    let program_key = mpl_token_metadata::id();
+    let authority = options.authority.pubkey();
+
+    let mut collections: HashMap<Pubkey, Metadata> = HashMap::new();
+    let mut pending = vec![];
+    for (entry, metadata) in manifest.into_iter().zip(fetch_metadata(&client, &mints)) {
+        let metadata = match metadata {
+            Some(metadata) => metadata,
+            None => {
+                println!("{}: no metadata found, skipping", entry.mint);
+                continue;
+            }
+        };
+        let collection_mint = entry
+            .collection
+            .as_deref()
+            .map(parse_pubkey)
+            .or(default_collection)
+            .unwrap_or_else(|| panic!("{}: no collection given.", entry.mint));
+        let collection_metadata = collections.entry(collection_mint).or_insert_with(|| {
+            fetch_metadata(&client, &[collection_mint])
+                .pop()
+                .flatten()
+                .unwrap_or_else(|| panic!("Collection {} has no metadata.", collection_mint))
+        });
+
+        let current = metadata.collection.as_ref();
+        if current.map(|collection| (collection.key, collection.verified))
+            == Some((collection_mint, true))
+        {
+            println!("{}: already verified", entry.mint);
+            if !options.dry_run {
+                options.checkpoint.record(&[entry.mint]);
+            }
+            continue;
+        }
+        if let Some(collection) = current.filter(|collection| collection.verified) {
+            println!(
+                "{}: already verified in collection {}, skipping",
+                entry.mint, collection.key
+            );
+            continue;
+        }
+        let set_collection = current.map(|collection| collection.key) != Some(collection_mint);
+        if set_collection && metadata.update_authority != authority {
+            println!(
+                "{}: collection can only be set by update authority {}, skipping",
+                entry.mint, metadata.update_authority
+            );
+            continue;
+        }
+        println!(
+            "{}: collection {:?} -> {} (verified)",
+            entry.mint,
+            current.map(|collection| collection.key),
+            collection_mint
+        );
+        if options.dry_run {
+            continue;
+        }
+
+        let metadata_key = find_metadata_account(&metadata.mint).0;
+        let collection_key = find_metadata_account(&collection_mint).0;
+        let (collection_edition, _) = find_master_edition_account(&collection_mint);
+        let sized = collection_metadata.collection_details.is_some();
+        let instruction = match (set_collection, sized) {
+            (true, true) => set_and_verify_sized_collection_item(
+                program_key,
+                metadata_key,
+                authority,
+                payer.pubkey(),
+                authority,
+                collection_mint,
+                collection_key,
+                collection_edition,
+                None,
+            ),
+            (true, false) => set_and_verify_collection(
+                program_key,
+                metadata_key,
+                authority,
+                payer.pubkey(),
+                authority,
+                collection_mint,
+                collection_key,
+                collection_edition,
+                None,
+            ),
+            (false, true) => verify_sized_collection_item(
+                program_key,
+                metadata_key,
+                authority,
+                payer.pubkey(),
+                collection_mint,
+                collection_key,
+                collection_edition,
+                None,
+            ),
+            (false, false) => verify_collection(
+                program_key,
+                metadata_key,
+                authority,
+                payer.pubkey(),
+                collection_mint,
+                collection_key,
+                collection_edition,
+                None,
+            ),
+        };
+        let mut instruction = with_history(instruction, &metadata);
+        // Verifying into a sized collection also changes the collection.
+        if sized {
+            instruction = with_history(instruction, collection_metadata);
+        }
+        pending.push(PendingEntry {
+            id: entry.mint,
+            instructions: vec![instruction],
+            signers: vec![],
+        });
+    }
+
+    if !options.dry_run {
+        send_packed(&client, &payer, &mut options, pending);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use {
+        super::*,
+        mpl_token_metadata::state::Data,
+        solana_sdk::instruction::AccountMeta,
+        std::{env, process},
+    };
+
+    fn create_entry(mint: Option<&str>, name: &str) -> CreateEntry {
+        CreateEntry {
+            mint: mint.map(str::to_owned),
+            name: name.to_owned(),
+            symbol: "TST".to_owned(),
+            uri: "https://example.com/0.json".to_owned(),
+            seller_fee_basis_points: None,
+            is_mutable: None,
+        }
+    }
+
+    fn pending_entry(id: &str, data_len: usize) -> PendingEntry {
+        PendingEntry {
+            id: id.to_owned(),
+            instructions: vec![Instruction::new_with_bytes(
+                mpl_token_metadata::id(),
+                &vec![0; data_len],
+                vec![AccountMeta::new(Pubkey::new_unique(), false)],
+            )],
+            signers: vec![],
+        }
+    }
+
+    fn batch_ids(batches: &[Vec<PendingEntry>]) -> Vec<Vec<&str>> {
+        batches
+            .iter()
+            .map(|batch| batch.iter().map(|pending| pending.id.as_str()).collect())
+            .collect()
+    }
+
+    fn metadata() -> Metadata {
+        Metadata {
+            update_authority: Pubkey::new_unique(),
+            mint: Pubkey::new_unique(),
+            data: Data {
+                name: format!("{:\0<32}", "Test"),
+                symbol: format!("{:\0<10}", "TST"),
+                uri: format!("{:\0<200}", "https://example.com/0.json"),
+                seller_fee_basis_points: 500,
+                creators: None,
+            },
+            is_mutable: true,
+            ..Metadata::default()
+        }
+    }
+
+    fn update_entry(mint: &str) -> UpdateEntry {
+        UpdateEntry {
+            mint: mint.to_owned(),
+            name: None,
+            symbol: None,
+            uri: None,
+            seller_fee_basis_points: None,
+            new_update_authority: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+        }
+    }
+
+    #[test]
+    fn parse_json_manifest() {
+        let mint = Pubkey::new_unique().to_string();
+        let contents = format!(
+            r#"[{{"mint": "{}", "uri": "https://example.com/1.json", "is_mutable": false}}]"#,
+            mint
+        );
+        let manifest: Vec<UpdateEntry> = parse_manifest("manifest.json", &contents);
+        assert_eq!(
+            manifest,
+            vec![UpdateEntry {
+                uri: Some("https://example.com/1.json".to_owned()),
+                is_mutable: Some(false),
+                ..update_entry(&mint)
+            }]
+        );
+    }
+
+    #[test]
+    fn parse_csv_manifest() {
+        let mint = Pubkey::new_unique().to_string();
+        let contents = format!(
+            "mint,name,symbol,uri,seller_fee_basis_points,is_mutable\n\
+             {},Test,TST,https://example.com/0.json,500,\n\
+             ,Other,TST,https://example.com/0.json,,false\n",
+            mint
+        );
+        let manifest: Vec<CreateEntry> = parse_manifest("manifest.csv", &contents);
+        assert_eq!(
+            manifest,
+            vec![
+                CreateEntry {
+                    seller_fee_basis_points: Some(500),
+                    ..create_entry(Some(&mint), "Test")
+                },
+                CreateEntry {
+                    is_mutable: Some(false),
+                    ..create_entry(None, "Other")
+                },
+            ]
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "Invalid manifest.")]
+    fn parse_invalid_json_manifest() {
+        let _: Vec<UpdateEntry> = parse_manifest("manifest.json", r#"[{"name": "Test"}]"#);
+    }
+
+    #[test]
+    fn create_entry_ids_do_not_depend_on_position() {
+        let mint = Pubkey::new_unique().to_string();
+        let manifest = vec![
+            create_entry(None, "First"),
+            create_entry(Some(&mint), "Minted"),
+            create_entry(None, "Second"),
+        ];
+        let ids = create_entry_ids(&manifest);
+        assert_eq!(ids[1], mint);
+
+        let reordered = vec![
+            create_entry(None, "Second"),
+            create_entry(None, "First"),
+            create_entry(Some(&mint), "Minted"),
+        ];
+        let reordered_ids = create_entry_ids(&reordered);
+        assert_eq!(reordered_ids, vec![ids[2].clone(), ids[0].clone(), mint]);
+    }
+
+    #[test]
+    fn create_entry_ids_number_repeated_entries() {
+        let manifest = vec![
+            create_entry(None, "Test"),
+            create_entry(None, "Test"),
+            create_entry(None, "Other"),
+        ];
+        let ids = create_entry_ids(&manifest);
+        assert_eq!(ids[1], format!("{}#2", ids[0]));
+        assert_ne!(ids[2], ids[0]);
+    }
+
+    #[test]
+    fn new_mint_keypair_is_derived_from_payer_and_id() {
+        let payer = Keypair::new();
+        assert_eq!(
+            new_mint_keypair(&payer, "a").pubkey(),
+            new_mint_keypair(&payer, "a").pubkey()
+        );
+        assert_ne!(
+            new_mint_keypair(&payer, "a").pubkey(),
+            new_mint_keypair(&payer, "b").pubkey()
+        );
+        assert_ne!(
+            new_mint_keypair(&payer, "a").pubkey(),
+            new_mint_keypair(&Keypair::new(), "a").pubkey()
+        );
+    }
+
+    #[test]
+    fn checkpoint_persists_recorded_ids() {
+        let path = env::temp_dir()
+            .join(format!(
+                "bulk-{}-{}.checkpoint",
+                process::id(),
+                Pubkey::new_unique()
+            ))
+            .to_string_lossy()
+            .into_owned();
+
+        let mut checkpoint = Checkpoint::open(path.clone());
+        assert!(!checkpoint.contains("a"));
+        checkpoint.record(&["a".to_owned(), "b".to_owned()]);
+        assert!(checkpoint.contains("a"));
+        checkpoint.record(&["c".to_owned()]);
+
+        let reopened = Checkpoint::open(path.clone());
+        for id in ["a", "b", "c"] {
+            assert!(reopened.contains(id));
+        }
+        assert!(!reopened.contains("d"));
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn pack_entries_respects_batch_size() {
+        let entries = ["a", "b", "c", "d", "e"]
+            .iter()
+            .map(|id| pending_entry(id, 8))
+            .collect();
+        let batches = pack_entries(entries, 2, &Pubkey::new_unique());
+        assert_eq!(
+            batch_ids(&batches),
+            vec![vec!["a", "b"], vec!["c", "d"], vec!["e"]]
+        );
+    }
+
+    #[test]
+    fn pack_entries_fills_transactions() {
+        // Two of these fit in a transaction, three do not.
+        let entries = ["a", "b", "c", "d"]
+            .iter()
+            .map(|id| pending_entry(id, 400))
+            .collect();
+        let batches = pack_entries(entries, usize::MAX, &Pubkey::new_unique());
+        assert_eq!(batch_ids(&batches), vec![vec!["a", "b"], vec!["c", "d"]]);
+    }
+
+    #[test]
+    fn pack_entries_keeps_oversized_entry_alone() {
+        let entries = vec![
+            pending_entry("a", 8),
+            pending_entry("b", 2 * PACKET_DATA_SIZE),
+            pending_entry("c", 8),
+        ];
+        let batches = pack_entries(entries, usize::MAX, &Pubkey::new_unique());
+        assert_eq!(batch_ids(&batches), vec![vec!["a"], vec!["b"], vec!["c"]]);
+    }
+
+    #[test]
+    fn plan_update_lists_changed_fields() {
+        let metadata = metadata();
+        let new_update_authority = Pubkey::new_unique();
+        let plan = plan_update(
+            UpdateEntry {
+                uri: Some("https://example.com/1.json".to_owned()),
+                new_update_authority: Some(new_update_authority.to_string()),
+                is_mutable: Some(false),
+                ..update_entry(&metadata.mint.to_string())
+            },
+            &metadata,
+        );
+
+        assert_eq!(
+            plan.changes,
+            vec![
+                "uri: \"https://example.com/0.json\" -> \"https://example.com/1.json\"".to_owned(),
+                format!(
+                    "update_authority: {:?} -> {:?}",
+                    metadata.update_authority, new_update_authority
+                ),
+                "is_mutable: true -> false".to_owned(),
+            ]
+        );
+        assert_eq!(plan.current.name, "Test");
+        assert_eq!(plan.data.name, "Test");
+        assert_eq!(plan.data.uri, "https://example.com/1.json");
+        assert_eq!(plan.new_update_authority, Some(new_update_authority));
+        assert!(!plan.is_mutable);
+    }
+
+    #[test]
+    fn plan_update_of_applied_entry_is_unchanged() {
+        let metadata = metadata();
+        let plan = plan_update(
+            UpdateEntry {
+                name: Some("Test".to_owned()),
+                seller_fee_basis_points: Some(500),
+                is_mutable: Some(true),
+                ..update_entry(&metadata.mint.to_string())
+            },
+            &metadata,
+        );
+        assert!(plan.changes.is_empty());
+        assert_eq!(plan.data, plan.current);
+    }
+}
@@ -0,0 +1,847 @@
+//! Subcommands for the collection, delegation, uses, burn and freeze instructions.
+//!
+//! Every command prints a JSON object with the transaction signature and the accounts involved.
+
+use {
+    crate::bulk::with_history,
+    clap::{App, Arg, ArgMatches, SubCommand},
+    mpl_token_metadata::{
+        instruction::{
+            approve_collection_authority, approve_use_authority, burn_nft,
+            create_metadata_accounts_v3, freeze_delegated_account, revoke_collection_authority,
+            revoke_use_authority, set_collection_size, set_token_standard, thaw_delegated_account,
+            unverify_collection, unverify_sized_collection_item, utilize, verify_collection,
+            verify_sized_collection_item,
+        },
+        pda::{
+            find_collection_authority_account, find_master_edition_account, find_metadata_account,
+            find_program_as_burner_account, find_use_authority_account,
+        },
+        state::{Collection, CollectionDetails, Metadata, TokenMetadataAccount, UseMethod, Uses},
+    },
+    serde_json::{json, Value},
+    solana_clap_utils::{
+        input_parsers::pubkey_of,
+        input_validators::{is_valid_pubkey, is_valid_signer},
+    },
+    solana_client::{rpc_client::RpcClient, rpc_request::TokenAccountsFilter},
+    solana_program::program_pack::Pack,
+    solana_sdk::{
+        instruction::Instruction,
+        pubkey::Pubkey,
+        signature::{read_keypair_file, Keypair, Signer},
+        system_instruction::create_account,
+        transaction::Transaction,
+    },
+    spl_token::{
+        instruction::{initialize_account, initialize_mint, mint_to},
+        state::{Account, Mint},
+    },
+    std::str::FromStr,
+};
+
+fn mint_arg<'a, 'b>(help: &'b str) -> Arg<'a, 'b> {
+    Arg::with_name("mint")
+        .long("mint")
+        .value_name("MINT")
+        .required(true)
+        .validator(is_valid_pubkey)
+        .takes_value(true)
+        .help(help)
+}
+
+fn pubkey_arg<'a>(name: &'a str, value_name: &'a str, help: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name)
+        .long(name)
+        .value_name(value_name)
+        .validator(is_valid_pubkey)
+        .takes_value(true)
+        .help(help)
+}
+
+fn signer_arg<'a>(name: &'a str, value_name: &'a str, help: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name)
+        .long(name)
+        .value_name(value_name)
+        .validator(is_valid_signer)
+        .takes_value(true)
+        .required(false)
+        .help(help)
+}
+
+fn value_arg<'a>(name: &'a str, value_name: &'a str, help: &'a str) -> Arg<'a, 'a> {
+    Arg::with_name(name)
+        .long(name)
+        .value_name(value_name)
+        .takes_value(true)
+        .help(help)
+}
+
+fn collection_subcommand<'a, 'b>(name: &'a str, about: &'b str) -> App<'a, 'b> {
+    SubCommand::with_name(name)
+        .about(about)
+        .arg(mint_arg("Mint of the collection item"))
+        .arg(
+            pubkey_arg("collection", "COLLECTION", "Mint of the collection").required(true),
+        )
+        .arg(signer_arg(
+            "collection_authority",
+            "COLLECTION_AUTHORITY",
+            "Filepath or URL to a keypair of the collection authority, defaults to the update authority",
+        ))
+        .arg(
+            Arg::with_name("delegated")
+                .long("delegated")
+                .takes_value(false)
+                .help("The collection authority is a delegate with a collection authority record"),
+        )
+}
+
+pub fn subcommands<'a, 'b>() -> Vec<App<'a, 'b>> {
+    vec![
+        SubCommand::with_name("create_metadata_accounts_v3")
+            .about("Create Metadata Accounts with collection, uses and collection details")
+            .arg(value_arg("name", "NAME", "name for the Mint").required(true))
+            .arg(value_arg("symbol", "SYMBOL", "symbol for the Mint").required(true))
+            .arg(value_arg("uri", "URI", "URI for the Mint").required(true))
+            .arg(pubkey_arg(
+                "mint",
+                "MINT",
+                "Pubkey for an existing mint (random new mint with one token otherwise)",
+            ))
+            .arg(value_arg(
+                "seller_fee_basis_points",
+                "SELLER_FEE_BASIS_POINTS",
+                "Royalties in basis points, defaults to 0",
+            ))
+            .arg(
+                Arg::with_name("mutable")
+                    .long("mutable")
+                    .takes_value(false)
+                    .help("Permit future metadata updates"),
+            )
+            .arg(pubkey_arg(
+                "collection",
+                "COLLECTION",
+                "Mint of the collection this NFT belongs to, unverified",
+            ))
+            .arg(
+                Arg::with_name("uses_method")
+                    .long("uses_method")
+                    .value_name("USES_METHOD")
+                    .takes_value(true)
+                    .possible_values(&["burn", "multiple", "single"])
+                    .requires("uses_total")
+                    .help("Use method of the NFT"),
+            )
+            .arg(
+                value_arg("uses_total", "USES_TOTAL", "Total number of uses")
+                    .requires("uses_method"),
+            )
+            .arg(
+                Arg::with_name("sized_collection")
+                    .long("sized_collection")
+                    .takes_value(false)
+                    .help("Make this NFT a sized collection parent"),
+            ),
+        collection_subcommand(
+            "verify_collection",
+            "Verify an NFT as a member of a sized or unsized collection",
+        ),
+        collection_subcommand(
+            "unverify_collection",
+            "Unverify an NFT as a member of a sized or unsized collection",
+        ),
+        SubCommand::with_name("approve_collection_authority")
+            .about("Approve a delegated collection authority")
+            .arg(mint_arg("Mint of the collection"))
+            .arg(
+                pubkey_arg(
+                    "new_collection_authority",
+                    "NEW_COLLECTION_AUTHORITY",
+                    "Collection authority to approve",
+                )
+                .required(true),
+            ),
+        SubCommand::with_name("revoke_collection_authority")
+            .about("Revoke a delegated collection authority")
+            .arg(mint_arg("Mint of the collection"))
+            .arg(
+                pubkey_arg("delegate", "DELEGATE", "Collection authority to revoke")
+                    .required(true),
+            )
+            .arg(signer_arg(
+                "revoke_authority",
+                "REVOKE_AUTHORITY",
+                "Filepath or URL to a keypair of the update authority or the delegate, defaults to the update authority",
+            )),
+        SubCommand::with_name("approve_use_authority")
+            .about("Approve a use authority of an NFT")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(pubkey_arg("user", "USER", "Use authority to approve").required(true))
+            .arg(
+                value_arg("number_of_uses", "NUMBER_OF_USES", "Uses granted to the user")
+                    .required(true),
+            )
+            .arg(signer_arg(
+                "owner",
+                "OWNER",
+                "Filepath or URL to a keypair of the NFT owner, defaults to you",
+            )),
+        SubCommand::with_name("revoke_use_authority")
+            .about("Revoke a use authority of an NFT")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(pubkey_arg("user", "USER", "Use authority to revoke").required(true))
+            .arg(signer_arg(
+                "owner",
+                "OWNER",
+                "Filepath or URL to a keypair of the NFT owner, defaults to you",
+            )),
+        SubCommand::with_name("utilize")
+            .about("Use an NFT as its owner or a delegated use authority")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(
+                value_arg("number_of_uses", "NUMBER_OF_USES", "Uses to spend")
+                    .required(true),
+            )
+            .arg(signer_arg(
+                "use_authority",
+                "USE_AUTHORITY",
+                "Filepath or URL to a keypair of the owner or use authority, defaults to you",
+            ))
+            .arg(pubkey_arg(
+                "owner",
+                "OWNER",
+                "Owner of the NFT, defaults to the use authority",
+            )),
+        SubCommand::with_name("set_collection_size")
+            .about("Set the size of a collection that predates sized collections")
+            .arg(mint_arg("Mint of the collection"))
+            .arg(value_arg("size", "SIZE", "Number of verified items").required(true))
+            .arg(
+                Arg::with_name("delegated")
+                    .long("delegated")
+                    .takes_value(false)
+                    .help("The update authority is a delegate with a collection authority record"),
+            ),
+        SubCommand::with_name("set_token_standard")
+            .about("Set the token standard of a metadata account from its mint and edition")
+            .arg(mint_arg("Mint of the metadata")),
+        SubCommand::with_name("burn_nft")
+            .about("Burn an NFT, closing its token, metadata and edition accounts")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(signer_arg(
+                "owner",
+                "OWNER",
+                "Filepath or URL to a keypair of the NFT owner, defaults to you",
+            )),
+        SubCommand::with_name("freeze_delegated_account")
+            .about("Freeze a token account as its delegate")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(
+                pubkey_arg("token_account", "TOKEN_ACCOUNT", "Token account to freeze")
+                    .required(true),
+            )
+            .arg(signer_arg(
+                "delegate",
+                "DELEGATE",
+                "Filepath or URL to a keypair of the delegate, defaults to you",
+            )),
+        SubCommand::with_name("thaw_delegated_account")
+            .about("Thaw a token account as its delegate")
+            .arg(mint_arg("Mint of the NFT"))
+            .arg(
+                pubkey_arg("token_account", "TOKEN_ACCOUNT", "Token account to thaw")
+                    .required(true),
+            )
+            .arg(signer_arg(
+                "delegate",
+                "DELEGATE",
+                "Filepath or URL to a keypair of the delegate, defaults to you",
+            )),
+    ]
+}
+
+/// Reads the keypair given for `name`, falling back to `fallback` and then to the payer keypair.
+fn keypair_of(app_matches: &ArgMatches, name: &str, fallback: &str) -> Keypair {
+    read_keypair_file(
+        app_matches
+            .value_of(name)
+            .or_else(|| app_matches.value_of(fallback))
+            .unwrap_or_else(|| app_matches.value_of("keypair").unwrap()),
+    )
+    .unwrap()
+}
+
+fn amount_of(app_matches: &ArgMatches, name: &str) -> u64 {
+    app_matches
+        .value_of(name)
+        .map(|val| val.parse::<u64>().unwrap())
+        .unwrap()
+}
+
+fn get_metadata(client: &RpcClient, mint: &Pubkey) -> (Pubkey, Metadata) {
+    let (metadata_key, _) = find_metadata_account(mint);
+    let account = client
+        .get_account(&metadata_key)
+        .expect("Could not find metadata account.");
+    let metadata: Metadata =
+        Metadata::safe_deserialize(&account.data).expect("Failed to deserialize metadata.");
+    (metadata_key, metadata)
+}
+
+/// Finds the token account of `owner` holding the mint.
+fn get_token_account(client: &RpcClient, owner: &Pubkey, mint: &Pubkey) -> Pubkey {
+    client
+        .get_token_accounts_by_owner(owner, TokenAccountsFilter::Mint(*mint))
+        .unwrap()
+        .iter()
+        .map(|account| Pubkey::from_str(&account.pubkey).unwrap())
+        .find(|token_account| {
+            client
+                .get_token_account_balance(token_account)
+                .unwrap()
+                .amount
+                != "0"
+        })
+        .unwrap_or_else(|| panic!("{} holds no token of mint {}.", owner, mint))
+}
+
+fn send(
+    client: &RpcClient,
+    payer: &Keypair,
+    instructions: &[Instruction],
+    signers: &[&Keypair],
+) -> String {
+    let mut all_signers = vec![payer];
+    for signer in signers {
+        if !all_signers
+            .iter()
+            .any(|added| added.pubkey() == signer.pubkey())
+        {
+            all_signers.push(signer);
+        }
+    }
+
+    let mut transaction = Transaction::new_with_payer(instructions, Some(&payer.pubkey()));
+    let recent_blockhash = client.get_latest_blockhash().unwrap();
+    transaction.sign(&all_signers, recent_blockhash);
+    client
+        .send_and_confirm_transaction(&transaction)
+        .unwrap()
+        .to_string()
+}
+
+fn create_metadata_accounts_v3_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Value {
+    let update_authority = keypair_of(app_matches, "update_authority", "keypair");
+    let token_key = spl_token::id();
+    let new_mint = Keypair::new();
+    let create_new_mint = !app_matches.is_present("mint");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap_or_else(|| new_mint.pubkey());
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+
+    let token_account = Keypair::new();
+    let mut instructions = vec![];
+    let mint_authority = if create_new_mint {
+        // Collection parents and NFTs with uses are of no use without a token, so mint one.
+        instructions.push(create_account(
+            &payer.pubkey(),
+            &mint_key,
+            client
+                .get_minimum_balance_for_rent_exemption(Mint::LEN)
+                .unwrap(),
+            Mint::LEN as u64,
+            &token_key,
+        ));
+        instructions.push(
+            initialize_mint(
+                &token_key,
+                &mint_key,
+                &payer.pubkey(),
+                Some(&payer.pubkey()),
+                0,
+            )
+            .unwrap(),
+        );
+        instructions.push(create_account(
+            &payer.pubkey(),
+            &token_account.pubkey(),
+            client
+                .get_minimum_balance_for_rent_exemption(Account::LEN)
+                .unwrap(),
+            Account::LEN as u64,
+            &token_key,
+        ));
+        instructions.push(
+            initialize_account(
+                &token_key,
+                &token_account.pubkey(),
+                &mint_key,
+                &payer.pubkey(),
+            )
+            .unwrap(),
+        );
+        instructions.push(
+            mint_to(
+                &token_key,
+                &mint_key,
+                &token_account.pubkey(),
+                &payer.pubkey(),
+                &[&payer.pubkey()],
+                1,
+            )
+            .unwrap(),
+        );
+        payer.pubkey()
+    } else {
+        let mint_account = client
+            .get_account(&mint_key)
+            .expect("Could not find mint account.");
+        let mint = Mint::unpack(&mint_account.data).expect("Failed to deserialize Mint account.");
+        mint.mint_authority.expect("Mint has no mint authority.")
+    };
+
+    let uses = app_matches.value_of("uses_method").map(|method| {
+        let total = amount_of(app_matches, "uses_total");
+        Uses {
+            use_method: match method {
+                "burn" => UseMethod::Burn,
+                "multiple" => UseMethod::Multiple,
+                _ => UseMethod::Single,
+            },
+            remaining: total,
+            total,
+        }
+    });
+    let collection = pubkey_of(app_matches, "collection").map(|key| Collection {
+        verified: false,
+        key,
+    });
+    let collection_details = app_matches
+        .is_present("sized_collection")
+        .then_some(CollectionDetails::V1 { size: 0 });
+
+    instructions.push(create_metadata_accounts_v3(
+        mpl_token_metadata::id(),
+        metadata_key,
+        mint_key,
+        mint_authority,
+        payer.pubkey(),
+        update_authority.pubkey(),
+        app_matches.value_of("name").unwrap().to_owned(),
+        app_matches.value_of("symbol").unwrap().to_owned(),
+        app_matches.value_of("uri").unwrap().to_owned(),
+        None,
+        app_matches
+            .value_of("seller_fee_basis_points")
+            .map(|val| val.parse::<u16>().unwrap())
+            .unwrap_or(0),
+        true,
+        app_matches.is_present("mutable"),
+        collection,
+        uses,
+        collection_details,
+    ));
+
+    let mut signers = vec![&update_authority];
+    if create_new_mint {
+        signers.push(&new_mint);
+        signers.push(&token_account);
+    }
+    let signature = send(&client, &payer, &instructions, &signers);
+    let (_, metadata) = get_metadata(&client, &mint_key);
+    json!({
+        "signature": signature,
+        "mint": mint_key.to_string(),
+        "metadata": metadata_key.to_string(),
+        "update_authority": metadata.update_authority.to_string(),
+    })
+}
+
+fn collection_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+    verify: bool,
+) -> Value {
+    let collection_authority = keypair_of(app_matches, "collection_authority", "update_authority");
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let collection_mint = pubkey_of(app_matches, "collection").unwrap();
+    let (metadata_key, metadata) = get_metadata(&client, &mint_key);
+    let (collection_key, collection_metadata) = get_metadata(&client, &collection_mint);
+    let (collection_edition, _) = find_master_edition_account(&collection_mint);
+    let collection_authority_record = app_matches.is_present("delegated").then(|| {
+        find_collection_authority_account(&collection_mint, &collection_authority.pubkey()).0
+    });
+    let sized = collection_metadata.collection_details.is_some();
+
+    let instruction = match (verify, sized) {
+        (true, true) => verify_sized_collection_item(
+            program_key,
+            metadata_key,
+            collection_authority.pubkey(),
+            payer.pubkey(),
+            collection_mint,
+            collection_key,
+            collection_edition,
+            collection_authority_record,
+        ),
+        (true, false) => verify_collection(
+            program_key,
+            metadata_key,
+            collection_authority.pubkey(),
+            payer.pubkey(),
+            collection_mint,
+            collection_key,
+            collection_edition,
+            collection_authority_record,
+        ),
+        (false, true) => unverify_sized_collection_item(
+            program_key,
+            metadata_key,
+            collection_authority.pubkey(),
+            payer.pubkey(),
+            collection_mint,
+            collection_key,
+            collection_edition,
+            collection_authority_record,
+        ),
+        (false, false) => unverify_collection(
+            program_key,
+            metadata_key,
+            collection_authority.pubkey(),
+            collection_mint,
+            collection_key,
+            collection_edition,
+            collection_authority_record,
+        ),
+    };
+
+    let signature = send(
+        &client,
+        &payer,
+        &[with_history(instruction, &metadata)],
+        &[&collection_authority],
+    );
+    json!({
+        "signature": signature,
+        "metadata": metadata_key.to_string(),
+        "collection": collection_mint.to_string(),
+        "sized": sized,
+        "verified": verify,
+    })
+}
+
+fn approve_collection_authority_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Value {
+    let update_authority = keypair_of(app_matches, "update_authority", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let new_collection_authority = pubkey_of(app_matches, "new_collection_authority").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (record, _) = find_collection_authority_account(&mint_key, &new_collection_authority);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[approve_collection_authority(
+            mpl_token_metadata::id(),
+            record,
+            new_collection_authority,
+            update_authority.pubkey(),
+            payer.pubkey(),
+            metadata_key,
+            mint_key,
+        )],
+        &[&update_authority],
+    );
+    json!({
+        "signature": signature,
+        "collection_authority": new_collection_authority.to_string(),
+        "collection_authority_record": record.to_string(),
+    })
+}
+
+fn revoke_collection_authority_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+) -> Value {
+    let revoke_authority = keypair_of(app_matches, "revoke_authority", "update_authority");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let delegate = pubkey_of(app_matches, "delegate").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (record, _) = find_collection_authority_account(&mint_key, &delegate);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[revoke_collection_authority(
+            mpl_token_metadata::id(),
+            record,
+            delegate,
+            revoke_authority.pubkey(),
+            metadata_key,
+            mint_key,
+        )],
+        &[&revoke_authority],
+    );
+    json!({
+        "signature": signature,
+        "collection_authority": delegate.to_string(),
+        "collection_authority_record": record.to_string(),
+    })
+}
+
+fn use_authority_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+    approve: bool,
+) -> Value {
+    let owner = keypair_of(app_matches, "owner", "keypair");
+    let program_key = mpl_token_metadata::id();
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let user = pubkey_of(app_matches, "user").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (record, _) = find_use_authority_account(&mint_key, &user);
+    let token_account = get_token_account(&client, &owner.pubkey(), &mint_key);
+
+    let instruction = if approve {
+        approve_use_authority(
+            program_key,
+            record,
+            user,
+            owner.pubkey(),
+            payer.pubkey(),
+            token_account,
+            metadata_key,
+            mint_key,
+            find_program_as_burner_account().0,
+            amount_of(app_matches, "number_of_uses"),
+        )
+    } else {
+        revoke_use_authority(
+            program_key,
+            record,
+            user,
+            owner.pubkey(),
+            token_account,
+            metadata_key,
+            mint_key,
+        )
+    };
+
+    let signature = send(&client, &payer, &[instruction], &[&owner]);
+    json!({
+        "signature": signature,
+        "use_authority": user.to_string(),
+        "use_authority_record": record.to_string(),
+    })
+}
+
+fn utilize_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Value {
+    let use_authority = keypair_of(app_matches, "use_authority", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let owner = pubkey_of(app_matches, "owner").unwrap_or_else(|| use_authority.pubkey());
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let token_account = get_token_account(&client, &owner, &mint_key);
+
+    // A delegated use authority spends the uses of its record and burns through the program.
+    let delegated = owner != use_authority.pubkey();
+    let record =
+        delegated.then(|| find_use_authority_account(&mint_key, &use_authority.pubkey()).0);
+    let burner = delegated.then(|| find_program_as_burner_account().0);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[utilize(
+            mpl_token_metadata::id(),
+            metadata_key,
+            token_account,
+            mint_key,
+            record,
+            use_authority.pubkey(),
+            owner,
+            burner,
+            amount_of(app_matches, "number_of_uses"),
+        )],
+        &[&use_authority],
+    );
+
+    // Burned once the last use of a burn NFT is spent.
+    let remaining = client
+        .get_account(&metadata_key)
+        .ok()
+        .and_then(|account| Metadata::safe_deserialize::<Metadata>(&account.data).ok())
+        .and_then(|metadata| metadata.uses)
+        .map(|uses| uses.remaining);
+    json!({
+        "signature": signature,
+        "metadata": metadata_key.to_string(),
+        "remaining_uses": remaining,
+    })
+}
+
+fn set_collection_size_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Value {
+    let update_authority = keypair_of(app_matches, "update_authority", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let size = amount_of(app_matches, "size");
+    let record = app_matches
+        .is_present("delegated")
+        .then(|| find_collection_authority_account(&mint_key, &update_authority.pubkey()).0);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[set_collection_size(
+            mpl_token_metadata::id(),
+            metadata_key,
+            update_authority.pubkey(),
+            mint_key,
+            record,
+            size,
+        )],
+        &[&update_authority],
+    );
+    json!({
+        "signature": signature,
+        "metadata": metadata_key.to_string(),
+        "size": size,
+    })
+}
+
+fn set_token_standard_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Value {
+    let update_authority = keypair_of(app_matches, "update_authority", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let (metadata_key, _) = find_metadata_account(&mint_key);
+    let (edition_key, _) = find_master_edition_account(&mint_key);
+    let edition = client
+        .get_account(&edition_key)
+        .is_ok()
+        .then_some(edition_key);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[set_token_standard(
+            mpl_token_metadata::id(),
+            metadata_key,
+            update_authority.pubkey(),
+            mint_key,
+            edition,
+        )],
+        &[&update_authority],
+    );
+    let (_, metadata) = get_metadata(&client, &mint_key);
+    json!({
+        "signature": signature,
+        "metadata": metadata_key.to_string(),
+        "token_standard": metadata.token_standard.map(|standard| format!("{:?}", standard)),
+    })
+}
+
+fn burn_nft_call(app_matches: &ArgMatches, payer: Keypair, client: RpcClient) -> Value {
+    let owner = keypair_of(app_matches, "owner", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let (metadata_key, metadata) = get_metadata(&client, &mint_key);
+    let (edition_key, _) = find_master_edition_account(&mint_key);
+    let token_account = get_token_account(&client, &owner.pubkey(), &mint_key);
+    let collection_metadata = metadata
+        .collection
+        .filter(|collection| collection.verified)
+        .map(|collection| find_metadata_account(&collection.key).0);
+
+    let signature = send(
+        &client,
+        &payer,
+        &[burn_nft(
+            mpl_token_metadata::id(),
+            metadata_key,
+            owner.pubkey(),
+            mint_key,
+            token_account,
+            edition_key,
+            spl_token::id(),
+            collection_metadata,
+        )],
+        &[&owner],
+    );
+    json!({
+        "signature": signature,
+        "mint": mint_key.to_string(),
+        "metadata": metadata_key.to_string(),
+    })
+}
+
+fn delegated_account_call(
+    app_matches: &ArgMatches,
+    payer: Keypair,
+    client: RpcClient,
+    freeze: bool,
+) -> Value {
+    let delegate = keypair_of(app_matches, "delegate", "keypair");
+    let mint_key = pubkey_of(app_matches, "mint").unwrap();
+    let token_account = pubkey_of(app_matches, "token_account").unwrap();
+    let (edition_key, _) = find_master_edition_account(&mint_key);
+
+    let instruction = if freeze {
+        freeze_delegated_account(
+            mpl_token_metadata::id(),
+            delegate.pubkey(),
+            token_account,
+            edition_key,
+            mint_key,
+        )
+    } else {
+        thaw_delegated_account(
+            mpl_token_metadata::id(),
+            delegate.pubkey(),
+            token_account,
+            edition_key,
+            mint_key,
+        )
+    };
+
+    let signature = send(&client, &payer, &[instruction], &[&delegate]);
+    json!({
+        "signature": signature,
+        "token_account": token_account.to_string(),
+        "frozen": freeze,
+    })
+}
+
+pub fn process(sub_command: &str, app_matches: &ArgMatches, payer: Keypair, client: RpcClient) {
+    let output = match sub_command {
+        "create_metadata_accounts_v3" => {
+            create_metadata_accounts_v3_call(app_matches, payer, client)
+        }
+        "verify_collection" => collection_call(app_matches, payer, client, true),
+        "unverify_collection" => collection_call(app_matches, payer, client, false),
+        "approve_collection_authority" => {
+            approve_collection_authority_call(app_matches, payer, client)
+        }
+        "revoke_collection_authority" => {
+            revoke_collection_authority_call(app_matches, payer, client)
+        }
+        "approve_use_authority" => use_authority_call(app_matches, payer, client, true),
+        "revoke_use_authority" => use_authority_call(app_matches, payer, client, false),
+        "utilize" => utilize_call(app_matches, payer, client),
+        "set_collection_size" => set_collection_size_call(app_matches, payer, client),
+        "set_token_standard" => set_token_standard_call(app_matches, payer, client),
+        "burn_nft" => burn_nft_call(app_matches, payer, client),
+        "freeze_delegated_account" => delegated_account_call(app_matches, payer, client, true),
+        "thaw_delegated_account" => delegated_account_call(app_matches, payer, client, false),
+        _ => unreachable!(),
+    };
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
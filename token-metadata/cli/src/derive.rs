@@ -0,0 +1,56 @@
+//! Offline derivation of the PDAs of every Metaplex program.
+
+use {
+    clap::ArgMatches,
+    mpl_pda::{find_definition, ArgKind, PDAS},
+    serde_json::{json, Value},
+    std::process::exit,
+};
+
+fn list() -> Value {
+    PDAS.iter()
+        .map(|pda| {
+            let args: Vec<String> = pda
+                .args
+                .iter()
+                .map(|(name, kind)| {
+                    let kind = match kind {
+                        ArgKind::Pubkey => "pubkey",
+                        ArgKind::U32 => "u32",
+                        ArgKind::U64 => "u64",
+                    };
+                    format!("{}:{}", name, kind)
+                })
+                .collect();
+            json!({ "program": pda.program, "pda": pda.name, "args": args })
+        })
+        .collect()
+}
+
+pub fn derive(app_matches: &ArgMatches) {
+    let output = match (app_matches.value_of("program"), app_matches.value_of("pda")) {
+        (Some(program), Some(name)) => {
+            let args: Vec<&str> = app_matches
+                .values_of("args")
+                .map(|args| args.collect())
+                .unwrap_or_default();
+            let derived = find_definition(program, name)
+                .and_then(|pda| pda.derive(&args).map(|derived| (pda, derived)));
+            match derived {
+                Ok((pda, (address, bump))) => json!({
+                    "program": pda.program,
+                    "program_id": pda.program_id.to_string(),
+                    "pda": pda.name,
+                    "address": address.to_string(),
+                    "bump": bump,
+                }),
+                Err(err) => {
+                    eprintln!("{}", err);
+                    exit(1);
+                }
+            }
+        }
+        _ => list(),
+    };
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
@@ -0,0 +1,86 @@
+//! Offline decoding of raw token-metadata accounts, detected by their `Key`.
+
+use {
+    borsh::BorshDeserialize,
+    clap::ArgMatches,
+    mpl_token_metadata::{
+        deser::{meta_deser_repaired, meta_deser_unchecked},
+        state::{
+            CollectionAuthorityRecord, CreatorChangeProposal, Edition, EditionMarker, Key,
+            MasterEditionV1, MasterEditionV2, MetadataAttributes, MetadataHistory, MetadataV2,
+            ReservationListV1, ReservationListV2, RuleSet, UpdateDelegateRecord,
+            UseAuthorityRecord,
+        },
+    },
+    serde::Serialize,
+    serde_json::{json, Value},
+    solana_program::borsh::try_from_slice_unchecked,
+    std::fs,
+};
+
+fn read_account_data(app_matches: &ArgMatches) -> Vec<u8> {
+    if let Some(data) = app_matches.value_of("base64") {
+        base64::decode(data.trim()).expect("Invalid base64 data.")
+    } else if let Some(data) = app_matches.value_of("hex") {
+        hex::decode(data.trim().trim_start_matches("0x")).expect("Invalid hex data.")
+    } else {
+        fs::read(app_matches.value_of("file").unwrap()).expect("Could not read account file.")
+    }
+}
+
+fn decode<T: BorshDeserialize + Serialize>(data: &[u8]) -> Result<Value, String> {
+    try_from_slice_unchecked::<T>(data)
+        .map(|account| serde_json::to_value(account).unwrap())
+        .map_err(|err| err.to_string())
+}
+
+/// Decodes the account as the type of its `Key`. Metadata accounts also report whether
+/// corrupted fields had to be repaired to read them.
+pub fn decode_account(data: &[u8]) -> Value {
+    let key = match data.first().map(|key| Key::try_from_slice(&[*key])) {
+        Some(Ok(key)) => key,
+        Some(Err(_)) => return json!({ "error": format!("Unknown key {}", data[0]) }),
+        None => return json!({ "error": "Empty account" }),
+    };
+
+    let mut repaired = None;
+    let account = match key {
+        Key::Uninitialized => Err("Uninitialized account".to_owned()),
+        Key::MetadataV1 => {
+            repaired = Some(meta_deser_repaired(data));
+            meta_deser_unchecked(&mut &data[..])
+                .map(|metadata| serde_json::to_value(metadata).unwrap())
+                .map_err(|err| err.to_string())
+        }
+        Key::MetadataV2 => decode::<MetadataV2>(data),
+        Key::EditionV1 => decode::<Edition>(data),
+        Key::MasterEditionV1 => decode::<MasterEditionV1>(data),
+        Key::MasterEditionV2 => decode::<MasterEditionV2>(data),
+        Key::ReservationListV1 => decode::<ReservationListV1>(data),
+        Key::ReservationListV2 => decode::<ReservationListV2>(data),
+        Key::EditionMarker => decode::<EditionMarker>(data),
+        Key::UseAuthorityRecord => decode::<UseAuthorityRecord>(data),
+        Key::CollectionAuthorityRecord => decode::<CollectionAuthorityRecord>(data),
+        Key::RuleSet => decode::<RuleSet>(data),
+        Key::UpdateDelegateRecord => decode::<UpdateDelegateRecord>(data),
+        Key::MetadataAttributes => decode::<MetadataAttributes>(data),
+        Key::CreatorChangeProposal => decode::<CreatorChangeProposal>(data),
+        Key::MetadataHistory => decode::<MetadataHistory>(data),
+    };
+
+    let mut output = json!({ "key": format!("{:?}", key), "length": data.len() });
+    if let Some(repaired) = repaired {
+        output["repaired"] = Value::Bool(repaired);
+    }
+    match account {
+        Ok(account) => output["account"] = account,
+        Err(err) => output["error"] = Value::String(err),
+    }
+    output
+}
+
+pub fn inspect(app_matches: &ArgMatches) {
+    let data = read_account_data(app_matches);
+    let output = decode_account(&data);
+    println!("{}", serde_json::to_string_pretty(&output).unwrap());
+}
@@ -1,8 +1,15 @@
+mod bulk;
+mod commands;
+mod derive;
+mod inspect;
+
 use solana_client::rpc_request::TokenAccountsFilter;
 use solana_sdk::account::ReadableAccount;
 
 use {
-    clap::{crate_description, crate_name, crate_version, App, Arg, ArgMatches, SubCommand},
+    clap::{
+        crate_description, crate_name, crate_version, App, Arg, ArgGroup, ArgMatches, SubCommand,
+    },
     mpl_token_metadata::{
         instruction::{
             create_master_edition, create_metadata_accounts,
@@ -614,6 +621,42 @@ fn create_metadata_account_call(
     (metadata, metadata_key)
 }
 
+fn bulk_subcommand<'a, 'b>(name: &'a str, about: &'a str) -> App<'a, 'b> {
+    SubCommand::with_name(name)
+        .about(about)
+        .arg(
+            Arg::with_name("manifest")
+                .long("manifest")
+                .value_name("MANIFEST")
+                .required(true)
+                .takes_value(true)
+                .help("JSON manifest, or CSV manifest if the path ends in .csv"),
+        )
+        .arg(
+            Arg::with_name("checkpoint")
+                .long("checkpoint")
+                .value_name("CHECKPOINT")
+                .required(false)
+                .takes_value(true)
+                .help("File recording confirmed entries to resume from, defaults to <MANIFEST>.checkpoint"),
+        )
+        .arg(
+            Arg::with_name("batch_size")
+                .long("batch-size")
+                .value_name("BATCH_SIZE")
+                .required(false)
+                .takes_value(true)
+                .help("Maximum entries per transaction, otherwise as many as fit"),
+        )
+        .arg(
+            Arg::with_name("dry_run")
+                .long("dry-run")
+                .takes_value(false)
+                .required(false)
+                .help("Print the computed changes without sending transactions"),
+        )
+}
+
 fn main() {
     let app_matches = App::new(crate_name!())
         .about(crate_description!())
@@ -839,9 +882,102 @@ fn main() {
                                 .help("Account's authority, defaults to you"),
                         )
 
-        ).subcommand(
+        )
+        .subcommand(bulk_subcommand(
+            "bulk-create",
+            "Create metadata for every manifest entry (mint, name, symbol, uri, seller_fee_basis_points, is_mutable), creating mints for entries without one",
+        ))
+        .subcommand(bulk_subcommand(
+            "bulk-update",
+            "Update the metadata of every manifest entry (mint, name, symbol, uri, seller_fee_basis_points, new_update_authority, primary_sale_happened, is_mutable)",
+        ))
+        .subcommand(
+            bulk_subcommand(
+                "bulk-verify-collection",
+                "Verify every manifest entry (mint, collection) as a member of its collection, setting the collection first if needed",
+            )
+            .arg(
+                Arg::with_name("collection")
+                    .long("collection")
+                    .value_name("COLLECTION")
+                    .required(false)
+                    .validator(is_valid_pubkey)
+                    .takes_value(true)
+                    .help("Collection mint for entries without one"),
+            ),
+        )
+        .subcommand(
+            SubCommand::with_name("inspect")
+                .about("Decode a raw token-metadata account offline and print it as JSON")
+                .arg(
+                    Arg::with_name("base64")
+                        .long("base64")
+                        .value_name("BASE64")
+                        .takes_value(true)
+                        .help("Account data encoded in base64"),
+                )
+                .arg(
+                    Arg::with_name("hex")
+                        .long("hex")
+                        .value_name("HEX")
+                        .takes_value(true)
+                        .help("Account data encoded in hex"),
+                )
+                .arg(
+                    Arg::with_name("file")
+                        .long("file")
+                        .value_name("FILE")
+                        .takes_value(true)
+                        .help("File with the raw account data, e.g. from `solana account --output-file`"),
+                )
+                .group(
+                    ArgGroup::with_name("account_data")
+                        .args(&["base64", "hex", "file"])
+                        .required(true),
+                ),
+        )
+        .subcommand(
                 SubCommand::with_name("puff_unpuffed_metadata")
-                        .about("Take metadata that still have variable length name, symbol, and uri fields and stretch them out with null symbols so they can be searched more easily by RPC.")).get_matches();
+                        .about("Take metadata that still have variable length name, symbol, and uri fields and stretch them out with null symbols so they can be searched more easily by RPC."))
+        .subcommand(
+            SubCommand::with_name("derive")
+                .about("Derive a PDA of any Metaplex program offline, or list all of them without arguments")
+                .arg(
+                    Arg::with_name("program")
+                        .value_name("PROGRAM")
+                        .index(1)
+                        .requires("pda")
+                        .help("Program of the PDA, e.g. token-metadata"),
+                )
+                .arg(
+                    Arg::with_name("pda")
+                        .value_name("PDA")
+                        .index(2)
+                        .help("Name of the PDA, e.g. edition-marker"),
+                )
+                .arg(
+                    Arg::with_name("args")
+                        .value_name("ARGS")
+                        .index(3)
+                        .multiple(true)
+                        .help("Seed arguments in order, pubkeys in base58 and integers in decimal"),
+                ),
+        )
+        .subcommands(commands::subcommands())
+        .get_matches();
+
+    // Inspecting and deriving need neither a keypair nor the cluster.
+    match app_matches.subcommand() {
+        ("inspect", Some(arg_matches)) => {
+            inspect::inspect(arg_matches);
+            return;
+        }
+        ("derive", Some(arg_matches)) => {
+            derive::derive(arg_matches);
+            return;
+        }
+        _ => {}
+    }
 
     let client = RpcClient::new(
         app_matches
@@ -896,6 +1032,18 @@ fn main() {
         ("puff_unpuffed_metadata", Some(arg_matches)) => {
             puff_unpuffed_metadata(arg_matches, payer, client);
         }
+        ("bulk-create", Some(arg_matches)) => {
+            bulk::bulk_create(arg_matches, payer, client);
+        }
+        ("bulk-update", Some(arg_matches)) => {
+            bulk::bulk_update(arg_matches, payer, client);
+        }
+        ("bulk-verify-collection", Some(arg_matches)) => {
+            bulk::bulk_verify_collection(arg_matches, payer, client);
+        }
+        (sub_command, Some(arg_matches)) => {
+            commands::process(sub_command, arg_matches, payer, client);
+        }
         _ => unreachable!(),
     }
 }
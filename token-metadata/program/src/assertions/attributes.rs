@@ -0,0 +1,29 @@
+use solana_program::program_error::ProgramError;
+
+use crate::{
+    error::MetadataError,
+    state::{Attribute, MAX_ATTRIBUTES, MAX_ATTRIBUTE_KEY_LENGTH, MAX_ATTRIBUTE_VALUE_LENGTH},
+};
+
+pub fn assert_attribute_valid(attribute: &Attribute) -> Result<(), ProgramError> {
+    if attribute.key.is_empty() || attribute.key.len() > MAX_ATTRIBUTE_KEY_LENGTH {
+        return Err(MetadataError::InvalidAttributeKey.into());
+    }
+    if attribute.value.len() > MAX_ATTRIBUTE_VALUE_LENGTH {
+        return Err(MetadataError::AttributeValueTooLong.into());
+    }
+    Ok(())
+}
+
+pub fn assert_attributes_valid(attributes: &[Attribute]) -> Result<(), ProgramError> {
+    if attributes.len() > MAX_ATTRIBUTES {
+        return Err(MetadataError::TooManyAttributes.into());
+    }
+    for (i, attribute) in attributes.iter().enumerate() {
+        assert_attribute_valid(attribute)?;
+        if attributes[..i].iter().any(|a| a.key == attribute.key) {
+            return Err(MetadataError::DuplicateAttributeKey.into());
+        }
+    }
+    Ok(())
+}
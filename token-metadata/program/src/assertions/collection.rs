@@ -5,9 +5,9 @@ use crate::{
     pda::find_collection_authority_account,
     state::{
         Collection, CollectionAuthorityRecord, MasterEditionV2, Metadata, TokenMetadataAccount,
-        TokenStandard, EDITION, PREFIX,
+        TokenStandard, EDITION, MAX_COLLECTION_DEPTH, PREFIX,
     },
-    utils::assert_derivation,
+    utils::{assert_derivation, assert_owned_by},
 };
 
 pub fn assert_collection_update_is_valid(
@@ -116,3 +116,111 @@ pub fn assert_master_edition(
     }
     Ok(())
 }
+
+/// Loads the metadata of the verified collection of `member` from `collection_info`.
+fn load_verified_collection(
+    program_id: &Pubkey,
+    member: &Metadata,
+    collection_info: Option<&AccountInfo>,
+) -> Result<Metadata, ProgramError> {
+    let collection_key = match &member.collection {
+        Some(collection) if collection.verified => collection.key,
+        _ => return Err(MetadataError::NotVerifiedMemberOfCollection.into()),
+    };
+
+    let collection_info = collection_info.ok_or(MetadataError::MissingCollectionMetadata)?;
+    assert_owned_by(collection_info, program_id)?;
+    let collection_metadata: Metadata = Metadata::from_account_info(collection_info)?;
+    if collection_metadata.mint != collection_key {
+        return Err(MetadataError::CollectionNotFound.into());
+    }
+    Ok(collection_metadata)
+}
+
+/// Asserts that `member` belongs to the collection of `ancestor_mint`, either directly or
+/// through nested collections. `collections` holds the metadata accounts of the collections
+/// above `member`, nearest first, and at most `max_depth` levels are followed.
+pub fn assert_collection_ancestry(
+    program_id: &Pubkey,
+    member: &Metadata,
+    collections: &[AccountInfo],
+    ancestor_mint: &Pubkey,
+    max_depth: usize,
+) -> Result<(), ProgramError> {
+    let mut current = member.clone();
+    for depth in 0..max_depth {
+        let collection_metadata =
+            load_verified_collection(program_id, &current, collections.get(depth))?;
+        if collection_metadata.mint == *ancestor_mint {
+            return Ok(());
+        }
+        current = collection_metadata;
+    }
+    Err(MetadataError::CollectionDepthExceeded.into())
+}
+
+/// Asserts that `collection` can be nested below `parent`, whose ancestors up to the root
+/// collection are passed in `ancestors`, nearest first. The hierarchy must stay within
+/// `MAX_COLLECTION_DEPTH` and must not contain `collection` itself.
+pub fn assert_collection_parent_is_valid(
+    program_id: &Pubkey,
+    collection: &Metadata,
+    parent: &Metadata,
+    ancestors: &[AccountInfo],
+    max_depth: usize,
+) -> Result<(), ProgramError> {
+    if collection.collection_details.is_none() {
+        return Err(MetadataError::NotACollectionParent.into());
+    }
+    assert_collection_hierarchy_is_valid(program_id, collection, parent, ancestors, max_depth)
+}
+
+/// Hierarchy checks for the plain verify instructions, which take no ancestor accounts. Items
+/// that are collections themselves can then only be verified into a root collection, nested
+/// ones have to go through `VerifyCollectionParent`.
+pub fn assert_collection_member_is_valid(
+    program_id: &Pubkey,
+    member: &Metadata,
+    collection: &Metadata,
+) -> Result<(), ProgramError> {
+    if member.collection_details.is_some() {
+        assert_collection_hierarchy_is_valid(
+            program_id,
+            member,
+            collection,
+            &[],
+            MAX_COLLECTION_DEPTH,
+        )
+    } else if member.mint == collection.mint {
+        Err(MetadataError::CollectionHierarchyCycle.into())
+    } else {
+        Ok(())
+    }
+}
+
+fn assert_collection_hierarchy_is_valid(
+    program_id: &Pubkey,
+    collection: &Metadata,
+    parent: &Metadata,
+    ancestors: &[AccountInfo],
+    max_depth: usize,
+) -> Result<(), ProgramError> {
+    let mut current = parent.clone();
+    let mut depth = 1;
+    loop {
+        if current.mint == collection.mint {
+            return Err(MetadataError::CollectionHierarchyCycle.into());
+        }
+
+        match &current.collection {
+            Some(parent_collection) if parent_collection.verified => {
+                if depth >= max_depth {
+                    return Err(MetadataError::CollectionDepthExceeded.into());
+                }
+                current = load_verified_collection(program_id, &current, ancestors.get(depth - 1))?;
+                depth += 1;
+            }
+            _ => return Ok(()),
+        }
+    }
+}
@@ -1,2 +1,5 @@
+pub mod attributes;
 pub mod collection;
+pub mod rule_set;
+pub mod update_delegate;
 pub mod uses;
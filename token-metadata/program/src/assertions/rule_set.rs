@@ -0,0 +1,88 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError, pubkey::Pubkey};
+
+use crate::{
+    error::MetadataError,
+    state::{
+        Metadata, RuleSet, TokenMetadataAccount, MAX_RULE_SET_ESCROW_AUTHORITIES,
+        MAX_RULE_SET_PROGRAMS,
+    },
+};
+
+pub fn assert_rule_set_entries_valid(
+    allowed_programs: &[Pubkey],
+    escrow_authorities: &[Pubkey],
+) -> Result<(), ProgramError> {
+    if allowed_programs.len() > MAX_RULE_SET_PROGRAMS
+        || escrow_authorities.len() > MAX_RULE_SET_ESCROW_AUTHORITIES
+    {
+        return Err(MetadataError::TooManyRuleSetEntries.into());
+    }
+    Ok(())
+}
+
+pub fn assert_rule_set_authority(
+    rule_set: &RuleSet,
+    authority_info: &AccountInfo,
+) -> Result<(), ProgramError> {
+    if rule_set.authority != *authority_info.key {
+        return Err(MetadataError::InvalidRuleSetAuthority.into());
+    }
+    if !authority_info.is_signer {
+        return Err(ProgramError::MissingRequiredSignature);
+    }
+    Ok(())
+}
+
+/// Loads the rule set bound to `metadata`, failing if the asset is unbound or bound elsewhere.
+pub fn assert_bound_rule_set(
+    metadata: &Metadata,
+    rule_set_info: &AccountInfo,
+) -> Result<RuleSet, ProgramError> {
+    match metadata.rule_set {
+        Some(rule_set) if rule_set == *rule_set_info.key => {
+            RuleSet::from_account_info(rule_set_info)
+        }
+        Some(_) => Err(MetadataError::InvalidRuleSet.into()),
+        None => Err(MetadataError::NotBoundToRuleSet.into()),
+    }
+}
+
+/// A delegate on a bound token account could thaw it through `ThawDelegatedAccount`,
+/// so only escrow authorities of the rule set may ever be delegates.
+pub fn assert_delegate_allowed(
+    rule_set: &RuleSet,
+    delegate: Option<&Pubkey>,
+) -> Result<(), ProgramError> {
+    match delegate {
+        Some(delegate) if !rule_set.is_escrow_authority(delegate) => {
+            Err(MetadataError::EscrowAuthorityNotAllowedByRuleSet.into())
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Checks a bound token move against the rule set:
+/// * CPI callers must be listed in `allowed_programs`.
+/// * Direct (top-level) calls may only move tokens into the custody of an escrow authority.
+/// * The authority must be the token owner, or a delegate that is an escrow authority.
+pub fn assert_transfer_allowed(
+    rule_set: &RuleSet,
+    program_id: &Pubkey,
+    calling_program: &Pubkey,
+    authority: &Pubkey,
+    source_owner: &Pubkey,
+    destination_owner: &Pubkey,
+) -> Result<(), ProgramError> {
+    if calling_program != program_id {
+        if !rule_set.is_program_allowed(calling_program) {
+            return Err(MetadataError::ProgramNotAllowedByRuleSet.into());
+        }
+    } else if !rule_set.is_escrow_authority(destination_owner) {
+        return Err(MetadataError::ProgramNotAllowedByRuleSet.into());
+    }
+
+    if authority != source_owner && !rule_set.is_escrow_authority(authority) {
+        return Err(MetadataError::EscrowAuthorityNotAllowedByRuleSet.into());
+    }
+    Ok(())
+}
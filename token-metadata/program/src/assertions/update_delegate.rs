@@ -0,0 +1,60 @@
+use solana_program::{account_info::AccountInfo, program_error::ProgramError};
+
+use crate::{
+    error::MetadataError,
+    pda::find_update_delegate_account,
+    state::{DataV2, Metadata, TokenMetadataAccount, UpdateDelegateRecord, UpdateDelegateScopes},
+};
+
+pub fn assert_update_delegate_record(
+    delegate_info: &AccountInfo,
+    metadata: &Metadata,
+    update_delegate_record_info: &AccountInfo,
+) -> Result<UpdateDelegateRecord, ProgramError> {
+    let (pda, bump) = find_update_delegate_account(&metadata.mint, delegate_info.key);
+    if pda != *update_delegate_record_info.key {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    if update_delegate_record_info.data_is_empty() {
+        return Err(MetadataError::UpdateDelegateRecordDoesNotExist.into());
+    }
+
+    let record: UpdateDelegateRecord =
+        UpdateDelegateRecord::from_account_info(update_delegate_record_info)?;
+    // A record approved by a previous update authority grants nothing.
+    if record.bump != bump || record.update_authority != metadata.update_authority {
+        return Err(MetadataError::InvalidUpdateDelegateRecord.into());
+    }
+    Ok(record)
+}
+
+fn unpuffed(s: &str) -> &str {
+    s.trim_end_matches(char::from(0))
+}
+
+/// Checks that every field `incoming` changes on `existing` is covered by `scopes`.
+pub fn assert_update_within_scopes(
+    scopes: &UpdateDelegateScopes,
+    existing: &Metadata,
+    incoming: &DataV2,
+) -> Result<(), ProgramError> {
+    let data = &existing.data;
+
+    let uri_changed = unpuffed(&data.uri) != unpuffed(&incoming.uri);
+    let name_symbol_changed = unpuffed(&data.name) != unpuffed(&incoming.name)
+        || unpuffed(&data.symbol) != unpuffed(&incoming.symbol);
+    let creators_changed = data.creators != incoming.creators;
+    let collection_changed = existing.collection != incoming.collection;
+    let uses_changed = existing.uses != incoming.uses;
+
+    if (uri_changed && !scopes.uri)
+        || (name_symbol_changed && !scopes.name_symbol)
+        || (creators_changed && !scopes.creators)
+        || (collection_changed && !scopes.collection)
+        || (uses_changed && !scopes.uses)
+        || data.seller_fee_basis_points != incoming.seller_fee_basis_points
+    {
+        return Err(MetadataError::UpdateDelegateScopeMissing.into());
+    }
+    Ok(())
+}
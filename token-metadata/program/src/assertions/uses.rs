@@ -84,3 +84,32 @@ pub fn process_use_authority_validation(
     }
     Ok(())
 }
+
+pub fn assert_use_authority_time_window_valid(
+    not_before: Option<i64>,
+    expires_at: Option<i64>,
+    unix_timestamp: i64,
+) -> Result<(), ProgramError> {
+    if let Some(expires_at) = expires_at {
+        if expires_at <= unix_timestamp {
+            return Err(MetadataError::InvalidUseAuthorityTimeWindow.into());
+        }
+        if matches!(not_before, Some(not_before) if not_before >= expires_at) {
+            return Err(MetadataError::InvalidUseAuthorityTimeWindow.into());
+        }
+    }
+    Ok(())
+}
+
+pub fn assert_use_authority_record_active(
+    use_authority_record: &UseAuthorityRecord,
+    unix_timestamp: i64,
+) -> Result<(), ProgramError> {
+    if use_authority_record.is_expired(unix_timestamp) {
+        return Err(MetadataError::UseAuthorityRecordExpired.into());
+    }
+    if !use_authority_record.is_active(unix_timestamp) {
+        return Err(MetadataError::UseAuthorityRecordNotActive.into());
+    }
+    Ok(())
+}
@@ -1,10 +1,11 @@
 use crate::{
     error::MetadataError,
+    event::{emit, MetadataEvent, MetadataState},
     state::{Data, DataV2, Metadata, TokenMetadataAccount},
     utils::{
-        assert_data_valid, assert_owned_by, assert_update_authority_is_correct,
-        process_create_metadata_accounts_logic, puff_out_data_fields,
-        CreateMetadataAccountsLogicArgs,
+        assert_creator_change_is_allowed, assert_data_valid, assert_owned_by,
+        assert_update_authority_is_correct, process_create_metadata_accounts_logic,
+        puff_out_data_fields, CreateMetadataAccountsLogicArgs,
     },
 };
 use borsh::BorshSerialize;
@@ -74,6 +75,7 @@ pub fn process_deprecated_update_metadata_accounts(
 
     assert_owned_by(metadata_account_info, program_id)?;
     assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    let before = MetadataState::from(&metadata);
 
     if let Some(data) = optional_data {
         if metadata.is_mutable {
@@ -84,6 +86,7 @@ pub fn process_deprecated_update_metadata_accounts(
                 false,
                 update_authority_info.is_signer,
             )?;
+            assert_creator_change_is_allowed(&metadata, &data.creators, update_authority_info.key)?;
             metadata.data = data;
         } else {
             return Err(MetadataError::DataIsImmutable.into());
@@ -105,5 +108,12 @@ pub fn process_deprecated_update_metadata_accounts(
     puff_out_data_fields(&mut metadata);
 
     metadata.serialize(&mut *metadata_account_info.data.borrow_mut())?;
+
+    emit(MetadataEvent::MetadataUpdated {
+        metadata: *metadata_account_info.key,
+        authority: *update_authority_info.key,
+        before,
+        after: MetadataState::from(&metadata),
+    });
     Ok(())
 }
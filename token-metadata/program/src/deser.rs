@@ -1,5 +1,12 @@
-use crate::state::{Collection, CollectionDetails, Data, Key, Metadata, TokenStandard, Uses};
-use borsh::{maybestd::io::Error as BorshError, BorshDeserialize, BorshSerialize};
+use crate::state::{
+    Collection, CollectionDetails, Data, FungibleDetails, Key, Metadata, TokenStandard, Uses,
+    MAX_CREATOR_LIMIT, MAX_NAME_LENGTH, MAX_SYMBOL_LENGTH, MAX_URI_LENGTH,
+    METADATA_EXTENSIONS_OFFSET,
+};
+use borsh::{
+    maybestd::io::{Error as BorshError, ErrorKind},
+    BorshDeserialize, BorshSerialize,
+};
 use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubkey::Pubkey};
 
 // Custom deserialization function to handle NFTs with corrupted data.
@@ -10,8 +17,22 @@ use solana_program::{account_info::AccountInfo, entrypoint::ProgramResult, pubke
 // It does not check `Key` type or account length and should only be used through the custom functions
 // `from_account_info` and `deserialize` implemented on the Metadata struct.
 pub fn meta_deser_unchecked(buf: &mut &[u8]) -> Result<Metadata, BorshError> {
+    meta_deser_with_repairs(buf).map(|(metadata, _)| metadata)
+}
+
+// Deserializes like `meta_deser_unchecked`, also returning whether any field that could not be
+// read cleanly fell back to its default.
+fn meta_deser_with_repairs(buf: &mut &[u8]) -> Result<(Metadata, bool), BorshError> {
+    // Migrated accounts were written cleanly, so there is nothing to guess.
+    if buf.len() >= METADATA_EXTENSIONS_OFFSET && buf[0] == Key::MetadataV2 as u8 {
+        let metadata = meta_deser_strict(&buf[..METADATA_EXTENSIONS_OFFSET])?;
+        *buf = &buf[METADATA_EXTENSIONS_OFFSET..];
+        return Ok((metadata, false));
+    }
+
     // Metadata corruption shouldn't appear until after edition_nonce.
     let key: Key = BorshDeserialize::deserialize(buf)?;
+
     let update_authority: Pubkey = BorshDeserialize::deserialize(buf)?;
     let mint: Pubkey = BorshDeserialize::deserialize(buf)?;
     let data: Data = BorshDeserialize::deserialize(buf)?;
@@ -29,6 +50,24 @@ pub fn meta_deser_unchecked(buf: &mut &[u8]) -> Result<Metadata, BorshError> {
     let collection_details_res: Result<Option<CollectionDetails>, BorshError> =
         BorshDeserialize::deserialize(buf);
 
+    // V1.4
+    let rule_set_res: Result<Option<Pubkey>, BorshError> = BorshDeserialize::deserialize(buf);
+
+    // V1.5
+    let fungible_details_res: Result<Option<FungibleDetails>, BorshError> =
+        BorshDeserialize::deserialize(buf);
+
+    // V1.6
+    let history_enabled_res: Result<bool, BorshError> = BorshDeserialize::deserialize(buf);
+
+    let repaired = token_standard_res.is_err()
+        || collection_res.is_err()
+        || uses_res.is_err()
+        || collection_details_res.is_err()
+        || rule_set_res.is_err()
+        || fungible_details_res.is_err()
+        || history_enabled_res.is_err();
+
     /* We can have accidentally valid, but corrupted data, particularly on the Collection struct,
     so to increase probability of catching errors If any of these deserializations fail, set all values to None.
     */
@@ -39,14 +78,27 @@ pub fn meta_deser_unchecked(buf: &mut &[u8]) -> Result<Metadata, BorshError> {
         _ => (None, None, None),
     };
 
-    // Handle v1.3 separately
-    let collection_details = match collection_details_res {
-        Ok(details) => details,
-        Err(_) => {
-            println!("no collection details found");
-            None
-        }
-    };
+    // Handle v1.3 through v1.6 separately. Each field is only trusted if the fields before
+    // it were read cleanly.
+    let (collection_details, rule_set, fungible_details, history_enabled) =
+        match collection_details_res {
+            Ok(details) => match rule_set_res {
+                Ok(rule_set) => match fungible_details_res {
+                    Ok(fungible_details) => (
+                        details,
+                        rule_set,
+                        fungible_details,
+                        history_enabled_res.unwrap_or(false),
+                    ),
+                    Err(_) => (details, rule_set, None, false),
+                },
+                Err(_) => (details, None, None, false),
+            },
+            Err(_) => {
+                println!("no collection details found");
+                (None, None, None, false)
+            }
+        };
 
     let metadata = Metadata {
         key,
@@ -60,20 +112,84 @@ pub fn meta_deser_unchecked(buf: &mut &[u8]) -> Result<Metadata, BorshError> {
         collection,
         uses,
         collection_details,
+        rule_set,
+        fungible_details,
+        history_enabled,
+    };
+
+    Ok((metadata, repaired))
+}
+
+// Strict deserialization of the metadata region of a migrated account. Every field must be
+// present and well formed and only zeros may follow them; unlike `meta_deser_unchecked`
+// nothing falls back to `None`.
+pub fn meta_deser_strict(mut region: &[u8]) -> Result<Metadata, BorshError> {
+    let key: Key = BorshDeserialize::deserialize(&mut region)?;
+    if key != Key::MetadataV2 {
+        return Err(BorshError::new(ErrorKind::InvalidData, "DataTypeMismatch"));
+    }
+    let metadata = meta_deser_strict_body(key, &mut region)?;
+    if region.iter().any(|b| *b != 0) {
+        return Err(BorshError::new(
+            ErrorKind::InvalidData,
+            "Unexpected data after metadata fields",
+        ));
+    }
+    Ok(metadata)
+}
+
+fn meta_deser_strict_body(key: Key, buf: &mut &[u8]) -> Result<Metadata, BorshError> {
+    let metadata = Metadata {
+        key,
+        update_authority: BorshDeserialize::deserialize(buf)?,
+        mint: BorshDeserialize::deserialize(buf)?,
+        data: BorshDeserialize::deserialize(buf)?,
+        primary_sale_happened: BorshDeserialize::deserialize(buf)?,
+        is_mutable: BorshDeserialize::deserialize(buf)?,
+        edition_nonce: BorshDeserialize::deserialize(buf)?,
+        token_standard: BorshDeserialize::deserialize(buf)?,
+        collection: BorshDeserialize::deserialize(buf)?,
+        uses: BorshDeserialize::deserialize(buf)?,
+        collection_details: BorshDeserialize::deserialize(buf)?,
+        rule_set: BorshDeserialize::deserialize(buf)?,
+        fungible_details: BorshDeserialize::deserialize(buf)?,
+        history_enabled: BorshDeserialize::deserialize(buf)?,
     };
 
+    let data = &metadata.data;
+    if data.name.len() > MAX_NAME_LENGTH
+        || data.symbol.len() > MAX_SYMBOL_LENGTH
+        || data.uri.len() > MAX_URI_LENGTH
+        || matches!(&data.creators, Some(creators) if creators.len() > MAX_CREATOR_LIMIT)
+    {
+        return Err(BorshError::new(
+            ErrorKind::InvalidData,
+            "Metadata field exceeds its maximum length",
+        ));
+    }
+
     Ok(metadata)
 }
 
-pub fn clean_write_metadata(
+// Whether `meta_deser_unchecked` had to fall back to defaults for fields of this account that
+// could not be read cleanly. Accounts it cannot read at all were not repaired either. Meant for
+// client side diagnostics of corrupted accounts.
+pub fn meta_deser_repaired(mut buf: &[u8]) -> bool {
+    matches!(meta_deser_with_repairs(&mut buf), Ok((_, true)))
+}
+
+pub(crate) fn clean_write_metadata(
     metadata: &mut Metadata,
     metadata_account_info: &AccountInfo,
 ) -> ProgramResult {
     // Clear all data to ensure it is serialized cleanly with no trailing data due to creators array resizing.
     let mut metadata_account_info_data = metadata_account_info.try_borrow_mut_data()?;
-    metadata_account_info_data[0..].fill(0);
 
-    metadata.serialize(&mut *metadata_account_info_data)?;
+    // Only clear the metadata region, migrated accounts keep their extension area after it.
+    let end = METADATA_EXTENSIONS_OFFSET.min(metadata_account_info_data.len());
+    metadata_account_info_data[0..end].fill(0);
+
+    metadata.serialize(&mut &mut metadata_account_info_data[0..end])?;
 
     Ok(())
 }
@@ -83,7 +199,13 @@ pub mod tests {
     use solana_program::pubkey;
 
     use super::*;
-    pub use crate::{state::Creator, utils::puff_out_data_fields};
+    pub use crate::{
+        state::{
+            Creator, MetadataV2, TokenMetadataAccount, MAX_CREATOR_LEN, MAX_METADATA_LEN,
+            MIN_METADATA_V2_LEN,
+        },
+        utils::puff_out_data_fields,
+    };
 
     // Pesky Penguins #8060 (NOOT!)
     // Corrupted data that can't be deserialized with the standard BoshDeserialization implementation.
@@ -161,6 +283,9 @@ pub mod tests {
             collection: None,
             uses: None,
             collection_details: None,
+            rule_set: None,
+            fungible_details: None,
+            history_enabled: false,
         };
 
         puff_out_data_fields(&mut metadata);
@@ -176,4 +301,160 @@ pub mod tests {
 
         assert_eq!(metadata, expected_metadata);
     }
+
+    #[test]
+    fn detect_repaired_metadata() {
+        assert!(meta_deser_repaired(pesky_data()));
+
+        let mut data = expected_pesky_metadata().try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+        assert!(!meta_deser_repaired(&data));
+    }
+
+    #[test]
+    fn detect_unrepaired_metadata_failing_strict_checks() {
+        // Fields over their maximum length fail strict deserialization but are read as they are.
+        let mut metadata = expected_pesky_metadata();
+        metadata.data.name = "a".repeat(MAX_NAME_LENGTH + 1);
+        let mut data = metadata.try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+        assert!(meta_deser_strict_body(Key::MetadataV1, &mut &data[1..]).is_err());
+        assert!(!meta_deser_repaired(&data));
+
+        // Nothing is repaired in an account that cannot be read at all.
+        assert!(!meta_deser_repaired(&[]));
+        assert!(!meta_deser_repaired(&data[..40]));
+    }
+
+    #[test]
+    fn deserialize_fungible_details() {
+        let mut metadata = expected_pesky_metadata();
+        metadata.fungible_details = Some(FungibleDetails::V1 {
+            supply_cap: 1_000_000,
+        });
+        let mut data = metadata.try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+
+        let deserialized: Metadata = Metadata::safe_deserialize(&data).unwrap();
+        assert_eq!(deserialized, metadata);
+    }
+
+    #[test]
+    fn deserialize_history_enabled() {
+        let mut metadata = expected_pesky_metadata();
+        metadata.history_enabled = true;
+        let mut data = metadata.try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+
+        let deserialized: Metadata = Metadata::safe_deserialize(&data).unwrap();
+        assert!(deserialized.history_enabled);
+    }
+
+    fn migrated_pesky_metadata() -> Metadata {
+        let mut metadata = expected_pesky_metadata();
+        metadata.key = Key::MetadataV2;
+        metadata
+    }
+
+    fn migrated_pesky_data() -> Vec<u8> {
+        MetadataV2 {
+            metadata: migrated_pesky_metadata(),
+            extensions: vec![1, 2, 3],
+        }
+        .try_to_vec()
+        .unwrap()
+    }
+
+    #[test]
+    fn metadata_v2_round_trip() {
+        let data = migrated_pesky_data();
+        assert_eq!(data.len(), MIN_METADATA_V2_LEN + 3);
+        assert_eq!(data[0], Key::MetadataV2 as u8);
+
+        let migrated: MetadataV2 = MetadataV2::safe_deserialize(&data).unwrap();
+        assert_eq!(migrated.metadata, migrated_pesky_metadata());
+        assert_eq!(migrated.extensions, vec![1, 2, 3]);
+        assert_eq!(migrated.account_size(), data.len());
+
+        // The metadata fields read the same as `Metadata`.
+        let metadata: Metadata = Metadata::safe_deserialize(&data).unwrap();
+        assert_eq!(metadata, migrated.metadata);
+    }
+
+    #[test]
+    fn metadata_v2_requires_migration() {
+        let mut data = expected_pesky_metadata().try_to_vec().unwrap();
+        data.resize(MAX_METADATA_LEN, 0);
+        assert!(MetadataV2::safe_deserialize::<MetadataV2>(&data).is_err());
+
+        // Only the `MetadataV2` key is written with the extension area.
+        let metadata = MetadataV2 {
+            metadata: expected_pesky_metadata(),
+            extensions: vec![],
+        };
+        assert!(metadata.try_to_vec().is_err());
+    }
+
+    #[test]
+    fn largest_metadata_fits_before_extensions() {
+        let creators = (0..MAX_CREATOR_LIMIT)
+            .map(|_| Creator {
+                address: Pubkey::new_unique(),
+                verified: true,
+                share: 1,
+            })
+            .collect::<Vec<_>>();
+        assert_eq!(
+            creators.try_to_vec().unwrap().len(),
+            4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN
+        );
+
+        let mut metadata = expected_pesky_metadata();
+        metadata.data.creators = Some(creators);
+        metadata.token_standard = Some(TokenStandard::NonFungible);
+        metadata.collection = Some(Collection {
+            verified: true,
+            key: Pubkey::new_unique(),
+        });
+        metadata.uses = Some(Uses {
+            use_method: crate::state::UseMethod::Multiple,
+            remaining: u64::MAX,
+            total: u64::MAX,
+        });
+        metadata.collection_details = Some(CollectionDetails::V1 { size: u64::MAX });
+        metadata.rule_set = Some(Pubkey::new_unique());
+        metadata.fungible_details = Some(FungibleDetails::V1 {
+            supply_cap: u64::MAX,
+        });
+        metadata.history_enabled = true;
+
+        assert!(metadata.try_to_vec().unwrap().len() <= METADATA_EXTENSIONS_OFFSET);
+    }
+
+    #[test]
+    fn metadata_v2_rejects_garbage() {
+        // Trailing data after the extensions.
+        let mut data = migrated_pesky_data();
+        data.push(1);
+        assert!(MetadataV2::safe_deserialize::<MetadataV2>(&data).is_err());
+
+        // Extension length larger than the rest of the account.
+        let mut data = migrated_pesky_data();
+        data[METADATA_EXTENSIONS_OFFSET..METADATA_EXTENSIONS_OFFSET + 4]
+            .copy_from_slice(&4u32.to_le_bytes());
+        assert!(MetadataV2::safe_deserialize::<MetadataV2>(&data).is_err());
+
+        // Stale data between the metadata fields and the extensions.
+        let mut data = migrated_pesky_data();
+        data[METADATA_EXTENSIONS_OFFSET - 1] = 1;
+        assert!(MetadataV2::safe_deserialize::<MetadataV2>(&data).is_err());
+        assert!(meta_deser_unchecked(&mut data.as_slice()).is_err());
+
+        // Invalid option tag where the fallback parser would have guessed `None`.
+        let offset = expected_pesky_metadata().try_to_vec().unwrap().len() - 1;
+        let mut data = migrated_pesky_data();
+        data[offset] = 2;
+        assert!(MetadataV2::safe_deserialize::<MetadataV2>(&data).is_err());
+        assert!(meta_deser_unchecked(&mut data.as_slice()).is_err());
+    }
 }
@@ -456,6 +456,190 @@ pub enum MetadataError {
     /// 115 - Item is already unverified.
     #[error("Item is already unverified.")]
     AlreadyUnverified,
+
+    /// 116 - Rule set account does not match the one bound to this asset.
+    #[error("Rule set account does not match the one bound to this asset")]
+    InvalidRuleSet,
+
+    /// 117 - Rule set has too many entries.
+    #[error("Rule set has too many entries")]
+    TooManyRuleSetEntries,
+
+    /// 118 - Asset is already bound to a rule set.
+    #[error("Asset is already bound to a rule set")]
+    AlreadyBoundToRuleSet,
+
+    /// 119 - Asset is not bound to a rule set.
+    #[error("Asset is not bound to a rule set")]
+    NotBoundToRuleSet,
+
+    /// 120 - Calling program is not allowed by the rule set.
+    #[error("Calling program is not allowed by the rule set")]
+    ProgramNotAllowedByRuleSet,
+
+    /// 121 - Authority is not an escrow authority of the rule set.
+    #[error("Authority is not an escrow authority of the rule set")]
+    EscrowAuthorityNotAllowedByRuleSet,
+
+    /// 122 - Rule set authority given does not match.
+    #[error("Rule set authority given does not match")]
+    InvalidRuleSetAuthority,
+
+    /// 123 - Metadata account has already been migrated.
+    #[error("Metadata account has already been migrated")]
+    MetadataAlreadyMigrated,
+
+    /// 124 - Update delegate record already exists.
+    #[error("Update delegate record already exists")]
+    UpdateDelegateRecordAlreadyExists,
+
+    /// 125 - Update delegate record does not exist.
+    #[error("Update delegate record does not exist")]
+    UpdateDelegateRecordDoesNotExist,
+
+    /// 126 - Update delegate record is invalid for this delegate or update authority.
+    #[error("Update delegate record is invalid for this delegate or update authority")]
+    InvalidUpdateDelegateRecord,
+
+    /// 127 - Update delegate is not allowed to make this change.
+    #[error("Update delegate is not allowed to make this change")]
+    UpdateDelegateScopeMissing,
+
+    /// 128 - Only the update authority or the delegate can revoke an update delegate.
+    #[error("Only the update authority or the delegate can revoke an update delegate")]
+    RevokeUpdateDelegateSignerIncorrect,
+
+    /// 129 - Too many attributes.
+    #[error("Too many attributes")]
+    TooManyAttributes,
+
+    /// 130 - Attribute key is empty or too long.
+    #[error("Attribute key is empty or too long")]
+    InvalidAttributeKey,
+
+    /// 131 - Attribute value too long.
+    #[error("Attribute value too long")]
+    AttributeValueTooLong,
+
+    /// 132 - Attribute keys must be unique.
+    #[error("Attribute keys must be unique")]
+    DuplicateAttributeKey,
+
+    /// 133 - Attribute not found.
+    #[error("Attribute not found")]
+    AttributeNotFound,
+
+    /// 134 - Metadata attributes account already exists.
+    #[error("Metadata attributes account already exists")]
+    MetadataAttributesAlreadyExist,
+
+    /// 135 - No metadata accounts were provided.
+    #[error("No metadata accounts were provided")]
+    NoMetadataAccountsProvided,
+
+    /// 136 - Not a Print Edition
+    #[error("This edition is not a Print Edition")]
+    NotAPrintEdition,
+
+    /// 137 - Print Edition does not belong to the Master Edition
+    #[error("Print Edition does not belong to the Master Edition")]
+    PrintEditionDoesNotMatchMasterEdition,
+
+    /// 138 - Edition is not marked as printed in the Edition Marker
+    #[error("Edition is not marked as printed in the Edition Marker")]
+    EditionNotMarked,
+
+    /// 139 - Collection hierarchy is too deep
+    #[error("Collection hierarchy is too deep")]
+    CollectionDepthExceeded,
+
+    /// 140 - A collection cannot be its own ancestor
+    #[error("A collection cannot be its own ancestor")]
+    CollectionHierarchyCycle,
+
+    /// 141 - Use authority record has expired
+    #[error("Use authority record has expired")]
+    UseAuthorityRecordExpired,
+
+    /// 142 - Use authority record is not active yet
+    #[error("Use authority record is not active yet")]
+    UseAuthorityRecordNotActive,
+
+    /// 143 - Use authority record has not expired
+    #[error("Use authority record has not expired")]
+    UseAuthorityRecordNotExpired,
+
+    /// 144 - Use authority time window is invalid
+    #[error("Use authority time window is invalid")]
+    InvalidUseAuthorityTimeWindow,
+
+    /// 145 - This token is not a fungible
+    #[error("This token is not a fungible")]
+    NotAFungible,
+
+    /// 146 - Fungible details are already set
+    #[error("Fungible details are already set")]
+    FungibleDetailsAlreadySet,
+
+    /// 147 - Missing fungible details
+    #[error("Missing fungible details")]
+    MissingFungibleDetails,
+
+    /// 148 - Minting would exceed the fungible supply cap
+    #[error("Minting would exceed the fungible supply cap")]
+    FungibleSupplyCapExceeded,
+
+    /// 149 - A creator change proposal already exists for this metadata
+    #[error("A creator change proposal already exists for this metadata")]
+    CreatorChangeProposalAlreadyExists,
+
+    /// 150 - Metadata has no verified creators, update the creators directly instead
+    #[error("Metadata has no verified creators, update the creators directly instead")]
+    CreatorChangeApprovalNotRequired,
+
+    /// 151 - Only verified creators can approve a creator change
+    #[error("Only verified creators can approve a creator change")]
+    CreatorChangeApproverNotVerified,
+
+    /// 152 - This creator already approved the creator change
+    #[error("This creator already approved the creator change")]
+    CreatorChangeAlreadyApproved,
+
+    /// 153 - Metadata history is already enabled
+    #[error("Metadata history is already enabled")]
+    MetadataHistoryAlreadyEnabled,
+
+    /// 154 - Metadata history does not belong to a metadata of this instruction
+    #[error("Metadata history does not belong to a metadata of this instruction")]
+    MissingMetadataHistory,
+
+    /// 155 - Metadata with history enabled cannot be changed by a batch instruction
+    #[error("Metadata with history enabled cannot be changed by a batch instruction")]
+    MetadataHistoryNotSupportedInBatch,
+
+    /// 156 - Only non-fungible assets can be bound to a rule set
+    #[error("Only non-fungible assets can be bound to a rule set")]
+    RuleSetRequiresNonFungible,
+
+    /// 157 - Bound assets cannot be transferred through nested CPI
+    #[error("Bound assets cannot be transferred through nested CPI")]
+    NestedCpiNotAllowedByRuleSet,
+
+    /// 158 - Token account or mint uses an unsupported Token-2022 extension
+    #[error("Token account or mint uses an unsupported Token-2022 extension")]
+    UnsupportedTokenExtension,
+
+    /// 159 - The fungible supply cap can only be raised
+    #[error("The fungible supply cap can only be raised")]
+    FungibleSupplyCapCannotDecrease,
+
+    /// 160 - Verified creators must approve this change through a creator change proposal
+    #[error("Verified creators must approve this change through a creator change proposal")]
+    CreatorChangeRequiresApproval,
+
+    /// 161 - This instruction cannot be recorded in a metadata history
+    #[error("This instruction cannot be recorded in a metadata history")]
+    MetadataHistoryNotSupported,
 }
 
 impl PrintProgramError for MetadataError {
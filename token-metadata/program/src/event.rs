@@ -0,0 +1,358 @@
+//! Structured events of state-changing instructions.
+//!
+//! Every event is logged with `sol_log_data` as two fields, [`EVENT_DISCRIMINATOR`] followed by
+//! the borsh serialized [`VersionedEvent`], and shows up in the transaction logs as a
+//! `Program data: <base64> <base64>` line. New events are only ever appended to
+//! [`MetadataEvent`]; a breaking change to an existing event gets a new [`VersionedEvent`]
+//! variant.
+
+use crate::state::{
+    Attribute, Collection, CollectionDetails, Creator, Data, FungibleDetails, Key, Metadata,
+    TokenStandard, Uses,
+};
+use borsh::{BorshDeserialize, BorshSerialize};
+use solana_program::{log::sol_log_data, pubkey::Pubkey};
+#[cfg(feature = "serde-feature")]
+use {
+    serde::{Deserialize, Serialize},
+    serde_with::{As, DisplayFromStr},
+};
+
+/// First field of every event log, telling token-metadata events apart from other program data.
+pub const EVENT_DISCRIMINATOR: [u8; 8] = *b"mpl_tmev";
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum VersionedEvent {
+    V1(MetadataEvent),
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Copy)]
+pub enum AuthorityRecordType {
+    Use,
+    Collection,
+    UpdateDelegate,
+}
+
+/// The mutable fields of a metadata account, as carried by `MetadataEvent::MetadataUpdated`.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct MetadataState {
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub update_authority: Pubkey,
+    pub data: Data,
+    pub primary_sale_happened: bool,
+    pub is_mutable: bool,
+    pub collection: Option<Collection>,
+    pub uses: Option<Uses>,
+    pub token_standard: Option<TokenStandard>,
+    pub collection_details: Option<CollectionDetails>,
+    #[cfg_attr(
+        feature = "serde-feature",
+        serde(with = "As::<Option<DisplayFromStr>>")
+    )]
+    pub rule_set: Option<Pubkey>,
+    pub fungible_details: Option<FungibleDetails>,
+    pub history_enabled: bool,
+}
+
+impl From<&Metadata> for MetadataState {
+    fn from(metadata: &Metadata) -> Self {
+        MetadataState {
+            update_authority: metadata.update_authority,
+            data: metadata.data.clone(),
+            primary_sale_happened: metadata.primary_sale_happened,
+            is_mutable: metadata.is_mutable,
+            collection: metadata.collection.clone(),
+            uses: metadata.uses.clone(),
+            token_standard: metadata.token_standard.clone(),
+            collection_details: metadata.collection_details.clone(),
+            rule_set: metadata.rule_set,
+            fungible_details: metadata.fungible_details.clone(),
+            history_enabled: metadata.history_enabled,
+        }
+    }
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum MetadataEvent {
+    MetadataCreated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        update_authority: Pubkey,
+        data: Data,
+    },
+    MetadataUpdated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        /// Update authority or update delegate that signed the update
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        before: MetadataState,
+        after: MetadataState,
+    },
+    CreatorVerificationChanged {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        creator: Pubkey,
+        verified: bool,
+    },
+    CollectionVerificationChanged {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        collection_mint: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        collection_authority: Pubkey,
+        verified: bool,
+    },
+    Burned {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        owner: Pubkey,
+        /// Master Edition of the burned print, if it was a print
+        #[cfg_attr(
+            feature = "serde-feature",
+            serde(with = "As::<Option<DisplayFromStr>>")
+        )]
+        master_edition: Option<Pubkey>,
+    },
+    Utilized {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        /// Owner or use authority that utilized the token
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        user: Pubkey,
+        number_of_uses: u64,
+        remaining_before: u64,
+        remaining_after: u64,
+    },
+    AuthorityApproved {
+        authority_type: AuthorityRecordType,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        record: Pubkey,
+    },
+    AuthorityRevoked {
+        authority_type: AuthorityRecordType,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        record: Pubkey,
+    },
+    EditionPrinted {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        master_metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        master_edition: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        new_metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        new_edition: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        new_mint: Pubkey,
+        edition: u64,
+    },
+    UsesRefilled {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        /// Update authority or update delegate that refilled the uses
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        refilled: u64,
+        remaining_before: u64,
+        remaining_after: u64,
+    },
+    CreatorChangeApproved {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        creator: Pubkey,
+        /// The creators now set on the metadata, if this was the last approval missing
+        applied_creators: Option<Vec<Creator>>,
+    },
+    FungibleMinted {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        mint: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        destination: Pubkey,
+        amount: u64,
+        supply_after: u64,
+    },
+    PrimarySaleHappened {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        /// Token owner that flagged the primary sale
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        owner: Pubkey,
+    },
+    CollectionSizeSet {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        collection_authority: Pubkey,
+        size: u64,
+    },
+    TokenStandardSet {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        token_standard: TokenStandard,
+    },
+    RuleSetBound {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        rule_set: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        token_account: Pubkey,
+    },
+    MetadataMigrated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        key: Key,
+    },
+    RuleSetCreated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        rule_set: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+        allowed_programs: Vec<Pubkey>,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+        escrow_authorities: Vec<Pubkey>,
+    },
+    RuleSetUpdated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        rule_set: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        authority: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+        allowed_programs: Vec<Pubkey>,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+        escrow_authorities: Vec<Pubkey>,
+    },
+    MetadataAttributesCreated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata_attributes: Pubkey,
+        attributes: Vec<Attribute>,
+    },
+    MetadataAttributesUpdated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata_attributes: Pubkey,
+        /// Attributes added or overwritten by the update
+        attributes: Vec<Attribute>,
+    },
+    MetadataAttributesRemoved {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata_attributes: Pubkey,
+        keys: Vec<String>,
+    },
+    FungibleDetailsChanged {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        /// `None` once the mint authority is released
+        before: Option<FungibleDetails>,
+        after: Option<FungibleDetails>,
+    },
+    CreatorChangeProposed {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        proposal: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        update_authority: Pubkey,
+        creators: Vec<Creator>,
+    },
+    CreatorChangeCancelled {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        proposal: Pubkey,
+    },
+    MetadataHistoryCreated {
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata: Pubkey,
+        #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+        metadata_history: Pubkey,
+    },
+}
+
+/// Logs the event as the current event version.
+pub fn emit(event: MetadataEvent) {
+    // Serializing into a Vec cannot fail.
+    let data = VersionedEvent::V1(event).try_to_vec().unwrap();
+    sol_log_data(&[&EVENT_DISCRIMINATOR, &data]);
+}
+
+/// Decodes the event of a `Program data:` log line, or `None` for any other line.
+#[cfg(any(feature = "serde-feature", feature = "test-bpf"))]
+pub fn decode_log(log: &str) -> Option<VersionedEvent> {
+    let mut fields = log.strip_prefix("Program data: ")?.split(' ');
+    let discriminator = base64::decode(fields.next()?).ok()?;
+    if discriminator != EVENT_DISCRIMINATOR {
+        return None;
+    }
+    let data = base64::decode(fields.next()?).ok()?;
+    VersionedEvent::try_from_slice(&data).ok()
+}
+
+/// Decodes all token-metadata events of a transaction's log messages, in order.
+#[cfg(any(feature = "serde-feature", feature = "test-bpf"))]
+pub fn decode_logs<'a, I: IntoIterator<Item = &'a str>>(logs: I) -> Vec<VersionedEvent> {
+    logs.into_iter().filter_map(decode_log).collect()
+}
+
+#[cfg(all(test, feature = "serde-feature"))]
+mod tests {
+    use super::*;
+
+    fn log_line(fields: &[&[u8]]) -> String {
+        let fields: Vec<String> = fields.iter().map(base64::encode).collect();
+        format!("Program data: {}", fields.join(" "))
+    }
+
+    #[test]
+    fn decode_event_log() {
+        let event = MetadataEvent::CreatorVerificationChanged {
+            metadata: Pubkey::new_unique(),
+            creator: Pubkey::new_unique(),
+            verified: true,
+        };
+        let data = VersionedEvent::V1(event.clone()).try_to_vec().unwrap();
+        let logs = [
+            "Program log: Instruction: Sign Metadata".to_string(),
+            log_line(&[&EVENT_DISCRIMINATOR, &data]),
+            log_line(&[b"not an event", &data]),
+        ];
+
+        let events = decode_logs(logs.iter().map(String::as_str));
+        assert_eq!(events, vec![VersionedEvent::V1(event)]);
+    }
+}
@@ -1,8 +1,8 @@
 use crate::{
     deprecated_instruction::{MintPrintingTokensViaTokenArgs, SetReservationListArgs},
     state::{
-        Collection, CollectionDetails, Creator, Data, DataV2, Uses, EDITION,
-        EDITION_MARKER_BIT_SIZE, PREFIX,
+        Attribute, Collection, CollectionDetails, Creator, Data, DataV2, UpdateDelegateScopes,
+        Uses, EDITION, EDITION_MARKER_BIT_SIZE, PREFIX,
     },
 };
 use borsh::{BorshDeserialize, BorshSerialize};
@@ -97,6 +97,27 @@ pub struct MintNewEditionFromMasterEditionViaTokenArgs {
     pub edition: u64,
 }
 
+/// Placeholder in an `EditionOverrides` name suffix that is replaced by the edition number.
+pub const EDITION_PLACEHOLDER: &str = "{edition}";
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, Default)]
+pub struct EditionOverrides {
+    /// Appended to the master name, with `{edition}` replaced by the edition number, e.g. ` #{edition}`
+    pub name_suffix: Option<String>,
+    /// Replaces the master uri for this edition
+    pub uri: Option<String>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct MintNewEditionWithOverridesArgs {
+    pub edition: u64,
+    pub overrides: EditionOverrides,
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -104,6 +125,25 @@ pub struct ApproveUseAuthorityArgs {
     pub number_of_uses: u64,
 }
 
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ApproveTimedUseAuthorityArgs {
+    pub number_of_uses: u64,
+    /// Unix timestamp before which the use authority cannot utilize
+    pub not_before: Option<i64>,
+    /// Unix timestamp from which the use authority cannot utilize and the record can be closed by anyone
+    pub expires_at: Option<i64>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct RefillUsesArgs {
+    /// Number of uses to add back, capped at the total
+    pub amount: u64,
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -118,6 +158,96 @@ pub struct SetCollectionSizeArgs {
     pub size: u64,
 }
 
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CreateFungibleDetailsArgs {
+    /// Hard cap on the mint supply, in base units
+    pub supply_cap: u64,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct MintFungibleViaMetadataArgs {
+    pub amount: u64,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UpdateFungibleDetailsArgs {
+    /// New supply cap, which cannot be lower than the current one. `None` removes the cap and
+    /// hands the mint authority to the new mint authority.
+    pub supply_cap: Option<u64>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct RecordMetadataHistoryArgs {
+    /// Borsh serialized `MetadataInstruction` to run, whose accounts follow the history account
+    pub instruction: Vec<u8>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ProposeCreatorChangeArgs {
+    pub creators: Vec<Creator>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CreateMetadataAttributesArgs {
+    pub attributes: Vec<Attribute>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UpdateMetadataAttributesArgs {
+    /// Attributes to insert, replacing the value of existing keys.
+    pub attributes: Vec<Attribute>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct RemoveMetadataAttributesArgs {
+    pub keys: Vec<String>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct ApproveUpdateDelegateArgs {
+    pub scopes: UpdateDelegateScopes,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct CreateRuleSetArgs {
+    /// Programs allowed to CPI into [transfer] for bound tokens.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub allowed_programs: Vec<Pubkey>,
+    /// Authorities allowed to hold or move bound tokens on behalf of their owners.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub escrow_authorities: Vec<Pubkey>,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct UpdateRuleSetArgs {
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub allowed_programs: Vec<Pubkey>,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub escrow_authorities: Vec<Pubkey>,
+}
+
 /// Instructions supported by the Metadata program.
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, Clone, ShankInstruction)]
@@ -310,7 +440,8 @@ pub enum MetadataInstruction {
 
     /// Update a Metadata with is_mutable as a parameter
     #[account(0, writable, name="metadata", desc="Metadata account")]
-    #[account(1, signer, name="update_authority", desc="Update authority key")]
+    #[account(1, signer, name="update_authority", desc="Update authority key, or an update delegate")]
+    #[account(2, optional, name="update_delegate_record", desc="Update Delegate Record PDA, if signing as an update delegate")]
     UpdateMetadataAccountV2(UpdateMetadataAccountArgsV2),
 
     /// Create Metadata object.
@@ -516,6 +647,263 @@ pub enum MetadataInstruction {
     #[account(2, name="mint", desc="Mint account")]
     #[account(3, optional, name="edition", desc="Edition account")]
     SetTokenStandard,
+
+    /// Create a royalty enforcement rule set owned by the signing authority.
+    #[account(0, writable, name="rule_set", desc="Rule set (pda of ['metadata', program id, 'rule_set', authority])")]
+    #[account(1, signer, name="authority", desc="Rule set authority")]
+    #[account(2, signer, writable, name="payer", desc="Payer")]
+    #[account(3, name="system_program", desc="System program")]
+    #[account(4, name="rent", desc="Rent info")]
+    CreateRuleSet(CreateRuleSetArgs),
+
+    /// Replace the allowed programs and escrow authorities of an existing rule set.
+    #[account(0, writable, name="rule_set", desc="Rule set (pda of ['metadata', program id, 'rule_set', authority])")]
+    #[account(1, signer, name="authority", desc="Rule set authority")]
+    UpdateRuleSet(UpdateRuleSetArgs),
+
+    /// Bind a NonFungible asset to a rule set. The holder's token account is frozen by the
+    /// edition and can afterwards only be moved through [transfer].
+    #[account(0, writable, name="metadata", desc="Metadata account")]
+    #[account(1, signer, name="update_authority", desc="Metadata update authority")]
+    #[account(2, name="rule_set", desc="Rule set account")]
+    #[account(3, name="edition", desc="Master Edition account, freeze authority of the mint")]
+    #[account(4, name="mint", desc="Mint of the asset")]
+    #[account(5, writable, name="token_account", desc="Token account holding the asset")]
+    #[account(6, signer, name="token_owner", desc="Owner of the token account")]
+    #[account(7, name="token_program", desc="Token program")]
+    BindRuleSet,
+
+    /// Move an asset bound to a rule set, thawing the source and freezing the destination.
+    /// Callable directly or through a single CPI from a program allowed by the rule set.
+    #[account(0, name="metadata", desc="Metadata account")]
+    #[account(1, name="rule_set", desc="Rule set bound to the metadata")]
+    #[account(2, name="edition", desc="Master Edition account, freeze authority of the mint")]
+    #[account(3, name="mint", desc="Mint of the asset")]
+    #[account(4, writable, name="source", desc="Source token account")]
+    #[account(5, writable, name="destination", desc="Destination token account")]
+    #[account(6, signer, name="authority", desc="Owner of the source, or an escrow authority delegate")]
+    #[account(7, name="token_program", desc="Token program")]
+    #[account(8, name="sysvar_instructions", desc="Instructions sysvar")]
+    Transfer,
+
+    /// Rewrite a metadata account cleanly in the MetadataV2 layout, which reallocates the account
+    /// to add a length-prefixed extension area after the metadata fields.
+    #[account(0, writable, name="metadata", desc="Metadata account")]
+    #[account(1, signer, name="update_authority", desc="Metadata update authority")]
+    #[account(2, signer, writable, name="payer", desc="Payer of the additional rent")]
+    #[account(3, name="system_program", desc="System program")]
+    #[account(4, name="rent", desc="Rent info")]
+    MigrateMetadata,
+
+    /// Approve a delegate to change a scoped subset of the metadata fields through [update_metadata_accounts_v2].
+    #[account(0, writable, name="update_delegate_record", desc="Update Delegate Record PDA")]
+    #[account(1, name="delegate", desc="Update delegate")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, signer, writable, name="payer", desc="Payer")]
+    #[account(4, name="metadata", desc="Metadata account")]
+    #[account(5, name="mint", desc="Mint of the metadata")]
+    #[account(6, name="system_program", desc="System program")]
+    #[account(7, name="rent", desc="Rent info")]
+    ApproveUpdateDelegate(ApproveUpdateDelegateArgs),
+
+    /// Revoke an update delegate.
+    #[account(0, writable, name="update_delegate_record", desc="Update Delegate Record PDA")]
+    #[account(1, name="delegate", desc="Update delegate")]
+    #[account(2, signer, writable, name="revoke_authority", desc="Update authority, or the delegate itself")]
+    #[account(3, name="metadata", desc="Metadata account")]
+    #[account(4, name="mint", desc="Mint of the metadata")]
+    RevokeUpdateDelegate,
+
+    /// Create the on-chain key/value attributes of a Metadata.
+    #[account(0, writable, name="metadata_attributes", desc="Metadata Attributes (pda of ['metadata', program id, metadata, 'attributes'])")]
+    #[account(1, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, signer, writable, name="payer", desc="Payer")]
+    #[account(4, name="system_program", desc="System program")]
+    #[account(5, name="rent", desc="Rent info")]
+    CreateMetadataAttributes(CreateMetadataAttributesArgs),
+
+    /// Insert or replace on-chain attributes of a Metadata.
+    #[account(0, writable, name="metadata_attributes", desc="Metadata Attributes account")]
+    #[account(1, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    UpdateMetadataAttributes(UpdateMetadataAttributesArgs),
+
+    /// Remove on-chain attributes of a Metadata by key.
+    #[account(0, writable, name="metadata_attributes", desc="Metadata Attributes account")]
+    #[account(1, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    RemoveMetadataAttributes(RemoveMetadataAttributesArgs),
+
+    /// Sign every metadata passed in the remaining accounts that has you as an unverified creator.
+    #[account(0, signer, name="creator", desc="Creator")]
+    SignMetadataBatch,
+
+    /// Verify every metadata passed in the remaining accounts as an item of a sized collection,
+    /// incrementing the collection size once by the number of items.
+    #[account(0, signer, name="collection_authority", desc="Collection Update authority")]
+    #[account(1, signer, writable, name="payer", desc="payer")]
+    #[account(2, name="collection_mint", desc="Mint of the Collection")]
+    #[account(3, writable, name="collection", desc="Metadata Account of the Collection")]
+    #[account(4, name="collection_master_edition_account", desc="MasterEdition2 Account of the Collection Token")]
+    #[account(5, name="collection_authority_record", desc="Collection Authority Record PDA, or the Token Metadata program id if not delegated")]
+    VerifySizedCollectionItemBatch,
+
+    /// Same as MintNewEditionFromMasterEditionViaToken, but the name and uri of the new edition's metadata
+    /// are customized with the given overrides. Requires the master update authority to sign.
+    #[account(0, writable, name="new_metadata", desc="New Metadata key (pda of ['metadata', program id, mint id])")]
+    #[account(1, writable, name="new_edition", desc="New Edition (pda of ['metadata', program id, mint id, 'edition'])")]
+    #[account(2, writable, name="master_edition", desc="Master Record Edition V2 (pda of ['metadata', program id, master metadata mint id, 'edition'])")]
+    #[account(3, writable, name="new_mint", desc="Mint of new token - THIS WILL TRANSFER AUTHORITY AWAY FROM THIS KEY")]
+    #[account(4, writable, name="edition_mark_pda", desc="Edition pda to mark creation - will be checked for pre-existence. (pda of ['metadata', program id, master metadata mint id, 'edition', edition_number]) where edition_number is NOT the edition number you pass in args but actually edition_number = floor(edition/EDITION_MARKER_BIT_SIZE).")]
+    #[account(5, signer, name="new_mint_authority", desc="Mint authority of new mint")]
+    #[account(6, signer, writable, name="payer", desc="payer")]
+    #[account(7, signer, name="token_account_owner", desc="owner of token account containing master token (#8)")]
+    #[account(8, name="token_account", desc="token account containing token from master metadata mint")]
+    #[account(9, signer, name="new_metadata_update_authority", desc="Update authority of the master metadata")]
+    #[account(10, name="metadata", desc="Master record metadata account")]
+    #[account(11, name="token_program", desc="Token program")]
+    #[account(12, name="system_program", desc="System program")]
+    #[account(13, name="rent", desc="Rent info")]
+    MintNewEditionFromMasterEditionViaTokenWithOverrides(MintNewEditionWithOverridesArgs),
+
+    /// Completely burn a print edition NFT, closing its metadata and edition accounts, clearing its
+    /// bit in the edition marker and decrementing the supply of the master edition, if it still exists.
+    /// The edition marker is closed once it no longer tracks any edition.
+    /// A master edition with outstanding prints is burned by passing it as both the master and the print
+    /// edition; its prints remain burnable afterwards.
+    #[account(0, writable, name="metadata", desc="Metadata (pda of ['metadata', program id, mint id])")]
+    #[account(1, signer, writable, name="owner", desc="NFT owner")]
+    #[account(2, writable, name="print_edition_mint", desc="Mint of the print edition NFT")]
+    #[account(3, name="master_edition_mint", desc="Mint of the original/master NFT")]
+    #[account(4, writable, name="print_edition_token_account", desc="Token account the print edition NFT is in")]
+    #[account(5, writable, name="master_edition_account", desc="MasterEdition2 of the original NFT")]
+    #[account(6, writable, name="print_edition_account", desc="Print Edition account of the NFT")]
+    #[account(7, writable, name="edition_marker_account", desc="Edition Marker PDA of the NFT")]
+    #[account(8, name="spl token program", desc="SPL Token Program")]
+    #[account(9, optional, writable, name="collection_metadata", desc="Metadata of the Collection")]
+    BurnEditionNft,
+
+    /// Verify a collection as a member of a parent collection, incrementing the size of the parent if it is sized.
+    /// The ancestors of the parent collection are passed in the remaining accounts so the hierarchy can be checked
+    /// for cycles and its depth bounded.
+    #[account(0, writable, name="collection_metadata", desc="Metadata account of the child collection")]
+    #[account(1, signer, name="collection_authority", desc="Update authority of the parent collection")]
+    #[account(2, signer, writable, name="payer", desc="payer")]
+    #[account(3, name="parent_collection_mint", desc="Mint of the parent collection")]
+    #[account(4, writable, name="parent_collection", desc="Metadata account of the parent collection")]
+    #[account(5, name="parent_collection_master_edition_account", desc="MasterEdition2 account of the parent collection")]
+    #[account(6, name="collection_authority_record", desc="Collection Authority Record PDA of the parent collection, or the Token Metadata program id if not delegated")]
+    VerifyCollectionParent,
+
+    /// Approve another account to call [utilize] on this NFT during a time window.
+    #[account(0, writable, name="use_authority_record", desc="Use Authority Record PDA")]
+    #[account(1, signer, writable, name="owner", desc="Owner")]
+    #[account(2, signer, writable, name="payer", desc="Payer")]
+    #[account(3, name="user", desc="A Use Authority")]
+    #[account(4, writable, name="owner_token_account", desc="Owned Token Account Of Mint")]
+    #[account(5, name="metadata", desc="Metadata account")]
+    #[account(6, name="mint", desc="Mint of Metadata")]
+    #[account(7, name="burner", desc="Program As Signer (Burner)")]
+    #[account(8, name="token_program", desc="Token program")]
+    #[account(9, name="system_program", desc="System program")]
+    #[account(10, name="rent", desc="Rent info")]
+    ApproveTimedUseAuthority(ApproveTimedUseAuthorityArgs),
+
+    /// Close an expired Use Authority Record. Permissionless, the rent is refunded to the owner of the NFT.
+    #[account(0, writable, name="use_authority_record", desc="Use Authority Record PDA")]
+    #[account(1, writable, name="owner", desc="Owner of the NFT")]
+    #[account(2, name="user", desc="The Use Authority of the record")]
+    #[account(3, name="owner_token_account", desc="Owned Token Account Of Mint")]
+    #[account(4, name="mint", desc="Mint of Metadata")]
+    CloseExpiredUseAuthorityRecord,
+
+    /// Add uses back to an NFT, up to its total. Works on immutable metadata too.
+    #[account(0, writable, name="metadata", desc="Metadata account")]
+    #[account(1, signer, name="refill_authority", desc="Update authority key, or an update delegate with the refill uses scope")]
+    #[account(2, optional, name="update_delegate_record", desc="Update Delegate Record PDA, if signing as an update delegate")]
+    RefillUses(RefillUsesArgs),
+
+    /// Record a hard supply cap on a fungible and hand its mint authority to a PDA, so that new
+    /// supply can only be minted through [MintFungibleViaMetadata] and never above the cap.
+    #[account(0, writable, name="metadata", desc="Metadata account")]
+    #[account(1, writable, name="mint", desc="Mint of the fungible")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, signer, name="mint_authority", desc="Current mint authority of the mint")]
+    #[account(4, name="fungible_mint_authority", desc="Mint authority PDA ['metadata', program id, mint id, 'mint_authority']")]
+    #[account(5, name="token_program", desc="Token program of the mint")]
+    CreateFungibleDetails(CreateFungibleDetailsArgs),
+
+    /// Mint new supply of a capped fungible, signed by the update authority.
+    #[account(0, name="metadata", desc="Metadata account")]
+    #[account(1, writable, name="mint", desc="Mint of the fungible")]
+    #[account(2, writable, name="destination", desc="Token account receiving the new supply")]
+    #[account(3, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(4, name="fungible_mint_authority", desc="Mint authority PDA ['metadata', program id, mint id, 'mint_authority']")]
+    #[account(5, name="token_program", desc="Token program of the mint")]
+    MintFungibleViaMetadata(MintFungibleViaMetadataArgs),
+
+    /// Propose a new creators array for a metadata whose creators are verified. While a creator
+    /// other than the signer is verified, UpdateMetadataAccountV2 rejects creator changes, and a
+    /// proposal approved by every verified creator is the only way to apply them.
+    #[account(0, writable, name="creator_change_proposal", desc="Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])")]
+    #[account(1, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, signer, writable, name="payer", desc="Payer")]
+    #[account(4, name="system_program", desc="System program")]
+    #[account(5, name="rent", desc="Rent info")]
+    ProposeCreatorChange(ProposeCreatorChangeArgs),
+
+    /// Approve a pending creator change as one of the verified creators. The last approval applies
+    /// the change and closes the proposal.
+    #[account(0, writable, name="creator_change_proposal", desc="Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])")]
+    #[account(1, writable, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="creator", desc="Verified creator of the metadata")]
+    #[account(3, writable, name="update_authority", desc="Update authority that proposed the change, receives the proposal rent")]
+    ApproveCreatorChange,
+
+    /// Cancel a pending creator change.
+    #[account(0, writable, name="creator_change_proposal", desc="Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])")]
+    #[account(1, name="metadata", desc="Metadata account")]
+    #[account(2, signer, writable, name="update_authority", desc="Update authority of the metadata, receives the proposal rent")]
+    CancelCreatorChange,
+
+    /// Create the history account of a metadata. Instructions that change the metadata record
+    /// the slot, signer, changed fields and a hash of the prior Data in it when the history is
+    /// passed as an extra account after their own accounts. Updates, creator and collection
+    /// (un)verification, collection size changes including burns of sized collection items,
+    /// creator change approvals, RefillUses, UpdatePrimarySaleHappenedViaToken, SetTokenStandard,
+    /// BindRuleSet, MigrateMetadata and the fungible details instructions take it.
+    #[account(0, writable, name="metadata_history", desc="Metadata history (pda of ['metadata', program id, metadata, 'history'])")]
+    #[account(1, writable, name="metadata", desc="Metadata account")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, signer, writable, name="payer", desc="Payer")]
+    #[account(4, name="system_program", desc="System program")]
+    #[account(5, name="rent", desc="Rent info")]
+    CreateMetadataHistory,
+
+    /// Assert that an asset is a verified member of a collection, either directly or through
+    /// nested collections, failing otherwise. Other programs can invoke it as a membership proof.
+    /// The metadata accounts of the collections between the asset and the ancestor, and of the
+    /// ancestor itself, are passed in the remaining accounts, nearest first.
+    #[account(0, name="metadata", desc="Metadata account of the asset")]
+    #[account(1, name="ancestor_collection_mint", desc="Mint of the ancestor collection")]
+    AssertCollectionAncestry,
+
+    /// Raise the supply cap of a fungible, or remove it and hand the mint authority back from
+    /// the PDA to a new mint authority.
+    #[account(0, writable, name="metadata", desc="Metadata account")]
+    #[account(1, writable, name="mint", desc="Mint of the fungible")]
+    #[account(2, signer, name="update_authority", desc="Update authority of the metadata")]
+    #[account(3, name="fungible_mint_authority", desc="Mint authority PDA ['metadata', program id, mint id, 'mint_authority']")]
+    #[account(4, name="new_mint_authority", desc="Mint authority of the mint once the cap is removed")]
+    #[account(5, name="token_program", desc="Token program of the mint")]
+    UpdateFungibleDetails(UpdateFungibleDetailsArgs),
+
+    /// Run an instruction that changes a metadata with history enabled and record the change in
+    /// its history, the same as passing the history after the accounts of the instruction.
+    #[account(0, writable, name="metadata_history", desc="Metadata history (pda of ['metadata', program id, metadata, 'history'])")]
+    RecordMetadataHistory(RecordMetadataHistoryArgs),
 }
 
 /// Creates an CreateMetadataAccounts instruction
@@ -663,6 +1051,32 @@ pub fn update_metadata_accounts_v2(
     }
 }
 
+/// update metadata account v2 instruction signed by an update delegate
+pub fn update_metadata_accounts_v2_as_delegate(
+    program_id: Pubkey,
+    metadata_account: Pubkey,
+    delegate: Pubkey,
+    update_delegate_record: Pubkey,
+    data: DataV2,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_account, false),
+            AccountMeta::new_readonly(delegate, true),
+            AccountMeta::new_readonly(update_delegate_record, false),
+        ],
+        data: MetadataInstruction::UpdateMetadataAccountV2(UpdateMetadataAccountArgsV2 {
+            data: Some(data),
+            update_authority: None,
+            primary_sale_happened: None,
+            is_mutable: None,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
 /// puff metadata account instruction
 pub fn puff_metadata_account(program_id: Pubkey, metadata_account: Pubkey) -> Instruction {
     Instruction {
@@ -1685,3 +2099,1139 @@ pub fn set_token_standard(
         data,
     }
 }
+
+///# Create Rule Set
+///
+/// Create a royalty enforcement rule set owned by the signing authority.
+///
+///### Accounts:
+///
+///   0. `[writable]` Rule set PDA
+///   1. `[signer]` Rule set authority
+///   2. `[signer, writable]` Payer
+///   3. `[]` System program
+///   4. `[]` Rent info
+pub fn create_rule_set(
+    program_id: Pubkey,
+    rule_set: Pubkey,
+    authority: Pubkey,
+    payer: Pubkey,
+    allowed_programs: Vec<Pubkey>,
+    escrow_authorities: Vec<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(rule_set, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::CreateRuleSet(CreateRuleSetArgs {
+            allowed_programs,
+            escrow_authorities,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Update Rule Set
+///
+/// Replace the allowed programs and escrow authorities of an existing rule set.
+///
+///### Accounts:
+///
+///   0. `[writable]` Rule set PDA
+///   1. `[signer]` Rule set authority
+pub fn update_rule_set(
+    program_id: Pubkey,
+    rule_set: Pubkey,
+    authority: Pubkey,
+    allowed_programs: Vec<Pubkey>,
+    escrow_authorities: Vec<Pubkey>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(rule_set, false),
+            AccountMeta::new_readonly(authority, true),
+        ],
+        data: MetadataInstruction::UpdateRuleSet(UpdateRuleSetArgs {
+            allowed_programs,
+            escrow_authorities,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Bind Rule Set
+///
+/// Bind a NonFungible asset to a rule set, freezing the holder's token account.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Metadata update authority
+///   2. `[]` Rule set account
+///   3. `[]` Master Edition account
+///   4. `[]` Mint of the asset
+///   5. `[writable]` Token account holding the asset
+///   6. `[signer]` Owner of the token account
+///   7. `[]` Token program
+#[allow(clippy::too_many_arguments)]
+pub fn bind_rule_set(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    rule_set: Pubkey,
+    edition: Pubkey,
+    mint: Pubkey,
+    token_account: Pubkey,
+    token_owner: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new_readonly(rule_set, false),
+            AccountMeta::new_readonly(edition, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(token_account, false),
+            AccountMeta::new_readonly(token_owner, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+        ],
+        data: MetadataInstruction::BindRuleSet.try_to_vec().unwrap(),
+    }
+}
+
+///# Transfer
+///
+/// Move an asset bound to a rule set. Other programs call this through CPI and must be
+/// listed in the rule set; direct calls may only move the asset to an escrow authority.
+///
+///### Accounts:
+///
+///   0. `[]` Metadata account
+///   1. `[]` Rule set bound to the metadata
+///   2. `[]` Master Edition account
+///   3. `[]` Mint of the asset
+///   4. `[writable]` Source token account
+///   5. `[writable]` Destination token account
+///   6. `[signer]` Owner of the source, or an escrow authority delegate
+///   7. `[]` Token program
+///   8. `[]` Instructions sysvar
+#[allow(clippy::too_many_arguments)]
+pub fn transfer(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    rule_set: Pubkey,
+    edition: Pubkey,
+    mint: Pubkey,
+    source: Pubkey,
+    destination: Pubkey,
+    authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(rule_set, false),
+            AccountMeta::new_readonly(edition, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new(source, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(authority, true),
+            AccountMeta::new_readonly(spl_token::id(), false),
+            AccountMeta::new_readonly(sysvar::instructions::id(), false),
+        ],
+        data: MetadataInstruction::Transfer.try_to_vec().unwrap(),
+    }
+}
+
+///# Migrate Metadata
+///
+/// Rewrite a metadata account cleanly in the MetadataV2 layout, which reallocates the account
+/// to add a length-prefixed extension area after the metadata fields.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Metadata update authority
+///   2. `[signer, writable]` Payer of the additional rent
+///   3. `[]` System program
+///   4. `[]` Rent info
+pub fn migrate_metadata(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::MigrateMetadata.try_to_vec().unwrap(),
+    }
+}
+
+///# Approve Update Delegate
+///
+/// Approve a delegate to change a scoped subset of the metadata fields.
+///
+///### Accounts:
+///
+///   0. `[writable]` Update Delegate Record PDA
+///   1. `[]` Update delegate
+///   2. `[signer]` Update authority of the metadata
+///   3. `[signer, writable]` Payer
+///   4. `[]` Metadata account
+///   5. `[]` Mint of the metadata
+///   6. `[]` System program
+///   7. `[]` Rent info
+#[allow(clippy::too_many_arguments)]
+pub fn approve_update_delegate(
+    program_id: Pubkey,
+    update_delegate_record: Pubkey,
+    delegate: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    scopes: UpdateDelegateScopes,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(update_delegate_record, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::ApproveUpdateDelegate(ApproveUpdateDelegateArgs { scopes })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Revoke Update Delegate
+///
+/// Revoke an update delegate, returning the record rent to the revoke authority.
+///
+///### Accounts:
+///
+///   0. `[writable]` Update Delegate Record PDA
+///   1. `[]` Update delegate
+///   2. `[signer, writable]` Update authority, or the delegate itself
+///   3. `[]` Metadata account
+///   4. `[]` Mint of the metadata
+pub fn revoke_update_delegate(
+    program_id: Pubkey,
+    update_delegate_record: Pubkey,
+    delegate: Pubkey,
+    revoke_authority: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(update_delegate_record, false),
+            AccountMeta::new_readonly(delegate, false),
+            AccountMeta::new(revoke_authority, true),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(mint, false),
+        ],
+        data: MetadataInstruction::RevokeUpdateDelegate
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Create Metadata Attributes
+///
+/// Create the on-chain key/value attributes of a Metadata.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata Attributes PDA
+///   1. `[]` Metadata account
+///   2. `[signer]` Update authority of the metadata
+///   3. `[signer, writable]` Payer
+///   4. `[]` System program
+///   5. `[]` Rent info
+pub fn create_metadata_attributes(
+    program_id: Pubkey,
+    metadata_attributes: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+    attributes: Vec<Attribute>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_attributes, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::CreateMetadataAttributes(CreateMetadataAttributesArgs {
+            attributes,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Update Metadata Attributes
+///
+/// Insert or replace on-chain attributes of a Metadata.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata Attributes PDA
+///   1. `[]` Metadata account
+///   2. `[signer]` Update authority of the metadata
+pub fn update_metadata_attributes(
+    program_id: Pubkey,
+    metadata_attributes: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    attributes: Vec<Attribute>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_attributes, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+        ],
+        data: MetadataInstruction::UpdateMetadataAttributes(UpdateMetadataAttributesArgs {
+            attributes,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# Remove Metadata Attributes
+///
+/// Remove on-chain attributes of a Metadata by key.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata Attributes PDA
+///   1. `[]` Metadata account
+///   2. `[signer]` Update authority of the metadata
+pub fn remove_metadata_attributes(
+    program_id: Pubkey,
+    metadata_attributes: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    keys: Vec<String>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_attributes, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+        ],
+        data: MetadataInstruction::RemoveMetadataAttributes(RemoveMetadataAttributesArgs { keys })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Sign Metadata Batch
+///
+/// Sign every metadata that has the creator as an unverified creator.
+///
+///### Accounts:
+///
+///   0. `[signer]` Creator
+///   1. `[writable]` Metadata accounts, one or more
+pub fn sign_metadata_batch(
+    program_id: Pubkey,
+    creator: Pubkey,
+    metadata_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new_readonly(creator, true)];
+    accounts.extend(
+        metadata_accounts
+            .iter()
+            .map(|metadata| AccountMeta::new(*metadata, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::SignMetadataBatch.try_to_vec().unwrap(),
+    }
+}
+
+///# Verify Sized Collection Item Batch
+///
+/// Verify every metadata as an item of a sized collection.
+///
+///### Accounts:
+///
+///   0. `[signer]` Collection Update authority
+///   1. `[signer, writable]` Payer
+///   2. `[]` Mint of the Collection
+///   3. `[writable]` Metadata Account of the Collection
+///   4. `[]` MasterEdition2 Account of the Collection Token
+///   5. `[]` Collection Authority Record PDA, or the Token Metadata program id if not delegated
+///   6. `[writable]` Metadata accounts of the items, one or more
+#[allow(clippy::too_many_arguments)]
+pub fn verify_sized_collection_item_batch(
+    program_id: Pubkey,
+    collection_authority: Pubkey,
+    payer: Pubkey,
+    collection_mint: Pubkey,
+    collection: Pubkey,
+    collection_master_edition_account: Pubkey,
+    collection_authority_record: Option<Pubkey>,
+    metadata_accounts: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(collection_authority, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(collection_mint, false),
+        AccountMeta::new(collection, false),
+        AccountMeta::new_readonly(collection_master_edition_account, false),
+        AccountMeta::new_readonly(collection_authority_record.unwrap_or(program_id), false),
+    ];
+    accounts.extend(
+        metadata_accounts
+            .iter()
+            .map(|metadata| AccountMeta::new(*metadata, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::VerifySizedCollectionItemBatch
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Mint New Edition from Master Edition Via Token With Overrides
+///
+/// Mint a new edition whose name and uri are customized with `overrides`.
+///
+///### Accounts:
+///
+///   0. `[writable]` New Metadata key (pda of ['metadata', program id, mint id])
+///   1. `[writable]` New Edition (pda of ['metadata', program id, mint id, 'edition'])
+///   2. `[writable]` Master Record Edition V2 (pda of ['metadata', program id, master metadata mint id, 'edition'])
+///   3. `[writable]` Mint of new token - THIS WILL TRANSFER AUTHORITY AWAY FROM THIS KEY
+///   4. `[writable]` Edition pda to mark creation
+///   5. `[signer]` Mint authority of new mint
+///   6. `[signer, writable]` Payer
+///   7. `[signer]` Owner of token account containing master token
+///   8. `[]` Token account containing token from master metadata mint
+///   9. `[signer]` Update authority of the master metadata
+///   10. `[]` Master record metadata account
+///   11. `[]` Token program
+///   12. `[]` System program
+///   13. `[]` Rent info
+#[allow(clippy::too_many_arguments)]
+pub fn mint_new_edition_from_master_edition_via_token_with_overrides(
+    program_id: Pubkey,
+    new_metadata: Pubkey,
+    new_edition: Pubkey,
+    master_edition: Pubkey,
+    new_mint: Pubkey,
+    new_mint_authority: Pubkey,
+    payer: Pubkey,
+    token_account_owner: Pubkey,
+    token_account: Pubkey,
+    update_authority: Pubkey,
+    metadata: Pubkey,
+    metadata_mint: Pubkey,
+    edition: u64,
+    overrides: EditionOverrides,
+) -> Instruction {
+    let mut instruction = mint_new_edition_from_master_edition_via_token(
+        program_id,
+        new_metadata,
+        new_edition,
+        master_edition,
+        new_mint,
+        new_mint_authority,
+        payer,
+        token_account_owner,
+        token_account,
+        update_authority,
+        metadata,
+        metadata_mint,
+        edition,
+    );
+    instruction.accounts[9] = AccountMeta::new_readonly(update_authority, true);
+    instruction.data = MetadataInstruction::MintNewEditionFromMasterEditionViaTokenWithOverrides(
+        MintNewEditionWithOverridesArgs { edition, overrides },
+    )
+    .try_to_vec()
+    .unwrap();
+    instruction
+}
+
+///# Burn Edition NFT
+///
+/// Burn a print edition NFT, closing its token, metadata and edition accounts, and closing
+/// the edition marker once it is empty. The master edition may already have been burned.
+///
+///### Accounts:
+///
+///   0. `[writable]` NFT metadata
+///   1. `[writable, signer]` Owner of NFT
+///   2. `[writable]` Mint of the print edition NFT
+///   3. `[]` Mint of the original/master NFT
+///   4. `[writable]` Token account the print edition NFT is in
+///   5. `[writable]` MasterEdition2 of the original NFT
+///   6. `[writable]` Print Edition account of the NFT
+///   7. `[writable]` Edition Marker PDA of the NFT
+///   8. `[]` SPL Token program.
+#[allow(clippy::too_many_arguments)]
+pub fn burn_edition_nft(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    print_edition_mint: Pubkey,
+    master_edition_mint: Pubkey,
+    print_edition_token: Pubkey,
+    master_edition: Pubkey,
+    print_edition: Pubkey,
+    edition_marker: Pubkey,
+    spl_token: Pubkey,
+) -> Instruction {
+    burn_edition_nft_with_collection(
+        program_id,
+        metadata,
+        owner,
+        print_edition_mint,
+        master_edition_mint,
+        print_edition_token,
+        master_edition,
+        print_edition,
+        edition_marker,
+        spl_token,
+        None,
+    )
+}
+
+///# Burn Edition NFT With Collection
+///
+/// Burn a print edition NFT like `burn_edition_nft`, decrementing the size of its sized
+/// collection when the collection metadata is passed.
+///
+///### Accounts:
+///
+///   0. `[writable]` NFT metadata
+///   1. `[writable, signer]` Owner of NFT
+///   2. `[writable]` Mint of the print edition NFT
+///   3. `[]` Mint of the original/master NFT
+///   4. `[writable]` Token account the print edition NFT is in
+///   5. `[writable]` MasterEdition2 of the original NFT
+///   6. `[writable]` Print Edition account of the NFT
+///   7. `[writable]` Edition Marker PDA of the NFT
+///   8. `[]` SPL Token program.
+///   9. Optional `[writable]` Collection metadata account
+#[allow(clippy::too_many_arguments)]
+pub fn burn_edition_nft_with_collection(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    print_edition_mint: Pubkey,
+    master_edition_mint: Pubkey,
+    print_edition_token: Pubkey,
+    master_edition: Pubkey,
+    print_edition: Pubkey,
+    edition_marker: Pubkey,
+    spl_token: Pubkey,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(metadata, false),
+        AccountMeta::new(owner, true),
+        AccountMeta::new(print_edition_mint, false),
+        AccountMeta::new_readonly(master_edition_mint, false),
+        AccountMeta::new(print_edition_token, false),
+        AccountMeta::new(master_edition, false),
+        AccountMeta::new(print_edition, false),
+        AccountMeta::new(edition_marker, false),
+        AccountMeta::new_readonly(spl_token, false),
+    ];
+
+    if let Some(collection_metadata) = collection_metadata {
+        accounts.push(AccountMeta::new(collection_metadata, false));
+    }
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::BurnEditionNft.try_to_vec().unwrap(),
+    }
+}
+
+///# Burn Master Edition NFT
+///
+/// Burn a master edition NFT through BurnEditionNft, even if it has outstanding prints,
+/// closing its token, metadata and master edition accounts. The prints stay burnable.
+///
+///### Accounts:
+///
+///   0. `[writable]` NFT metadata
+///   1. `[writable, signer]` Owner of NFT
+///   2. `[writable]` Mint of NFT
+///   3. `[]` Mint of NFT
+///   4. `[writable]` NFT token account
+///   5. `[writable]` MasterEdition2 of the NFT
+///   6. `[writable]` MasterEdition2 of the NFT
+///   7. `[writable]` MasterEdition2 of the NFT, in place of the unused Edition Marker
+///   8. `[]` SPL Token program.
+///   9. Optional `[writable]` Collection metadata account
+#[allow(clippy::too_many_arguments)]
+pub fn burn_master_edition_nft(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    owner: Pubkey,
+    mint: Pubkey,
+    token: Pubkey,
+    master_edition: Pubkey,
+    spl_token: Pubkey,
+    collection_metadata: Option<Pubkey>,
+) -> Instruction {
+    burn_edition_nft_with_collection(
+        program_id,
+        metadata,
+        owner,
+        mint,
+        mint,
+        token,
+        master_edition,
+        master_edition,
+        master_edition,
+        spl_token,
+        collection_metadata,
+    )
+}
+
+///# Verify Collection Parent
+///
+/// Verify a collection as a member of a parent collection.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account of the child collection
+///   1. `[signer]` Update authority of the parent collection
+///   2. `[signer, writable]` Payer
+///   3. `[]` Mint of the parent collection
+///   4. `[writable]` Metadata account of the parent collection
+///   5. `[]` MasterEdition2 account of the parent collection
+///   6. `[]` Collection Authority Record PDA, or the Token Metadata program id if not delegated
+///   7. `[]` Metadata accounts of the ancestors of the parent collection, nearest first, if any
+#[allow(clippy::too_many_arguments)]
+pub fn verify_collection_parent(
+    program_id: Pubkey,
+    collection_metadata: Pubkey,
+    collection_authority: Pubkey,
+    payer: Pubkey,
+    parent_collection_mint: Pubkey,
+    parent_collection: Pubkey,
+    parent_collection_master_edition_account: Pubkey,
+    collection_authority_record: Option<Pubkey>,
+    ancestors: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(collection_metadata, false),
+        AccountMeta::new_readonly(collection_authority, true),
+        AccountMeta::new(payer, true),
+        AccountMeta::new_readonly(parent_collection_mint, false),
+        AccountMeta::new(parent_collection, false),
+        AccountMeta::new_readonly(parent_collection_master_edition_account, false),
+        AccountMeta::new_readonly(collection_authority_record.unwrap_or(program_id), false),
+    ];
+    accounts.extend(
+        ancestors
+            .iter()
+            .map(|ancestor| AccountMeta::new_readonly(*ancestor, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::VerifyCollectionParent
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Approve Timed Use Authority
+///
+///Approve another account to call [utilize] on this NFT between `not_before` and `expires_at`
+///
+///### Args:
+///
+///See: [ApproveTimedUseAuthorityArgs]
+///
+///### Accounts:
+///
+///   0. `[writable]` Use Authority Record PDA
+///   1. `[writable]` Owned Token Account Of Mint
+///   2. `[signer]` Owner
+///   3. `[signer]` Payer
+///   4. `[]` A Use Authority
+///   5. `[]` Metadata account
+///   6. `[]` Mint of Metadata
+///   7. `[]` Program As Signer (Burner)
+///   8. `[]` Token program
+///   9. `[]` System program
+///   10. `[]` Rent info
+#[allow(clippy::too_many_arguments)]
+pub fn approve_timed_use_authority(
+    program_id: Pubkey,
+    use_authority_record: Pubkey,
+    user: Pubkey,
+    owner: Pubkey,
+    payer: Pubkey,
+    owner_token_account: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    burner: Pubkey,
+    number_of_uses: u64,
+    not_before: Option<i64>,
+    expires_at: Option<i64>,
+) -> Instruction {
+    let mut instruction = approve_use_authority(
+        program_id,
+        use_authority_record,
+        user,
+        owner,
+        payer,
+        owner_token_account,
+        metadata,
+        mint,
+        burner,
+        number_of_uses,
+    );
+    instruction.data =
+        MetadataInstruction::ApproveTimedUseAuthority(ApproveTimedUseAuthorityArgs {
+            number_of_uses,
+            not_before,
+            expires_at,
+        })
+        .try_to_vec()
+        .unwrap();
+    instruction
+}
+
+///# Close Expired Use Authority Record
+///
+///Close an expired Use Authority Record, refunding the rent to the owner of the NFT
+///
+///### Accounts:
+///
+///   0. `[writable]` Use Authority Record PDA
+///   1. `[writable]` Owner of the NFT
+///   2. `[]` A Use Authority
+///   3. `[]` Owned Token Account Of Mint
+///   4. `[]` Mint of Metadata
+pub fn close_expired_use_authority_record(
+    program_id: Pubkey,
+    use_authority_record: Pubkey,
+    owner: Pubkey,
+    user: Pubkey,
+    owner_token_account: Pubkey,
+    mint: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(use_authority_record, false),
+            AccountMeta::new(owner, false),
+            AccountMeta::new_readonly(user, false),
+            AccountMeta::new_readonly(owner_token_account, false),
+            AccountMeta::new_readonly(mint, false),
+        ],
+        data: MetadataInstruction::CloseExpiredUseAuthorityRecord
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Refill Uses
+///
+///Add uses back to an NFT, up to its total. Works on immutable metadata too.
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[signer]` Update authority key, or an update delegate with the refill uses scope
+///   2. Optional `[]` Update Delegate Record PDA, if signing as an update delegate
+pub fn refill_uses(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    refill_authority: Pubkey,
+    update_delegate_record: Option<Pubkey>,
+    amount: u64,
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new(metadata, false),
+        AccountMeta::new_readonly(refill_authority, true),
+    ];
+    if let Some(update_delegate_record) = update_delegate_record {
+        accounts.push(AccountMeta::new_readonly(update_delegate_record, false));
+    }
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::RefillUses(RefillUsesArgs { amount })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Create Fungible Details
+///
+///Record a hard supply cap on a fungible and hand its mint authority to a PDA
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[writable]` Mint of the fungible
+///   2. `[signer]` Update authority of the metadata
+///   3. `[signer]` Current mint authority of the mint
+///   4. `[]` Mint authority PDA ['metadata', program id, mint id, 'mint_authority']
+///   5. `[]` Token program of the mint
+#[allow(clippy::too_many_arguments)]
+pub fn create_fungible_details(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    update_authority: Pubkey,
+    mint_authority: Pubkey,
+    fungible_mint_authority: Pubkey,
+    token_program: Pubkey,
+    supply_cap: u64,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new_readonly(mint_authority, true),
+            AccountMeta::new_readonly(fungible_mint_authority, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: MetadataInstruction::CreateFungibleDetails(CreateFungibleDetailsArgs { supply_cap })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Mint Fungible Via Metadata
+///
+///Mint new supply of a capped fungible, up to its supply cap
+///
+///### Accounts:
+///
+///   0. `[]` Metadata account
+///   1. `[writable]` Mint of the fungible
+///   2. `[writable]` Token account receiving the new supply
+///   3. `[signer]` Update authority of the metadata
+///   4. `[]` Mint authority PDA ['metadata', program id, mint id, 'mint_authority']
+///   5. `[]` Token program of the mint
+#[allow(clippy::too_many_arguments)]
+pub fn mint_fungible_via_metadata(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    destination: Pubkey,
+    update_authority: Pubkey,
+    fungible_mint_authority: Pubkey,
+    token_program: Pubkey,
+    amount: u64,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new(destination, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new_readonly(fungible_mint_authority, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: MetadataInstruction::MintFungibleViaMetadata(MintFungibleViaMetadataArgs { amount })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Propose Creator Change
+///
+///Propose a new creators array that each verified creator must approve
+///
+///### Accounts:
+///
+///   0. `[writable]` Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])
+///   1. `[]` Metadata account
+///   2. `[signer]` Update authority of the metadata
+///   3. `[signer, writable]` Payer
+///   4. `[]` System program
+///   5. `[]` Rent info
+pub fn propose_creator_change(
+    program_id: Pubkey,
+    creator_change_proposal: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+    creators: Vec<Creator>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator_change_proposal, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::ProposeCreatorChange(ProposeCreatorChangeArgs { creators })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Approve Creator Change
+///
+///Approve a pending creator change, applying it once every verified creator has approved
+///
+///### Accounts:
+///
+///   0. `[writable]` Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])
+///   1. `[writable]` Metadata account
+///   2. `[signer]` Verified creator of the metadata
+///   3. `[writable]` Update authority that proposed the change, receives the proposal rent
+pub fn approve_creator_change(
+    program_id: Pubkey,
+    creator_change_proposal: Pubkey,
+    metadata: Pubkey,
+    creator: Pubkey,
+    update_authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator_change_proposal, false),
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(creator, true),
+            AccountMeta::new(update_authority, false),
+        ],
+        data: MetadataInstruction::ApproveCreatorChange
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Cancel Creator Change
+///
+///Cancel a pending creator change
+///
+///### Accounts:
+///
+///   0. `[writable]` Creator change proposal (pda of ['metadata', program id, metadata, 'creator_change'])
+///   1. `[]` Metadata account
+///   2. `[signer, writable]` Update authority of the metadata, receives the proposal rent
+pub fn cancel_creator_change(
+    program_id: Pubkey,
+    creator_change_proposal: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(creator_change_proposal, false),
+            AccountMeta::new_readonly(metadata, false),
+            AccountMeta::new(update_authority, true),
+        ],
+        data: MetadataInstruction::CancelCreatorChange
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Create Metadata History
+///
+///Create the history account of a metadata, recording its changes from then on
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata history (pda of ['metadata', program id, metadata, 'history'])
+///   1. `[writable]` Metadata account
+///   2. `[signer]` Update authority of the metadata
+///   3. `[signer, writable]` Payer
+///   4. `[]` System program
+///   5. `[]` Rent info
+pub fn create_metadata_history(
+    program_id: Pubkey,
+    metadata_history: Pubkey,
+    metadata: Pubkey,
+    update_authority: Pubkey,
+    payer: Pubkey,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata_history, false),
+            AccountMeta::new(metadata, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new(payer, true),
+            AccountMeta::new_readonly(solana_program::system_program::id(), false),
+            AccountMeta::new_readonly(sysvar::rent::id(), false),
+        ],
+        data: MetadataInstruction::CreateMetadataHistory
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Assert Collection Ancestry
+///
+/// Assert that an asset is a verified member of a collection, directly or through nested collections.
+///
+///### Accounts:
+///
+///   0. `[]` Metadata account of the asset
+///   1. `[]` Mint of the ancestor collection
+///   2. `[]` Metadata accounts of the collections above the asset up to the ancestor, nearest first
+pub fn assert_collection_ancestry(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    ancestor_collection_mint: Pubkey,
+    collections: &[Pubkey],
+) -> Instruction {
+    let mut accounts = vec![
+        AccountMeta::new_readonly(metadata, false),
+        AccountMeta::new_readonly(ancestor_collection_mint, false),
+    ];
+    accounts.extend(
+        collections
+            .iter()
+            .map(|collection| AccountMeta::new_readonly(*collection, false)),
+    );
+
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::AssertCollectionAncestry
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Update Fungible Details
+///
+///Raise the supply cap of a fungible, or remove it and hand the mint authority back
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata account
+///   1. `[writable]` Mint of the fungible
+///   2. `[signer]` Update authority of the metadata
+///   3. `[]` Mint authority PDA ['metadata', program id, mint id, 'mint_authority']
+///   4. `[]` Mint authority of the mint once the cap is removed
+///   5. `[]` Token program of the mint
+#[allow(clippy::too_many_arguments)]
+pub fn update_fungible_details(
+    program_id: Pubkey,
+    metadata: Pubkey,
+    mint: Pubkey,
+    update_authority: Pubkey,
+    fungible_mint_authority: Pubkey,
+    new_mint_authority: Pubkey,
+    token_program: Pubkey,
+    supply_cap: Option<u64>,
+) -> Instruction {
+    Instruction {
+        program_id,
+        accounts: vec![
+            AccountMeta::new(metadata, false),
+            AccountMeta::new(mint, false),
+            AccountMeta::new_readonly(update_authority, true),
+            AccountMeta::new_readonly(fungible_mint_authority, false),
+            AccountMeta::new_readonly(new_mint_authority, false),
+            AccountMeta::new_readonly(token_program, false),
+        ],
+        data: MetadataInstruction::UpdateFungibleDetails(UpdateFungibleDetailsArgs { supply_cap })
+            .try_to_vec()
+            .unwrap(),
+    }
+}
+
+///# Record Metadata History
+///
+///Run `instruction` against a metadata with history enabled and record the change in its history,
+///the same as [with_metadata_history]
+///
+///### Accounts:
+///
+///   0. `[writable]` Metadata history (pda of ['metadata', program id, metadata, 'history'])
+///   1. The accounts of `instruction`, in order
+pub fn record_metadata_history(
+    program_id: Pubkey,
+    metadata_history: Pubkey,
+    instruction: Instruction,
+) -> Instruction {
+    let mut accounts = vec![AccountMeta::new(metadata_history, false)];
+    accounts.extend(instruction.accounts);
+    Instruction {
+        program_id,
+        accounts,
+        data: MetadataInstruction::RecordMetadataHistory(RecordMetadataHistoryArgs {
+            instruction: instruction.data,
+        })
+        .try_to_vec()
+        .unwrap(),
+    }
+}
+
+///# With Metadata History
+///
+///Pass a metadata history after the accounts of `instruction` to record the change of its metadata,
+///which may be the changed metadata itself or a collection whose size the instruction changes
+///
+///### Accounts:
+///
+///   0. The accounts of `instruction`, in order
+///   1. `[writable]` Metadata history (pda of ['metadata', program id, metadata, 'history'])
+pub fn with_metadata_history(
+    mut instruction: Instruction,
+    metadata_history: Pubkey,
+) -> Instruction {
+    instruction
+        .accounts
+        .push(AccountMeta::new(metadata_history, false));
+    instruction
+}
@@ -3,9 +3,10 @@
 pub mod assertions;
 pub mod deprecated_instruction;
 pub mod deprecated_processor;
-mod deser;
+pub mod deser;
 pub mod entrypoint;
 pub mod error;
+pub mod event;
 pub mod instruction;
 pub mod pda;
 pub mod processor;
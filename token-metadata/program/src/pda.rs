@@ -1,6 +1,9 @@
 use solana_program::pubkey::Pubkey;
 
-use crate::state::{BURN, COLLECTION_AUTHORITY, EDITION, PREFIX, USER};
+use crate::state::{
+    ATTRIBUTES, BURN, COLLECTION_AUTHORITY, CREATOR_CHANGE, EDITION, HISTORY, MINT_AUTHORITY,
+    PREFIX, RULE_SET, UPDATE_DELEGATE, USER,
+};
 
 pub fn find_edition_account(mint: &Pubkey, edition_number: String) -> (Pubkey, u8) {
     Pubkey::find_program_address(
@@ -65,3 +68,76 @@ pub fn find_program_as_burner_account() -> (Pubkey, u8) {
         &crate::id(),
     )
 }
+
+pub fn find_rule_set_account(authority: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            RULE_SET.as_bytes(),
+            authority.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_update_delegate_account(mint: &Pubkey, delegate: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            mint.as_ref(),
+            UPDATE_DELEGATE.as_bytes(),
+            delegate.as_ref(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_metadata_attributes_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            metadata.as_ref(),
+            ATTRIBUTES.as_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_fungible_mint_authority_account(mint: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            mint.as_ref(),
+            MINT_AUTHORITY.as_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_creator_change_proposal_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            metadata.as_ref(),
+            CREATOR_CHANGE.as_bytes(),
+        ],
+        &crate::id(),
+    )
+}
+
+pub fn find_metadata_history_account(metadata: &Pubkey) -> (Pubkey, u8) {
+    Pubkey::find_program_address(
+        &[
+            PREFIX.as_bytes(),
+            crate::id().as_ref(),
+            metadata.as_ref(),
+            HISTORY.as_bytes(),
+        ],
+        &crate::id(),
+    )
+}
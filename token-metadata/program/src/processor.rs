@@ -1,35 +1,62 @@
 use crate::{
     assertions::{
+        attributes::{assert_attribute_valid, assert_attributes_valid},
         collection::{
-            assert_collection_update_is_valid, assert_collection_verify_is_valid,
-            assert_has_collection_authority,
+            assert_collection_ancestry, assert_collection_member_is_valid,
+            assert_collection_parent_is_valid, assert_collection_update_is_valid,
+            assert_collection_verify_is_valid, assert_has_collection_authority,
+        },
+        rule_set::{
+            assert_bound_rule_set, assert_delegate_allowed, assert_rule_set_authority,
+            assert_rule_set_entries_valid, assert_transfer_allowed,
+        },
+        update_delegate::{assert_update_delegate_record, assert_update_within_scopes},
+        uses::{
+            assert_use_authority_record_active, assert_use_authority_time_window_valid,
+            assert_valid_use, process_use_authority_validation,
         },
-        uses::{assert_valid_use, process_use_authority_validation},
     },
     deprecated_processor::{
         process_deprecated_create_metadata_accounts, process_deprecated_update_metadata_accounts,
     },
     deser::clean_write_metadata,
     error::MetadataError,
-    instruction::{MetadataInstruction, SetCollectionSizeArgs},
+    event::{emit, AuthorityRecordType, MetadataEvent, MetadataState},
+    instruction::{
+        ApproveUpdateDelegateArgs, CreateMetadataAttributesArgs, CreateRuleSetArgs,
+        EditionOverrides, MetadataInstruction, ProposeCreatorChangeArgs,
+        RemoveMetadataAttributesArgs, SetCollectionSizeArgs, UpdateMetadataAttributesArgs,
+        UpdateRuleSetArgs,
+    },
+    pda::{find_metadata_account, find_update_delegate_account},
     solana_program::program_memory::sol_memset,
     state::{
-        Collection, CollectionAuthorityRecord, CollectionDetails, DataV2, Key, MasterEditionV1,
-        MasterEditionV2, Metadata, TokenMetadataAccount, TokenStandard, UseAuthorityRecord,
-        UseMethod, Uses, BURN, COLLECTION_AUTHORITY, COLLECTION_AUTHORITY_RECORD_SIZE, EDITION,
-        MAX_MASTER_EDITION_LEN, MAX_METADATA_LEN, PREFIX, USER, USE_AUTHORITY_RECORD_SIZE,
+        get_master_edition, Collection, CollectionAuthorityRecord, CollectionDetails, Creator,
+        CreatorChangeProposal, DataV2, Edition, EditionMarker, FungibleDetails, Key,
+        MasterEditionV1, MasterEditionV2, Metadata, MetadataAttributes, MetadataHistory,
+        MetadataHistoryEntry, MetadataV2, RuleSet, TokenMetadataAccount, TokenStandard,
+        UpdateDelegateRecord, UseAuthorityRecord, UseMethod, Uses, ATTRIBUTES, BURN,
+        COLLECTION_AUTHORITY, COLLECTION_AUTHORITY_RECORD_SIZE, CREATOR_CHANGE,
+        CREATOR_CHANGE_PROPOSAL_SIZE, EDITION, EDITION_MARKER_BIT_SIZE, HISTORY, MAX_ATTRIBUTES,
+        MAX_COLLECTION_DEPTH, MAX_MASTER_EDITION_LEN, METADATA_ATTRIBUTES_SIZE,
+        METADATA_HISTORY_SIZE, MINT_AUTHORITY, PREFIX, RULE_SET, RULE_SET_SIZE,
+        TIMED_USE_AUTHORITY_RECORD_SIZE, UPDATE_DELEGATE, UPDATE_DELEGATE_RECORD_SIZE, USER,
+        USE_AUTHORITY_RECORD_SIZE,
     },
     utils::{
-        assert_currently_holding, assert_data_valid, assert_delegated_tokens, assert_derivation,
-        assert_freeze_authority_matches_mint, assert_initialized,
-        assert_mint_authority_matches_mint, assert_owned_by, assert_signer,
-        assert_token_program_matches_package, assert_update_authority_is_correct,
-        assert_verified_member_of_collection, check_token_standard, create_or_allocate_account_raw,
-        decrement_collection_size, get_owner_from_token_account, increment_collection_size,
-        process_create_metadata_accounts_logic,
+        assert_creator_change_is_allowed, assert_currently_holding, assert_data_valid,
+        assert_delegated_tokens, assert_derivation, assert_freeze_authority_matches_mint,
+        assert_initialized, assert_mint_authority_matches_mint, assert_owned_by,
+        assert_owned_by_token_program, assert_signer, assert_token_program_matches_package,
+        assert_update_authority_is_correct, assert_verified_member_of_collection,
+        check_token_standard, create_or_allocate_account_raw, decrement_collection_size, freeze,
+        get_mint_supply, get_owner_from_token_account, increment_collection_size,
+        increment_collection_size_by, process_create_metadata_accounts_logic,
         process_mint_new_edition_from_master_edition_via_token_logic, puff_out_data_fields,
-        spl_token_burn, spl_token_close, transfer_mint_authority, CreateMetadataAccountsLogicArgs,
+        resize_or_reallocate_account_raw, spl_token_burn, spl_token_close, spl_token_mint_to,
+        spl_token_transfer, thaw, transfer_mint_authority, CreateMetadataAccountsLogicArgs,
         MintNewEditionFromMasterEditionViaTokenLogicArgs, TokenBurnParams, TokenCloseParams,
+        TokenMintToParams, TokenTransferParams,
     },
 };
 use arrayref::array_ref;
@@ -38,15 +65,18 @@ use mpl_token_vault::{error::VaultError, state::VaultState};
 use solana_program::{
     account_info::{next_account_info, AccountInfo},
     entrypoint::ProgramResult,
+    instruction::{get_stack_height, TRANSACTION_LEVEL_STACK_HEIGHT},
     msg,
     program::{invoke, invoke_signed},
     program_error::ProgramError,
     pubkey::Pubkey,
+    sysvar::{self, clock::Clock, instructions::get_instruction_relative, Sysvar},
 };
-use spl_token::{
-    instruction::{approve, freeze_account, revoke, thaw_account},
-    state::{Account, Mint},
+use spl_token::state::{Account, Mint};
+use spl_token_2022::instruction::{
+    approve, freeze_account, revoke, set_authority, thaw_account, AuthorityType,
 };
+use std::slice;
 
 use crate::assertions::uses::{assert_burner, assert_use_authority_derivation, assert_valid_bump};
 
@@ -56,6 +86,141 @@ pub fn process_instruction<'a>(
     input: &[u8],
 ) -> ProgramResult {
     let instruction = MetadataInstruction::try_from_slice(input)?;
+
+    // Instructions that change metadata take the histories of the metadata they change as
+    // optional trailing accounts and record the change in them.
+    if let Some(signer_index) = metadata_history_signer(&instruction) {
+        let (accounts, history_infos) = split_metadata_histories(program_id, accounts);
+        if !history_infos.is_empty() {
+            return process_recording_histories(
+                program_id,
+                accounts,
+                history_infos,
+                instruction,
+                signer_index,
+            );
+        }
+    }
+
+    process_metadata_instruction(program_id, accounts, instruction)
+}
+
+/// Index of the signer account of instructions that can be recorded in a metadata history.
+fn metadata_history_signer(instruction: &MetadataInstruction) -> Option<usize> {
+    match instruction {
+        MetadataInstruction::UpdateMetadataAccount(_)
+        | MetadataInstruction::UpdateMetadataAccountV2(_)
+        | MetadataInstruction::UpdatePrimarySaleHappenedViaToken
+        | MetadataInstruction::SignMetadata
+        | MetadataInstruction::RemoveCreatorVerification
+        | MetadataInstruction::VerifyCollection
+        | MetadataInstruction::UnverifyCollection
+        | MetadataInstruction::SetAndVerifyCollection
+        | MetadataInstruction::VerifySizedCollectionItem
+        | MetadataInstruction::UnverifySizedCollectionItem
+        | MetadataInstruction::SetAndVerifySizedCollectionItem
+        | MetadataInstruction::SetCollectionSize(_)
+        | MetadataInstruction::SetTokenStandard
+        | MetadataInstruction::BindRuleSet
+        | MetadataInstruction::MigrateMetadata
+        | MetadataInstruction::VerifyCollectionParent
+        | MetadataInstruction::RefillUses(_)
+        | MetadataInstruction::BurnNft
+        | MetadataInstruction::BurnEditionNft => Some(1),
+        MetadataInstruction::VerifySizedCollectionItemBatch => Some(0),
+        MetadataInstruction::ApproveCreatorChange
+        | MetadataInstruction::CreateFungibleDetails(_)
+        | MetadataInstruction::UpdateFungibleDetails(_) => Some(2),
+        _ => None,
+    }
+}
+
+/// Splits the metadata histories passed after the accounts of the instruction off the end.
+fn split_metadata_histories<'a>(
+    program_id: &Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> (&'a [AccountInfo<'a>], &'a [AccountInfo<'a>]) {
+    let is_metadata_history = |account_info: &&AccountInfo| {
+        account_info.owner == program_id
+            && account_info.data_len() == METADATA_HISTORY_SIZE
+            && account_info
+                .try_borrow_data()
+                .map_or(false, |data| data[0] == Key::MetadataHistory as u8)
+    };
+    let history_count = accounts
+        .iter()
+        .rev()
+        .take_while(is_metadata_history)
+        .count();
+    accounts.split_at(accounts.len() - history_count)
+}
+
+fn load_metadata_history(
+    program_id: &Pubkey,
+    metadata_history_info: &AccountInfo,
+) -> Result<MetadataHistory, ProgramError> {
+    if metadata_history_info.owner != program_id {
+        return Err(MetadataError::MissingMetadataHistory.into());
+    }
+    MetadataHistory::from_account_info(metadata_history_info)
+        .map_err(|_| MetadataError::MissingMetadataHistory.into())
+}
+
+/// Runs the instruction and appends its change to each of the metadata histories. A history
+/// records the change of the instruction account holding its metadata, such as an item or the
+/// collection whose size it changed, unless the instruction closed that account.
+fn process_recording_histories<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    history_infos: &[AccountInfo<'a>],
+    instruction: MetadataInstruction,
+    signer_index: usize,
+) -> ProgramResult {
+    let signer_info = accounts
+        .get(signer_index)
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    let mut recordings = Vec::with_capacity(history_infos.len());
+    for metadata_history_info in history_infos {
+        let metadata_history = load_metadata_history(program_id, metadata_history_info)?;
+        let metadata_info = accounts
+            .iter()
+            .find(|account_info| *account_info.key == metadata_history.metadata)
+            .ok_or(MetadataError::MissingMetadataHistory)?;
+        assert_owned_by(metadata_info, program_id)?;
+        let prior: Metadata = Metadata::from_account_info(metadata_info)?;
+        recordings.push((
+            metadata_history_info,
+            metadata_history,
+            metadata_info,
+            prior,
+        ));
+    }
+
+    process_metadata_instruction(program_id, accounts, instruction)?;
+
+    let slot = Clock::get()?.slot;
+    for (metadata_history_info, mut metadata_history, metadata_info, prior) in recordings {
+        if metadata_info.lamports() == 0 {
+            continue;
+        }
+        let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+        metadata_history.record(MetadataHistoryEntry::new(
+            &prior,
+            &metadata,
+            slot,
+            *signer_info.key,
+        ));
+        metadata_history.serialize(&mut *metadata_history_info.try_borrow_mut_data()?)?;
+    }
+    Ok(())
+}
+
+fn process_metadata_instruction<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction: MetadataInstruction,
+) -> ProgramResult {
     match instruction {
         MetadataInstruction::CreateMetadataAccount(args) => {
             msg!("(Deprecated as of 1.1.0) Instruction: Create Metadata Accounts");
@@ -159,6 +324,7 @@ pub fn process_instruction<'a>(
                 program_id,
                 accounts,
                 args.edition,
+                None,
                 false,
             )
         }
@@ -196,7 +362,7 @@ pub fn process_instruction<'a>(
         }
         MetadataInstruction::ApproveUseAuthority(args) => {
             msg!("Instruction: Approve Use Authority");
-            process_approve_use_authority(program_id, accounts, args.number_of_uses)
+            process_approve_use_authority(program_id, accounts, args.number_of_uses, None, None)
         }
         MetadataInstruction::RevokeUseAuthority => {
             msg!("Instruction: Revoke Use Authority");
@@ -242,6 +408,126 @@ pub fn process_instruction<'a>(
             msg!("Instruction: Set Token Standard");
             set_token_standard(program_id, accounts)
         }
+        MetadataInstruction::CreateRuleSet(args) => {
+            msg!("Instruction: Create Rule Set");
+            process_create_rule_set(program_id, accounts, args)
+        }
+        MetadataInstruction::UpdateRuleSet(args) => {
+            msg!("Instruction: Update Rule Set");
+            process_update_rule_set(program_id, accounts, args)
+        }
+        MetadataInstruction::BindRuleSet => {
+            msg!("Instruction: Bind Rule Set");
+            process_bind_rule_set(program_id, accounts)
+        }
+        MetadataInstruction::Transfer => {
+            msg!("Instruction: Transfer");
+            process_transfer(program_id, accounts)
+        }
+        MetadataInstruction::MigrateMetadata => {
+            msg!("Instruction: Migrate Metadata");
+            process_migrate_metadata(program_id, accounts)
+        }
+        MetadataInstruction::ApproveUpdateDelegate(args) => {
+            msg!("Instruction: Approve Update Delegate");
+            process_approve_update_delegate(program_id, accounts, args)
+        }
+        MetadataInstruction::RevokeUpdateDelegate => {
+            msg!("Instruction: Revoke Update Delegate");
+            process_revoke_update_delegate(program_id, accounts)
+        }
+        MetadataInstruction::CreateMetadataAttributes(args) => {
+            msg!("Instruction: Create Metadata Attributes");
+            process_create_metadata_attributes(program_id, accounts, args)
+        }
+        MetadataInstruction::UpdateMetadataAttributes(args) => {
+            msg!("Instruction: Update Metadata Attributes");
+            process_update_metadata_attributes(program_id, accounts, args)
+        }
+        MetadataInstruction::RemoveMetadataAttributes(args) => {
+            msg!("Instruction: Remove Metadata Attributes");
+            process_remove_metadata_attributes(program_id, accounts, args)
+        }
+        MetadataInstruction::SignMetadataBatch => {
+            msg!("Instruction: Sign Metadata Batch");
+            process_sign_metadata_batch(program_id, accounts)
+        }
+        MetadataInstruction::VerifySizedCollectionItemBatch => {
+            msg!("Instruction: Verify Sized Collection Item Batch");
+            verify_sized_collection_item_batch(program_id, accounts)
+        }
+        MetadataInstruction::MintNewEditionFromMasterEditionViaTokenWithOverrides(args) => {
+            msg!("Instruction: Mint New Edition from Master Edition Via Token With Overrides");
+            process_mint_new_edition_from_master_edition_via_token(
+                program_id,
+                accounts,
+                args.edition,
+                Some(&args.overrides),
+                false,
+            )
+        }
+        MetadataInstruction::BurnEditionNft => {
+            msg!("Instruction: Burn Edition NFT");
+            process_burn_edition_nft(program_id, accounts)
+        }
+        MetadataInstruction::VerifyCollectionParent => {
+            msg!("Instruction: Verify Collection Parent");
+            verify_collection_parent(program_id, accounts)
+        }
+        MetadataInstruction::ApproveTimedUseAuthority(args) => {
+            msg!("Instruction: Approve Timed Use Authority");
+            process_approve_use_authority(
+                program_id,
+                accounts,
+                args.number_of_uses,
+                args.not_before,
+                args.expires_at,
+            )
+        }
+        MetadataInstruction::CloseExpiredUseAuthorityRecord => {
+            msg!("Instruction: Close Expired Use Authority Record");
+            process_close_expired_use_authority_record(program_id, accounts)
+        }
+        MetadataInstruction::RefillUses(args) => {
+            msg!("Instruction: Refill Uses");
+            process_refill_uses(program_id, accounts, args.amount)
+        }
+        MetadataInstruction::CreateFungibleDetails(args) => {
+            msg!("Instruction: Create Fungible Details");
+            process_create_fungible_details(program_id, accounts, args.supply_cap)
+        }
+        MetadataInstruction::MintFungibleViaMetadata(args) => {
+            msg!("Instruction: Mint Fungible Via Metadata");
+            process_mint_fungible_via_metadata(program_id, accounts, args.amount)
+        }
+        MetadataInstruction::ProposeCreatorChange(args) => {
+            msg!("Instruction: Propose Creator Change");
+            process_propose_creator_change(program_id, accounts, args)
+        }
+        MetadataInstruction::ApproveCreatorChange => {
+            msg!("Instruction: Approve Creator Change");
+            process_approve_creator_change(program_id, accounts)
+        }
+        MetadataInstruction::CancelCreatorChange => {
+            msg!("Instruction: Cancel Creator Change");
+            process_cancel_creator_change(program_id, accounts)
+        }
+        MetadataInstruction::CreateMetadataHistory => {
+            msg!("Instruction: Create Metadata History");
+            process_create_metadata_history(program_id, accounts)
+        }
+        MetadataInstruction::AssertCollectionAncestry => {
+            msg!("Instruction: Assert Collection Ancestry");
+            process_assert_collection_ancestry(program_id, accounts)
+        }
+        MetadataInstruction::UpdateFungibleDetails(args) => {
+            msg!("Instruction: Update Fungible Details");
+            process_update_fungible_details(program_id, accounts, args.supply_cap)
+        }
+        MetadataInstruction::RecordMetadataHistory(args) => {
+            msg!("Instruction: Record Metadata History");
+            process_record_metadata_history(program_id, accounts, &args.instruction)
+        }
     }
 }
 
@@ -331,13 +617,34 @@ pub fn process_update_metadata_accounts_v2(
 
     let metadata_account_info = next_account_info(account_info_iter)?;
     let update_authority_info = next_account_info(account_info_iter)?;
-    let mut metadata = Metadata::from_account_info(metadata_account_info)?;
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_account_info)?;
 
     assert_owned_by(metadata_account_info, program_id)?;
-    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    let before = MetadataState::from(&metadata);
+
+    // An update delegate signs in place of the update authority and may only touch its scopes.
+    let delegate_scopes = if accounts.len() == 3 {
+        let update_delegate_record_info = next_account_info(account_info_iter)?;
+        assert_signer(update_authority_info)?;
+        let record = assert_update_delegate_record(
+            update_authority_info,
+            &metadata,
+            update_delegate_record_info,
+        )?;
+        if update_authority.is_some() || primary_sale_happened.is_some() || is_mutable.is_some() {
+            return Err(MetadataError::UpdateDelegateScopeMissing.into());
+        }
+        Some(record.scopes)
+    } else {
+        assert_update_authority_is_correct(&metadata, update_authority_info)?;
+        None
+    };
 
     if let Some(data) = optional_data {
         if metadata.is_mutable {
+            if let Some(scopes) = delegate_scopes {
+                assert_update_within_scopes(&scopes, &metadata, &data)?;
+            }
             let compatible_data = data.to_v1();
             assert_data_valid(
                 &compatible_data,
@@ -346,6 +653,7 @@ pub fn process_update_metadata_accounts_v2(
                 false,
                 update_authority_info.is_signer,
             )?;
+            assert_creator_change_is_allowed(&metadata, &data.creators, update_authority_info.key)?;
             metadata.data = compatible_data;
             // If the user passes in Collection data, only allow updating if it's unverified
             // or if it exactly matches the existing collection info.
@@ -393,6 +701,13 @@ pub fn process_update_metadata_accounts_v2(
 
     puff_out_data_fields(&mut metadata);
     clean_write_metadata(&mut metadata, metadata_account_info)?;
+
+    emit(MetadataEvent::MetadataUpdated {
+        metadata: *metadata_account_info.key,
+        authority: *update_authority_info.key,
+        before,
+        after: MetadataState::from(&metadata),
+    });
     Ok(())
 }
 
@@ -410,7 +725,7 @@ pub fn process_update_primary_sale_happened_via_token(
     let mut metadata: Metadata = Metadata::from_account_info(metadata_account_info)?;
 
     assert_owned_by(metadata_account_info, program_id)?;
-    assert_owned_by(token_account_info, &spl_token::id())?;
+    assert_owned_by_token_program(token_account_info)?;
 
     if !owner_info.is_signer {
         return Err(ProgramError::MissingRequiredSignature);
@@ -431,6 +746,10 @@ pub fn process_update_primary_sale_happened_via_token(
     metadata.primary_sale_happened = true;
     metadata.serialize(&mut *metadata_account_info.try_borrow_mut_data()?)?;
 
+    emit(MetadataEvent::PrimarySaleHappened {
+        metadata: *metadata_account_info.key,
+        owner: *owner_info.key,
+    });
     Ok(())
 }
 
@@ -441,6 +760,47 @@ pub fn process_sign_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> P
     let creator_info = next_account_info(account_info_iter)?;
 
     assert_signer(creator_info)?;
+    sign_metadata(program_id, metadata_info, creator_info)?;
+    Ok(())
+}
+
+pub fn process_sign_metadata_batch(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let creator_info = next_account_info(account_info_iter)?;
+    assert_signer(creator_info)?;
+
+    if accounts.len() < 2 {
+        return Err(MetadataError::NoMetadataAccountsProvided.into());
+    }
+    assert_batch_has_no_history(program_id, account_info_iter.as_slice())?;
+    for metadata_info in account_info_iter {
+        sign_metadata(program_id, metadata_info, creator_info)?;
+    }
+    Ok(())
+}
+
+/// Batch instructions cannot record a metadata history, so metadata with history enabled is
+/// rejected before anything is written.
+fn assert_batch_has_no_history(
+    program_id: &Pubkey,
+    metadata_infos: &[AccountInfo],
+) -> ProgramResult {
+    for metadata_info in metadata_infos {
+        assert_owned_by(metadata_info, program_id)?;
+        let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+        if metadata.history_enabled {
+            return Err(MetadataError::MetadataHistoryNotSupportedInBatch.into());
+        }
+    }
+    Ok(())
+}
+
+fn sign_metadata(
+    program_id: &Pubkey,
+    metadata_info: &AccountInfo,
+    creator_info: &AccountInfo,
+) -> ProgramResult {
     assert_owned_by(metadata_info, program_id)?;
 
     let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
@@ -462,6 +822,11 @@ pub fn process_sign_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> P
     }
     metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
 
+    emit(MetadataEvent::CreatorVerificationChanged {
+        metadata: *metadata_info.key,
+        creator: *creator_info.key,
+        verified: true,
+    });
     Ok(())
 }
 
@@ -496,6 +861,11 @@ pub fn process_remove_creator_verification(
     }
     metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
 
+    emit(MetadataEvent::CreatorVerificationChanged {
+        metadata: *metadata_info.key,
+        creator: *creator_info.key,
+        verified: false,
+    });
     Ok(())
 }
 
@@ -534,7 +904,7 @@ pub fn process_create_master_edition(
     assert_token_program_matches_package(token_program_info)?;
     assert_mint_authority_matches_mint(&mint.mint_authority, mint_authority_info)?;
     assert_owned_by(metadata_account_info, program_id)?;
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by(mint_info, token_program_info.key)?;
 
     if metadata.mint != *mint_info.key {
         return Err(MetadataError::MintMismatch.into());
@@ -597,6 +967,7 @@ pub fn process_mint_new_edition_from_master_edition_via_token<'a>(
     program_id: &'a Pubkey,
     accounts: &'a [AccountInfo<'a>],
     edition: u64,
+    overrides: Option<&EditionOverrides>,
     ignore_owner_signer: bool,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
@@ -635,6 +1006,7 @@ pub fn process_mint_new_edition_from_master_edition_via_token<'a>(
             rent_info,
         },
         edition,
+        overrides,
         ignore_owner_signer,
     )
 }
@@ -793,7 +1165,9 @@ pub fn process_mint_new_edition_from_master_edition_via_vault_proxy<'a>(
         rent_info,
     };
 
-    process_mint_new_edition_from_master_edition_via_token_logic(program_id, args, edition, true)
+    process_mint_new_edition_from_master_edition_via_token_logic(
+        program_id, args, edition, None, true,
+    )
 }
 
 /// Puff out the variable length fields to a fixed length on a metadata
@@ -838,7 +1212,7 @@ pub fn verify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progr
 
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
@@ -851,6 +1225,8 @@ pub fn verify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progr
         edition_account_info,
     )?;
 
+    assert_collection_member_is_valid(program_id, &metadata, &collection_metadata)?;
+
     if using_delegated_collection_authority {
         let collection_authority_record = next_account_info(account_info_iter)?;
         assert_has_collection_authority(
@@ -877,10 +1253,43 @@ pub fn verify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progr
     if let Some(collection) = &mut metadata.collection {
         collection.verified = true;
         metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+        emit_collection_verification_changed(
+            metadata_info,
+            collection_mint,
+            collection_authority_info,
+            true,
+        );
     }
     Ok(())
 }
 
+// Instructions taking remaining accounts make an optional account impossible to detect by
+// length, so the program id stands in for a missing collection authority record.
+fn optional_collection_authority_record<'a, 'b>(
+    program_id: &Pubkey,
+    collection_authority_record: &'a AccountInfo<'b>,
+) -> Option<&'a AccountInfo<'b>> {
+    if collection_authority_record.key == program_id {
+        None
+    } else {
+        Some(collection_authority_record)
+    }
+}
+
+fn emit_collection_verification_changed(
+    metadata_info: &AccountInfo,
+    collection_mint: &AccountInfo,
+    collection_authority_info: &AccountInfo,
+    verified: bool,
+) {
+    emit(MetadataEvent::CollectionVerificationChanged {
+        metadata: *metadata_info.key,
+        collection_mint: *collection_mint.key,
+        collection_authority: *collection_authority_info.key,
+        verified,
+    });
+}
+
 pub fn verify_sized_collection_item(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
@@ -900,7 +1309,7 @@ pub fn verify_sized_collection_item(
 
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
@@ -920,6 +1329,8 @@ pub fn verify_sized_collection_item(
         edition_account_info,
     )?;
 
+    assert_collection_member_is_valid(program_id, &metadata, &collection_metadata)?;
+
     if using_delegated_collection_authority {
         let collection_authority_record = next_account_info(account_info_iter)?;
         assert_has_collection_authority(
@@ -945,12 +1356,191 @@ pub fn verify_sized_collection_item(
 
         collection.verified = true;
         clean_write_metadata(&mut metadata, metadata_info)?;
+        emit_collection_verification_changed(
+            metadata_info,
+            collection_mint,
+            collection_authority_info,
+            true,
+        );
+    } else {
+        return Err(MetadataError::CollectionNotFound.into());
+    }
+    Ok(())
+}
+
+pub fn verify_sized_collection_item_batch(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let collection_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let collection_mint = next_account_info(account_info_iter)?;
+    let collection_info = next_account_info(account_info_iter)?;
+    let edition_account_info = next_account_info(account_info_iter)?;
+    let collection_authority_record =
+        optional_collection_authority_record(program_id, next_account_info(account_info_iter)?);
+
+    assert_signer(collection_authority_info)?;
+    assert_signer(payer_info)?;
+
+    assert_owned_by(collection_info, program_id)?;
+    assert_owned_by_token_program(collection_mint)?;
+    assert_owned_by(edition_account_info, program_id)?;
+
+    let mut collection_metadata = Metadata::from_account_info(collection_info)?;
+
+    assert_has_collection_authority(
+        collection_authority_info,
+        &collection_metadata,
+        collection_mint.key,
+        collection_authority_record,
+    )?;
+
+    assert_batch_has_no_history(program_id, account_info_iter.as_slice())?;
+    let mut verified_count: u64 = 0;
+    for metadata_info in account_info_iter {
+        // The collection is written once after the loop, which would overwrite the item.
+        if metadata_info.key == collection_info.key {
+            return Err(MetadataError::CollectionCannotBeVerifiedInThisInstruction.into());
+        }
+        assert_owned_by(metadata_info, program_id)?;
+        let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+
+        // Don't verify already verified items, otherwise we end up with invalid size data.
+        // This also rejects the same item being passed twice.
+        if let Some(collection) = &metadata.collection {
+            if collection.verified {
+                return Err(MetadataError::AlreadyVerified.into());
+            }
+        }
+
+        assert_collection_verify_is_valid(
+            &metadata,
+            &collection_metadata,
+            collection_mint,
+            edition_account_info,
+        )?;
+
+        assert_collection_member_is_valid(program_id, &metadata, &collection_metadata)?;
+
+        if let Some(collection) = &mut metadata.collection {
+            collection.verified = true;
+            clean_write_metadata(&mut metadata, metadata_info)?;
+            emit_collection_verification_changed(
+                metadata_info,
+                collection_mint,
+                collection_authority_info,
+                true,
+            );
+        } else {
+            return Err(MetadataError::CollectionNotFound.into());
+        }
+        verified_count = verified_count
+            .checked_add(1)
+            .ok_or(MetadataError::NumericalOverflowError)?;
+    }
+
+    if verified_count == 0 {
+        return Err(MetadataError::NoMetadataAccountsProvided.into());
+    }
+
+    msg!("Verified {} sized collection items", verified_count);
+    increment_collection_size_by(&mut collection_metadata, collection_info, verified_count)
+}
+
+pub fn verify_collection_parent(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let collection_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let parent_collection_mint = next_account_info(account_info_iter)?;
+    let parent_collection_info = next_account_info(account_info_iter)?;
+    let edition_account_info = next_account_info(account_info_iter)?;
+    let collection_authority_record =
+        optional_collection_authority_record(program_id, next_account_info(account_info_iter)?);
+    let ancestors = account_info_iter.as_slice();
+
+    assert_signer(collection_authority_info)?;
+    assert_signer(payer_info)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by(parent_collection_info, program_id)?;
+    assert_owned_by_token_program(parent_collection_mint)?;
+    assert_owned_by(edition_account_info, program_id)?;
+
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    let mut parent_metadata = Metadata::from_account_info(parent_collection_info)?;
+
+    if let Some(collection) = &metadata.collection {
+        if collection.verified {
+            return Err(MetadataError::AlreadyVerified.into());
+        }
+    }
+
+    assert_collection_verify_is_valid(
+        &metadata,
+        &parent_metadata,
+        parent_collection_mint,
+        edition_account_info,
+    )?;
+
+    assert_has_collection_authority(
+        collection_authority_info,
+        &parent_metadata,
+        parent_collection_mint.key,
+        collection_authority_record,
+    )?;
+
+    assert_collection_parent_is_valid(
+        program_id,
+        &metadata,
+        &parent_metadata,
+        ancestors,
+        MAX_COLLECTION_DEPTH,
+    )?;
+
+    if let Some(collection) = &mut metadata.collection {
+        collection.verified = true;
+        clean_write_metadata(&mut metadata, metadata_info)?;
+        emit_collection_verification_changed(
+            metadata_info,
+            parent_collection_mint,
+            collection_authority_info,
+            true,
+        );
     } else {
         return Err(MetadataError::CollectionNotFound.into());
     }
+
+    if parent_metadata.collection_details.is_some() {
+        increment_collection_size(&mut parent_metadata, parent_collection_info)?;
+    }
     Ok(())
 }
 
+pub fn process_assert_collection_ancestry(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let ancestor_collection_mint = next_account_info(account_info_iter)?;
+    let collections = account_info_iter.as_slice();
+
+    assert_owned_by(metadata_info, program_id)?;
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+
+    // An item sits one level below the deepest collection of a hierarchy.
+    assert_collection_ancestry(
+        program_id,
+        &metadata,
+        collections,
+        ancestor_collection_mint.key,
+        MAX_COLLECTION_DEPTH + 1,
+    )
+}
+
 pub fn unverify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let metadata_info = next_account_info(account_info_iter)?;
@@ -963,7 +1553,7 @@ pub fn unverify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     assert_signer(collection_authority_info)?;
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata = Metadata::from_account_info(metadata_info)?;
@@ -1001,6 +1591,12 @@ pub fn unverify_collection(program_id: &Pubkey, accounts: &[AccountInfo]) -> Pro
     // size on the Collection Parent.
     if let Some(collection) = &mut metadata.collection {
         collection.verified = false;
+        emit_collection_verification_changed(
+            metadata_info,
+            collection_mint,
+            collection_authority_info,
+            false,
+        );
     }
     metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
     Ok(())
@@ -1025,7 +1621,7 @@ pub fn unverify_sized_collection_item(
 
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
@@ -1068,6 +1664,12 @@ pub fn unverify_sized_collection_item(
 
         collection.verified = false;
         clean_write_metadata(&mut metadata, metadata_info)?;
+        emit_collection_verification_changed(
+            metadata_info,
+            collection_mint,
+            collection_authority_info,
+            false,
+        );
     } else {
         return Err(MetadataError::CollectionNotFound.into());
     }
@@ -1078,6 +1680,8 @@ pub fn process_approve_use_authority(
     program_id: &Pubkey,
     accounts: &[AccountInfo],
     number_of_uses: u64,
+    not_before: Option<i64>,
+    expires_at: Option<i64>,
 ) -> ProgramResult {
     let account_info_iter = &mut accounts.iter();
     let use_authority_record_info = next_account_info(account_info_iter)?;
@@ -1095,9 +1699,7 @@ pub fn process_approve_use_authority(
     if metadata.uses.is_none() {
         return Err(MetadataError::Unusable.into());
     }
-    if *token_program_account_info.key != spl_token::id() {
-        return Err(MetadataError::InvalidTokenProgram.into());
-    }
+    assert_token_program_matches_package(token_program_account_info)?;
     assert_signer(owner_info)?;
     assert_signer(payer)?;
     assert_currently_holding(
@@ -1124,13 +1726,20 @@ pub fn process_approve_use_authority(
         &[bump_seed],
     ];
     process_use_authority_validation(use_authority_record_info.data_len(), true)?;
+    assert_use_authority_time_window_valid(not_before, expires_at, Clock::get()?.unix_timestamp)?;
+    // Only records with a time window pay for the larger account.
+    let record_size = if not_before.is_none() && expires_at.is_none() {
+        USE_AUTHORITY_RECORD_SIZE
+    } else {
+        TIMED_USE_AUTHORITY_RECORD_SIZE
+    };
     create_or_allocate_account_raw(
         *program_id,
         use_authority_record_info,
         rent_info,
         system_account_info,
         payer,
-        USE_AUTHORITY_RECORD_SIZE,
+        record_size,
         use_authority_seeds,
     )?;
     if number_of_uses > metadata_uses.remaining {
@@ -1161,7 +1770,16 @@ pub fn process_approve_use_authority(
     record.key = Key::UseAuthorityRecord;
     record.allowed_uses = number_of_uses;
     record.bump = bump_seed;
+    record.not_before = not_before;
+    record.expires_at = expires_at;
     record.serialize(mutable_data)?;
+
+    emit(MetadataEvent::AuthorityApproved {
+        authority_type: AuthorityRecordType::Use,
+        metadata: *metadata_info.key,
+        authority: *user_info.key,
+        record: *use_authority_record_info.key,
+    });
     Ok(())
 }
 
@@ -1181,9 +1799,7 @@ pub fn process_revoke_use_authority(
     if metadata.uses.is_none() {
         return Err(MetadataError::Unusable.into());
     }
-    if *token_program_account_info.key != spl_token::id() {
-        return Err(MetadataError::InvalidTokenProgram.into());
-    }
+    assert_token_program_matches_package(token_program_account_info)?;
     assert_signer(owner_info)?;
     assert_currently_holding(
         program_id,
@@ -1230,7 +1846,15 @@ pub fn process_revoke_use_authority(
         .lamports()
         .checked_add(lamports)
         .ok_or(MetadataError::NumericalOverflowError)?;
-    sol_memset(data, 0, USE_AUTHORITY_RECORD_SIZE);
+    let data_len = data.len();
+    sol_memset(data, 0, data_len);
+
+    emit(MetadataEvent::AuthorityRevoked {
+        authority_type: AuthorityRecordType::Use,
+        metadata: *metadata_info.key,
+        authority: *user_info.key,
+        record: *use_authority_record_info.key,
+    });
     Ok(())
 }
 
@@ -1254,9 +1878,7 @@ pub fn process_utilize(
     if metadata.uses.is_none() {
         return Err(MetadataError::Unusable.into());
     }
-    if *token_program_account_info.key != spl_token::id() {
-        return Err(MetadataError::InvalidTokenProgram.into());
-    }
+    assert_token_program_matches_package(token_program_account_info)?;
     assert_signer(user_info)?;
     assert_currently_holding(
         program_id,
@@ -1298,6 +1920,7 @@ pub fn process_utilize(
             record.bump = canonical_bump;
         }
         assert_valid_bump(canonical_bump, &record)?;
+        assert_use_authority_record_active(&record, Clock::get()?.unix_timestamp)?;
         record.allowed_uses = record
             .allowed_uses
             .checked_sub(number_of_uses)
@@ -1336,6 +1959,14 @@ pub fn process_utilize(
             })?;
         }
     }
+
+    emit(MetadataEvent::Utilized {
+        metadata: *metadata_info.key,
+        user: *user_info.key,
+        number_of_uses,
+        remaining_before: metadata_uses.remaining,
+        remaining_after: remaining_uses,
+    });
     Ok(())
 }
 
@@ -1355,7 +1986,7 @@ pub fn process_approve_collection_authority(
 
     let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
     assert_owned_by(metadata_info, program_id)?;
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by_token_program(mint_info)?;
     assert_signer(update_authority)?;
     assert_signer(payer)?;
     if metadata.update_authority != *update_authority.key {
@@ -1397,6 +2028,13 @@ pub fn process_approve_collection_authority(
     record.key = Key::CollectionAuthorityRecord;
     record.bump = collection_authority_bump_seed[0];
     record.serialize(&mut *collection_authority_record.try_borrow_mut_data()?)?;
+
+    emit(MetadataEvent::AuthorityApproved {
+        authority_type: AuthorityRecordType::Collection,
+        metadata: *metadata_info.key,
+        authority: *new_collection_authority.key,
+        record: *collection_authority_record.key,
+    });
     Ok(())
 }
 
@@ -1412,7 +2050,7 @@ pub fn process_revoke_collection_authority(
     let mint_info = next_account_info(account_info_iter)?;
     let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
     assert_owned_by(metadata_info, program_id)?;
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by_token_program(mint_info)?;
     assert_signer(revoke_authority)?;
     if metadata.update_authority != *revoke_authority.key
         && *delegate_authority.key != *revoke_authority.key
@@ -1444,6 +2082,12 @@ pub fn process_revoke_collection_authority(
         USE_AUTHORITY_RECORD_SIZE,
     );
 
+    emit(MetadataEvent::AuthorityRevoked {
+        authority_type: AuthorityRecordType::Collection,
+        metadata: *metadata_info.key,
+        authority: *delegate_authority.key,
+        record: *collection_authority_record.key,
+    });
     Ok(())
 }
 
@@ -1462,7 +2106,7 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
 
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
@@ -1499,6 +2143,7 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
         collection_mint,
         edition_account_info,
     )?;
+    assert_collection_member_is_valid(program_id, &metadata, &collection_data)?;
 
     // This handler can only verify non-sized NFTs
     if collection_data.collection_details.is_some() {
@@ -1506,6 +2151,12 @@ pub fn set_and_verify_collection(program_id: &Pubkey, accounts: &[AccountInfo])
     }
 
     metadata.serialize(&mut *metadata_info.try_borrow_mut_data()?)?;
+    emit_collection_verification_changed(
+        metadata_info,
+        collection_mint,
+        collection_authority_info,
+        true,
+    );
     Ok(())
 }
 
@@ -1528,7 +2179,7 @@ pub fn set_and_verify_sized_collection_item(
 
     assert_owned_by(metadata_info, program_id)?;
     assert_owned_by(collection_info, program_id)?;
-    assert_owned_by(collection_mint, &spl_token::id())?;
+    assert_owned_by_token_program(collection_mint)?;
     assert_owned_by(edition_account_info, program_id)?;
 
     let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
@@ -1573,11 +2224,18 @@ pub fn set_and_verify_sized_collection_item(
         collection_mint,
         edition_account_info,
     )?;
+    assert_collection_member_is_valid(program_id, &metadata, &collection_metadata)?;
 
     // Update the collection size if this is a valid parent collection NFT.
     increment_collection_size(&mut collection_metadata, collection_info)?;
 
     clean_write_metadata(&mut metadata, metadata_info)?;
+    emit_collection_verification_changed(
+        metadata_info,
+        collection_mint,
+        collection_authority_info,
+        true,
+    );
 
     Ok(())
 }
@@ -1593,9 +2251,7 @@ pub fn process_freeze_delegated_account(
     let mint_info = next_account_info(account_info_iter)?;
     let token_program_account_info = next_account_info(account_info_iter)?;
 
-    if *token_program_account_info.key != spl_token::id() {
-        return Err(MetadataError::InvalidTokenProgram.into());
-    }
+    assert_token_program_matches_package(token_program_account_info)?;
 
     // assert that edition pda is the freeze authority of this mint
     let mint: Mint = assert_initialized(mint_info)?;
@@ -1648,9 +2304,7 @@ pub fn process_thaw_delegated_account(
     let edition_info = next_account_info(account_info_iter)?;
     let mint_info = next_account_info(account_info_iter)?;
     let token_program_account_info = next_account_info(account_info_iter)?;
-    if *token_program_account_info.key != spl_token::id() {
-        return Err(MetadataError::InvalidTokenProgram.into());
-    }
+    assert_token_program_matches_package(token_program_account_info)?;
 
     // assert that edition pda is the freeze authority of this mint
     let mint: Mint = assert_initialized(mint_info)?;
@@ -1741,8 +2395,8 @@ pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
 
     // Checks:
     // * Metadata is owned by the token-metadata program
-    // * Mint is owned by the spl-token program
-    // * Token is owned by the spl-token program
+    // * Mint is owned by the spl-token or Token-2022 program
+    // * Token is owned by the same program as the mint
     // * Token account is initialized
     // * Token account data owner is 'owner'
     // * Token account belongs to mint
@@ -1760,6 +2414,10 @@ pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
     // Owned by token-metadata program.
     assert_owned_by(edition_info, program_id)?;
 
+    // The mint's own token program receives the burn and close.
+    assert_token_program_matches_package(spl_token_program_info)?;
+    assert_owned_by(mint_info, spl_token_program_info.key)?;
+
     // Owner is a signer.
     assert_signer(owner_info)?;
 
@@ -1770,9 +2428,26 @@ pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
         mint_info.key.as_ref(),
         EDITION.as_bytes(),
     ]);
-    assert_derivation(program_id, edition_info, &edition_info_path)?;
-
-    // Burn the SPL token
+    let edition_info_path_bump_seed = &[assert_derivation(
+        program_id,
+        edition_info,
+        &edition_info_path,
+    )?];
+
+    // Tokens bound to a rule set are held frozen by the edition, so thaw before burning.
+    if metadata.rule_set.is_some() && assert_initialized::<Account>(token_info)?.is_frozen() {
+        let mut edition_info_seeds = edition_info_path.clone();
+        edition_info_seeds.push(edition_info_path_bump_seed);
+        thaw(
+            mint_info.clone(),
+            token_info.clone(),
+            edition_info.clone(),
+            spl_token_program_info.clone(),
+            &edition_info_seeds,
+        )?;
+    }
+
+    // Burn the SPL token
     let params = TokenBurnParams {
         mint: mint_info.clone(),
         source: token_info.clone(),
@@ -1813,8 +2488,10 @@ pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
     let edition_data = &mut edition_info.try_borrow_mut_data()?;
     let edition_data_len = edition_data.len();
 
-    // Use MAX_METADATA_LEN because it has unused padding, making it longer than current metadata len.
-    sol_memset(metadata_data, 0, MAX_METADATA_LEN);
+    // Use the account length since migrated metadata accounts carry an extension area past
+    // MAX_METADATA_LEN.
+    let metadata_data_len = metadata_data.len();
+    sol_memset(metadata_data, 0, metadata_data_len);
     sol_memset(edition_data, 0, edition_data_len);
 
     if collection_nft_provided {
@@ -1844,6 +2521,12 @@ pub fn process_burn_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> Progra
         }
     }
 
+    emit(MetadataEvent::Burned {
+        metadata: *metadata_info.key,
+        mint: *mint_info.key,
+        owner: *owner_info.key,
+        master_edition: None,
+    });
     Ok(())
 }
 
@@ -1896,6 +2579,12 @@ pub fn set_collection_size(
     }
 
     clean_write_metadata(&mut metadata, parent_nft_metadata_account_info)?;
+
+    emit(MetadataEvent::CollectionSizeSet {
+        metadata: *parent_nft_metadata_account_info.key,
+        collection_authority: *collection_update_authority_account_info.key,
+        size,
+    });
     Ok(())
 }
 
@@ -1936,7 +2625,1528 @@ pub fn set_token_standard(program_id: &Pubkey, accounts: &[AccountInfo]) -> Prog
         check_token_standard(mint_account_info, None)?
     };
 
-    metadata.token_standard = Some(token_standard);
+    metadata.token_standard = Some(token_standard.clone());
     clean_write_metadata(&mut metadata, metadata_account_info)?;
+
+    emit(MetadataEvent::TokenStandardSet {
+        metadata: *metadata_account_info.key,
+        token_standard,
+    });
+    Ok(())
+}
+
+pub fn process_create_rule_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateRuleSetArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(authority_info)?;
+    assert_signer(payer_info)?;
+    assert_rule_set_entries_valid(&args.allowed_programs, &args.escrow_authorities)?;
+
+    if !rule_set_info.try_data_is_empty()? {
+        return Err(MetadataError::AlreadyInitialized.into());
+    }
+
+    let rule_set_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        RULE_SET.as_bytes(),
+        authority_info.key.as_ref(),
+    ]);
+    let rule_set_bump_seed = &[assert_derivation(
+        program_id,
+        rule_set_info,
+        &rule_set_path,
+    )?];
+    let mut rule_set_seeds = rule_set_path.clone();
+    rule_set_seeds.push(rule_set_bump_seed);
+    create_or_allocate_account_raw(
+        *program_id,
+        rule_set_info,
+        rent_info,
+        system_account_info,
+        payer_info,
+        RULE_SET_SIZE,
+        &rule_set_seeds,
+    )?;
+
+    let rule_set = RuleSet {
+        key: Key::RuleSet,
+        authority: *authority_info.key,
+        allowed_programs: args.allowed_programs,
+        escrow_authorities: args.escrow_authorities,
+        bump: rule_set_bump_seed[0],
+    };
+    rule_set.serialize(&mut *rule_set_info.try_borrow_mut_data()?)?;
+
+    emit(MetadataEvent::RuleSetCreated {
+        rule_set: *rule_set_info.key,
+        authority: rule_set.authority,
+        allowed_programs: rule_set.allowed_programs,
+        escrow_authorities: rule_set.escrow_authorities,
+    });
+    Ok(())
+}
+
+pub fn process_update_rule_set(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateRuleSetArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(rule_set_info, program_id)?;
+    let mut rule_set: RuleSet = RuleSet::from_account_info(rule_set_info)?;
+    assert_rule_set_authority(&rule_set, authority_info)?;
+    assert_rule_set_entries_valid(&args.allowed_programs, &args.escrow_authorities)?;
+
+    rule_set.allowed_programs = args.allowed_programs;
+    rule_set.escrow_authorities = args.escrow_authorities;
+
+    // Lists may shrink, so clear any stale entries before writing.
+    let mut data = rule_set_info.try_borrow_mut_data()?;
+    sol_memset(&mut data, 0, RULE_SET_SIZE);
+    rule_set.serialize(&mut *data)?;
+
+    emit(MetadataEvent::RuleSetUpdated {
+        rule_set: *rule_set_info.key,
+        authority: rule_set.authority,
+        allowed_programs: rule_set.allowed_programs,
+        escrow_authorities: rule_set.escrow_authorities,
+    });
+    Ok(())
+}
+
+pub fn process_bind_rule_set<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let token_info = next_account_info(account_info_iter)?;
+    let token_owner_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_token_program_matches_package(token_program_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by_token_program(mint_info)?;
+    assert_owned_by_token_program(token_info)?;
+
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    if metadata.token_standard != Some(TokenStandard::NonFungible) {
+        return Err(MetadataError::RuleSetRequiresNonFungible.into());
+    }
+    if metadata.rule_set.is_some() {
+        return Err(MetadataError::AlreadyBoundToRuleSet.into());
+    }
+
+    let rule_set: RuleSet = RuleSet::from_account_info(rule_set_info)?;
+
+    // Only assets whose mint is frozen by their edition can be bound.
+    let mint: Mint = assert_initialized(mint_info)?;
+    assert_owned_by(edition_info, program_id)?;
+    assert_freeze_authority_matches_mint(&mint.freeze_authority, edition_info)?;
+    let edition_info_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        EDITION.as_bytes(),
+    ]);
+    let edition_info_path_bump_seed = &[assert_derivation(
+        program_id,
+        edition_info,
+        &edition_info_path,
+    )?];
+
+    assert_signer(token_owner_info)?;
+    let token: Account = assert_initialized(token_info)?;
+    if token.owner != *token_owner_info.key {
+        return Err(MetadataError::InvalidOwner.into());
+    }
+    if token.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    if token.amount != 1 {
+        return Err(MetadataError::NotEnoughTokens.into());
+    }
+    assert_delegate_allowed(&rule_set, Option::from(token.delegate.as_ref()))?;
+
+    if !token.is_frozen() {
+        let mut edition_info_seeds = edition_info_path.clone();
+        edition_info_seeds.push(edition_info_path_bump_seed);
+        freeze(
+            mint_info.clone(),
+            token_info.clone(),
+            edition_info.clone(),
+            token_program_info.clone(),
+            &edition_info_seeds,
+        )?;
+    }
+
+    metadata.rule_set = Some(*rule_set_info.key);
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::RuleSetBound {
+        metadata: *metadata_info.key,
+        rule_set: *rule_set_info.key,
+        token_account: *token_info.key,
+    });
+    Ok(())
+}
+
+pub fn process_transfer<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let metadata_info = next_account_info(account_info_iter)?;
+    let rule_set_info = next_account_info(account_info_iter)?;
+    let edition_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let source_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+    let sysvar_instructions_info = next_account_info(account_info_iter)?;
+
+    assert_token_program_matches_package(token_program_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by_token_program(mint_info)?;
+    assert_owned_by_token_program(source_info)?;
+    assert_owned_by_token_program(destination_info)?;
+    assert_signer(authority_info)?;
+
+    if *sysvar_instructions_info.key != sysvar::instructions::id() {
+        return Err(ProgramError::UnsupportedSysvar);
+    }
+
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    let rule_set = assert_bound_rule_set(&metadata, rule_set_info)?;
+
+    let mint: Mint = assert_initialized(mint_info)?;
+    let source: Account = assert_initialized(source_info)?;
+    let destination: Account = assert_initialized(destination_info)?;
+    if source.mint != *mint_info.key || destination.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+
+    // The instructions sysvar only exposes the top-level instruction, which is our immediate
+    // caller only when invoked directly or through a single CPI. Deeper nesting would let an
+    // allowed program vouch for whatever it was called by, so it is rejected.
+    if get_stack_height() > TRANSACTION_LEVEL_STACK_HEIGHT + 1 {
+        return Err(MetadataError::NestedCpiNotAllowedByRuleSet.into());
+    }
+    let calling_program = get_instruction_relative(0, sysvar_instructions_info)?.program_id;
+    assert_transfer_allowed(
+        &rule_set,
+        program_id,
+        &calling_program,
+        authority_info.key,
+        &source.owner,
+        &destination.owner,
+    )?;
+    assert_delegate_allowed(&rule_set, Option::from(destination.delegate.as_ref()))?;
+
+    assert_owned_by(edition_info, program_id)?;
+    let edition_info_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        EDITION.as_bytes(),
+    ]);
+    let edition_info_path_bump_seed = &[assert_derivation(
+        program_id,
+        edition_info,
+        &edition_info_path,
+    )?];
+    let mut edition_info_seeds = edition_info_path.clone();
+    edition_info_seeds.push(edition_info_path_bump_seed);
+
+    if source.is_frozen() {
+        thaw(
+            mint_info.clone(),
+            source_info.clone(),
+            edition_info.clone(),
+            token_program_info.clone(),
+            &edition_info_seeds,
+        )?;
+    }
+    if destination.is_frozen() {
+        thaw(
+            mint_info.clone(),
+            destination_info.clone(),
+            edition_info.clone(),
+            token_program_info.clone(),
+            &edition_info_seeds,
+        )?;
+    }
+
+    spl_token_transfer(TokenTransferParams {
+        mint: mint_info.clone(),
+        source: source_info.clone(),
+        destination: destination_info.clone(),
+        authority: authority_info.clone(),
+        token_program: token_program_info.clone(),
+        amount: 1,
+        decimals: mint.decimals,
+        authority_signer_seeds: None,
+    })?;
+
+    freeze(
+        mint_info.clone(),
+        destination_info.clone(),
+        edition_info.clone(),
+        token_program_info.clone(),
+        &edition_info_seeds,
+    )?;
+    Ok(())
+}
+
+pub fn process_migrate_metadata(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    assert_signer(payer_info)?;
+
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.key == Key::MetadataV2 {
+        return Err(MetadataError::MetadataAlreadyMigrated.into());
+    }
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    // Fields that were dropped by the fallback parser are written back cleanly as `None`, and
+    // any stale bytes left behind by resized creator arrays are cleared.
+    metadata.key = Key::MetadataV2;
+    let migrated = MetadataV2 {
+        metadata,
+        extensions: vec![],
+    };
+    resize_or_reallocate_account_raw(
+        metadata_info,
+        payer_info,
+        rent_info,
+        system_account_info,
+        migrated.account_size(),
+    )?;
+    let mut data = metadata_info.try_borrow_mut_data()?;
+    let len = data.len();
+    sol_memset(&mut data, 0, len);
+    migrated.serialize(&mut *data)?;
+
+    emit(MetadataEvent::MetadataMigrated {
+        metadata: *metadata_info.key,
+        key: Key::MetadataV2,
+    });
+    Ok(())
+}
+
+pub fn process_approve_update_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ApproveUpdateDelegateArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let update_delegate_record = next_account_info(account_info_iter)?;
+    let delegate = next_account_info(account_info_iter)?;
+    let update_authority = next_account_info(account_info_iter)?;
+    let payer = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by_token_program(mint_info)?;
+    assert_signer(payer)?;
+    assert_update_authority_is_correct(&metadata, update_authority)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    if !update_delegate_record.try_data_is_empty()? {
+        return Err(MetadataError::UpdateDelegateRecordAlreadyExists.into());
+    }
+
+    let update_delegate_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        UPDATE_DELEGATE.as_bytes(),
+        delegate.key.as_ref(),
+    ]);
+    let update_delegate_bump_seed = &[assert_derivation(
+        program_id,
+        update_delegate_record,
+        &update_delegate_path,
+    )?];
+    let mut update_delegate_seeds = update_delegate_path.clone();
+    update_delegate_seeds.push(update_delegate_bump_seed);
+    create_or_allocate_account_raw(
+        *program_id,
+        update_delegate_record,
+        rent_info,
+        system_account_info,
+        payer,
+        UPDATE_DELEGATE_RECORD_SIZE,
+        &update_delegate_seeds,
+    )?;
+
+    let record = UpdateDelegateRecord {
+        key: Key::UpdateDelegateRecord,
+        bump: update_delegate_bump_seed[0],
+        update_authority: metadata.update_authority,
+        scopes: args.scopes,
+    };
+    record.serialize(&mut *update_delegate_record.try_borrow_mut_data()?)?;
+
+    emit(MetadataEvent::AuthorityApproved {
+        authority_type: AuthorityRecordType::UpdateDelegate,
+        metadata: *metadata_info.key,
+        authority: *delegate.key,
+        record: *update_delegate_record.key,
+    });
     Ok(())
 }
+
+pub fn process_revoke_update_delegate(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let update_delegate_record = next_account_info(account_info_iter)?;
+    let delegate = next_account_info(account_info_iter)?;
+    let revoke_authority = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    assert_owned_by_token_program(mint_info)?;
+    assert_owned_by(update_delegate_record, program_id)?;
+    assert_signer(revoke_authority)?;
+    if metadata.update_authority != *revoke_authority.key && *delegate.key != *revoke_authority.key
+    {
+        return Err(MetadataError::RevokeUpdateDelegateSignerIncorrect.into());
+    }
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+
+    let (pda, _) = find_update_delegate_account(mint_info.key, delegate.key);
+    if pda != *update_delegate_record.key {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    if update_delegate_record.try_data_is_empty()? {
+        return Err(MetadataError::UpdateDelegateRecordDoesNotExist.into());
+    }
+
+    // Records approved by a previous update authority can still be cleaned up.
+    let lamports = update_delegate_record.lamports();
+    **update_delegate_record.try_borrow_mut_lamports()? = 0;
+    **revoke_authority.try_borrow_mut_lamports()? = revoke_authority
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+    sol_memset(
+        *update_delegate_record.try_borrow_mut_data()?,
+        0,
+        UPDATE_DELEGATE_RECORD_SIZE,
+    );
+
+    emit(MetadataEvent::AuthorityRevoked {
+        authority_type: AuthorityRecordType::UpdateDelegate,
+        metadata: *metadata_info.key,
+        authority: *delegate.key,
+        record: *update_delegate_record.key,
+    });
+    Ok(())
+}
+
+/// Loads the metadata behind an attributes account, checking it can still be changed by the signer.
+fn assert_metadata_attributes_authority(
+    program_id: &Pubkey,
+    metadata_info: &AccountInfo,
+    update_authority_info: &AccountInfo,
+) -> Result<Metadata, ProgramError> {
+    assert_owned_by(metadata_info, program_id)?;
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    if !metadata.is_mutable {
+        return Err(MetadataError::DataIsImmutable.into());
+    }
+    Ok(metadata)
+}
+
+fn load_metadata_attributes(
+    program_id: &Pubkey,
+    metadata_attributes_info: &AccountInfo,
+    metadata_info: &AccountInfo,
+) -> Result<MetadataAttributes, ProgramError> {
+    assert_owned_by(metadata_attributes_info, program_id)?;
+    let metadata_attributes: MetadataAttributes =
+        MetadataAttributes::from_account_info(metadata_attributes_info)?;
+    if metadata_attributes.metadata != *metadata_info.key {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    Ok(metadata_attributes)
+}
+
+fn write_metadata_attributes(
+    metadata_attributes: &MetadataAttributes,
+    metadata_attributes_info: &AccountInfo,
+) -> ProgramResult {
+    // Attributes may shrink, so clear any stale entries before writing.
+    let mut data = metadata_attributes_info.try_borrow_mut_data()?;
+    sol_memset(&mut data, 0, METADATA_ATTRIBUTES_SIZE);
+    metadata_attributes.serialize(&mut *data)?;
+    Ok(())
+}
+
+pub fn process_create_metadata_attributes(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: CreateMetadataAttributesArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_attributes_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(payer_info)?;
+    assert_metadata_attributes_authority(program_id, metadata_info, update_authority_info)?;
+    assert_attributes_valid(&args.attributes)?;
+
+    if !metadata_attributes_info.try_data_is_empty()? {
+        return Err(MetadataError::MetadataAttributesAlreadyExist.into());
+    }
+
+    let metadata_attributes_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        metadata_info.key.as_ref(),
+        ATTRIBUTES.as_bytes(),
+    ]);
+    let metadata_attributes_bump_seed = &[assert_derivation(
+        program_id,
+        metadata_attributes_info,
+        &metadata_attributes_path,
+    )?];
+    let mut metadata_attributes_seeds = metadata_attributes_path.clone();
+    metadata_attributes_seeds.push(metadata_attributes_bump_seed);
+    create_or_allocate_account_raw(
+        *program_id,
+        metadata_attributes_info,
+        rent_info,
+        system_account_info,
+        payer_info,
+        METADATA_ATTRIBUTES_SIZE,
+        &metadata_attributes_seeds,
+    )?;
+
+    let metadata_attributes = MetadataAttributes {
+        key: Key::MetadataAttributes,
+        metadata: *metadata_info.key,
+        bump: metadata_attributes_bump_seed[0],
+        attributes: args.attributes,
+    };
+    write_metadata_attributes(&metadata_attributes, metadata_attributes_info)?;
+
+    emit(MetadataEvent::MetadataAttributesCreated {
+        metadata: *metadata_info.key,
+        metadata_attributes: *metadata_attributes_info.key,
+        attributes: metadata_attributes.attributes,
+    });
+    Ok(())
+}
+
+pub fn process_update_metadata_attributes(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: UpdateMetadataAttributesArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_attributes_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_metadata_attributes_authority(program_id, metadata_info, update_authority_info)?;
+    let mut metadata_attributes =
+        load_metadata_attributes(program_id, metadata_attributes_info, metadata_info)?;
+
+    for attribute in &args.attributes {
+        assert_attribute_valid(attribute)?;
+        metadata_attributes.set(attribute.clone());
+    }
+    if metadata_attributes.attributes.len() > MAX_ATTRIBUTES {
+        return Err(MetadataError::TooManyAttributes.into());
+    }
+
+    write_metadata_attributes(&metadata_attributes, metadata_attributes_info)?;
+
+    emit(MetadataEvent::MetadataAttributesUpdated {
+        metadata: *metadata_info.key,
+        metadata_attributes: *metadata_attributes_info.key,
+        attributes: args.attributes,
+    });
+    Ok(())
+}
+
+pub fn process_remove_metadata_attributes(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: RemoveMetadataAttributesArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_attributes_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_metadata_attributes_authority(program_id, metadata_info, update_authority_info)?;
+    let mut metadata_attributes =
+        load_metadata_attributes(program_id, metadata_attributes_info, metadata_info)?;
+
+    for key in &args.keys {
+        if !metadata_attributes.remove(key) {
+            return Err(MetadataError::AttributeNotFound.into());
+        }
+    }
+
+    write_metadata_attributes(&metadata_attributes, metadata_attributes_info)?;
+
+    emit(MetadataEvent::MetadataAttributesRemoved {
+        metadata: *metadata_info.key,
+        metadata_attributes: *metadata_attributes_info.key,
+        keys: args.keys,
+    });
+    Ok(())
+}
+
+pub fn process_burn_edition_nft(program_id: &Pubkey, accounts: &[AccountInfo]) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+
+    let metadata_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let print_edition_mint_info = next_account_info(account_info_iter)?;
+    let master_edition_mint_info = next_account_info(account_info_iter)?;
+    let print_edition_token_info = next_account_info(account_info_iter)?;
+    let master_edition_info = next_account_info(account_info_iter)?;
+    let print_edition_info = next_account_info(account_info_iter)?;
+    let edition_marker_info = next_account_info(account_info_iter)?;
+    let spl_token_program_info = next_account_info(account_info_iter)?;
+
+    let collection_nft_provided = accounts.len() == 10;
+
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+
+    // If the NFT is a verified part of a collection but the user has not provided the collection
+    // metadata account, we cannot burn it because we need to decrement the collection size.
+    if !collection_nft_provided
+        && metadata.collection.is_some()
+        && metadata.collection.as_ref().unwrap().verified
+    {
+        return Err(MetadataError::MissingCollectionMetadata.into());
+    }
+
+    // Checks:
+    // * Metadata is owned by the token-metadata program
+    // * Mint is owned by the spl-token or Token-2022 program
+    // * Token is owned by the same program as the mint
+    // * Token account is initialized
+    // * Token account data owner is 'owner'
+    // * Token account belongs to mint
+    // * Token account has 1 or more tokens
+    // * Mint matches metadata.mint
+    assert_currently_holding(
+        program_id,
+        owner_info,
+        metadata_info,
+        &metadata,
+        print_edition_mint_info,
+        print_edition_token_info,
+    )?;
+
+    // Owner is a signer.
+    assert_signer(owner_info)?;
+
+    // The mint's own token program receives the burn and close.
+    assert_token_program_matches_package(spl_token_program_info)?;
+    assert_owned_by(print_edition_mint_info, spl_token_program_info.key)?;
+
+    assert_owned_by_token_program(master_edition_mint_info)?;
+    assert_owned_by(print_edition_info, program_id)?;
+
+    let print_edition_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        print_edition_mint_info.key.as_ref(),
+        EDITION.as_bytes(),
+    ]);
+    let print_edition_bump_seed = &[assert_derivation(
+        program_id,
+        print_edition_info,
+        &print_edition_path,
+    )?];
+
+    let edition_key = print_edition_info.try_borrow_data()?[0];
+    let is_master_edition =
+        edition_key == Key::MasterEditionV1 as u8 || edition_key == Key::MasterEditionV2 as u8;
+    if !is_master_edition && edition_key != Key::EditionV1 as u8 {
+        return Err(MetadataError::NotAPrintEdition.into());
+    }
+
+    // A Master Edition is passed as both the master and the burned edition, and its
+    // outstanding prints stay burnable on their own afterwards.
+    if is_master_edition
+        && (master_edition_info.key != print_edition_info.key
+            || master_edition_mint_info.key != print_edition_mint_info.key)
+    {
+        return Err(MetadataError::PrintEditionDoesNotMatchMasterEdition.into());
+    }
+
+    // Prints of an already burned Master Edition have no supply left to decrement. A Master
+    // Edition burned earlier in the same transaction is drained and zeroed but not yet removed.
+    let master_edition_key = master_edition_info
+        .try_borrow_data()?
+        .first()
+        .copied()
+        .unwrap_or(Key::Uninitialized as u8);
+    let print_of_burned_master = !is_master_edition
+        && (master_edition_info.lamports() == 0 || master_edition_key == Key::Uninitialized as u8);
+
+    let mut print_edition_number = None;
+    if !is_master_edition {
+        let print_edition: Edition = Edition::from_account_info(print_edition_info)?;
+        if print_edition.parent != *master_edition_info.key {
+            return Err(MetadataError::PrintEditionDoesNotMatchMasterEdition.into());
+        }
+
+        assert_derivation(
+            program_id,
+            master_edition_info,
+            &[
+                PREFIX.as_bytes(),
+                program_id.as_ref(),
+                master_edition_mint_info.key.as_ref(),
+                EDITION.as_bytes(),
+            ],
+        )?;
+        print_edition_number = Some(print_edition.edition);
+    }
+
+    // Tokens bound to a rule set are held frozen by the edition, so thaw before burning.
+    if metadata.rule_set.is_some()
+        && assert_initialized::<Account>(print_edition_token_info)?.is_frozen()
+    {
+        let mut print_edition_seeds = print_edition_path.clone();
+        print_edition_seeds.push(print_edition_bump_seed);
+        thaw(
+            print_edition_mint_info.clone(),
+            print_edition_token_info.clone(),
+            print_edition_info.clone(),
+            spl_token_program_info.clone(),
+            &print_edition_seeds,
+        )?;
+    }
+
+    // Burn the SPL token
+    let params = TokenBurnParams {
+        mint: print_edition_mint_info.clone(),
+        source: print_edition_token_info.clone(),
+        authority: owner_info.clone(),
+        token_program: spl_token_program_info.clone(),
+        amount: 1,
+        authority_signer_seeds: None,
+    };
+    spl_token_burn(params)?;
+
+    // Close token account.
+    let params = TokenCloseParams {
+        token_program: spl_token_program_info.clone(),
+        account: print_edition_token_info.clone(),
+        destination: owner_info.clone(),
+        owner: owner_info.clone(),
+        authority_signer_seeds: None,
+    };
+    spl_token_close(params)?;
+
+    // Close the metadata, the edition and, once it no longer tracks any edition, the edition
+    // marker by transferring rent funds to owner and zeroing out the data.
+    let mut closed_accounts = vec![metadata_info, print_edition_info];
+
+    if let Some(edition) = print_edition_number {
+        if !print_of_burned_master {
+            assert_owned_by(master_edition_info, program_id)?;
+            let mut master_edition = get_master_edition(master_edition_info)?;
+
+            // Free up the edition number and account for the burned print in the supply.
+            let supply = master_edition
+                .supply()
+                .checked_sub(1)
+                .ok_or(MetadataError::NumericalOverflowError)?;
+            master_edition.set_supply(supply);
+            master_edition.save(master_edition_info)?;
+        }
+
+        // Prints of a Master Edition V1 may have come from a reservation list, which never
+        // marks the edition, and the version of a burned Master Edition is unknown.
+        let edition_must_be_marked =
+            !print_of_burned_master && master_edition_key == Key::MasterEditionV2 as u8;
+
+        let edition_marker_number = edition
+            .checked_div(EDITION_MARKER_BIT_SIZE)
+            .ok_or(MetadataError::NumericalOverflowError)?
+            .to_string();
+        assert_derivation(
+            program_id,
+            edition_marker_info,
+            &[
+                PREFIX.as_bytes(),
+                program_id.as_ref(),
+                master_edition_mint_info.key.as_ref(),
+                EDITION.as_bytes(),
+                edition_marker_number.as_bytes(),
+            ],
+        )?;
+
+        if edition_must_be_marked || !edition_marker_info.data_is_empty() {
+            assert_owned_by(edition_marker_info, program_id)?;
+            let mut edition_marker: EditionMarker =
+                EditionMarker::from_account_info(edition_marker_info)?;
+
+            if edition_marker.edition_taken(edition)? {
+                edition_marker.remove_edition(edition)?;
+
+                if edition_marker.is_empty() {
+                    closed_accounts.push(edition_marker_info);
+                } else {
+                    edition_marker.serialize(&mut *edition_marker_info.try_borrow_mut_data()?)?;
+                }
+            } else if edition_must_be_marked {
+                return Err(MetadataError::EditionNotMarked.into());
+            }
+        }
+    }
+
+    for account_info in closed_accounts {
+        let lamports = account_info.lamports();
+        **account_info.try_borrow_mut_lamports()? = 0;
+        **owner_info.try_borrow_mut_lamports()? = owner_info
+            .lamports()
+            .checked_add(lamports)
+            .ok_or(MetadataError::NumericalOverflowError)?;
+
+        let data = &mut account_info.try_borrow_mut_data()?;
+        let data_len = data.len();
+        sol_memset(data, 0, data_len);
+    }
+
+    if collection_nft_provided {
+        let collection_metadata_info = next_account_info(account_info_iter)?;
+        assert_owned_by(collection_metadata_info, program_id)?;
+        let mut collection_metadata = Metadata::from_account_info(collection_metadata_info)?;
+
+        // NFT is actually a verified member of the specified collection.
+        assert_verified_member_of_collection(&metadata, &collection_metadata)?;
+
+        if collection_metadata.collection_details.is_some() {
+            decrement_collection_size(&mut collection_metadata, collection_metadata_info)?;
+        }
+    }
+
+    emit(MetadataEvent::Burned {
+        metadata: *metadata_info.key,
+        mint: *print_edition_mint_info.key,
+        owner: *owner_info.key,
+        master_edition: (!is_master_edition).then_some(*master_edition_info.key),
+    });
+    Ok(())
+}
+
+pub fn process_close_expired_use_authority_record(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let use_authority_record_info = next_account_info(account_info_iter)?;
+    let owner_info = next_account_info(account_info_iter)?;
+    let user_info = next_account_info(account_info_iter)?;
+    let token_account_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(use_authority_record_info, program_id)?;
+    assert_owned_by_token_program(token_account_info)?;
+
+    // The rent goes back to whoever currently holds the NFT.
+    let token_account: Account = assert_initialized(token_account_info)?;
+    if token_account.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    if token_account.owner != *owner_info.key {
+        return Err(MetadataError::InvalidOwner.into());
+    }
+    if token_account.amount < 1 {
+        return Err(MetadataError::NotEnoughTokens.into());
+    }
+
+    let data = &mut use_authority_record_info.try_borrow_mut_data()?;
+    process_use_authority_validation(data.len(), false)?;
+    let canonical_bump = assert_use_authority_derivation(
+        program_id,
+        use_authority_record_info,
+        user_info,
+        mint_info,
+    )?;
+    let mut record = UseAuthorityRecord::from_bytes(data)?;
+    if record.bump_empty() {
+        record.bump = canonical_bump;
+    }
+    assert_valid_bump(canonical_bump, &record)?;
+
+    if !record.is_expired(Clock::get()?.unix_timestamp) {
+        return Err(MetadataError::UseAuthorityRecordNotExpired.into());
+    }
+
+    let lamports = use_authority_record_info.lamports();
+    **use_authority_record_info.try_borrow_mut_lamports()? = 0;
+    **owner_info.try_borrow_mut_lamports()? = owner_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+    let data_len = data.len();
+    sol_memset(data, 0, data_len);
+
+    emit(MetadataEvent::AuthorityRevoked {
+        authority_type: AuthorityRecordType::Use,
+        metadata: find_metadata_account(mint_info.key).0,
+        authority: *user_info.key,
+        record: *use_authority_record_info.key,
+    });
+    Ok(())
+}
+
+pub fn process_refill_uses(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let refill_authority_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    let mut metadata = Metadata::from_account_info(metadata_info)?;
+
+    if accounts.len() == 3 {
+        let update_delegate_record_info = next_account_info(account_info_iter)?;
+        assert_signer(refill_authority_info)?;
+        let record = assert_update_delegate_record(
+            refill_authority_info,
+            &metadata,
+            update_delegate_record_info,
+        )?;
+        if !record.scopes.refill_uses {
+            return Err(MetadataError::UpdateDelegateScopeMissing.into());
+        }
+    } else {
+        assert_update_authority_is_correct(&metadata, refill_authority_info)?;
+    }
+
+    // Refills only restore uses up to the total fixed at creation, so they are allowed on
+    // immutable metadata as well.
+    let uses = metadata.uses.as_mut().ok_or(MetadataError::Unusable)?;
+    let remaining_before = uses.remaining;
+    let refilled = amount.min(uses.total.saturating_sub(uses.remaining));
+    uses.remaining += refilled;
+    let remaining_after = uses.remaining;
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::UsesRefilled {
+        metadata: *metadata_info.key,
+        authority: *refill_authority_info.key,
+        refilled,
+        remaining_before,
+        remaining_after,
+    });
+    Ok(())
+}
+
+pub fn process_create_fungible_details(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    supply_cap: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let mint_authority_info = next_account_info(account_info_iter)?;
+    let fungible_mint_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    assert_token_program_matches_package(token_program_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    if !matches!(
+        check_token_standard(mint_info, None)?,
+        TokenStandard::Fungible | TokenStandard::FungibleAsset
+    ) || matches!(
+        metadata.token_standard,
+        Some(TokenStandard::NonFungible) | Some(TokenStandard::NonFungibleEdition)
+    ) {
+        return Err(MetadataError::NotAFungible.into());
+    }
+    if metadata.fungible_details.is_some() {
+        return Err(MetadataError::FungibleDetailsAlreadySet.into());
+    }
+
+    let mint: Mint = assert_initialized(mint_info)?;
+    assert_mint_authority_matches_mint(&mint.mint_authority, mint_authority_info)?;
+    if mint.supply > supply_cap {
+        return Err(MetadataError::FungibleSupplyCapExceeded.into());
+    }
+
+    assert_derivation(
+        program_id,
+        fungible_mint_authority_info,
+        &[
+            PREFIX.as_bytes(),
+            program_id.as_ref(),
+            mint_info.key.as_ref(),
+            MINT_AUTHORITY.as_bytes(),
+        ],
+    )?;
+    invoke(
+        &set_authority(
+            token_program_info.key,
+            mint_info.key,
+            Some(fungible_mint_authority_info.key),
+            AuthorityType::MintTokens,
+            mint_authority_info.key,
+            &[],
+        )?,
+        &[
+            mint_info.clone(),
+            mint_authority_info.clone(),
+            token_program_info.clone(),
+        ],
+    )?;
+
+    metadata.fungible_details = Some(FungibleDetails::V1 { supply_cap });
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::FungibleDetailsChanged {
+        metadata: *metadata_info.key,
+        before: None,
+        after: metadata.fungible_details,
+    });
+    Ok(())
+}
+
+pub fn process_mint_fungible_via_metadata(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    amount: u64,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let destination_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let fungible_mint_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    assert_token_program_matches_package(token_program_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    let supply_cap = match metadata.fungible_details {
+        Some(FungibleDetails::V1 { supply_cap }) => supply_cap,
+        None => return Err(MetadataError::MissingFungibleDetails.into()),
+    };
+    let new_supply = get_mint_supply(mint_info)?
+        .checked_add(amount)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+    if new_supply > supply_cap {
+        return Err(MetadataError::FungibleSupplyCapExceeded.into());
+    }
+
+    let mut mint_authority_seeds = vec![
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        mint_info.key.as_ref(),
+        MINT_AUTHORITY.as_bytes(),
+    ];
+    let bump = &[assert_derivation(
+        program_id,
+        fungible_mint_authority_info,
+        &mint_authority_seeds,
+    )?];
+    mint_authority_seeds.push(bump);
+
+    spl_token_mint_to(TokenMintToParams {
+        mint: mint_info.clone(),
+        destination: destination_info.clone(),
+        amount,
+        authority: fungible_mint_authority_info.clone(),
+        authority_signer_seeds: Some(&mint_authority_seeds),
+        token_program: token_program_info.clone(),
+    })?;
+
+    emit(MetadataEvent::FungibleMinted {
+        metadata: *metadata_info.key,
+        mint: *mint_info.key,
+        destination: *destination_info.key,
+        amount,
+        supply_after: new_supply,
+    });
+    Ok(())
+}
+
+pub fn process_update_fungible_details(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    supply_cap: Option<u64>,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_info = next_account_info(account_info_iter)?;
+    let mint_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let fungible_mint_authority_info = next_account_info(account_info_iter)?;
+    let new_mint_authority_info = next_account_info(account_info_iter)?;
+    let token_program_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    assert_token_program_matches_package(token_program_info)?;
+    assert_owned_by(mint_info, token_program_info.key)?;
+
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    if metadata.mint != *mint_info.key {
+        return Err(MetadataError::MintMismatch.into());
+    }
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    let before = metadata.fungible_details.clone();
+    let current_cap = match before {
+        Some(FungibleDetails::V1 { supply_cap }) => supply_cap,
+        None => return Err(MetadataError::MissingFungibleDetails.into()),
+    };
+
+    match supply_cap {
+        Some(supply_cap) => {
+            if supply_cap < current_cap {
+                return Err(MetadataError::FungibleSupplyCapCannotDecrease.into());
+            }
+            metadata.fungible_details = Some(FungibleDetails::V1 { supply_cap });
+        }
+        None => {
+            let mut mint_authority_seeds = vec![
+                PREFIX.as_bytes(),
+                program_id.as_ref(),
+                mint_info.key.as_ref(),
+                MINT_AUTHORITY.as_bytes(),
+            ];
+            let bump = &[assert_derivation(
+                program_id,
+                fungible_mint_authority_info,
+                &mint_authority_seeds,
+            )?];
+            mint_authority_seeds.push(bump);
+
+            invoke_signed(
+                &set_authority(
+                    token_program_info.key,
+                    mint_info.key,
+                    Some(new_mint_authority_info.key),
+                    AuthorityType::MintTokens,
+                    fungible_mint_authority_info.key,
+                    &[],
+                )?,
+                &[
+                    mint_info.clone(),
+                    fungible_mint_authority_info.clone(),
+                    token_program_info.clone(),
+                ],
+                &[&mint_authority_seeds],
+            )?;
+            metadata.fungible_details = None;
+        }
+    }
+
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::FungibleDetailsChanged {
+        metadata: *metadata_info.key,
+        before,
+        after: metadata.fungible_details,
+    });
+    Ok(())
+}
+
+fn load_creator_change_proposal(
+    program_id: &Pubkey,
+    creator_change_proposal_info: &AccountInfo,
+    metadata_info: &AccountInfo,
+) -> Result<CreatorChangeProposal, ProgramError> {
+    assert_owned_by(creator_change_proposal_info, program_id)?;
+    let proposal: CreatorChangeProposal =
+        CreatorChangeProposal::from_account_info(creator_change_proposal_info)?;
+    if proposal.metadata != *metadata_info.key {
+        return Err(MetadataError::DerivedKeyInvalid.into());
+    }
+    Ok(proposal)
+}
+
+fn close_creator_change_proposal(
+    creator_change_proposal_info: &AccountInfo,
+    destination_info: &AccountInfo,
+) -> ProgramResult {
+    let lamports = creator_change_proposal_info.lamports();
+    **creator_change_proposal_info.try_borrow_mut_lamports()? = 0;
+    **destination_info.try_borrow_mut_lamports()? = destination_info
+        .lamports()
+        .checked_add(lamports)
+        .ok_or(MetadataError::NumericalOverflowError)?;
+    let mut data = creator_change_proposal_info.try_borrow_mut_data()?;
+    let data_len = data.len();
+    sol_memset(&mut data, 0, data_len);
+    Ok(())
+}
+
+pub fn process_propose_creator_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+    args: ProposeCreatorChangeArgs,
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let creator_change_proposal_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(payer_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    if !metadata.is_mutable {
+        return Err(MetadataError::DataIsImmutable.into());
+    }
+
+    // Without verified creators there is nobody to ask, the creators can be updated directly.
+    let has_verified_creators = metadata
+        .data
+        .creators
+        .iter()
+        .flatten()
+        .any(|creator| creator.verified);
+    if !has_verified_creators {
+        return Err(MetadataError::CreatorChangeApprovalNotRequired.into());
+    }
+
+    // Verification is only carried over when the change is applied.
+    let creators: Vec<Creator> = args
+        .creators
+        .into_iter()
+        .map(|creator| Creator {
+            verified: false,
+            ..creator
+        })
+        .collect();
+    let mut data = metadata.data.clone();
+    data.creators = Some(creators.clone());
+    assert_data_valid(
+        &data,
+        update_authority_info.key,
+        &metadata,
+        true,
+        update_authority_info.is_signer,
+    )?;
+
+    if !creator_change_proposal_info.try_data_is_empty()? {
+        return Err(MetadataError::CreatorChangeProposalAlreadyExists.into());
+    }
+
+    let creator_change_proposal_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        metadata_info.key.as_ref(),
+        CREATOR_CHANGE.as_bytes(),
+    ]);
+    let creator_change_proposal_bump_seed = &[assert_derivation(
+        program_id,
+        creator_change_proposal_info,
+        &creator_change_proposal_path,
+    )?];
+    let mut creator_change_proposal_seeds = creator_change_proposal_path.clone();
+    creator_change_proposal_seeds.push(creator_change_proposal_bump_seed);
+    create_or_allocate_account_raw(
+        *program_id,
+        creator_change_proposal_info,
+        rent_info,
+        system_account_info,
+        payer_info,
+        CREATOR_CHANGE_PROPOSAL_SIZE,
+        &creator_change_proposal_seeds,
+    )?;
+
+    let proposal = CreatorChangeProposal {
+        key: Key::CreatorChangeProposal,
+        metadata: *metadata_info.key,
+        bump: creator_change_proposal_bump_seed[0],
+        update_authority: *update_authority_info.key,
+        creators,
+        approvals: vec![],
+    };
+    proposal.serialize(&mut *creator_change_proposal_info.try_borrow_mut_data()?)?;
+
+    emit(MetadataEvent::CreatorChangeProposed {
+        metadata: *metadata_info.key,
+        proposal: *creator_change_proposal_info.key,
+        update_authority: proposal.update_authority,
+        creators: proposal.creators,
+    });
+    Ok(())
+}
+
+pub fn process_approve_creator_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let creator_change_proposal_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let creator_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_signer(creator_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    let mut proposal =
+        load_creator_change_proposal(program_id, creator_change_proposal_info, metadata_info)?;
+
+    // A proposal made by a previous update authority can no longer be approved.
+    if proposal.update_authority != metadata.update_authority
+        || proposal.update_authority != *update_authority_info.key
+    {
+        return Err(MetadataError::UpdateAuthorityIncorrect.into());
+    }
+    if !metadata.is_mutable {
+        return Err(MetadataError::DataIsImmutable.into());
+    }
+
+    let is_verified_creator = metadata
+        .data
+        .creators
+        .iter()
+        .flatten()
+        .any(|creator| creator.verified && creator.address == *creator_info.key);
+    if !is_verified_creator {
+        return Err(MetadataError::CreatorChangeApproverNotVerified.into());
+    }
+    if proposal.approvals.contains(creator_info.key) {
+        return Err(MetadataError::CreatorChangeAlreadyApproved.into());
+    }
+    proposal.approvals.push(*creator_info.key);
+
+    if !proposal.is_approved_by(&metadata) {
+        proposal.serialize(&mut *creator_change_proposal_info.try_borrow_mut_data()?)?;
+        emit(MetadataEvent::CreatorChangeApproved {
+            metadata: *metadata_info.key,
+            creator: *creator_info.key,
+            applied_creators: None,
+        });
+        return Ok(());
+    }
+
+    let verified: Vec<Pubkey> = metadata
+        .data
+        .creators
+        .iter()
+        .flatten()
+        .filter(|creator| creator.verified)
+        .map(|creator| creator.address)
+        .collect();
+    let creators: Vec<Creator> = proposal
+        .creators
+        .into_iter()
+        .map(|creator| Creator {
+            verified: verified.contains(&creator.address),
+            ..creator
+        })
+        .collect();
+    metadata.data.creators = Some(creators.clone());
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::CreatorChangeApproved {
+        metadata: *metadata_info.key,
+        creator: *creator_info.key,
+        applied_creators: Some(creators),
+    });
+
+    close_creator_change_proposal(creator_change_proposal_info, update_authority_info)
+}
+
+pub fn process_cancel_creator_change(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let creator_change_proposal_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+
+    assert_owned_by(metadata_info, program_id)?;
+    let metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+    load_creator_change_proposal(program_id, creator_change_proposal_info, metadata_info)?;
+
+    close_creator_change_proposal(creator_change_proposal_info, update_authority_info)?;
+
+    emit(MetadataEvent::CreatorChangeCancelled {
+        metadata: *metadata_info.key,
+        proposal: *creator_change_proposal_info.key,
+    });
+    Ok(())
+}
+
+pub fn process_create_metadata_history(
+    program_id: &Pubkey,
+    accounts: &[AccountInfo],
+) -> ProgramResult {
+    let account_info_iter = &mut accounts.iter();
+    let metadata_history_info = next_account_info(account_info_iter)?;
+    let metadata_info = next_account_info(account_info_iter)?;
+    let update_authority_info = next_account_info(account_info_iter)?;
+    let payer_info = next_account_info(account_info_iter)?;
+    let system_account_info = next_account_info(account_info_iter)?;
+    let rent_info = next_account_info(account_info_iter)?;
+
+    assert_signer(payer_info)?;
+    assert_owned_by(metadata_info, program_id)?;
+    let mut metadata: Metadata = Metadata::from_account_info(metadata_info)?;
+    assert_update_authority_is_correct(&metadata, update_authority_info)?;
+
+    if metadata.history_enabled {
+        return Err(MetadataError::MetadataHistoryAlreadyEnabled.into());
+    }
+
+    let metadata_history_path = Vec::from([
+        PREFIX.as_bytes(),
+        program_id.as_ref(),
+        metadata_info.key.as_ref(),
+        HISTORY.as_bytes(),
+    ]);
+    let metadata_history_bump_seed = &[assert_derivation(
+        program_id,
+        metadata_history_info,
+        &metadata_history_path,
+    )?];
+    let mut metadata_history_seeds = metadata_history_path.clone();
+    metadata_history_seeds.push(metadata_history_bump_seed);
+    create_or_allocate_account_raw(
+        *program_id,
+        metadata_history_info,
+        rent_info,
+        system_account_info,
+        payer_info,
+        METADATA_HISTORY_SIZE,
+        &metadata_history_seeds,
+    )?;
+
+    // The first entry anchors the Data the history starts from.
+    let mut metadata_history = MetadataHistory {
+        key: Key::MetadataHistory,
+        metadata: *metadata_info.key,
+        bump: metadata_history_bump_seed[0],
+        total_entries: 0,
+        entries: vec![],
+    };
+    metadata_history.record(MetadataHistoryEntry::new(
+        &metadata,
+        &metadata,
+        Clock::get()?.slot,
+        *update_authority_info.key,
+    ));
+    metadata_history.serialize(&mut *metadata_history_info.try_borrow_mut_data()?)?;
+
+    metadata.history_enabled = true;
+    clean_write_metadata(&mut metadata, metadata_info)?;
+
+    emit(MetadataEvent::MetadataHistoryCreated {
+        metadata: *metadata_info.key,
+        metadata_history: *metadata_history_info.key,
+    });
+    Ok(())
+}
+
+pub fn process_record_metadata_history<'a>(
+    program_id: &'a Pubkey,
+    accounts: &'a [AccountInfo<'a>],
+    instruction: &[u8],
+) -> ProgramResult {
+    let instruction = MetadataInstruction::try_from_slice(instruction)?;
+    // Only instructions that change metadata can be recorded, which also rules out nesting
+    // RecordMetadataHistory.
+    let signer_index =
+        metadata_history_signer(&instruction).ok_or(MetadataError::MetadataHistoryNotSupported)?;
+
+    let (metadata_history_info, accounts) = accounts
+        .split_first()
+        .ok_or(ProgramError::NotEnoughAccountKeys)?;
+
+    process_recording_histories(
+        program_id,
+        accounts,
+        slice::from_ref(metadata_history_info),
+        instruction,
+        signer_index,
+    )
+}
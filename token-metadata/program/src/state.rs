@@ -1,7 +1,7 @@
 use std::io::ErrorKind;
 
 use crate::{
-    deser::meta_deser_unchecked,
+    deser::{meta_deser_strict, meta_deser_unchecked},
     error::MetadataError,
     utils::{assert_owned_by, is_correct_account_type, try_from_slice_checked},
     ID,
@@ -9,7 +9,7 @@ use crate::{
 use borsh::{maybestd::io::Error as BorshError, BorshDeserialize, BorshSerialize};
 use shank::ShankAccount;
 use solana_program::{
-    account_info::AccountInfo, entrypoint::ProgramResult, program_error::ProgramError,
+    account_info::AccountInfo, entrypoint::ProgramResult, hash::hash, program_error::ProgramError,
     pubkey::Pubkey,
 };
 
@@ -33,6 +33,18 @@ pub const BURN: &str = "burn";
 
 pub const COLLECTION_AUTHORITY: &str = "collection_authority";
 
+pub const RULE_SET: &str = "rule_set";
+
+pub const UPDATE_DELEGATE: &str = "update_delegate";
+
+pub const ATTRIBUTES: &str = "attributes";
+
+pub const MINT_AUTHORITY: &str = "mint_authority";
+
+pub const CREATOR_CHANGE: &str = "creator_change";
+
+pub const HISTORY: &str = "history";
+
 pub const MAX_NAME_LENGTH: usize = 32;
 
 pub const MAX_SYMBOL_LENGTH: usize = 10;
@@ -51,6 +63,14 @@ pub const MAX_METADATA_LEN: usize = 1 //key
 + 18 // uses
 + 118; // Padding
 
+/// The extension area of a `MetadataV2` account always starts right after the
+/// `MAX_METADATA_LEN` bytes reserved for the metadata fields.
+pub const METADATA_EXTENSIONS_OFFSET: usize = MAX_METADATA_LEN;
+
+/// Size of a `MetadataV2` account with an empty extension area. The account grows with its
+/// extensions.
+pub const MIN_METADATA_V2_LEN: usize = METADATA_EXTENSIONS_OFFSET + 4; // extensions length
+
 pub const MAX_DATA_SIZE: usize = 4
     + MAX_NAME_LENGTH
     + 4
@@ -87,8 +107,86 @@ pub const EDITION_MARKER_BIT_SIZE: u64 = 248;
 
 pub const USE_AUTHORITY_RECORD_SIZE: usize = 18; //8 byte padding
 
+pub const TIMED_USE_AUTHORITY_RECORD_SIZE: usize = 1 // key
++ 8 // allowed uses
++ 1 // bump
++ 9 // expires at
++ 9 // not before
++ 8; // Padding
+
 pub const COLLECTION_AUTHORITY_RECORD_SIZE: usize = 11; //10 byte padding
 
+pub const UPDATE_DELEGATE_RECORD_SIZE: usize = 1 // key
++ 1 // bump
++ 32 // update authority
++ 6 // scopes
++ 24; // Padding
+
+pub const MAX_ATTRIBUTES: usize = 16;
+
+pub const MAX_ATTRIBUTE_KEY_LENGTH: usize = 32;
+
+pub const MAX_ATTRIBUTE_VALUE_LENGTH: usize = 64;
+
+pub const METADATA_ATTRIBUTES_SIZE: usize = 1 // key
++ 32 // metadata
++ 1 // bump
++ 4 + MAX_ATTRIBUTES * (4 + MAX_ATTRIBUTE_KEY_LENGTH + 4 + MAX_ATTRIBUTE_VALUE_LENGTH) // attributes
++ 64; // Padding
+
+pub const CREATOR_CHANGE_PROPOSAL_SIZE: usize = 1 // key
++ 32 // metadata
++ 1 // bump
++ 32 // update authority
++ 4 + MAX_CREATOR_LIMIT * MAX_CREATOR_LEN // creators
++ 4 + MAX_CREATOR_LIMIT * 32 // approvals
++ 32; // Padding
+
+pub const MAX_METADATA_HISTORY_ENTRIES: usize = 16;
+
+pub const METADATA_HISTORY_ENTRY_SIZE: usize = 32 // prior data hash
++ 8 // slot
++ 32 // signer
++ 2; // changed fields
+
+pub const METADATA_HISTORY_SIZE: usize = 1 // key
++ 32 // metadata
++ 1 // bump
++ 8 // total entries
++ 4 + MAX_METADATA_HISTORY_ENTRIES * METADATA_HISTORY_ENTRY_SIZE // entries
++ 32; // Padding
+
+/// Bits of `MetadataHistoryEntry::changed_fields`.
+pub const HISTORY_NAME_CHANGED: u16 = 1 << 0;
+pub const HISTORY_SYMBOL_CHANGED: u16 = 1 << 1;
+pub const HISTORY_URI_CHANGED: u16 = 1 << 2;
+pub const HISTORY_SELLER_FEE_CHANGED: u16 = 1 << 3;
+pub const HISTORY_CREATORS_CHANGED: u16 = 1 << 4;
+pub const HISTORY_CREATOR_VERIFICATION_CHANGED: u16 = 1 << 5;
+pub const HISTORY_COLLECTION_CHANGED: u16 = 1 << 6;
+pub const HISTORY_UPDATE_AUTHORITY_CHANGED: u16 = 1 << 7;
+pub const HISTORY_PRIMARY_SALE_CHANGED: u16 = 1 << 8;
+pub const HISTORY_IS_MUTABLE_CHANGED: u16 = 1 << 9;
+pub const HISTORY_USES_CHANGED: u16 = 1 << 10;
+pub const HISTORY_COLLECTION_DETAILS_CHANGED: u16 = 1 << 11;
+pub const HISTORY_TOKEN_STANDARD_CHANGED: u16 = 1 << 12;
+pub const HISTORY_RULE_SET_CHANGED: u16 = 1 << 13;
+pub const HISTORY_FUNGIBLE_DETAILS_CHANGED: u16 = 1 << 14;
+
+pub const MAX_RULE_SET_PROGRAMS: usize = 8;
+
+pub const MAX_RULE_SET_ESCROW_AUTHORITIES: usize = 8;
+
+pub const RULE_SET_SIZE: usize = 1 // key
++ 32 // authority
++ 4 + MAX_RULE_SET_PROGRAMS * 32 // allowed programs
++ 4 + MAX_RULE_SET_ESCROW_AUTHORITIES * 32 // escrow authorities
++ 1 // bump
++ 64; // Padding
+
+/// Maximum number of collections above a collection in a collection hierarchy.
+pub const MAX_COLLECTION_DEPTH: usize = 4;
+
 pub trait TokenMetadataAccount {
     fn key() -> Key;
 
@@ -138,6 +236,12 @@ pub enum Key {
     EditionMarker,
     UseAuthorityRecord,
     CollectionAuthorityRecord,
+    RuleSet,
+    MetadataV2,
+    UpdateDelegateRecord,
+    MetadataAttributes,
+    CreatorChangeProposal,
+    MetadataHistory,
 }
 #[repr(C)]
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
@@ -204,6 +308,14 @@ pub enum CollectionDetails {
     V1 { size: u64 },
 }
 
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub enum FungibleDetails {
+    /// Hard cap on the mint supply, enforced by `MintFungibleViaMetadata`
+    V1 { supply_cap: u64 },
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
@@ -231,6 +343,9 @@ pub struct UseAuthorityRecord {
     pub key: Key,          //1
     pub allowed_uses: u64, //8
     pub bump: u8,
+    // Records without a time window keep the original size, where these read as None.
+    pub expires_at: Option<i64>, //9
+    pub not_before: Option<i64>, //9
 }
 
 impl Default for UseAuthorityRecord {
@@ -239,6 +354,8 @@ impl Default for UseAuthorityRecord {
             key: Key::UseAuthorityRecord,
             allowed_uses: 0,
             bump: 255,
+            expires_at: None,
+            not_before: None,
         }
     }
 }
@@ -251,15 +368,44 @@ impl TokenMetadataAccount for UseAuthorityRecord {
     fn size() -> usize {
         USE_AUTHORITY_RECORD_SIZE
     }
+
+    fn safe_deserialize<T: BorshDeserialize>(mut data: &[u8]) -> Result<T, BorshError> {
+        if !is_correct_account_type(data, Key::UseAuthorityRecord, USE_AUTHORITY_RECORD_SIZE)
+            && !is_correct_account_type(
+                data,
+                Key::UseAuthorityRecord,
+                TIMED_USE_AUTHORITY_RECORD_SIZE,
+            )
+        {
+            return Err(BorshError::new(ErrorKind::Other, "DataTypeMismatch"));
+        }
+
+        let result: T = T::deserialize(&mut data)?;
+
+        Ok(result)
+    }
 }
 
 impl UseAuthorityRecord {
     pub fn from_bytes(b: &[u8]) -> Result<UseAuthorityRecord, ProgramError> {
-        let ua: UseAuthorityRecord =
-            try_from_slice_checked(b, Key::UseAuthorityRecord, USE_AUTHORITY_RECORD_SIZE)?;
+        let size = if b.len() == TIMED_USE_AUTHORITY_RECORD_SIZE {
+            TIMED_USE_AUTHORITY_RECORD_SIZE
+        } else {
+            USE_AUTHORITY_RECORD_SIZE
+        };
+        let ua: UseAuthorityRecord = try_from_slice_checked(b, Key::UseAuthorityRecord, size)?;
         Ok(ua)
     }
 
+    pub fn is_expired(&self, unix_timestamp: i64) -> bool {
+        matches!(self.expires_at, Some(expires_at) if unix_timestamp >= expires_at)
+    }
+
+    pub fn is_active(&self, unix_timestamp: i64) -> bool {
+        !self.is_expired(unix_timestamp)
+            && !matches!(self.not_before, Some(not_before) if unix_timestamp < not_before)
+    }
+
     pub fn bump_empty(&self) -> bool {
         self.bump == 0 && self.key == Key::UseAuthorityRecord
     }
@@ -303,11 +449,392 @@ impl CollectionAuthorityRecord {
     }
 }
 
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct Attribute {
+    pub key: String,
+    pub value: String,
+}
+
+/// On-chain key/value attributes of a metadata account, so programs can gate on traits
+/// without trusting the off-chain JSON behind `Data.uri`.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct MetadataAttributes {
+    pub key: Key,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub metadata: Pubkey,
+    pub bump: u8,
+    pub attributes: Vec<Attribute>,
+}
+
+impl Default for MetadataAttributes {
+    fn default() -> Self {
+        MetadataAttributes {
+            key: Key::MetadataAttributes,
+            metadata: Pubkey::default(),
+            bump: 255,
+            attributes: vec![],
+        }
+    }
+}
+
+impl TokenMetadataAccount for MetadataAttributes {
+    fn key() -> Key {
+        Key::MetadataAttributes
+    }
+
+    fn size() -> usize {
+        METADATA_ATTRIBUTES_SIZE
+    }
+}
+
+impl MetadataAttributes {
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.attributes
+            .iter()
+            .find(|attribute| attribute.key == key)
+            .map(|attribute| attribute.value.as_str())
+    }
+
+    /// Inserts the attribute, replacing the value of an existing attribute with the same key.
+    pub fn set(&mut self, attribute: Attribute) {
+        match self.attributes.iter_mut().find(|a| a.key == attribute.key) {
+            Some(existing) => existing.value = attribute.value,
+            None => self.attributes.push(attribute),
+        }
+    }
+
+    /// Removes the attribute with the given key, returning whether it existed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.attributes.len();
+        self.attributes.retain(|attribute| attribute.key != key);
+        self.attributes.len() != len
+    }
+}
+
+/// A pending replacement of a metadata's creators array. Every creator that is verified on the
+/// metadata must approve before the change is applied, after which their verification carries
+/// over to the new array.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct CreatorChangeProposal {
+    pub key: Key,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub metadata: Pubkey,
+    pub bump: u8,
+    /// Update authority that proposed the change. The proposal is void once it changes.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub update_authority: Pubkey,
+    pub creators: Vec<Creator>,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub approvals: Vec<Pubkey>,
+}
+
+impl Default for CreatorChangeProposal {
+    fn default() -> Self {
+        CreatorChangeProposal {
+            key: Key::CreatorChangeProposal,
+            metadata: Pubkey::default(),
+            bump: 255,
+            update_authority: Pubkey::default(),
+            creators: vec![],
+            approvals: vec![],
+        }
+    }
+}
+
+impl TokenMetadataAccount for CreatorChangeProposal {
+    fn key() -> Key {
+        Key::CreatorChangeProposal
+    }
+
+    fn size() -> usize {
+        CREATOR_CHANGE_PROPOSAL_SIZE
+    }
+}
+
+impl CreatorChangeProposal {
+    /// Whether every creator currently verified on the metadata has approved the change.
+    pub fn is_approved_by(&self, metadata: &Metadata) -> bool {
+        metadata
+            .data
+            .creators
+            .iter()
+            .flatten()
+            .filter(|creator| creator.verified)
+            .all(|creator| self.approvals.contains(&creator.address))
+    }
+}
+
+/// A single recorded change of a metadata account.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
+pub struct MetadataHistoryEntry {
+    /// SHA-256 of the borsh serialized `Data` before the change.
+    pub prior_data_hash: [u8; 32],
+    pub slot: u64,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub signer: Pubkey,
+    /// Bitmask of the `HISTORY_*_CHANGED` fields the change touched.
+    pub changed_fields: u16,
+}
+
+impl MetadataHistoryEntry {
+    pub fn new(prior: &Metadata, current: &Metadata, slot: u64, signer: Pubkey) -> Self {
+        MetadataHistoryEntry {
+            prior_data_hash: hash_data(&prior.data),
+            slot,
+            signer,
+            changed_fields: changed_fields(prior, current),
+        }
+    }
+}
+
+/// SHA-256 of the borsh serialized `Data`, as stored in `MetadataHistoryEntry::prior_data_hash`.
+pub fn hash_data(data: &Data) -> [u8; 32] {
+    // Serializing into a Vec cannot fail.
+    hash(&data.try_to_vec().unwrap()).to_bytes()
+}
+
+fn changed_fields(prior: &Metadata, current: &Metadata) -> u16 {
+    let creator_shares = |metadata: &Metadata| -> Vec<(Pubkey, u8)> {
+        metadata
+            .data
+            .creators
+            .iter()
+            .flatten()
+            .map(|creator| (creator.address, creator.share))
+            .collect()
+    };
+
+    let mut changed = 0;
+    let mut flag = |differs: bool, bit: u16| {
+        if differs {
+            changed |= bit;
+        }
+    };
+    flag(prior.data.name != current.data.name, HISTORY_NAME_CHANGED);
+    flag(
+        prior.data.symbol != current.data.symbol,
+        HISTORY_SYMBOL_CHANGED,
+    );
+    flag(prior.data.uri != current.data.uri, HISTORY_URI_CHANGED);
+    flag(
+        prior.data.seller_fee_basis_points != current.data.seller_fee_basis_points,
+        HISTORY_SELLER_FEE_CHANGED,
+    );
+    let creators_changed = creator_shares(prior) != creator_shares(current);
+    flag(creators_changed, HISTORY_CREATORS_CHANGED);
+    flag(
+        !creators_changed && prior.data.creators != current.data.creators,
+        HISTORY_CREATOR_VERIFICATION_CHANGED,
+    );
+    flag(
+        prior.collection != current.collection,
+        HISTORY_COLLECTION_CHANGED,
+    );
+    flag(
+        prior.update_authority != current.update_authority,
+        HISTORY_UPDATE_AUTHORITY_CHANGED,
+    );
+    flag(
+        prior.primary_sale_happened != current.primary_sale_happened,
+        HISTORY_PRIMARY_SALE_CHANGED,
+    );
+    flag(
+        prior.is_mutable != current.is_mutable,
+        HISTORY_IS_MUTABLE_CHANGED,
+    );
+    flag(prior.uses != current.uses, HISTORY_USES_CHANGED);
+    flag(
+        prior.collection_details != current.collection_details,
+        HISTORY_COLLECTION_DETAILS_CHANGED,
+    );
+    flag(
+        prior.token_standard != current.token_standard,
+        HISTORY_TOKEN_STANDARD_CHANGED,
+    );
+    flag(prior.rule_set != current.rule_set, HISTORY_RULE_SET_CHANGED);
+    flag(
+        prior.fungible_details != current.fungible_details,
+        HISTORY_FUNGIBLE_DETAILS_CHANGED,
+    );
+    changed
+}
+
+/// Append-only record of the last `MAX_METADATA_HISTORY_ENTRIES` changes of a metadata account,
+/// so provenance can be checked without replaying transactions. A change is recorded when the
+/// history is passed to the instruction making it.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct MetadataHistory {
+    pub key: Key,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub metadata: Pubkey,
+    pub bump: u8,
+    /// Number of entries ever recorded. The next entry goes to
+    /// `total_entries % MAX_METADATA_HISTORY_ENTRIES` once the buffer is full.
+    pub total_entries: u64,
+    pub entries: Vec<MetadataHistoryEntry>,
+}
+
+impl Default for MetadataHistory {
+    fn default() -> Self {
+        MetadataHistory {
+            key: Key::MetadataHistory,
+            metadata: Pubkey::default(),
+            bump: 255,
+            total_entries: 0,
+            entries: vec![],
+        }
+    }
+}
+
+impl TokenMetadataAccount for MetadataHistory {
+    fn key() -> Key {
+        Key::MetadataHistory
+    }
+
+    fn size() -> usize {
+        METADATA_HISTORY_SIZE
+    }
+}
+
+impl MetadataHistory {
+    /// Records the entry, overwriting the oldest one once the buffer is full.
+    pub fn record(&mut self, entry: MetadataHistoryEntry) {
+        if self.entries.len() < MAX_METADATA_HISTORY_ENTRIES {
+            self.entries.push(entry);
+        } else {
+            let index = (self.total_entries % MAX_METADATA_HISTORY_ENTRIES as u64) as usize;
+            self.entries[index] = entry;
+        }
+        self.total_entries = self.total_entries.saturating_add(1);
+    }
+
+    /// The recorded entries from oldest to newest.
+    pub fn ordered_entries(&self) -> Vec<&MetadataHistoryEntry> {
+        let start = if self.entries.len() < MAX_METADATA_HISTORY_ENTRIES {
+            0
+        } else {
+            (self.total_entries % MAX_METADATA_HISTORY_ENTRIES as u64) as usize
+        };
+        self.entries[start..]
+            .iter()
+            .chain(self.entries[..start].iter())
+            .collect()
+    }
+}
+
+/// Metadata fields an update delegate is allowed to change.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, Default, PartialEq, Debug, Clone, Copy)]
+pub struct UpdateDelegateScopes {
+    pub uri: bool,
+    pub name_symbol: bool,
+    pub creators: bool,
+    pub collection: bool,
+    pub uses: bool,
+    /// Allows calling `RefillUses`
+    pub refill_uses: bool,
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+pub struct UpdateDelegateRecord {
+    pub key: Key, //1
+    pub bump: u8, //1
+    /// Update authority that approved the delegate. The record is void once it changes.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub update_authority: Pubkey, //32
+    pub scopes: UpdateDelegateScopes, //6
+}
+
+impl Default for UpdateDelegateRecord {
+    fn default() -> Self {
+        UpdateDelegateRecord {
+            key: Key::UpdateDelegateRecord,
+            bump: 255,
+            update_authority: Pubkey::default(),
+            scopes: UpdateDelegateScopes::default(),
+        }
+    }
+}
+
+impl TokenMetadataAccount for UpdateDelegateRecord {
+    fn key() -> Key {
+        Key::UpdateDelegateRecord
+    }
+
+    fn size() -> usize {
+        UPDATE_DELEGATE_RECORD_SIZE
+    }
+}
+
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone, ShankAccount)]
+/// Opt-in royalty enforcement rules. Tokens bound to a rule set stay frozen and can only
+/// be moved through the `Transfer` instruction, which checks the caller against these lists.
+pub struct RuleSet {
+    pub key: Key,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
+    pub authority: Pubkey,
+    /// Programs allowed to CPI into `Transfer`
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub allowed_programs: Vec<Pubkey>,
+    /// Authorities allowed to hold or move bound tokens on behalf of their owners
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<Vec<DisplayFromStr>>"))]
+    pub escrow_authorities: Vec<Pubkey>,
+    pub bump: u8,
+}
+
+impl Default for RuleSet {
+    fn default() -> Self {
+        RuleSet {
+            key: Key::RuleSet,
+            authority: Pubkey::default(),
+            allowed_programs: vec![],
+            escrow_authorities: vec![],
+            bump: 255,
+        }
+    }
+}
+
+impl TokenMetadataAccount for RuleSet {
+    fn key() -> Key {
+        Key::RuleSet
+    }
+
+    fn size() -> usize {
+        RULE_SET_SIZE
+    }
+}
+
+impl RuleSet {
+    pub fn is_program_allowed(&self, program: &Pubkey) -> bool {
+        self.allowed_programs.contains(program)
+    }
+
+    pub fn is_escrow_authority(&self, authority: &Pubkey) -> bool {
+        self.escrow_authorities.contains(authority)
+    }
+}
+
 #[repr(C)]
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct Collection {
     pub verified: bool,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub key: Pubkey,
 }
 
@@ -316,7 +843,9 @@ pub struct Collection {
 #[derive(Clone, BorshSerialize, Debug, PartialEq, ShankAccount)]
 pub struct Metadata {
     pub key: Key,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub update_authority: Pubkey,
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub mint: Pubkey,
     pub data: Data,
     // Immutable, once flipped, all sales of this metadata are considered secondary.
@@ -333,6 +862,16 @@ pub struct Metadata {
     pub uses: Option<Uses>,
     /// Item Details
     pub collection_details: Option<CollectionDetails>,
+    /// Royalty enforcement rule set the token is bound to, if any
+    #[cfg_attr(
+        feature = "serde-feature",
+        serde(with = "As::<Option<DisplayFromStr>>")
+    )]
+    pub rule_set: Option<Pubkey>,
+    /// Fungible Details
+    pub fungible_details: Option<FungibleDetails>,
+    /// Whether changes must be recorded in the metadata's `MetadataHistory` account
+    pub history_enabled: bool,
 }
 
 impl Default for Metadata {
@@ -349,6 +888,9 @@ impl Default for Metadata {
             collection: None,
             uses: None,
             collection_details: None,
+            rule_set: None,
+            fungible_details: None,
+            history_enabled: false,
         }
     }
 }
@@ -361,6 +903,24 @@ impl TokenMetadataAccount for Metadata {
     fn size() -> usize {
         MAX_METADATA_LEN
     }
+
+    // Migrated accounts share the metadata fields with the legacy layout, so `Metadata`
+    // can be read from either.
+    fn safe_deserialize<T: BorshDeserialize>(mut data: &[u8]) -> Result<T, BorshError> {
+        if !is_correct_account_type(data, Key::MetadataV1, MAX_METADATA_LEN)
+            && !is_metadata_v2_account(data)
+        {
+            return Err(BorshError::new(ErrorKind::Other, "DataTypeMismatch"));
+        }
+
+        let result: T = T::deserialize(&mut data)?;
+
+        Ok(result)
+    }
+}
+
+fn is_metadata_v2_account(data: &[u8]) -> bool {
+    data.len() >= MIN_METADATA_V2_LEN && data[0] == Key::MetadataV2 as u8
 }
 
 // We have a custom implementation of BorshDeserialize for Metadata because of corrupted metadata issues
@@ -373,6 +933,92 @@ impl borsh::de::BorshDeserialize for Metadata {
     }
 }
 
+/// Versioned metadata layout produced by `MigrateMetadata`.
+///
+/// The metadata fields are serialized into the first `MAX_METADATA_LEN` bytes exactly as in the
+/// legacy layout, only with the `MetadataV2` key, and must be followed by zeros. A `u32`
+/// length-prefixed extension area starts at `METADATA_EXTENSIONS_OFFSET` and runs to the end of
+/// the account, which is reallocated whenever the extensions change size.
+#[repr(C)]
+#[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
+#[derive(PartialEq, Debug, Clone)]
+pub struct MetadataV2 {
+    pub metadata: Metadata,
+    pub extensions: Vec<u8>,
+}
+
+impl MetadataV2 {
+    /// Size of the account holding this metadata.
+    pub fn account_size(&self) -> usize {
+        MIN_METADATA_V2_LEN + self.extensions.len()
+    }
+}
+
+impl TokenMetadataAccount for MetadataV2 {
+    fn key() -> Key {
+        Key::MetadataV2
+    }
+
+    fn size() -> usize {
+        MIN_METADATA_V2_LEN
+    }
+
+    fn safe_deserialize<T: BorshDeserialize>(data: &[u8]) -> Result<T, BorshError> {
+        if !is_metadata_v2_account(data) {
+            return Err(BorshError::new(ErrorKind::Other, "DataTypeMismatch"));
+        }
+
+        // The account ends with the extensions, so nothing may be left over.
+        T::try_from_slice(data)
+    }
+}
+
+impl BorshSerialize for MetadataV2 {
+    fn serialize<W: std::io::Write>(&self, writer: &mut W) -> std::io::Result<()> {
+        if self.metadata.key != Key::MetadataV2 {
+            return Err(BorshError::new(
+                ErrorKind::InvalidInput,
+                "Metadata has not been migrated",
+            ));
+        }
+
+        let mut buf = self.metadata.try_to_vec()?;
+        if buf.len() > METADATA_EXTENSIONS_OFFSET {
+            return Err(BorshError::new(
+                ErrorKind::InvalidInput,
+                "Metadata fields exceed their capacity",
+            ));
+        }
+        buf.resize(METADATA_EXTENSIONS_OFFSET, 0);
+        BorshSerialize::serialize(&self.extensions, &mut buf)?;
+
+        writer.write_all(&buf)
+    }
+}
+
+// Strict counterpart of the `Metadata` deserializer: garbage anywhere in the account is an
+// error rather than something to be guessed around.
+impl BorshDeserialize for MetadataV2 {
+    fn deserialize(buf: &mut &[u8]) -> Result<Self, BorshError> {
+        if buf.len() < MIN_METADATA_V2_LEN {
+            return Err(BorshError::new(
+                ErrorKind::UnexpectedEof,
+                "Unexpected length of input",
+            ));
+        }
+        let (region, mut rest) = buf.split_at(METADATA_EXTENSIONS_OFFSET);
+
+        let metadata = meta_deser_strict(region)?;
+        let extensions: Vec<u8> = BorshDeserialize::deserialize(&mut rest)?;
+
+        *buf = rest;
+        Ok(MetadataV2 {
+            metadata,
+            extensions,
+        })
+    }
+}
+
 pub trait MasterEdition {
     fn key(&self) -> Key;
     fn supply(&self) -> u64;
@@ -465,6 +1111,7 @@ pub struct MasterEditionV1 {
     pub max_supply: Option<u64>,
 
     /// Can be used to mint tokens that give one-time permission to mint a single limited edition.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub printing_mint: Pubkey,
 
     /// If you don't know how many printing tokens you are going to need, but you do know
@@ -477,6 +1124,7 @@ pub struct MasterEditionV1 {
     /// but at the end we will. At the end it then burns this token with token-metadata to
     /// get the printing tokens it needs to give to bidders. Each bidder then redeems a printing token
     /// to get their limited editions.
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub one_time_printing_authorization_mint: Pubkey,
 }
 
@@ -524,6 +1172,7 @@ pub struct Edition {
     pub key: Key,
 
     /// Points at MasterEdition struct
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub parent: Pubkey,
 
     /// Starting at 0 for master record, this is incremented for each edition minted.
@@ -612,6 +1261,7 @@ pub fn get_reservation_list(
 pub struct ReservationListV2 {
     pub key: Key,
     /// Present for reverse lookups
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub master_edition: Pubkey,
 
     /// What supply counter was on master_edition when this reservation was created.
@@ -730,6 +1380,7 @@ impl ReservationList for ReservationListV2 {
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct Reservation {
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub address: Pubkey,
     pub spots_remaining: u64,
     pub total_spots: u64,
@@ -742,6 +1393,7 @@ pub struct Reservation {
 pub struct ReservationListV1 {
     pub key: Key,
     /// Present for reverse lookups
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub master_edition: Pubkey,
 
     /// What supply counter was on master_edition when this reservation was created.
@@ -831,6 +1483,7 @@ impl ReservationList for ReservationListV1 {
 #[cfg_attr(feature = "serde-feature", derive(Serialize, Deserialize))]
 #[derive(BorshSerialize, BorshDeserialize, PartialEq, Debug, Clone)]
 pub struct ReservationV1 {
+    #[cfg_attr(feature = "serde-feature", serde(with = "As::<DisplayFromStr>"))]
     pub address: Pubkey,
     pub spots_remaining: u8,
     pub total_spots: u8,
@@ -926,4 +1579,15 @@ impl EditionMarker {
         self.ledger[index] |= mask;
         Ok(())
     }
+
+    pub fn remove_edition(&mut self, edition: u64) -> ProgramResult {
+        let (index, mask) = EditionMarker::get_index_and_mask(edition)?;
+        // bitwise and a 0 into our position in that position
+        self.ledger[index] &= !mask;
+        Ok(())
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ledger.iter().all(|i| *i == 0)
+    }
 }
@@ -197,6 +197,8 @@ mod metadata {
             key: Key::UseAuthorityRecord,
             allowed_uses: 14,
             bump: 255,
+            expires_at: None,
+            not_before: None,
         };
 
         let mut buf = Vec::new();
@@ -471,6 +473,25 @@ mod edition_marker {
         let error = EditionMarker::from_account_info::<EditionMarker>(&account_info).unwrap_err();
         assert_eq!(error, MetadataError::DataTypeMismatch.into());
     }
+
+    #[test]
+    fn insert_and_remove_editions() {
+        let mut edition_marker = EditionMarker::default();
+        assert!(edition_marker.is_empty());
+
+        edition_marker.insert_edition(1).unwrap();
+        edition_marker.insert_edition(9).unwrap();
+        assert!(edition_marker.edition_taken(1).unwrap());
+        assert!(edition_marker.edition_taken(9).unwrap());
+
+        edition_marker.remove_edition(1).unwrap();
+        assert!(!edition_marker.edition_taken(1).unwrap());
+        assert!(edition_marker.edition_taken(9).unwrap());
+        assert!(!edition_marker.is_empty());
+
+        edition_marker.remove_edition(9).unwrap();
+        assert!(edition_marker.is_empty());
+    }
 }
 
 mod use_authority_record {
@@ -604,3 +625,108 @@ mod collection_authority_record {
         assert_eq!(error, MetadataError::DataTypeMismatch.into());
     }
 }
+
+mod metadata_attributes {
+    use super::*;
+    use crate::state::{
+        Attribute, MetadataAttributes, TokenMetadataAccount, METADATA_ATTRIBUTES_SIZE,
+    };
+
+    fn attribute(key: &str, value: &str) -> Attribute {
+        Attribute {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    #[test]
+    fn set_and_remove_attributes() {
+        let mut attributes = MetadataAttributes::default();
+
+        attributes.set(attribute("tier", "Silver"));
+        attributes.set(attribute("level", "3"));
+        attributes.set(attribute("tier", "Gold"));
+
+        assert_eq!(attributes.attributes.len(), 2);
+        assert_eq!(attributes.get("tier"), Some("Gold"));
+
+        assert!(attributes.remove("level"));
+        assert!(!attributes.remove("level"));
+        assert_eq!(attributes.get("level"), None);
+    }
+
+    #[test]
+    fn deserialize_padded_attributes() {
+        let mut attributes = MetadataAttributes {
+            metadata: Keypair::new().pubkey(),
+            ..Default::default()
+        };
+        attributes.set(attribute("tier", "Gold"));
+
+        let mut buf = attributes.try_to_vec().unwrap();
+        MetadataAttributes::pad_length(&mut buf).unwrap();
+        assert_eq!(buf.len(), METADATA_ATTRIBUTES_SIZE);
+
+        let deserialized: MetadataAttributes = MetadataAttributes::safe_deserialize(&buf).unwrap();
+        assert_eq!(deserialized, attributes);
+    }
+}
+
+mod metadata_history {
+    use super::*;
+    use crate::state::{
+        hash_data, Collection, MetadataHistory, MetadataHistoryEntry, TokenMetadataAccount,
+        HISTORY_COLLECTION_CHANGED, HISTORY_CREATOR_VERIFICATION_CHANGED, HISTORY_URI_CHANGED,
+        MAX_METADATA_HISTORY_ENTRIES, METADATA_HISTORY_SIZE,
+    };
+    use solana_program::pubkey::Pubkey;
+
+    fn entry(slot: u64) -> MetadataHistoryEntry {
+        MetadataHistoryEntry {
+            prior_data_hash: [0; 32],
+            slot,
+            signer: Pubkey::default(),
+            changed_fields: 0,
+        }
+    }
+
+    #[test]
+    fn changed_fields_of_update() {
+        let prior = expected_pesky_metadata();
+        let mut current = prior.clone();
+        current.data.uri = "https://example.com/swapped.json".to_string();
+        current.data.creators.as_mut().unwrap()[0].verified = false;
+        current.collection = Some(Collection {
+            key: Keypair::new().pubkey(),
+            verified: false,
+        });
+
+        let entry = MetadataHistoryEntry::new(&prior, &current, 42, prior.update_authority);
+        assert_eq!(entry.prior_data_hash, hash_data(&prior.data));
+        assert_ne!(entry.prior_data_hash, hash_data(&current.data));
+        assert_eq!(
+            entry.changed_fields,
+            HISTORY_URI_CHANGED | HISTORY_CREATOR_VERIFICATION_CHANGED | HISTORY_COLLECTION_CHANGED
+        );
+    }
+
+    #[test]
+    fn ring_buffer_keeps_last_entries() {
+        let mut history = MetadataHistory::default();
+        let total = MAX_METADATA_HISTORY_ENTRIES as u64 + 3;
+        for slot in 0..total {
+            history.record(entry(slot));
+        }
+
+        assert_eq!(history.total_entries, total);
+        assert_eq!(history.entries.len(), MAX_METADATA_HISTORY_ENTRIES);
+        let slots: Vec<u64> = history.ordered_entries().iter().map(|e| e.slot).collect();
+        assert_eq!(slots, (3..total).collect::<Vec<u64>>());
+
+        let mut buf = history.try_to_vec().unwrap();
+        MetadataHistory::pad_length(&mut buf).unwrap();
+        assert_eq!(buf.len(), METADATA_HISTORY_SIZE);
+        let deserialized: MetadataHistory = MetadataHistory::safe_deserialize(&buf).unwrap();
+        assert_eq!(deserialized, history);
+    }
+}
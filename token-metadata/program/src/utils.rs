@@ -2,6 +2,8 @@ use crate::{
     assertions::{collection::assert_collection_update_is_valid, uses::assert_valid_use},
     deser::clean_write_metadata,
     error::MetadataError,
+    event::{emit, MetadataEvent},
+    instruction::{EditionOverrides, EDITION_PLACEHOLDER},
     pda::find_master_edition_account,
     state::{
         get_reservation_list, CollectionDetails, Creator, Data, DataV2, Edition, EditionMarker,
@@ -26,9 +28,10 @@ use solana_program::{
     system_instruction,
     sysvar::{rent::Rent, Sysvar},
 };
-use spl_token::{
+use spl_token::state::{Account, Mint};
+use spl_token_2022::{
+    extension::{AccountType, ExtensionType, StateWithExtensions},
     instruction::{set_authority, AuthorityType},
-    state::{Account, Mint},
 };
 use std::{collections::HashMap, convert::TryInto};
 
@@ -157,7 +160,14 @@ pub fn assert_data_valid(
 pub fn assert_initialized<T: Pack + IsInitialized>(
     account_info: &AccountInfo,
 ) -> Result<T, ProgramError> {
-    let account: T = T::unpack_unchecked(&account_info.data.borrow())?;
+    let data = account_info.data.borrow();
+    // Token-2022 keeps the legacy layout for the base state and appends its extensions after it.
+    let account: T = if *account_info.owner == spl_token_2022::id() && data.len() > T::LEN {
+        assert_token_2022_extensions_valid::<T>(&data)?;
+        T::unpack_unchecked(&data[..T::LEN])?
+    } else {
+        T::unpack_unchecked(&data)?
+    };
     if !account.is_initialized() {
         Err(MetadataError::Uninitialized.into())
     } else {
@@ -165,6 +175,37 @@ pub fn assert_initialized<T: Pack + IsInitialized>(
     }
 }
 
+/// Token-2022 extensions that cannot move, freeze or destroy a token behind its owner's back.
+/// Transfer fees and interest only change the amount a transfer delivers or a wallet displays.
+/// Extension types this version of the program does not know about are rejected.
+pub const ALLOWED_TOKEN_2022_EXTENSIONS: &[ExtensionType] = &[
+    ExtensionType::ImmutableOwner,
+    ExtensionType::MemoTransfer,
+    ExtensionType::TransferFeeConfig,
+    ExtensionType::TransferFeeAmount,
+    ExtensionType::InterestBearingConfig,
+];
+
+/// Checks that `data` is a Token-2022 mint or token account of the type `T` expects,
+/// rejecting multisigs, mismatched account types and extensions outside
+/// [`ALLOWED_TOKEN_2022_EXTENSIONS`] before the base state is read.
+fn assert_token_2022_extensions_valid<T: Pack>(data: &[u8]) -> ProgramResult {
+    let extension_types = if T::LEN == Mint::LEN {
+        StateWithExtensions::<spl_token_2022::state::Mint>::unpack(data)?.get_extension_types()
+    } else {
+        StateWithExtensions::<spl_token_2022::state::Account>::unpack(data)?.get_extension_types()
+    }
+    .map_err(|_| MetadataError::UnsupportedTokenExtension)?;
+
+    if extension_types
+        .iter()
+        .any(|extension_type| !ALLOWED_TOKEN_2022_EXTENSIONS.contains(extension_type))
+    {
+        return Err(MetadataError::UnsupportedTokenExtension.into());
+    }
+    Ok(())
+}
+
 /// Create account almost from scratch, lifted from
 /// <https://github.com/solana-labs/solana-program-library/tree/master/associated-token-account/program/src/processor.rs#L51-L98>
 #[inline(always)]
@@ -214,6 +255,67 @@ pub fn create_or_allocate_account_raw<'a>(
     Ok(())
 }
 
+/// Resize a program owned account, topping up its rent from the payer when it grows.
+#[inline(always)]
+pub fn resize_or_reallocate_account_raw<'a>(
+    target_account: &AccountInfo<'a>,
+    payer_info: &AccountInfo<'a>,
+    rent_sysvar_info: &AccountInfo<'a>,
+    system_program_info: &AccountInfo<'a>,
+    new_size: usize,
+) -> ProgramResult {
+    let rent = &Rent::from_account_info(rent_sysvar_info)?;
+    let required_lamports = rent
+        .minimum_balance(new_size)
+        .max(1)
+        .saturating_sub(target_account.lamports());
+
+    if required_lamports > 0 {
+        msg!("Transfer {} lamports to the account", required_lamports);
+        invoke(
+            &system_instruction::transfer(payer_info.key, target_account.key, required_lamports),
+            &[
+                payer_info.clone(),
+                target_account.clone(),
+                system_program_info.clone(),
+            ],
+        )?;
+    }
+
+    target_account.realloc(new_size, false)
+}
+
+/// Creator addresses and shares can only change directly while no other creator has verified
+/// them; otherwise the change must be approved through a creator change proposal.
+pub fn assert_creator_change_is_allowed(
+    existing: &Metadata,
+    incoming: &Option<Vec<Creator>>,
+    signer: &Pubkey,
+) -> ProgramResult {
+    let shares = |creators: &Option<Vec<Creator>>| {
+        creators.as_ref().map(|creators| {
+            creators
+                .iter()
+                .map(|creator| (creator.address, creator.share))
+                .collect::<Vec<_>>()
+        })
+    };
+    if shares(&existing.data.creators) == shares(incoming) {
+        return Ok(());
+    }
+
+    let verified_by_others = existing
+        .data
+        .creators
+        .iter()
+        .flatten()
+        .any(|creator| creator.verified && creator.address != *signer);
+    if verified_by_others {
+        return Err(MetadataError::CreatorChangeRequiresApproval.into());
+    }
+    Ok(())
+}
+
 pub fn assert_update_authority_is_correct(
     metadata: &Metadata,
     update_authority_info: &AccountInfo,
@@ -549,6 +651,21 @@ pub fn calculate_supply_change<'a>(
     Ok(())
 }
 
+/// Applies per-edition overrides to the data copied from the master edition. The result is
+/// checked by `assert_data_valid` when the edition's metadata is created, so an override that
+/// pushes the name or uri past its maximum length fails the mint.
+pub fn apply_edition_overrides(data: &mut DataV2, overrides: &EditionOverrides, edition: u64) {
+    if let Some(suffix) = &overrides.name_suffix {
+        let mut name = data.name.trim_end_matches(char::from(0)).to_string();
+        name.push_str(&suffix.replace(EDITION_PLACEHOLDER, &edition.to_string()));
+        data.name = name;
+    }
+
+    if let Some(uri) = &overrides.uri {
+        data.uri = uri.clone();
+    }
+}
+
 #[allow(clippy::too_many_arguments)]
 pub fn mint_limited_edition<'a>(
     program_id: &'a Pubkey,
@@ -569,6 +686,8 @@ pub fn mint_limited_edition<'a>(
     // Only present with MasterEditionV2 calls, if present, means
     // directing to a specific version, otherwise just pull off the top
     edition_override: Option<u64>,
+    // Customizes the name and uri of the new edition, requires the master update authority to sign
+    overrides: Option<&EditionOverrides>,
 ) -> ProgramResult {
     let me_supply = get_supply_off_master_edition(master_edition_account_info)?;
     let mint_authority = get_mint_authority(mint_info)?;
@@ -606,9 +725,21 @@ pub fn mint_limited_edition<'a>(
     if mint_supply != 1 {
         return Err(MetadataError::EditionsMustHaveExactlyOneToken.into());
     }
+
+    if overrides.is_some() {
+        assert_update_authority_is_correct(&master_metadata, update_authority_info)?;
+    }
+
+    let edition_number = calculate_edition_number(
+        mint_authority_info,
+        reservation_list_info,
+        edition_override,
+        me_supply,
+    )?;
+
     let master_data = master_metadata.data;
     // bundle data into v2
-    let data_v2 = DataV2 {
+    let mut data_v2 = DataV2 {
         name: master_data.name,
         symbol: master_data.symbol,
         uri: master_data.uri,
@@ -621,6 +752,9 @@ pub fn mint_limited_edition<'a>(
             total: u.total,
         }),
     };
+    if let Some(overrides) = overrides {
+        apply_edition_overrides(&mut data_v2, overrides, edition_number);
+    }
     // create the metadata the normal way, except `allow_direct_creator_writes` is set to true
     // because we are directly copying from the Master Edition metadata.
 
@@ -669,13 +803,7 @@ pub fn mint_limited_edition<'a>(
     *key = [Key::EditionV1 as u8];
     parent.copy_from_slice(master_edition_account_info.key.as_ref());
 
-    *edition = calculate_edition_number(
-        mint_authority_info,
-        reservation_list_info,
-        edition_override,
-        me_supply,
-    )?
-    .to_le_bytes();
+    *edition = edition_number.to_le_bytes();
 
     // Now make sure this mint can never be used by anybody else.
     transfer_mint_authority(
@@ -703,7 +831,7 @@ pub fn spl_token_burn(params: TokenBurnParams<'_, '_>) -> ProgramResult {
         seeds.push(seed);
     }
     let result = invoke_signed(
-        &spl_token::instruction::burn(
+        &spl_token_2022::instruction::burn(
             token_program.key,
             source.key,
             mint.key,
@@ -730,7 +858,7 @@ pub fn spl_token_close(params: TokenCloseParams<'_, '_>) -> ProgramResult {
         seeds.push(seed);
     }
     let result = invoke_signed(
-        &spl_token::instruction::close_account(
+        &spl_token_2022::instruction::close_account(
             token_program.key,
             account.key,
             destination.key,
@@ -787,7 +915,7 @@ pub fn spl_token_mint_to(params: TokenMintToParams<'_, '_>) -> ProgramResult {
         seeds.push(seed);
     }
     let result = invoke_signed(
-        &spl_token::instruction::mint_to(
+        &spl_token_2022::instruction::mint_to(
             token_program.key,
             mint.key,
             destination.key,
@@ -817,6 +945,99 @@ pub struct TokenMintToParams<'a: 'b, 'b> {
     pub token_program: AccountInfo<'a>,
 }
 
+pub fn spl_token_transfer(params: TokenTransferParams<'_, '_>) -> ProgramResult {
+    let TokenTransferParams {
+        mint,
+        source,
+        destination,
+        authority,
+        token_program,
+        amount,
+        decimals,
+        authority_signer_seeds,
+    } = params;
+    let mut seeds: Vec<&[&[u8]]> = vec![];
+    if let Some(seed) = authority_signer_seeds {
+        seeds.push(seed);
+    }
+    invoke_signed(
+        &spl_token_2022::instruction::transfer_checked(
+            token_program.key,
+            source.key,
+            mint.key,
+            destination.key,
+            authority.key,
+            &[],
+            amount,
+            decimals,
+        )?,
+        &[source, mint, destination, authority, token_program],
+        seeds.as_slice(),
+    )
+}
+
+/// TokenTransferParams
+pub struct TokenTransferParams<'a: 'b, 'b> {
+    /// mint
+    pub mint: AccountInfo<'a>,
+    /// source
+    pub source: AccountInfo<'a>,
+    /// destination
+    pub destination: AccountInfo<'a>,
+    /// amount
+    pub amount: u64,
+    /// decimals
+    pub decimals: u8,
+    /// authority
+    pub authority: AccountInfo<'a>,
+    /// authority_signer_seeds
+    pub authority_signer_seeds: Option<&'b [&'b [u8]]>,
+    /// token_program
+    pub token_program: AccountInfo<'a>,
+}
+
+/// Freezes `token_info` using the edition PDA, which must be the freeze authority of the mint.
+pub fn freeze<'a>(
+    mint_info: AccountInfo<'a>,
+    token_info: AccountInfo<'a>,
+    edition_info: AccountInfo<'a>,
+    token_program_info: AccountInfo<'a>,
+    edition_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token_2022::instruction::freeze_account(
+            token_program_info.key,
+            token_info.key,
+            mint_info.key,
+            edition_info.key,
+            &[],
+        )?,
+        &[token_info, mint_info, edition_info],
+        &[edition_seeds],
+    )
+}
+
+/// Thaws `token_info` using the edition PDA, which must be the freeze authority of the mint.
+pub fn thaw<'a>(
+    mint_info: AccountInfo<'a>,
+    token_info: AccountInfo<'a>,
+    edition_info: AccountInfo<'a>,
+    token_program_info: AccountInfo<'a>,
+    edition_seeds: &[&[u8]],
+) -> ProgramResult {
+    invoke_signed(
+        &spl_token_2022::instruction::thaw_account(
+            token_program_info.key,
+            token_info.key,
+            mint_info.key,
+            edition_info.key,
+            &[],
+        )?,
+        &[token_info, mint_info, edition_info],
+        &[edition_seeds],
+    )
+}
+
 pub fn assert_derivation(
     program_id: &Pubkey,
     account: &AccountInfo,
@@ -845,14 +1066,37 @@ pub fn assert_owned_by(account: &AccountInfo, owner: &Pubkey) -> ProgramResult {
     }
 }
 
+pub fn is_token_program(program_id: &Pubkey) -> bool {
+    *program_id == spl_token::id() || *program_id == spl_token_2022::id()
+}
+
 pub fn assert_token_program_matches_package(token_program_info: &AccountInfo) -> ProgramResult {
-    if *token_program_info.key != spl_token::id() {
+    if !is_token_program(token_program_info.key) {
         return Err(MetadataError::InvalidTokenProgram.into());
     }
 
     Ok(())
 }
 
+/// Mints and token accounts may belong to either spl-token or Token-2022. Token-2022 accounts
+/// must only carry allowed extensions.
+pub fn assert_owned_by_token_program(account: &AccountInfo) -> ProgramResult {
+    if !is_token_program(account.owner) {
+        return Err(MetadataError::IncorrectOwner.into());
+    }
+
+    let data = account.try_borrow_data()?;
+    if *account.owner == spl_token_2022::id() && data.len() > Account::LEN {
+        // Extended mints are padded to the token account length and tagged right after it.
+        if data[Account::LEN] == AccountType::Mint as u8 {
+            assert_token_2022_extensions_valid::<Mint>(&data)?;
+        } else {
+            assert_token_2022_extensions_valid::<Account>(&data)?;
+        }
+    }
+    Ok(())
+}
+
 pub fn is_correct_account_type(data: &[u8], data_type: Key, data_size: usize) -> bool {
     (data[0] == data_type as u8 || data[0] == Key::Uninitialized as u8) && (data.len() == data_size)
 }
@@ -931,7 +1175,7 @@ pub fn process_create_metadata_accounts_logic(
             }
         },
     )?;
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by_token_program(mint_info)?;
 
     let metadata_seeds = &[
         PREFIX.as_bytes(),
@@ -1021,6 +1265,12 @@ pub fn process_create_metadata_accounts_logic(
     metadata.edition_nonce = Some(edition_bump_seed);
     metadata.serialize(&mut *metadata_account_info.data.borrow_mut())?;
 
+    emit(MetadataEvent::MetadataCreated {
+        metadata: *metadata_account_info.key,
+        mint: *mint_info.key,
+        update_authority: metadata.update_authority,
+        data: metadata.data,
+    });
     Ok(())
 }
 
@@ -1076,6 +1326,7 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
     program_id: &'a Pubkey,
     accounts: MintNewEditionFromMasterEditionViaTokenLogicArgs<'a>,
     edition: u64,
+    overrides: Option<&EditionOverrides>,
     ignore_owner_signer: bool,
 ) -> ProgramResult {
     let MintNewEditionFromMasterEditionViaTokenLogicArgs {
@@ -1096,8 +1347,8 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
     } = accounts;
 
     assert_token_program_matches_package(token_program_account_info)?;
-    assert_owned_by(mint_info, &spl_token::id())?;
-    assert_owned_by(token_account_info, &spl_token::id())?;
+    assert_owned_by(mint_info, token_program_account_info.key)?;
+    assert_owned_by_token_program(token_account_info)?;
     assert_owned_by(master_edition_account_info, program_id)?;
     assert_owned_by(master_metadata_account_info, program_id)?;
 
@@ -1189,7 +1440,17 @@ pub fn process_mint_new_edition_from_master_edition_via_token_logic<'a>(
         rent_info,
         None,
         Some(edition),
+        overrides,
     )?;
+
+    emit(MetadataEvent::EditionPrinted {
+        master_metadata: *master_metadata_account_info.key,
+        master_edition: *master_edition_account_info.key,
+        new_metadata: *new_metadata_account_info.key,
+        new_edition: *new_edition_account_info.key,
+        new_mint: *mint_info.key,
+        edition,
+    });
     Ok(())
 }
 pub fn assert_currently_holding(
@@ -1201,11 +1462,11 @@ pub fn assert_currently_holding(
     token_account_info: &AccountInfo,
 ) -> ProgramResult {
     assert_owned_by(metadata_info, program_id)?;
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by_token_program(mint_info)?;
 
     let token_account: Account = assert_initialized(token_account_info)?;
 
-    assert_owned_by(token_account_info, &spl_token::id())?;
+    assert_owned_by(token_account_info, mint_info.owner)?;
 
     if token_account.owner != *owner_info.key {
         return Err(MetadataError::InvalidOwner.into());
@@ -1247,11 +1508,11 @@ pub fn assert_delegated_tokens(
     mint_info: &AccountInfo,
     token_account_info: &AccountInfo,
 ) -> ProgramResult {
-    assert_owned_by(mint_info, &spl_token::id())?;
+    assert_owned_by_token_program(mint_info)?;
 
     let token_account: Account = assert_initialized(token_account_info)?;
 
-    assert_owned_by(token_account_info, &spl_token::id())?;
+    assert_owned_by(token_account_info, mint_info.owner)?;
 
     if token_account.mint != *mint_info.key {
         return Err(MetadataError::MintMismatch.into());
@@ -1273,13 +1534,21 @@ pub fn assert_delegated_tokens(
 pub fn increment_collection_size(
     metadata: &mut Metadata,
     metadata_info: &AccountInfo,
+) -> ProgramResult {
+    increment_collection_size_by(metadata, metadata_info, 1)
+}
+
+pub fn increment_collection_size_by(
+    metadata: &mut Metadata,
+    metadata_info: &AccountInfo,
+    amount: u64,
 ) -> ProgramResult {
     if let Some(ref details) = metadata.collection_details {
         match details {
             CollectionDetails::V1 { size } => {
                 metadata.collection_details = Some(CollectionDetails::V1 {
                     size: size
-                        .checked_add(1)
+                        .checked_add(amount)
                         .ok_or(MetadataError::NumericalOverflowError)?,
                 });
                 msg!("Clean writing collection parent metadata");
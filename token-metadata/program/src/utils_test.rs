@@ -46,6 +46,9 @@ mod puff_out_test {
             uses: None,
             token_standard: None,
             collection_details: None,
+            rule_set: None,
+            fungible_details: None,
+            history_enabled: false,
         };
 
         puff_out_data_fields(&mut metadata);
@@ -86,3 +89,207 @@ mod try_from_slice_checked {
         assert_eq!(metadata, expected_metadata);
     }
 }
+
+mod edition_overrides {
+    use crate::{
+        instruction::EditionOverrides,
+        state::DataV2,
+        utils::{apply_edition_overrides, puffed_out_string},
+    };
+
+    fn master_data() -> DataV2 {
+        DataV2 {
+            name: puffed_out_string("Ticket", 32),
+            symbol: "TKT".to_string(),
+            uri: puffed_out_string("https://example.com/master.json", 200),
+            seller_fee_basis_points: 500,
+            creators: None,
+            collection: None,
+            uses: None,
+        }
+    }
+
+    #[test]
+    fn name_suffix_is_rendered() {
+        let mut data = master_data();
+        let overrides = EditionOverrides {
+            name_suffix: Some(" #{edition}".to_string()),
+            uri: None,
+        };
+
+        apply_edition_overrides(&mut data, &overrides, 42);
+
+        assert_eq!(data.name, "Ticket #42");
+        assert_eq!(data.uri, master_data().uri);
+    }
+
+    #[test]
+    fn uri_is_replaced() {
+        let mut data = master_data();
+        let overrides = EditionOverrides {
+            name_suffix: None,
+            uri: Some("https://example.com/42.json".to_string()),
+        };
+
+        apply_edition_overrides(&mut data, &overrides, 42);
+
+        assert_eq!(data.name, master_data().name);
+        assert_eq!(data.uri, "https://example.com/42.json");
+    }
+}
+
+mod token_2022 {
+    use solana_program::{
+        account_info::AccountInfo, program_error::ProgramError, program_option::COption,
+        program_pack::Pack, pubkey::Pubkey,
+    };
+    use spl_token::state::{Account, Mint};
+    use spl_token_2022::extension::{
+        immutable_owner::ImmutableOwner, interest_bearing_mint::InterestBearingConfig,
+        mint_close_authority::MintCloseAuthority, transfer_fee::TransferFeeConfig, ExtensionType,
+        StateWithExtensionsMut,
+    };
+
+    use crate::{
+        error::MetadataError,
+        utils::{assert_initialized, assert_owned_by_token_program},
+    };
+
+    fn with_token_2022_account<R>(mut data: Vec<u8>, f: impl FnOnce(&AccountInfo) -> R) -> R {
+        let key = Pubkey::new_unique();
+        let owner = spl_token_2022::id();
+        let mut lamports = 0;
+        let account_info = AccountInfo::new(
+            &key,
+            false,
+            false,
+            &mut lamports,
+            &mut data,
+            &owner,
+            false,
+            0,
+        );
+        f(&account_info)
+    }
+
+    fn extended_mint_data(mint_authority: Pubkey) -> Vec<u8> {
+        let len = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::MintCloseAuthority,
+        ]);
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        state.base = spl_token_2022::state::Mint {
+            mint_authority: COption::Some(mint_authority),
+            supply: 1,
+            decimals: 0,
+            is_initialized: true,
+            freeze_authority: COption::None,
+        };
+        state.pack_base();
+        state.init_extension::<MintCloseAuthority>(true).unwrap();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn extended_account_data(owner: Pubkey) -> Vec<u8> {
+        let len = ExtensionType::get_account_len::<spl_token_2022::state::Account>(&[
+            ExtensionType::ImmutableOwner,
+        ]);
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<spl_token_2022::state::Account>::unpack_uninitialized(
+                &mut data,
+            )
+            .unwrap();
+        state.base = spl_token_2022::state::Account {
+            mint: Pubkey::new_unique(),
+            owner,
+            amount: 1,
+            state: spl_token_2022::state::AccountState::Initialized,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_extension::<ImmutableOwner>(true).unwrap();
+        state.init_account_type().unwrap();
+        data
+    }
+
+    fn unsupported_extension() -> ProgramError {
+        MetadataError::UnsupportedTokenExtension.into()
+    }
+
+    #[test]
+    fn extended_account_is_initialized() {
+        let owner = Pubkey::new_unique();
+        with_token_2022_account(extended_account_data(owner), |account_info| {
+            let account: Account = assert_initialized(account_info).unwrap();
+
+            assert_eq!(account.owner, owner);
+            assert_eq!(account.amount, 1);
+            assert!(assert_owned_by_token_program(account_info).is_ok());
+        });
+    }
+
+    #[test]
+    fn mint_close_authority_is_rejected() {
+        with_token_2022_account(extended_mint_data(Pubkey::new_unique()), |account_info| {
+            assert_eq!(
+                assert_initialized::<Mint>(account_info).unwrap_err(),
+                unsupported_extension()
+            );
+            assert_eq!(
+                assert_owned_by_token_program(account_info).unwrap_err(),
+                unsupported_extension()
+            );
+        });
+    }
+
+    #[test]
+    fn transfer_fee_and_interest_mint_is_initialized() {
+        let len = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(&[
+            ExtensionType::TransferFeeConfig,
+            ExtensionType::InterestBearingConfig,
+        ]);
+        let mut data = vec![0; len];
+        let mut state =
+            StateWithExtensionsMut::<spl_token_2022::state::Mint>::unpack_uninitialized(&mut data)
+                .unwrap();
+        state.base = spl_token_2022::state::Mint {
+            supply: 1_000,
+            decimals: 6,
+            is_initialized: true,
+            ..Default::default()
+        };
+        state.pack_base();
+        state.init_extension::<TransferFeeConfig>(true).unwrap();
+        state.init_extension::<InterestBearingConfig>(true).unwrap();
+        state.init_account_type().unwrap();
+
+        with_token_2022_account(data, |account_info| {
+            let mint: Mint = assert_initialized(account_info).unwrap();
+
+            assert_eq!(mint.decimals, 6);
+            assert!(assert_owned_by_token_program(account_info).is_ok());
+        });
+    }
+
+    #[test]
+    fn extended_account_is_not_a_mint() {
+        with_token_2022_account(
+            extended_account_data(Pubkey::new_unique()),
+            |account_info| {
+                assert!(assert_initialized::<Mint>(account_info).is_err());
+            },
+        );
+    }
+
+    #[test]
+    fn multisig_is_rejected() {
+        let data = vec![1; spl_token_2022::state::Multisig::LEN];
+        with_token_2022_account(data, |account_info| {
+            assert!(assert_initialized::<Account>(account_info).is_err());
+        });
+    }
+}
@@ -61,6 +61,8 @@ mod bump_seed_migration {
             key: MetadataKey::UseAuthorityRecord,
             allowed_uses: 10,
             bump: 0,
+            expires_at: None,
+            not_before: None,
         };
         let mut account = Account {
             lamports: 1113600,
@@ -71,7 +73,7 @@ mod bump_seed_migration {
         };
         let data_mut = account.data_mut();
         use_record_struct.serialize(data_mut).unwrap();
-        data_mut.append(&mut vec![0, 0, 0, 0, 0, 0, 0, 0]);
+        data_mut.append(&mut vec![0, 0, 0, 0, 0, 0]);
         let shared_data = &AccountSharedData::from(account);
         context.set_account(&record, shared_data);
         airdrop(&mut context, &use_authority_account.pubkey(), 1113600)
@@ -0,0 +1,342 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    state::{Collection, CollectionDetails},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program::pubkey::Pubkey;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod burn_edition_nft {
+    use super::*;
+
+    async fn create_master(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+        let original_nft = Metadata::new();
+        original_nft
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let master_edition = MasterEditionV2::new(&original_nft);
+        master_edition.create_v3(context, Some(10)).await.unwrap();
+
+        (original_nft, master_edition)
+    }
+
+    async fn burn_master(
+        context: &mut ProgramTestContext,
+        nft: &Metadata,
+        master_edition: &MasterEditionV2,
+        collection_metadata: Option<Pubkey>,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_master_edition_nft(
+                PROGRAM_ID,
+                nft.pubkey,
+                context.payer.pubkey(),
+                nft.mint.pubkey(),
+                nft.token.pubkey(),
+                master_edition.pubkey,
+                spl_token::id(),
+                collection_metadata,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn successfully_burn_print_edition() {
+        let mut context = program_test().start_with_context().await;
+        let (original_nft, master_edition) = create_master(&mut context).await;
+
+        let first_print = EditionMarker::new(&original_nft, &master_edition, 1);
+        first_print.create(&mut context).await.unwrap();
+        let second_print = EditionMarker::new(&original_nft, &master_edition, 2);
+        second_print.create(&mut context).await.unwrap();
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        first_print.burn(&mut context, &payer).await.unwrap();
+
+        // Metadata, Print Edition and token account are closed.
+        for pubkey in [
+            first_print.new_metadata_pubkey,
+            first_print.new_edition_pubkey,
+            first_print.token.pubkey(),
+        ] {
+            let account = context.banks_client.get_account(pubkey).await.unwrap();
+            assert!(account.is_none());
+        }
+
+        // The edition marker still tracks the second print.
+        let edition_marker = first_print.get_data(&mut context).await;
+        assert!(!edition_marker.edition_taken(1).unwrap());
+        assert!(edition_marker.edition_taken(2).unwrap());
+
+        let master_edition_data = master_edition.get_data(&mut context).await;
+        assert_eq!(master_edition_data.supply, 1);
+
+        second_print.burn(&mut context, &payer).await.unwrap();
+
+        // The edition marker is closed once empty.
+        let edition_marker_account = context
+            .banks_client
+            .get_account(second_print.pubkey)
+            .await
+            .unwrap();
+        assert!(edition_marker_account.is_none());
+
+        let master_edition_data = master_edition.get_data(&mut context).await;
+        assert_eq!(master_edition_data.supply, 0);
+    }
+
+    #[tokio::test]
+    async fn only_owner_can_burn() {
+        let mut context = program_test().start_with_context().await;
+        let (original_nft, master_edition) = create_master(&mut context).await;
+
+        let print_edition = EditionMarker::new(&original_nft, &master_edition, 1);
+        print_edition.create(&mut context).await.unwrap();
+
+        let not_owner = Keypair::new();
+        airdrop(&mut context, &not_owner.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+
+        let err = print_edition
+            .burn(&mut context, &not_owner)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::InvalidOwner);
+    }
+
+    #[tokio::test]
+    async fn fail_to_burn_print_of_other_master_edition() {
+        let mut context = program_test().start_with_context().await;
+        let (original_nft, master_edition) = create_master(&mut context).await;
+        let (other_nft, other_master_edition) = create_master(&mut context).await;
+
+        let print_edition = EditionMarker::new(&original_nft, &master_edition, 1);
+        print_edition.create(&mut context).await.unwrap();
+
+        let other_print_edition = EditionMarker::new(&other_nft, &other_master_edition, 1);
+        other_print_edition.create(&mut context).await.unwrap();
+        let mismatched = EditionMarker {
+            master_edition_pubkey: other_print_edition.master_edition_pubkey,
+            metadata_mint_pubkey: other_print_edition.metadata_mint_pubkey,
+            pubkey: other_print_edition.pubkey,
+            ..print_edition
+        };
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        let err = mismatched.burn(&mut context, &payer).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::PrintEditionDoesNotMatchMasterEdition);
+    }
+
+    #[tokio::test]
+    async fn successfully_burn_master_edition_with_prints() {
+        let mut context = program_test().start_with_context().await;
+        let (original_nft, master_edition) = create_master(&mut context).await;
+
+        let print_edition = EditionMarker::new(&original_nft, &master_edition, 1);
+        print_edition.create(&mut context).await.unwrap();
+
+        burn_master(&mut context, &original_nft, &master_edition, None)
+            .await
+            .unwrap();
+
+        for pubkey in [
+            original_nft.pubkey,
+            master_edition.pubkey,
+            original_nft.token.pubkey(),
+        ] {
+            let account = context.banks_client.get_account(pubkey).await.unwrap();
+            assert!(account.is_none());
+        }
+
+        // The print of the burned master can still be burned.
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        print_edition.burn(&mut context, &payer).await.unwrap();
+
+        for pubkey in [
+            print_edition.new_metadata_pubkey,
+            print_edition.new_edition_pubkey,
+            print_edition.pubkey,
+        ] {
+            let account = context.banks_client.get_account(pubkey).await.unwrap();
+            assert!(account.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn successfully_burn_master_edition_and_print_in_one_transaction() {
+        let mut context = program_test().start_with_context().await;
+        let (original_nft, master_edition) = create_master(&mut context).await;
+
+        let print_edition = EditionMarker::new(&original_nft, &master_edition, 1);
+        print_edition.create(&mut context).await.unwrap();
+
+        // The drained Master Edition is still present, zeroed, when the print is burned.
+        let payer = context.payer.pubkey();
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                instruction::burn_master_edition_nft(
+                    PROGRAM_ID,
+                    original_nft.pubkey,
+                    payer,
+                    original_nft.mint.pubkey(),
+                    original_nft.token.pubkey(),
+                    master_edition.pubkey,
+                    spl_token::id(),
+                    None,
+                ),
+                instruction::burn_edition_nft(
+                    PROGRAM_ID,
+                    print_edition.new_metadata_pubkey,
+                    payer,
+                    print_edition.mint.pubkey(),
+                    original_nft.mint.pubkey(),
+                    print_edition.token.pubkey(),
+                    master_edition.pubkey,
+                    print_edition.new_edition_pubkey,
+                    print_edition.pubkey,
+                    spl_token::id(),
+                ),
+            ],
+            Some(&payer),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        for pubkey in [
+            master_edition.pubkey,
+            print_edition.new_metadata_pubkey,
+            print_edition.new_edition_pubkey,
+            print_edition.pubkey,
+        ] {
+            let account = context.banks_client.get_account(pubkey).await.unwrap();
+            assert!(account.is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn burning_master_edition_decrements_collection_size() {
+        let mut context = program_test().start_with_context().await;
+
+        let collection_parent_nft = Metadata::new();
+        collection_parent_nft
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_COLLECTION_DETAILS,
+            )
+            .await
+            .unwrap();
+        let parent_master_edition = MasterEditionV2::new(&collection_parent_nft);
+        parent_master_edition
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let collection_item_nft = Metadata::new();
+        collection_item_nft
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                Some(Collection {
+                    key: collection_parent_nft.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let item_master_edition = MasterEditionV2::new(&collection_item_nft);
+        item_master_edition
+            .create_v3(&mut context, Some(10))
+            .await
+            .unwrap();
+
+        let print_edition = EditionMarker::new(&collection_item_nft, &item_master_edition, 1);
+        print_edition.create(&mut context).await.unwrap();
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        collection_item_nft
+            .verify_sized_collection_item(
+                &mut context,
+                collection_parent_nft.pubkey,
+                &payer,
+                collection_parent_nft.mint.pubkey(),
+                parent_master_edition.pubkey,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let err = burn_master(
+            &mut context,
+            &collection_item_nft,
+            &item_master_edition,
+            None,
+        )
+        .await
+        .unwrap_err();
+        assert_custom_error!(err, MetadataError::MissingCollectionMetadata);
+
+        burn_master(
+            &mut context,
+            &collection_item_nft,
+            &item_master_edition,
+            Some(collection_parent_nft.pubkey),
+        )
+        .await
+        .unwrap();
+
+        let parent_metadata = collection_parent_nft.get_data(&mut context).await;
+        assert_eq!(
+            parent_metadata.collection_details,
+            Some(CollectionDetails::V1 { size: 0 })
+        );
+    }
+}
@@ -0,0 +1,340 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::{find_creator_change_proposal_account, find_update_delegate_account},
+    state::{Creator, CreatorChangeProposal, DataV2, TokenMetadataAccount, UpdateDelegateScopes},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod creator_change {
+    use super::*;
+
+    fn creator(address: Pubkey, share: u8) -> Creator {
+        Creator {
+            address,
+            verified: false,
+            share,
+        }
+    }
+
+    /// Creates a metadata with the payer and `second` as verified creators.
+    async fn create_metadata(context: &mut ProgramTestContext, second: &Keypair) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                Some(vec![
+                    Creator {
+                        address: context.payer.pubkey(),
+                        verified: true,
+                        share: 50,
+                    },
+                    creator(second.pubkey(), 50),
+                ]),
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::sign_metadata(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                second.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, second],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        test_metadata
+    }
+
+    async fn propose(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        creators: Vec<Creator>,
+    ) -> Result<Pubkey, BanksClientError> {
+        let (proposal, _) = find_creator_change_proposal_account(&test_metadata.pubkey);
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::propose_creator_change(
+                PROGRAM_ID,
+                proposal,
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                creators,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await?;
+        Ok(proposal)
+    }
+
+    async fn approve(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        proposal: Pubkey,
+        creator: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_creator_change(
+                PROGRAM_ID,
+                proposal,
+                test_metadata.pubkey,
+                creator.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, creator],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success_all_verified_creators_approve() {
+        let mut context = program_test().start_with_context().await;
+        let second = Keypair::new();
+        let newcomer = Keypair::new();
+        let test_metadata = create_metadata(&mut context, &second).await;
+
+        let payer = context.payer.pubkey();
+        let proposal = propose(
+            &mut context,
+            &test_metadata,
+            vec![
+                creator(payer, 40),
+                creator(second.pubkey(), 40),
+                creator(newcomer.pubkey(), 20),
+            ],
+        )
+        .await
+        .unwrap();
+
+        approve(&mut context, &test_metadata, proposal, &second)
+            .await
+            .unwrap();
+
+        // One approval is still missing, so the creators are unchanged.
+        let account = get_account(&mut context, &proposal).await;
+        let proposal_data: CreatorChangeProposal =
+            CreatorChangeProposal::safe_deserialize(&account.data).unwrap();
+        assert_eq!(proposal_data.approvals, vec![second.pubkey()]);
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.data.creators.unwrap().len(), 2);
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        approve(&mut context, &test_metadata, proposal, &payer)
+            .await
+            .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.data.creators,
+            Some(vec![
+                Creator {
+                    address: context.payer.pubkey(),
+                    verified: true,
+                    share: 40,
+                },
+                Creator {
+                    address: second.pubkey(),
+                    verified: true,
+                    share: 40,
+                },
+                creator(newcomer.pubkey(), 20),
+            ])
+        );
+
+        let proposal_account = context.banks_client.get_account(proposal).await.unwrap();
+        assert!(proposal_account.is_none());
+    }
+
+    #[tokio::test]
+    async fn fail_approval_from_unverified_creator() {
+        let mut context = program_test().start_with_context().await;
+        let second = Keypair::new();
+        let newcomer = Keypair::new();
+        let test_metadata = create_metadata(&mut context, &second).await;
+
+        let proposal = propose(
+            &mut context,
+            &test_metadata,
+            vec![creator(second.pubkey(), 50), creator(newcomer.pubkey(), 50)],
+        )
+        .await
+        .unwrap();
+
+        let err = approve(&mut context, &test_metadata, proposal, &newcomer)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CreatorChangeApproverNotVerified);
+    }
+
+    #[tokio::test]
+    async fn fail_duplicate_proposal_and_approval() {
+        let mut context = program_test().start_with_context().await;
+        let second = Keypair::new();
+        let test_metadata = create_metadata(&mut context, &second).await;
+
+        let proposal = propose(
+            &mut context,
+            &test_metadata,
+            vec![creator(second.pubkey(), 100)],
+        )
+        .await
+        .unwrap();
+
+        let payer = context.payer.pubkey();
+        let err = propose(&mut context, &test_metadata, vec![creator(payer, 100)])
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::CreatorChangeProposalAlreadyExists);
+
+        approve(&mut context, &test_metadata, proposal, &second)
+            .await
+            .unwrap();
+
+        // Fresh blockhash so the repeated transaction is not deduplicated.
+        context.warp_to_slot(100).unwrap();
+        let err = approve(&mut context, &test_metadata, proposal, &second)
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::CreatorChangeAlreadyApproved);
+    }
+
+    fn data_with_creators(creators: Vec<Creator>) -> DataV2 {
+        DataV2 {
+            name: "Test".to_string(),
+            symbol: "TST".to_string(),
+            uri: "uri".to_string(),
+            creators: Some(creators),
+            seller_fee_basis_points: 10,
+            collection: None,
+            uses: None,
+        }
+    }
+
+    /// The shares of the creators swapped, keeping both verified.
+    fn reshared_creators(context: &ProgramTestContext, second: &Keypair) -> Vec<Creator> {
+        vec![
+            Creator {
+                address: context.payer.pubkey(),
+                verified: true,
+                share: 30,
+            },
+            Creator {
+                address: second.pubkey(),
+                verified: true,
+                share: 70,
+            },
+        ]
+    }
+
+    #[tokio::test]
+    async fn fail_direct_update_with_other_verified_creator() {
+        let mut context = program_test().start_with_context().await;
+        let second = Keypair::new();
+        let test_metadata = create_metadata(&mut context, &second).await;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_metadata_accounts_v2(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                None,
+                Some(data_with_creators(reshared_creators(&context, &second))),
+                None,
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CreatorChangeRequiresApproval);
+    }
+
+    #[tokio::test]
+    async fn fail_delegate_update_with_other_verified_creator() {
+        let mut context = program_test().start_with_context().await;
+        let second = Keypair::new();
+        let delegate = Keypair::new();
+        let test_metadata = create_metadata(&mut context, &second).await;
+
+        let (record, _) =
+            find_update_delegate_account(&test_metadata.mint.pubkey(), &delegate.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_update_delegate(
+                PROGRAM_ID,
+                record,
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                UpdateDelegateScopes {
+                    uri: false,
+                    name_symbol: false,
+                    creators: true,
+                    collection: false,
+                    uses: false,
+                    refill_uses: false,
+                },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_metadata_accounts_v2_as_delegate(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                delegate.pubkey(),
+                record,
+                data_with_creators(reshared_creators(&context, &second)),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &delegate],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CreatorChangeRequiresApproval);
+    }
+}
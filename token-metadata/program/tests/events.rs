@@ -0,0 +1,544 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    event::{decode_logs, MetadataEvent, VersionedEvent},
+    id, instruction,
+    pda::{
+        find_creator_change_proposal_account, find_metadata_attributes_account,
+        find_metadata_history_account, find_rule_set_account,
+    },
+    state::{Attribute, Collection, Creator, DataV2, UseMethod, Uses},
+};
+use solana_program::instruction::Instruction;
+use solana_program_test::*;
+use solana_sdk::{pubkey::Pubkey, signature::Signer, transaction::Transaction};
+use utils::*;
+
+mod events {
+    use super::*;
+
+    /// Processes the instructions and returns the token-metadata events they logged.
+    async fn process_with_events(
+        context: &mut ProgramTestContext,
+        instructions: &[Instruction],
+    ) -> Vec<MetadataEvent> {
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        // Simulating first is the only way to get at the logs of a transaction.
+        let simulation = context
+            .banks_client
+            .simulate_transaction(tx.clone())
+            .await
+            .unwrap();
+        simulation.result.unwrap().unwrap();
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let logs = simulation.simulation_details.unwrap().logs;
+        decode_logs(logs.iter().map(String::as_str))
+            .into_iter()
+            .map(|VersionedEvent::V1(event)| event)
+            .collect()
+    }
+
+    async fn create_nft(
+        context: &mut ProgramTestContext,
+        collection: Option<Collection>,
+        uses: Option<Uses>,
+        max_supply: Option<u64>,
+    ) -> (Metadata, MasterEditionV2) {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                collection,
+                uses,
+            )
+            .await
+            .unwrap();
+        let master_edition = MasterEditionV2::new(&test_metadata);
+        master_edition.create_v3(context, max_supply).await.unwrap();
+        (test_metadata, master_edition)
+    }
+
+    #[tokio::test]
+    async fn create_emits_metadata_created() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        let payer = context.payer.pubkey();
+        create_mint(&mut context, &test_metadata.mint, &payer, None, 0)
+            .await
+            .unwrap();
+
+        let events = process_with_events(
+            &mut context,
+            &[instruction::create_metadata_accounts_v3(
+                id(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                payer,
+                payer,
+                payer,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                true,
+                None,
+                None,
+                None,
+            )],
+        )
+        .await;
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            events,
+            vec![MetadataEvent::MetadataCreated {
+                metadata: test_metadata.pubkey,
+                mint: test_metadata.mint.pubkey(),
+                update_authority: payer,
+                data: metadata.data,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_emits_metadata_updated() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, _) = create_nft(&mut context, None, None, Some(0)).await;
+        let before = test_metadata.get_data(&mut context).await;
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[instruction::update_metadata_accounts_v2(
+                id(),
+                test_metadata.pubkey,
+                payer,
+                None,
+                Some(DataV2 {
+                    name: "Test2".to_string(),
+                    symbol: "TST2".to_string(),
+                    uri: "uri2".to_string(),
+                    creators: None,
+                    seller_fee_basis_points: 10,
+                    collection: None,
+                    uses: None,
+                }),
+                None,
+                None,
+            )],
+        )
+        .await;
+
+        let after = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            events,
+            vec![MetadataEvent::MetadataUpdated {
+                metadata: test_metadata.pubkey,
+                authority: payer,
+                before: (&before).into(),
+                after: (&after).into(),
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn verify_collection_emits_collection_verification_changed() {
+        let mut context = program_test().start_with_context().await;
+        let (test_collection, collection_master_edition) =
+            create_nft(&mut context, None, None, Some(0)).await;
+        let (test_metadata, _) = create_nft(
+            &mut context,
+            Some(Collection {
+                key: test_collection.mint.pubkey(),
+                verified: false,
+            }),
+            None,
+            Some(0),
+        )
+        .await;
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[instruction::verify_collection(
+                id(),
+                test_metadata.pubkey,
+                payer,
+                payer,
+                test_collection.mint.pubkey(),
+                test_collection.pubkey,
+                collection_master_edition.pubkey,
+                None,
+            )],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![MetadataEvent::CollectionVerificationChanged {
+                metadata: test_metadata.pubkey,
+                collection_mint: test_collection.mint.pubkey(),
+                collection_authority: payer,
+                verified: true,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn burn_emits_burned() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, master_edition) = create_nft(&mut context, None, None, Some(0)).await;
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[instruction::burn_nft(
+                id(),
+                test_metadata.pubkey,
+                payer,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                master_edition.pubkey,
+                spl_token::id(),
+                None,
+            )],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![MetadataEvent::Burned {
+                metadata: test_metadata.pubkey,
+                mint: test_metadata.mint.pubkey(),
+                owner: payer,
+                master_edition: None,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn print_emits_edition_printed() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, master_edition) = create_nft(&mut context, None, None, Some(10)).await;
+        let print = EditionMarker::new(&test_metadata, &master_edition, 1);
+
+        let payer = context.payer.pubkey();
+        create_mint(&mut context, &print.mint, &payer, None, 0)
+            .await
+            .unwrap();
+        create_token_account(&mut context, &print.token, &print.mint.pubkey(), &payer)
+            .await
+            .unwrap();
+        mint_tokens(
+            &mut context,
+            &print.mint.pubkey(),
+            &print.token.pubkey(),
+            1,
+            &payer,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let events = process_with_events(
+            &mut context,
+            &[instruction::mint_new_edition_from_master_edition_via_token(
+                id(),
+                print.new_metadata_pubkey,
+                print.new_edition_pubkey,
+                master_edition.pubkey,
+                print.mint.pubkey(),
+                payer,
+                payer,
+                payer,
+                test_metadata.token.pubkey(),
+                payer,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                1,
+            )],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![MetadataEvent::EditionPrinted {
+                master_metadata: test_metadata.pubkey,
+                master_edition: master_edition.pubkey,
+                new_metadata: print.new_metadata_pubkey,
+                new_edition: print.new_edition_pubkey,
+                new_mint: print.mint.pubkey(),
+                edition: 1,
+            }]
+        );
+    }
+
+    #[tokio::test]
+    async fn refill_emits_uses_refilled() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, _) = create_nft(
+            &mut context,
+            None,
+            Some(Uses {
+                use_method: UseMethod::Multiple,
+                total: 10,
+                remaining: 10,
+            }),
+            Some(0),
+        )
+        .await;
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[
+                instruction::utilize(
+                    id(),
+                    test_metadata.pubkey,
+                    test_metadata.token.pubkey(),
+                    test_metadata.mint.pubkey(),
+                    None,
+                    payer,
+                    payer,
+                    None,
+                    4,
+                ),
+                instruction::refill_uses(id(), test_metadata.pubkey, payer, None, 3),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                MetadataEvent::Utilized {
+                    metadata: test_metadata.pubkey,
+                    user: payer,
+                    number_of_uses: 4,
+                    remaining_before: 10,
+                    remaining_after: 6,
+                },
+                MetadataEvent::UsesRefilled {
+                    metadata: test_metadata.pubkey,
+                    authority: payer,
+                    refilled: 3,
+                    remaining_before: 6,
+                    remaining_after: 9,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn rule_set_emits_rule_set_created_and_updated() {
+        let mut context = program_test().start_with_context().await;
+        let payer = context.payer.pubkey();
+        let (rule_set, _) = find_rule_set_account(&payer);
+        let allowed_program = Pubkey::new_unique();
+        let escrow_authority = Pubkey::new_unique();
+
+        let events = process_with_events(
+            &mut context,
+            &[
+                instruction::create_rule_set(
+                    id(),
+                    rule_set,
+                    payer,
+                    payer,
+                    vec![allowed_program],
+                    vec![],
+                ),
+                instruction::update_rule_set(id(), rule_set, payer, vec![], vec![escrow_authority]),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                MetadataEvent::RuleSetCreated {
+                    rule_set,
+                    authority: payer,
+                    allowed_programs: vec![allowed_program],
+                    escrow_authorities: vec![],
+                },
+                MetadataEvent::RuleSetUpdated {
+                    rule_set,
+                    authority: payer,
+                    allowed_programs: vec![],
+                    escrow_authorities: vec![escrow_authority],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn attributes_emit_attribute_events() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, _) = create_nft(&mut context, None, None, Some(0)).await;
+        let (metadata_attributes, _) = find_metadata_attributes_account(&test_metadata.pubkey);
+        let attribute = |key: &str, value: &str| Attribute {
+            key: key.to_string(),
+            value: value.to_string(),
+        };
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[
+                instruction::create_metadata_attributes(
+                    id(),
+                    metadata_attributes,
+                    test_metadata.pubkey,
+                    payer,
+                    payer,
+                    vec![attribute("eyes", "blue"), attribute("hat", "none")],
+                ),
+                instruction::update_metadata_attributes(
+                    id(),
+                    metadata_attributes,
+                    test_metadata.pubkey,
+                    payer,
+                    vec![attribute("eyes", "green")],
+                ),
+                instruction::remove_metadata_attributes(
+                    id(),
+                    metadata_attributes,
+                    test_metadata.pubkey,
+                    payer,
+                    vec!["hat".to_string()],
+                ),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                MetadataEvent::MetadataAttributesCreated {
+                    metadata: test_metadata.pubkey,
+                    metadata_attributes,
+                    attributes: vec![attribute("eyes", "blue"), attribute("hat", "none")],
+                },
+                MetadataEvent::MetadataAttributesUpdated {
+                    metadata: test_metadata.pubkey,
+                    metadata_attributes,
+                    attributes: vec![attribute("eyes", "green")],
+                },
+                MetadataEvent::MetadataAttributesRemoved {
+                    metadata: test_metadata.pubkey,
+                    metadata_attributes,
+                    keys: vec!["hat".to_string()],
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn creator_change_emits_proposed_and_cancelled() {
+        let mut context = program_test().start_with_context().await;
+        let payer = context.payer.pubkey();
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                Some(vec![Creator {
+                    address: payer,
+                    verified: true,
+                    share: 100,
+                }]),
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let (proposal, _) = find_creator_change_proposal_account(&test_metadata.pubkey);
+        let creators = vec![Creator {
+            address: Pubkey::new_unique(),
+            verified: false,
+            share: 100,
+        }];
+
+        let events = process_with_events(
+            &mut context,
+            &[
+                instruction::propose_creator_change(
+                    id(),
+                    proposal,
+                    test_metadata.pubkey,
+                    payer,
+                    payer,
+                    creators.clone(),
+                ),
+                instruction::cancel_creator_change(id(), proposal, test_metadata.pubkey, payer),
+            ],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![
+                MetadataEvent::CreatorChangeProposed {
+                    metadata: test_metadata.pubkey,
+                    proposal,
+                    update_authority: payer,
+                    creators,
+                },
+                MetadataEvent::CreatorChangeCancelled {
+                    metadata: test_metadata.pubkey,
+                    proposal,
+                },
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn create_history_emits_metadata_history_created() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, _) = create_nft(&mut context, None, None, Some(0)).await;
+        let (metadata_history, _) = find_metadata_history_account(&test_metadata.pubkey);
+
+        let payer = context.payer.pubkey();
+        let events = process_with_events(
+            &mut context,
+            &[instruction::create_metadata_history(
+                id(),
+                metadata_history,
+                test_metadata.pubkey,
+                payer,
+                payer,
+            )],
+        )
+        .await;
+
+        assert_eq!(
+            events,
+            vec![MetadataEvent::MetadataHistoryCreated {
+                metadata: test_metadata.pubkey,
+                metadata_history,
+            }]
+        );
+    }
+}
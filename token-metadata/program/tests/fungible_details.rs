@@ -0,0 +1,279 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError, instruction, pda::find_fungible_mint_authority_account,
+    state::FungibleDetails, ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program::program_option::COption;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod fungible_details {
+    use super::*;
+
+    async fn create_capped_fungible(
+        context: &mut ProgramTestContext,
+        supply_cap: u64,
+    ) -> Result<Metadata, BanksClientError> {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Gold".to_string(),
+                "GLD".to_string(),
+                "uri".to_string(),
+                None,
+                0,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let (fungible_mint_authority, _) =
+            find_fungible_mint_authority_account(&test_metadata.mint.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_fungible_details(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                fungible_mint_authority,
+                spl_token::id(),
+                supply_cap,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await?;
+
+        Ok(test_metadata)
+    }
+
+    async fn mint_via_metadata(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let (fungible_mint_authority, _) =
+            find_fungible_mint_authority_account(&test_metadata.mint.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::mint_fungible_via_metadata(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                context.payer.pubkey(),
+                fungible_mint_authority,
+                spl_token::id(),
+                amount,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn update_fungible_details(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        new_mint_authority: &Pubkey,
+        supply_cap: Option<u64>,
+    ) -> Result<(), BanksClientError> {
+        let (fungible_mint_authority, _) =
+            find_fungible_mint_authority_account(&test_metadata.mint.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_fungible_details(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                context.payer.pubkey(),
+                fungible_mint_authority,
+                *new_mint_authority,
+                spl_token::id(),
+                supply_cap,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn mint_up_to_supply_cap() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_capped_fungible(&mut context, 100).await.unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.fungible_details,
+            Some(FungibleDetails::V1 { supply_cap: 100 })
+        );
+
+        let (fungible_mint_authority, _) =
+            find_fungible_mint_authority_account(&test_metadata.mint.pubkey());
+        let mint = get_mint(&mut context, &test_metadata.mint.pubkey()).await;
+        assert_eq!(mint.mint_authority, COption::Some(fungible_mint_authority));
+
+        mint_via_metadata(&mut context, &test_metadata, 99)
+            .await
+            .unwrap();
+        let mint = get_mint(&mut context, &test_metadata.mint.pubkey()).await;
+        assert_eq!(mint.supply, 100);
+
+        let err = mint_via_metadata(&mut context, &test_metadata, 1)
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::FungibleSupplyCapExceeded);
+    }
+
+    #[tokio::test]
+    async fn fail_cap_below_current_supply() {
+        let mut context = program_test().start_with_context().await;
+
+        let err = create_capped_fungible(&mut context, 0).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::FungibleSupplyCapExceeded);
+    }
+
+    #[tokio::test]
+    async fn fail_mint_without_fungible_details() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Gold".to_string(),
+                "GLD".to_string(),
+                "uri".to_string(),
+                None,
+                0,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let err = mint_via_metadata(&mut context, &test_metadata, 1)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MissingFungibleDetails);
+    }
+
+    #[tokio::test]
+    async fn raise_supply_cap() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_capped_fungible(&mut context, 10).await.unwrap();
+        mint_via_metadata(&mut context, &test_metadata, 9)
+            .await
+            .unwrap();
+
+        let payer = context.payer.pubkey();
+        update_fungible_details(&mut context, &test_metadata, &payer, Some(20))
+            .await
+            .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.fungible_details,
+            Some(FungibleDetails::V1 { supply_cap: 20 })
+        );
+        mint_via_metadata(&mut context, &test_metadata, 10)
+            .await
+            .unwrap();
+        let mint = get_mint(&mut context, &test_metadata.mint.pubkey()).await;
+        assert_eq!(mint.supply, 20);
+    }
+
+    #[tokio::test]
+    async fn fail_lower_supply_cap() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_capped_fungible(&mut context, 10).await.unwrap();
+
+        let payer = context.payer.pubkey();
+        let err = update_fungible_details(&mut context, &test_metadata, &payer, Some(9))
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::FungibleSupplyCapCannotDecrease);
+    }
+
+    #[tokio::test]
+    async fn remove_supply_cap() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_capped_fungible(&mut context, 10).await.unwrap();
+
+        let new_mint_authority = Keypair::new();
+        update_fungible_details(
+            &mut context,
+            &test_metadata,
+            &new_mint_authority.pubkey(),
+            None,
+        )
+        .await
+        .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.fungible_details, None);
+        let mint = get_mint(&mut context, &test_metadata.mint.pubkey()).await;
+        assert_eq!(
+            mint.mint_authority,
+            COption::Some(new_mint_authority.pubkey())
+        );
+
+        context.warp_to_slot(100).unwrap();
+        let err = mint_via_metadata(&mut context, &test_metadata, 1)
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::MissingFungibleDetails);
+    }
+
+    #[tokio::test]
+    async fn fail_update_invalid_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_capped_fungible(&mut context, 10).await.unwrap();
+
+        let fake_authority = Keypair::new();
+        let (fungible_mint_authority, _) =
+            find_fungible_mint_authority_account(&test_metadata.mint.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_fungible_details(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                fake_authority.pubkey(),
+                fungible_mint_authority,
+                fake_authority.pubkey(),
+                spl_token::id(),
+                None,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &fake_authority],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateAuthorityIncorrect);
+    }
+}
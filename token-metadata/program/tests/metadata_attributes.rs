@@ -0,0 +1,160 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::find_metadata_attributes_account,
+    state::{Attribute, MetadataAttributes, TokenMetadataAccount},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::Signer,
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod metadata_attributes {
+    use super::*;
+
+    fn attribute(key: &str, value: &str) -> Attribute {
+        Attribute {
+            key: key.to_string(),
+            value: value.to_string(),
+        }
+    }
+
+    async fn create_metadata(context: &mut ProgramTestContext, is_mutable: bool) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                is_mutable,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        test_metadata
+    }
+
+    async fn create_attributes(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        attributes: Vec<Attribute>,
+    ) -> Result<Pubkey, BanksClientError> {
+        let (metadata_attributes, _) = find_metadata_attributes_account(&test_metadata.pubkey);
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_metadata_attributes(
+                PROGRAM_ID,
+                metadata_attributes,
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                attributes,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await?;
+        Ok(metadata_attributes)
+    }
+
+    async fn get_attributes(
+        context: &mut ProgramTestContext,
+        metadata_attributes: &Pubkey,
+    ) -> MetadataAttributes {
+        let account = get_account(context, metadata_attributes).await;
+        MetadataAttributes::safe_deserialize(&account.data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_update_and_remove() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context, true).await;
+
+        let metadata_attributes = create_attributes(
+            &mut context,
+            &test_metadata,
+            vec![attribute("tier", "Silver"), attribute("level", "3")],
+        )
+        .await
+        .unwrap();
+
+        let attributes = get_attributes(&mut context, &metadata_attributes).await;
+        assert_eq!(attributes.metadata, test_metadata.pubkey);
+        assert_eq!(attributes.get("tier"), Some("Silver"));
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                instruction::update_metadata_attributes(
+                    PROGRAM_ID,
+                    metadata_attributes,
+                    test_metadata.pubkey,
+                    context.payer.pubkey(),
+                    vec![attribute("tier", "Gold"), attribute("rank", "1")],
+                ),
+                instruction::remove_metadata_attributes(
+                    PROGRAM_ID,
+                    metadata_attributes,
+                    test_metadata.pubkey,
+                    context.payer.pubkey(),
+                    vec!["level".to_string()],
+                ),
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let attributes = get_attributes(&mut context, &metadata_attributes).await;
+        assert_eq!(attributes.attributes.len(), 2);
+        assert_eq!(attributes.get("tier"), Some("Gold"));
+        assert_eq!(attributes.get("rank"), Some("1"));
+        assert_eq!(attributes.get("level"), None);
+    }
+
+    #[tokio::test]
+    async fn fail_duplicate_keys() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context, true).await;
+
+        let err = create_attributes(
+            &mut context,
+            &test_metadata,
+            vec![attribute("tier", "Silver"), attribute("tier", "Gold")],
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::DuplicateAttributeKey);
+    }
+
+    #[tokio::test]
+    async fn fail_immutable_metadata() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_metadata(&mut context, false).await;
+
+        let err = create_attributes(
+            &mut context,
+            &test_metadata,
+            vec![attribute("tier", "Gold")],
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::DataIsImmutable);
+    }
+}
@@ -0,0 +1,392 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::find_metadata_history_account,
+    state::{
+        hash_data, Collection, CollectionDetails, Creator, DataV2, MetadataHistory,
+        TokenMetadataAccount, HISTORY_COLLECTION_DETAILS_CHANGED, HISTORY_TOKEN_STANDARD_CHANGED,
+        HISTORY_URI_CHANGED,
+    },
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::{Instruction, InstructionError},
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod metadata_history {
+    use super::*;
+
+    async fn create_metadata_with_history(context: &mut ProgramTestContext) -> (Metadata, Pubkey) {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let metadata_history = enable_history(context, &test_metadata).await;
+        (test_metadata, metadata_history)
+    }
+
+    async fn enable_history(context: &mut ProgramTestContext, test_metadata: &Metadata) -> Pubkey {
+        let (metadata_history, _) = find_metadata_history_account(&test_metadata.pubkey);
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_metadata_history(
+                PROGRAM_ID,
+                metadata_history,
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        metadata_history
+    }
+
+    fn update_uri(test_metadata: &Metadata, update_authority: Pubkey, uri: &str) -> Instruction {
+        instruction::update_metadata_accounts_v2(
+            PROGRAM_ID,
+            test_metadata.pubkey,
+            update_authority,
+            None,
+            Some(DataV2 {
+                name: "Test".to_string(),
+                symbol: "TST".to_string(),
+                uri: uri.to_string(),
+                creators: None,
+                seller_fee_basis_points: 10,
+                collection: None,
+                uses: None,
+            }),
+            None,
+            None,
+        )
+    }
+
+    async fn process(
+        context: &mut ProgramTestContext,
+        ix: Instruction,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn get_history(
+        context: &mut ProgramTestContext,
+        metadata_history: &Pubkey,
+    ) -> MetadataHistory {
+        let account = get_account(context, metadata_history).await;
+        MetadataHistory::safe_deserialize(&account.data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn records_updates() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+
+        let history = get_history(&mut context, &metadata_history).await;
+        assert_eq!(history.metadata, test_metadata.pubkey);
+        assert_eq!(history.total_entries, 1);
+        let original = test_metadata.get_data(&mut context).await;
+        assert!(original.history_enabled);
+
+        let ix = update_uri(&test_metadata, context.payer.pubkey(), "swapped");
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::record_metadata_history(
+                PROGRAM_ID,
+                metadata_history,
+                ix,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert!(metadata.data.uri.starts_with("swapped"));
+
+        let history = get_history(&mut context, &metadata_history).await;
+        assert_eq!(history.total_entries, 2);
+        let entry = history.ordered_entries()[1].clone();
+        assert_eq!(entry.prior_data_hash, hash_data(&original.data));
+        assert_eq!(entry.signer, context.payer.pubkey());
+        assert_eq!(entry.changed_fields, HISTORY_URI_CHANGED);
+    }
+
+    #[tokio::test]
+    async fn records_updates_with_trailing_history() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+        let original = test_metadata.get_data(&mut context).await;
+
+        let ix = update_uri(&test_metadata, context.payer.pubkey(), "swapped");
+        process(
+            &mut context,
+            instruction::with_metadata_history(ix, metadata_history),
+        )
+        .await
+        .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert!(metadata.data.uri.starts_with("swapped"));
+
+        let history = get_history(&mut context, &metadata_history).await;
+        assert_eq!(history.total_entries, 2);
+        let entry = history.ordered_entries()[1].clone();
+        assert_eq!(entry.prior_data_hash, hash_data(&original.data));
+        assert_eq!(entry.changed_fields, HISTORY_URI_CHANGED);
+    }
+
+    #[tokio::test]
+    async fn update_without_history_account_is_not_recorded() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+
+        let ix = update_uri(&test_metadata, context.payer.pubkey(), "swapped");
+        process(&mut context, ix).await.unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert!(metadata.data.uri.starts_with("swapped"));
+        let history = get_history(&mut context, &metadata_history).await;
+        assert_eq!(history.total_entries, 1);
+    }
+
+    #[tokio::test]
+    async fn fail_history_of_another_metadata() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, _) = create_metadata_with_history(&mut context).await;
+        let (_, other_history) = create_metadata_with_history(&mut context).await;
+
+        let ix = update_uri(&test_metadata, context.payer.pubkey(), "swapped");
+        let err = process(
+            &mut context,
+            instruction::with_metadata_history(ix, other_history),
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MissingMetadataHistory);
+    }
+
+    #[tokio::test]
+    async fn records_token_standard_changes() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+
+        // The history is not mistaken for the optional trailing edition account.
+        let ix = instruction::set_token_standard(
+            PROGRAM_ID,
+            test_metadata.pubkey,
+            context.payer.pubkey(),
+            test_metadata.mint.pubkey(),
+            None,
+        );
+        process(
+            &mut context,
+            instruction::with_metadata_history(ix, metadata_history),
+        )
+        .await
+        .unwrap();
+
+        let history = get_history(&mut context, &metadata_history).await;
+        assert_eq!(history.total_entries, 2);
+        let entry = history.ordered_entries()[1].clone();
+        assert_eq!(entry.changed_fields, HISTORY_TOKEN_STANDARD_CHANGED);
+    }
+
+    #[tokio::test]
+    async fn records_collection_size_changes() {
+        let mut context = program_test().start_with_context().await;
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+                DEFAULT_COLLECTION_DETAILS,
+            )
+            .await
+            .unwrap();
+        let collection_master_edition = MasterEditionV2::new(&test_collection);
+        collection_master_edition
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+        let collection_history = enable_history(&mut context, &test_collection).await;
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                Some(Collection {
+                    key: test_collection.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+            )
+            .await
+            .unwrap();
+
+        let ix = instruction::verify_sized_collection_item(
+            PROGRAM_ID,
+            test_metadata.pubkey,
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            test_collection.mint.pubkey(),
+            test_collection.pubkey,
+            collection_master_edition.pubkey,
+            None,
+        );
+        process(
+            &mut context,
+            instruction::with_metadata_history(ix, collection_history),
+        )
+        .await
+        .unwrap();
+
+        let collection = test_collection.get_data(&mut context).await;
+        assert_eq!(
+            collection.collection_details,
+            Some(CollectionDetails::V1 { size: 1 })
+        );
+        let history = get_history(&mut context, &collection_history).await;
+        assert_eq!(history.total_entries, 2);
+        let entry = history.ordered_entries()[1].clone();
+        assert_eq!(entry.signer, context.payer.pubkey());
+        assert_eq!(entry.changed_fields, HISTORY_COLLECTION_DETAILS_CHANGED);
+    }
+
+    #[tokio::test]
+    async fn fail_record_unsupported_instruction() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+
+        let ix = instruction::record_metadata_history(
+            PROGRAM_ID,
+            metadata_history,
+            instruction::puff_metadata_account(PROGRAM_ID, test_metadata.pubkey),
+        );
+        let err = process(&mut context, ix).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataHistoryNotSupported);
+    }
+
+    #[tokio::test]
+    async fn fail_batch_with_history_before_any_write() {
+        let mut context = program_test().start_with_context().await;
+        let creator = Keypair::new();
+        let creators = Some(vec![Creator {
+            address: creator.pubkey(),
+            verified: false,
+            share: 100,
+        }]);
+
+        let plain_metadata = Metadata::new();
+        plain_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                creators,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        // Has no creators, so it would fail only after the first metadata was signed if it was
+        // checked in the loop.
+        let (test_metadata, _) = create_metadata_with_history(&mut context).await;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::sign_metadata_batch(
+                PROGRAM_ID,
+                creator.pubkey(),
+                &[plain_metadata.pubkey, test_metadata.pubkey],
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, &creator],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataHistoryNotSupportedInBatch);
+    }
+
+    #[tokio::test]
+    async fn fail_enable_history_twice() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, metadata_history) = create_metadata_with_history(&mut context).await;
+
+        context.warp_to_slot(100).unwrap();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_metadata_history(
+                PROGRAM_ID,
+                metadata_history,
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataHistoryAlreadyEnabled);
+    }
+}
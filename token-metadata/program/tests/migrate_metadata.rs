@@ -0,0 +1,160 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    state::{Key, MetadataV2, TokenMetadataAccount, MIN_METADATA_V2_LEN},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod migrate_metadata {
+    use super::*;
+
+    async fn migrate(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        update_authority: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::migrate_metadata(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                update_authority.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, update_authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let before = test_metadata.get_data(&mut context).await;
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        migrate(&mut context, &test_metadata, &payer).await.unwrap();
+
+        let account = get_account(&mut context, &test_metadata.pubkey).await;
+        assert_eq!(account.data.len(), MIN_METADATA_V2_LEN);
+        assert_eq!(account.data[0], Key::MetadataV2 as u8);
+
+        let migrated: MetadataV2 = MetadataV2::safe_deserialize(&account.data).unwrap();
+        assert_eq!(migrated.metadata.key, Key::MetadataV2);
+        assert_eq!(migrated.metadata.data, before.data);
+        assert!(migrated.extensions.is_empty());
+
+        // The metadata fields stay readable as `Metadata`.
+        let metadata: mpl_token_metadata::state::Metadata =
+            mpl_token_metadata::state::Metadata::safe_deserialize(&account.data).unwrap();
+        assert_eq!(metadata, migrated.metadata);
+
+        // Existing instructions keep working on migrated accounts.
+        test_metadata
+            .update_v2(
+                &mut context,
+                "Test2".to_string(),
+                "TST2".to_string(),
+                "uri2".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let account = get_account(&mut context, &test_metadata.pubkey).await;
+        assert_eq!(account.data.len(), MIN_METADATA_V2_LEN);
+        let migrated: MetadataV2 = MetadataV2::safe_deserialize(&account.data).unwrap();
+        assert_eq!(migrated.metadata.key, Key::MetadataV2);
+        assert!(migrated.metadata.data.name.starts_with("Test2"));
+    }
+
+    #[tokio::test]
+    async fn fail_already_migrated() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        migrate(&mut context, &test_metadata, &payer).await.unwrap();
+
+        context.warp_to_slot(100).unwrap();
+        let err = migrate(&mut context, &test_metadata, &payer)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MetadataAlreadyMigrated);
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let fake_authority = Keypair::new();
+        let err = migrate(&mut context, &test_metadata, &fake_authority)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateAuthorityIncorrect);
+    }
+}
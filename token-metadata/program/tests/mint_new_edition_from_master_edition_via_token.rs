@@ -1,8 +1,14 @@
 #![cfg(feature = "test-bpf")]
 pub mod utils;
 
-use mpl_token_metadata::{error::MetadataError, id, instruction, state::Key};
+use mpl_token_metadata::{
+    error::MetadataError,
+    id,
+    instruction::{self, EditionOverrides},
+    state::Key,
+};
 use num_traits::FromPrimitive;
+use solana_program::borsh::try_from_slice_unchecked;
 use solana_program_test::*;
 use solana_sdk::{
     instruction::InstructionError,
@@ -298,4 +304,104 @@ mod mint_new_edition_from_master_edition_via_token {
         let result = test_edition_marker.create(&mut context).await.unwrap_err();
         assert_custom_error!(result, MetadataError::EditionOverrideCannotBeZero);
     }
+
+    async fn create_master(context: &mut ProgramTestContext) -> (Metadata, MasterEditionV2) {
+        let test_metadata = Metadata::new();
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+
+        test_metadata
+            .create(
+                context,
+                "Ticket".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                0,
+            )
+            .await
+            .unwrap();
+
+        test_master_edition.create(context, Some(10)).await.unwrap();
+
+        (test_metadata, test_master_edition)
+    }
+
+    #[tokio::test]
+    async fn success_with_overrides() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master(&mut context).await;
+        let test_edition_marker = EditionMarker::new(&test_metadata, &test_master_edition, 7);
+        let update_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        test_edition_marker
+            .create_with_overrides(
+                &mut context,
+                &update_authority,
+                EditionOverrides {
+                    name_suffix: Some(" #{edition}".to_string()),
+                    uri: Some("https://example.com/7.json".to_string()),
+                },
+            )
+            .await
+            .unwrap();
+
+        let account = get_account(&mut context, &test_edition_marker.new_metadata_pubkey).await;
+        let metadata: mpl_token_metadata::state::Metadata =
+            try_from_slice_unchecked(&account.data).unwrap();
+
+        assert_eq!(
+            metadata.data.name.trim_end_matches(char::from(0)),
+            "Ticket #7"
+        );
+        assert_eq!(
+            metadata.data.uri.trim_end_matches(char::from(0)),
+            "https://example.com/7.json"
+        );
+        assert_eq!(metadata.data.symbol.trim_end_matches(char::from(0)), "TST");
+    }
+
+    #[tokio::test]
+    async fn fail_overrides_name_too_long() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master(&mut context).await;
+        let test_edition_marker = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+        let update_authority = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        let result = test_edition_marker
+            .create_with_overrides(
+                &mut context,
+                &update_authority,
+                EditionOverrides {
+                    name_suffix: Some(" - a suffix that does not fit #{edition}".to_string()),
+                    uri: None,
+                },
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(result, MetadataError::NameTooLong);
+    }
+
+    #[tokio::test]
+    async fn fail_overrides_without_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let (test_metadata, test_master_edition) = create_master(&mut context).await;
+        let test_edition_marker = EditionMarker::new(&test_metadata, &test_master_edition, 1);
+
+        let result = test_edition_marker
+            .create_with_overrides(
+                &mut context,
+                &Keypair::new(),
+                EditionOverrides {
+                    name_suffix: None,
+                    uri: Some("https://example.com/1.json".to_string()),
+                },
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(result, MetadataError::UpdateAuthorityIncorrect);
+    }
 }
@@ -0,0 +1,209 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::find_update_delegate_account,
+    state::{UpdateDelegateScopes, UseMethod, Uses},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod refill_uses {
+    use super::*;
+
+    async fn create_used_metadata(
+        context: &mut ProgramTestContext,
+        used: u64,
+        is_mutable: bool,
+    ) -> Metadata {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                is_mutable,
+                None,
+                None,
+                Some(Uses {
+                    use_method: UseMethod::Multiple,
+                    total: 10,
+                    remaining: 10,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::utilize(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                test_metadata.token.pubkey(),
+                test_metadata.mint.pubkey(),
+                None,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                None,
+                used,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        test_metadata
+    }
+
+    async fn approve_delegate(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        delegate: &Keypair,
+        scopes: UpdateDelegateScopes,
+    ) -> Pubkey {
+        let (record, _) =
+            find_update_delegate_account(&test_metadata.mint.pubkey(), &delegate.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_update_delegate(
+                PROGRAM_ID,
+                record,
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                scopes,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+        record
+    }
+
+    async fn refill(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        authority: &Keypair,
+        update_delegate_record: Option<Pubkey>,
+        amount: u64,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::refill_uses(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                authority.pubkey(),
+                update_delegate_record,
+                amount,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn update_authority_refills_up_to_total() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_used_metadata(&mut context, 6, true).await;
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        refill(&mut context, &test_metadata, &payer, None, 4)
+            .await
+            .unwrap();
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.uses.unwrap().remaining, 8);
+
+        refill(&mut context, &test_metadata, &payer, None, 5)
+            .await
+            .unwrap();
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(
+            metadata.uses,
+            Some(Uses {
+                use_method: UseMethod::Multiple,
+                total: 10,
+                remaining: 10,
+            })
+        );
+    }
+
+    #[tokio::test]
+    async fn update_authority_refills_immutable_metadata() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_used_metadata(&mut context, 3, false).await;
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+
+        refill(&mut context, &test_metadata, &payer, None, 3)
+            .await
+            .unwrap();
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert!(!metadata.is_mutable);
+        assert_eq!(metadata.uses.unwrap().remaining, 10);
+    }
+
+    #[tokio::test]
+    async fn refill_delegate_refills() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_used_metadata(&mut context, 10, true).await;
+        let delegate = Keypair::new();
+        let scopes = UpdateDelegateScopes {
+            refill_uses: true,
+            ..Default::default()
+        };
+        let record = approve_delegate(&mut context, &test_metadata, &delegate, scopes).await;
+
+        refill(&mut context, &test_metadata, &delegate, Some(record), 3)
+            .await
+            .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.uses.unwrap().remaining, 3);
+    }
+
+    #[tokio::test]
+    async fn fail_delegate_without_refill_scope() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_used_metadata(&mut context, 10, true).await;
+        let delegate = Keypair::new();
+        let scopes = UpdateDelegateScopes {
+            uses: true,
+            ..Default::default()
+        };
+        let record = approve_delegate(&mut context, &test_metadata, &delegate, scopes).await;
+
+        let err = refill(&mut context, &test_metadata, &delegate, Some(record), 3)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateDelegateScopeMissing);
+    }
+
+    #[tokio::test]
+    async fn fail_invalid_update_authority() {
+        let mut context = program_test().start_with_context().await;
+        let test_metadata = create_used_metadata(&mut context, 10, true).await;
+        let fake_authority = Keypair::new();
+
+        let err = refill(&mut context, &test_metadata, &fake_authority, None, 3)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateAuthorityIncorrect);
+    }
+}
@@ -0,0 +1,321 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::{find_master_edition_account, find_rule_set_account},
+    state::{Key, RuleSet, TokenMetadataAccount},
+};
+use num_traits::FromPrimitive;
+use solana_program::program_pack::Pack;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod rule_set {
+    use super::*;
+
+    async fn create_rule_set(
+        context: &mut ProgramTestContext,
+        escrow_authorities: Vec<Pubkey>,
+    ) -> Pubkey {
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_rule_set(
+                mpl_token_metadata::id(),
+                rule_set,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                vec![],
+                escrow_authorities,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+        rule_set
+    }
+
+    async fn create_bound_nft(
+        context: &mut ProgramTestContext,
+        rule_set: &Pubkey,
+    ) -> (Metadata, MasterEditionV2) {
+        let freeze_authority = context.payer.pubkey();
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                Some(&freeze_authority),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let test_master_edition = MasterEditionV2::new(&test_metadata);
+        test_master_edition
+            .create_v3(context, Some(0))
+            .await
+            .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::bind_rule_set(
+                mpl_token_metadata::id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                *rule_set,
+                test_master_edition.pubkey,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        (test_metadata, test_master_edition)
+    }
+
+    async fn get_token_account(
+        context: &mut ProgramTestContext,
+        pubkey: &Pubkey,
+    ) -> spl_token::state::Account {
+        let account = get_account(context, pubkey).await;
+        spl_token::state::Account::unpack(&account.data).unwrap()
+    }
+
+    #[tokio::test]
+    async fn create_rule_set_success() {
+        let mut context = program_test().start_with_context().await;
+        let escrow = Keypair::new();
+
+        let rule_set = create_rule_set(&mut context, vec![escrow.pubkey()]).await;
+
+        let account = get_account(&mut context, &rule_set).await;
+        let rule_set_data: RuleSet = RuleSet::safe_deserialize(&account.data).unwrap();
+        assert_eq!(rule_set_data.key, Key::RuleSet);
+        assert_eq!(rule_set_data.authority, context.payer.pubkey());
+        assert!(rule_set_data.allowed_programs.is_empty());
+        assert_eq!(rule_set_data.escrow_authorities, vec![escrow.pubkey()]);
+    }
+
+    #[tokio::test]
+    async fn create_rule_set_too_many_entries() {
+        let mut context = program_test().start_with_context().await;
+        let (rule_set, _) = find_rule_set_account(&context.payer.pubkey());
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::create_rule_set(
+                mpl_token_metadata::id(),
+                rule_set,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                (0..9).map(|_| Pubkey::new_unique()).collect(),
+                vec![],
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::TooManyRuleSetEntries);
+    }
+
+    #[tokio::test]
+    async fn bind_rule_set_freezes_token() {
+        let mut context = program_test().start_with_context().await;
+        let rule_set = create_rule_set(&mut context, vec![]).await;
+
+        let (test_metadata, _) = create_bound_nft(&mut context, &rule_set).await;
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert_eq!(metadata.rule_set, Some(rule_set));
+
+        let token = get_token_account(&mut context, &test_metadata.token.pubkey()).await;
+        assert!(token.is_frozen());
+
+        // plain spl-token transfers are no longer possible
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap();
+        let transfer_ix = spl_token::instruction::transfer(
+            &spl_token::id(),
+            &test_metadata.token.pubkey(),
+            &destination.pubkey(),
+            &context.payer.pubkey(),
+            &[],
+            1,
+        )
+        .unwrap();
+        let transfer_tx = Transaction::new_signed_with_payer(
+            &[transfer_ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(transfer_tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, spl_token::error::TokenError::AccountFrozen);
+    }
+
+    #[tokio::test]
+    async fn bind_rule_set_requires_non_fungible() {
+        let mut context = program_test().start_with_context().await;
+        let rule_set = create_rule_set(&mut context, vec![]).await;
+
+        // Without a master edition the asset is not a NonFungible.
+        let freeze_authority = context.payer.pubkey();
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                Some(&freeze_authority),
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+        let (edition, _) = find_master_edition_account(&test_metadata.mint.pubkey());
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::bind_rule_set(
+                mpl_token_metadata::id(),
+                test_metadata.pubkey,
+                context.payer.pubkey(),
+                rule_set,
+                edition,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::RuleSetRequiresNonFungible);
+    }
+
+    #[tokio::test]
+    async fn transfer_to_non_escrow_fails() {
+        let mut context = program_test().start_with_context().await;
+        let rule_set = create_rule_set(&mut context, vec![]).await;
+        let (test_metadata, test_master_edition) = create_bound_nft(&mut context, &rule_set).await;
+
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &Keypair::new().pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::transfer(
+                mpl_token_metadata::id(),
+                test_metadata.pubkey,
+                rule_set,
+                test_master_edition.pubkey,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                destination.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::ProgramNotAllowedByRuleSet);
+    }
+
+    #[tokio::test]
+    async fn transfer_to_escrow_success() {
+        let mut context = program_test().start_with_context().await;
+        let escrow = Keypair::new();
+        let rule_set = create_rule_set(&mut context, vec![escrow.pubkey()]).await;
+        let (test_metadata, test_master_edition) = create_bound_nft(&mut context, &rule_set).await;
+
+        let destination = Keypair::new();
+        create_token_account(
+            &mut context,
+            &destination,
+            &test_metadata.mint.pubkey(),
+            &escrow.pubkey(),
+        )
+        .await
+        .unwrap();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::transfer(
+                mpl_token_metadata::id(),
+                test_metadata.pubkey,
+                rule_set,
+                test_master_edition.pubkey,
+                test_metadata.mint.pubkey(),
+                test_metadata.token.pubkey(),
+                destination.pubkey(),
+                context.payer.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let source = get_token_account(&mut context, &test_metadata.token.pubkey()).await;
+        assert_eq!(source.amount, 0);
+
+        let destination = get_token_account(&mut context, &destination.pubkey()).await;
+        assert_eq!(destination.amount, 1);
+        assert!(destination.is_frozen());
+    }
+}
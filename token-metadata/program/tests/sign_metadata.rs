@@ -11,7 +11,9 @@ mod sign_metadata {
 
     use solana_sdk::transaction::Transaction;
 
-    use mpl_token_metadata::instruction::{remove_creator_verification, sign_metadata};
+    use mpl_token_metadata::instruction::{
+        remove_creator_verification, sign_metadata, sign_metadata_batch,
+    };
     use mpl_token_metadata::state::Creator;
 
     use super::*;
@@ -82,4 +84,66 @@ mod sign_metadata {
         let after_remove = test_meta.get_data(&mut context).await;
         assert!(!after_remove.data.creators.unwrap()[1].verified);
     }
+
+    #[tokio::test]
+    async fn success_verify_creator_batch() {
+        let mut context = program_test().start_with_context().await;
+        let creator = Keypair::new();
+        let ua_creator = context.payer.pubkey();
+
+        let mut test_metas = vec![];
+        for _ in 0..3 {
+            let test_meta = Metadata::new();
+            test_meta
+                .create_v2(
+                    &mut context,
+                    "Test".to_string(),
+                    "TST".to_string(),
+                    "uri".to_string(),
+                    Some(vec![
+                        Creator {
+                            address: ua_creator,
+                            verified: true,
+                            share: 0,
+                        },
+                        Creator {
+                            address: creator.pubkey(),
+                            verified: false,
+                            share: 100,
+                        },
+                    ]),
+                    10,
+                    false,
+                    None,
+                    None,
+                    None,
+                )
+                .await
+                .unwrap();
+            test_metas.push(test_meta);
+        }
+
+        let metadata_accounts: Vec<_> = test_metas.iter().map(|meta| meta.pubkey).collect();
+        let sign_ix = sign_metadata_batch(
+            mpl_token_metadata::id(),
+            creator.pubkey(),
+            &metadata_accounts,
+        );
+        let sign_tx = Transaction::new_signed_with_payer(
+            &[sign_ix],
+            Some(&context.payer.pubkey()),
+            &[&creator, &context.payer],
+            context.last_blockhash,
+        );
+        context
+            .banks_client
+            .process_transaction(sign_tx)
+            .await
+            .unwrap();
+
+        for test_meta in test_metas {
+            let after_sign = test_meta.get_data(&mut context).await;
+            assert!(after_sign.data.creators.unwrap()[1].verified);
+        }
+    }
 }
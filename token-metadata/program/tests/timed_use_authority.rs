@@ -0,0 +1,243 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::{find_program_as_burner_account, find_use_authority_account},
+    state::{UseAuthorityRecord, UseMethod, Uses, TIMED_USE_AUTHORITY_RECORD_SIZE},
+};
+use num_traits::FromPrimitive;
+use solana_program::{borsh::try_from_slice_unchecked, clock::Clock};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod timed_use_authority {
+    use super::*;
+
+    async fn now(context: &mut ProgramTestContext) -> i64 {
+        context
+            .banks_client
+            .get_sysvar::<Clock>()
+            .await
+            .unwrap()
+            .unix_timestamp
+    }
+
+    async fn warp_to_timestamp(context: &mut ProgramTestContext, unix_timestamp: i64) {
+        let mut clock = context.banks_client.get_sysvar::<Clock>().await.unwrap();
+        clock.unix_timestamp = unix_timestamp;
+        context.set_sysvar(&clock);
+    }
+
+    async fn setup(
+        context: &mut ProgramTestContext,
+        use_authority: &Keypair,
+        not_before: Option<i64>,
+        expires_at: Option<i64>,
+    ) -> (Metadata, Pubkey) {
+        let test_meta = Metadata::new();
+        test_meta
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                Some(Uses {
+                    use_method: UseMethod::Multiple,
+                    total: 10,
+                    remaining: 10,
+                }),
+            )
+            .await
+            .unwrap();
+
+        let (record, _) =
+            find_use_authority_account(&test_meta.mint.pubkey(), &use_authority.pubkey());
+        let (burner, _) = find_program_as_burner_account();
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_timed_use_authority(
+                mpl_token_metadata::id(),
+                record,
+                use_authority.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_meta.token.pubkey(),
+                test_meta.pubkey,
+                test_meta.mint.pubkey(),
+                burner,
+                5,
+                not_before,
+                expires_at,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        airdrop(context, &use_authority.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+
+        (test_meta, record)
+    }
+
+    async fn utilize(
+        context: &mut ProgramTestContext,
+        test_meta: &Metadata,
+        use_authority: &Keypair,
+        record: Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let (burner, _) = find_program_as_burner_account();
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::utilize(
+                mpl_token_metadata::id(),
+                test_meta.pubkey,
+                test_meta.token.pubkey(),
+                test_meta.mint.pubkey(),
+                Some(record),
+                use_authority.pubkey(),
+                context.payer.pubkey(),
+                Some(burner),
+                1,
+            )],
+            Some(&use_authority.pubkey()),
+            &[use_authority],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn close_expired(
+        context: &mut ProgramTestContext,
+        test_meta: &Metadata,
+        use_authority: &Keypair,
+        record: Pubkey,
+        closer: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::close_expired_use_authority_record(
+                mpl_token_metadata::id(),
+                record,
+                context.payer.pubkey(),
+                use_authority.pubkey(),
+                test_meta.token.pubkey(),
+                test_meta.mint.pubkey(),
+            )],
+            Some(&closer.pubkey()),
+            &[closer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn success_within_time_window() {
+        let mut context = program_test().start_with_context().await;
+        let use_authority = Keypair::new();
+        let now = now(&mut context).await;
+        let (test_meta, record) = setup(
+            &mut context,
+            &use_authority,
+            Some(now - 10),
+            Some(now + 1_000),
+        )
+        .await;
+
+        let account = get_account(&mut context, &record).await;
+        assert_eq!(account.data.len(), TIMED_USE_AUTHORITY_RECORD_SIZE);
+        let record_acct: UseAuthorityRecord = try_from_slice_unchecked(&account.data).unwrap();
+        assert_eq!(record_acct.not_before, Some(now - 10));
+        assert_eq!(record_acct.expires_at, Some(now + 1_000));
+
+        utilize(&mut context, &test_meta, &use_authority, record)
+            .await
+            .unwrap();
+
+        let account = get_account(&mut context, &record).await;
+        let record_acct: UseAuthorityRecord = try_from_slice_unchecked(&account.data).unwrap();
+        assert_eq!(record_acct.allowed_uses, 4);
+    }
+
+    #[tokio::test]
+    async fn fail_utilize_before_not_before() {
+        let mut context = program_test().start_with_context().await;
+        let use_authority = Keypair::new();
+        let now = now(&mut context).await;
+        let (test_meta, record) =
+            setup(&mut context, &use_authority, Some(now + 1_000), None).await;
+
+        let err = utilize(&mut context, &test_meta, &use_authority, record)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UseAuthorityRecordNotActive);
+    }
+
+    #[tokio::test]
+    async fn expired_record_cannot_utilize_and_can_be_closed_by_anyone() {
+        let mut context = program_test().start_with_context().await;
+        let use_authority = Keypair::new();
+        let now = now(&mut context).await;
+        let (test_meta, record) = setup(&mut context, &use_authority, None, Some(now + 100)).await;
+
+        warp_to_timestamp(&mut context, now + 200).await;
+
+        let err = utilize(&mut context, &test_meta, &use_authority, record)
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::UseAuthorityRecordExpired);
+
+        let closer = Keypair::new();
+        airdrop(&mut context, &closer.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+
+        let owner = context.payer.pubkey();
+        let owner_lamports = get_account(&mut context, &owner).await.lamports;
+        let record_lamports = get_account(&mut context, &record).await.lamports;
+
+        close_expired(&mut context, &test_meta, &use_authority, record, &closer)
+            .await
+            .unwrap();
+
+        let record_account = context.banks_client.get_account(record).await.unwrap();
+        assert!(record_account.is_none());
+
+        // The rent goes to the owner, while the closer only pays the fee.
+        let owner_account = get_account(&mut context, &owner).await;
+        assert_eq!(owner_account.lamports, owner_lamports + record_lamports);
+    }
+
+    #[tokio::test]
+    async fn fail_close_unexpired_record() {
+        let mut context = program_test().start_with_context().await;
+        let use_authority = Keypair::new();
+        let now = now(&mut context).await;
+        let (test_meta, record) =
+            setup(&mut context, &use_authority, None, Some(now + 1_000)).await;
+
+        let closer = Keypair::new();
+        airdrop(&mut context, &closer.pubkey(), 1_000_000_000)
+            .await
+            .unwrap();
+        let err = close_expired(&mut context, &test_meta, &use_authority, record, &closer)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UseAuthorityRecordNotExpired);
+    }
+}
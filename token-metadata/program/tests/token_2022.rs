@@ -0,0 +1,439 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    id, instruction,
+    pda::{find_master_edition_account, find_metadata_account},
+    state::{
+        Collection, MasterEditionV2 as ProgramMasterEdition, Metadata as ProgramMetadata,
+        TokenMetadataAccount, TokenStandard,
+    },
+};
+use num_traits::FromPrimitive;
+use solana_program::{instruction::Instruction, program_pack::Pack};
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    system_instruction,
+    transaction::{Transaction, TransactionError},
+};
+use spl_token_2022::extension::ExtensionType;
+use utils::*;
+
+mod token_2022 {
+    use super::*;
+
+    struct Nft {
+        mint: Keypair,
+        token: Keypair,
+        metadata: Pubkey,
+        edition: Pubkey,
+    }
+
+    impl Nft {
+        fn new() -> Self {
+            let mint = Keypair::new();
+            let (metadata, _) = find_metadata_account(&mint.pubkey());
+            let (edition, _) = find_master_edition_account(&mint.pubkey());
+            Nft {
+                mint,
+                token: Keypair::new(),
+                metadata,
+                edition,
+            }
+        }
+    }
+
+    /// The builders default to spl-token; point them at Token-2022 instead.
+    fn with_token_2022(mut ix: Instruction) -> Instruction {
+        for account in ix.accounts.iter_mut() {
+            if account.pubkey == spl_token::id() {
+                account.pubkey = spl_token_2022::id();
+            }
+        }
+        ix
+    }
+
+    async fn process(
+        context: &mut ProgramTestContext,
+        instructions: &[Instruction],
+        signers: &[&Keypair],
+    ) -> Result<(), BanksClientError> {
+        let mut all_signers = vec![&context.payer];
+        all_signers.extend_from_slice(signers);
+        let tx = Transaction::new_signed_with_payer(
+            instructions,
+            Some(&context.payer.pubkey()),
+            &all_signers,
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    /// Creates a Token-2022 mint carrying `extensions`, followed by `init_extensions`.
+    async fn create_mint_2022(
+        context: &mut ProgramTestContext,
+        mint: &Keypair,
+        decimals: u8,
+        extensions: &[ExtensionType],
+        init_extensions: Vec<Instruction>,
+    ) -> Result<(), BanksClientError> {
+        let payer = context.payer.pubkey();
+        let len = ExtensionType::get_account_len::<spl_token_2022::state::Mint>(extensions);
+        let rent = context.banks_client.get_rent().await.unwrap();
+
+        let mut instructions = vec![system_instruction::create_account(
+            &payer,
+            &mint.pubkey(),
+            rent.minimum_balance(len),
+            len as u64,
+            &spl_token_2022::id(),
+        )];
+        instructions.extend(init_extensions);
+        instructions.push(
+            spl_token_2022::instruction::initialize_mint(
+                &spl_token_2022::id(),
+                &mint.pubkey(),
+                &payer,
+                Some(&payer),
+                decimals,
+            )
+            .unwrap(),
+        );
+        process(context, &instructions, &[mint]).await
+    }
+
+    /// Creates a Token-2022 token account with an immutable owner, as the associated token
+    /// account program does, and mints a single token into it.
+    async fn create_token_2022(
+        context: &mut ProgramTestContext,
+        token: &Keypair,
+        mint: &Pubkey,
+    ) -> Result<(), BanksClientError> {
+        let payer = context.payer.pubkey();
+        let len = ExtensionType::get_account_len::<spl_token_2022::state::Account>(&[
+            ExtensionType::ImmutableOwner,
+        ]);
+        let rent = context.banks_client.get_rent().await.unwrap();
+
+        let instructions = [
+            system_instruction::create_account(
+                &payer,
+                &token.pubkey(),
+                rent.minimum_balance(len),
+                len as u64,
+                &spl_token_2022::id(),
+            ),
+            spl_token_2022::instruction::initialize_immutable_owner(
+                &spl_token_2022::id(),
+                &token.pubkey(),
+            )
+            .unwrap(),
+            spl_token_2022::instruction::initialize_account(
+                &spl_token_2022::id(),
+                &token.pubkey(),
+                mint,
+                &payer,
+            )
+            .unwrap(),
+            spl_token_2022::instruction::mint_to(
+                &spl_token_2022::id(),
+                mint,
+                &token.pubkey(),
+                &payer,
+                &[],
+                1,
+            )
+            .unwrap(),
+        ];
+        process(context, &instructions, &[token]).await
+    }
+
+    fn create_metadata_ix(
+        context: &ProgramTestContext,
+        nft: &Nft,
+        collection: Option<Collection>,
+    ) -> Instruction {
+        let payer = context.payer.pubkey();
+        instruction::create_metadata_accounts_v3(
+            id(),
+            nft.metadata,
+            nft.mint.pubkey(),
+            payer,
+            payer,
+            payer,
+            "Test".to_string(),
+            "TST".to_string(),
+            "uri".to_string(),
+            None,
+            10,
+            true,
+            true,
+            collection,
+            None,
+            None,
+        )
+    }
+
+    async fn create_nft(
+        context: &mut ProgramTestContext,
+        collection: Option<Collection>,
+        max_supply: Option<u64>,
+    ) -> Nft {
+        let nft = Nft::new();
+        create_mint_2022(context, &nft.mint, 0, &[], vec![])
+            .await
+            .unwrap();
+        create_token_2022(context, &nft.token, &nft.mint.pubkey())
+            .await
+            .unwrap();
+
+        let payer = context.payer.pubkey();
+        let instructions = [
+            create_metadata_ix(context, &nft, collection),
+            with_token_2022(instruction::create_master_edition_v3(
+                id(),
+                nft.edition,
+                nft.mint.pubkey(),
+                payer,
+                payer,
+                nft.metadata,
+                payer,
+                max_supply,
+            )),
+        ];
+        process(context, &instructions, &[]).await.unwrap();
+        nft
+    }
+
+    /// Creates the metadata of a fungible Token-2022 mint carrying `extensions`.
+    async fn create_fungible_metadata(
+        context: &mut ProgramTestContext,
+        extensions: &[ExtensionType],
+        init_extensions: impl FnOnce(&Pubkey, &Pubkey) -> Vec<Instruction>,
+    ) -> (Nft, ProgramMetadata) {
+        let token = Nft::new();
+        let payer = context.payer.pubkey();
+        let init_extensions = init_extensions(&token.mint.pubkey(), &payer);
+        create_mint_2022(context, &token.mint, 6, extensions, init_extensions)
+            .await
+            .unwrap();
+
+        let ix = create_metadata_ix(context, &token, None);
+        process(context, &[ix], &[]).await.unwrap();
+
+        let metadata: ProgramMetadata =
+            ProgramMetadata::safe_deserialize(&get_account(context, &token.metadata).await.data)
+                .unwrap();
+        (token, metadata)
+    }
+
+    #[tokio::test]
+    async fn successfully_create_fungible_with_transfer_fee() {
+        let mut context = program_test().start_with_context().await;
+        let (token, metadata) = create_fungible_metadata(
+            &mut context,
+            &[ExtensionType::TransferFeeConfig],
+            |mint, payer| {
+                vec![
+                    spl_token_2022::extension::transfer_fee::instruction::initialize_transfer_fee_config(
+                        &spl_token_2022::id(),
+                        mint,
+                        Some(payer),
+                        Some(payer),
+                        100,
+                        1_000_000,
+                    )
+                    .unwrap(),
+                ]
+            },
+        )
+        .await;
+
+        assert_eq!(metadata.mint, token.mint.pubkey());
+        assert_eq!(metadata.token_standard, Some(TokenStandard::Fungible));
+    }
+
+    #[tokio::test]
+    async fn successfully_create_fungible_with_interest() {
+        let mut context = program_test().start_with_context().await;
+        let (token, metadata) = create_fungible_metadata(
+            &mut context,
+            &[ExtensionType::InterestBearingConfig],
+            |mint, payer| {
+                vec![
+                    spl_token_2022::extension::interest_bearing_mint::instruction::initialize(
+                        &spl_token_2022::id(),
+                        mint,
+                        Some(*payer),
+                        500,
+                    )
+                    .unwrap(),
+                ]
+            },
+        )
+        .await;
+
+        assert_eq!(metadata.mint, token.mint.pubkey());
+        assert_eq!(metadata.token_standard, Some(TokenStandard::Fungible));
+    }
+
+    #[tokio::test]
+    async fn successfully_create_master_edition() {
+        let mut context = program_test().start_with_context().await;
+        let nft = create_nft(&mut context, None, Some(10)).await;
+
+        let metadata: ProgramMetadata =
+            ProgramMetadata::safe_deserialize(&get_account(&mut context, &nft.metadata).await.data)
+                .unwrap();
+        assert_eq!(metadata.mint, nft.mint.pubkey());
+
+        let master_edition: ProgramMasterEdition = ProgramMasterEdition::safe_deserialize(
+            &get_account(&mut context, &nft.edition).await.data,
+        )
+        .unwrap();
+        assert_eq!(master_edition.max_supply, Some(10));
+
+        let mint = spl_token_2022::state::Mint::unpack(
+            &get_account(&mut context, &nft.mint.pubkey()).await.data,
+        )
+        .unwrap();
+        assert_eq!(mint.mint_authority, Some(nft.edition).into());
+    }
+
+    #[tokio::test]
+    async fn successfully_print_edition() {
+        let mut context = program_test().start_with_context().await;
+        let master = create_nft(&mut context, None, Some(10)).await;
+
+        let print = Nft::new();
+        create_mint_2022(&mut context, &print.mint, 0, &[], vec![])
+            .await
+            .unwrap();
+        create_token_2022(&mut context, &print.token, &print.mint.pubkey())
+            .await
+            .unwrap();
+
+        let payer = context.payer.pubkey();
+        let ix = with_token_2022(instruction::mint_new_edition_from_master_edition_via_token(
+            id(),
+            print.metadata,
+            print.edition,
+            master.edition,
+            print.mint.pubkey(),
+            payer,
+            payer,
+            payer,
+            master.token.pubkey(),
+            payer,
+            master.metadata,
+            master.mint.pubkey(),
+            1,
+        ));
+        process(&mut context, &[ix], &[]).await.unwrap();
+
+        let master_edition: ProgramMasterEdition = ProgramMasterEdition::safe_deserialize(
+            &get_account(&mut context, &master.edition).await.data,
+        )
+        .unwrap();
+        assert_eq!(master_edition.supply, 1);
+
+        let print_metadata: ProgramMetadata = ProgramMetadata::safe_deserialize(
+            &get_account(&mut context, &print.metadata).await.data,
+        )
+        .unwrap();
+        assert_eq!(print_metadata.mint, print.mint.pubkey());
+    }
+
+    #[tokio::test]
+    async fn successfully_verify_collection() {
+        let mut context = program_test().start_with_context().await;
+        let collection = create_nft(&mut context, None, Some(0)).await;
+        let item = create_nft(
+            &mut context,
+            Some(Collection {
+                key: collection.mint.pubkey(),
+                verified: false,
+            }),
+            Some(0),
+        )
+        .await;
+
+        let payer = context.payer.pubkey();
+        let ix = instruction::verify_collection(
+            id(),
+            item.metadata,
+            payer,
+            payer,
+            collection.mint.pubkey(),
+            collection.metadata,
+            collection.edition,
+            None,
+        );
+        process(&mut context, &[ix], &[]).await.unwrap();
+
+        let metadata: ProgramMetadata = ProgramMetadata::safe_deserialize(
+            &get_account(&mut context, &item.metadata).await.data,
+        )
+        .unwrap();
+        assert!(metadata.collection.unwrap().verified);
+    }
+
+    #[tokio::test]
+    async fn successfully_burn() {
+        let mut context = program_test().start_with_context().await;
+        let nft = create_nft(&mut context, None, Some(0)).await;
+
+        let ix = instruction::burn_nft(
+            id(),
+            nft.metadata,
+            context.payer.pubkey(),
+            nft.mint.pubkey(),
+            nft.token.pubkey(),
+            nft.edition,
+            spl_token_2022::id(),
+            None,
+        );
+        process(&mut context, &[ix], &[]).await.unwrap();
+
+        for account in [nft.metadata, nft.edition, nft.token.pubkey()] {
+            assert!(context
+                .banks_client
+                .get_account(account)
+                .await
+                .unwrap()
+                .is_none());
+        }
+    }
+
+    #[tokio::test]
+    async fn fail_mint_close_authority() {
+        let mut context = program_test().start_with_context().await;
+        let nft = Nft::new();
+        let payer = context.payer.pubkey();
+        create_mint_2022(
+            &mut context,
+            &nft.mint,
+            0,
+            &[ExtensionType::MintCloseAuthority],
+            vec![
+                spl_token_2022::instruction::initialize_mint_close_authority(
+                    &spl_token_2022::id(),
+                    &nft.mint.pubkey(),
+                    Some(&payer),
+                )
+                .unwrap(),
+            ],
+        )
+        .await
+        .unwrap();
+
+        let ix = create_metadata_ix(&context, &nft, None);
+        let err = process(&mut context, &[ix], &[]).await.unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UnsupportedTokenExtension);
+    }
+}
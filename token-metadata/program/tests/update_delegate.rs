@@ -0,0 +1,188 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    pda::find_update_delegate_account,
+    state::{DataV2, UpdateDelegateScopes},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod update_delegate {
+    use super::*;
+
+    async fn setup(
+        context: &mut ProgramTestContext,
+        delegate: &Keypair,
+        scopes: UpdateDelegateScopes,
+    ) -> (Metadata, Pubkey) {
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v2(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                true,
+                None,
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        let (record, _) =
+            find_update_delegate_account(&test_metadata.mint.pubkey(), &delegate.pubkey());
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::approve_update_delegate(
+                PROGRAM_ID,
+                record,
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+                scopes,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        (test_metadata, record)
+    }
+
+    async fn update_as_delegate(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        delegate: &Keypair,
+        record: Pubkey,
+        name: &str,
+        uri: &str,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::update_metadata_accounts_v2_as_delegate(
+                PROGRAM_ID,
+                test_metadata.pubkey,
+                delegate.pubkey(),
+                record,
+                DataV2 {
+                    name: name.to_string(),
+                    symbol: "TST".to_string(),
+                    uri: uri.to_string(),
+                    seller_fee_basis_points: 10,
+                    creators: None,
+                    collection: None,
+                    uses: None,
+                },
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, delegate],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn uri_delegate_updates_uri() {
+        let mut context = program_test().start_with_context().await;
+        let delegate = Keypair::new();
+        let scopes = UpdateDelegateScopes {
+            uri: true,
+            ..Default::default()
+        };
+        let (test_metadata, record) = setup(&mut context, &delegate, scopes).await;
+
+        update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            record,
+            "Test",
+            "new_uri",
+        )
+        .await
+        .unwrap();
+
+        let metadata = test_metadata.get_data(&mut context).await;
+        assert!(metadata.data.uri.starts_with("new_uri"));
+        assert!(metadata.data.name.starts_with("Test"));
+        assert_eq!(metadata.update_authority, context.payer.pubkey());
+    }
+
+    #[tokio::test]
+    async fn uri_delegate_cannot_update_name() {
+        let mut context = program_test().start_with_context().await;
+        let delegate = Keypair::new();
+        let scopes = UpdateDelegateScopes {
+            uri: true,
+            ..Default::default()
+        };
+        let (test_metadata, record) = setup(&mut context, &delegate, scopes).await;
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            record,
+            "Other",
+            "uri",
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateDelegateScopeMissing);
+    }
+
+    #[tokio::test]
+    async fn revoked_delegate_cannot_update() {
+        let mut context = program_test().start_with_context().await;
+        let delegate = Keypair::new();
+        let scopes = UpdateDelegateScopes {
+            uri: true,
+            ..Default::default()
+        };
+        let (test_metadata, record) = setup(&mut context, &delegate, scopes).await;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::revoke_update_delegate(
+                PROGRAM_ID,
+                record,
+                delegate.pubkey(),
+                context.payer.pubkey(),
+                test_metadata.pubkey,
+                test_metadata.mint.pubkey(),
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        let err = update_as_delegate(
+            &mut context,
+            &test_metadata,
+            &delegate,
+            record,
+            "Test",
+            "new_uri",
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::UpdateDelegateRecordDoesNotExist);
+    }
+}
@@ -2,7 +2,9 @@ use crate::*;
 use borsh::BorshSerialize;
 use mpl_token_metadata::{
     id,
-    instruction::{self, MetadataInstruction, MintNewEditionFromMasterEditionViaTokenArgs},
+    instruction::{
+        self, EditionOverrides, MetadataInstruction, MintNewEditionFromMasterEditionViaTokenArgs,
+    },
     state::{EDITION, EDITION_MARKER_BIT_SIZE, PREFIX},
 };
 use solana_program::{
@@ -149,23 +151,7 @@ impl EditionMarker {
     }
 
     pub async fn create(&self, context: &mut ProgramTestContext) -> Result<(), BanksClientError> {
-        create_mint(context, &self.mint, &context.payer.pubkey(), None, 0).await?;
-        create_token_account(
-            context,
-            &self.token,
-            &self.mint.pubkey(),
-            &context.payer.pubkey(),
-        )
-        .await?;
-        mint_tokens(
-            context,
-            &self.mint.pubkey(),
-            &self.token.pubkey(),
-            1,
-            &context.payer.pubkey(),
-            None,
-        )
-        .await?;
+        self.create_mint_and_token(context).await?;
 
         let tx = Transaction::new_signed_with_payer(
             &[instruction::mint_new_edition_from_master_edition_via_token(
@@ -191,6 +177,90 @@ impl EditionMarker {
         context.banks_client.process_transaction(tx).await
     }
 
+    pub async fn create_with_overrides(
+        &self,
+        context: &mut ProgramTestContext,
+        update_authority: &Keypair,
+        overrides: EditionOverrides,
+    ) -> Result<(), BanksClientError> {
+        self.create_mint_and_token(context).await?;
+
+        let tx = Transaction::new_signed_with_payer(
+            &[
+                instruction::mint_new_edition_from_master_edition_via_token_with_overrides(
+                    id(),
+                    self.new_metadata_pubkey,
+                    self.new_edition_pubkey,
+                    self.master_edition_pubkey,
+                    self.mint.pubkey(),
+                    context.payer.pubkey(),
+                    context.payer.pubkey(),
+                    context.payer.pubkey(),
+                    self.metadata_token_pubkey,
+                    update_authority.pubkey(),
+                    self.metadata_pubkey,
+                    self.metadata_mint_pubkey,
+                    self.edition,
+                    overrides,
+                ),
+            ],
+            Some(&context.payer.pubkey()),
+            &[&context.payer, update_authority],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    pub async fn burn(
+        &self,
+        context: &mut ProgramTestContext,
+        owner: &Keypair,
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::burn_edition_nft(
+                id(),
+                self.new_metadata_pubkey,
+                owner.pubkey(),
+                self.mint.pubkey(),
+                self.metadata_mint_pubkey,
+                self.token.pubkey(),
+                self.master_edition_pubkey,
+                self.new_edition_pubkey,
+                self.pubkey,
+                spl_token::id(),
+            )],
+            Some(&owner.pubkey()),
+            &[owner],
+            context.last_blockhash,
+        );
+
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn create_mint_and_token(
+        &self,
+        context: &mut ProgramTestContext,
+    ) -> Result<(), BanksClientError> {
+        create_mint(context, &self.mint, &context.payer.pubkey(), None, 0).await?;
+        create_token_account(
+            context,
+            &self.token,
+            &self.mint.pubkey(),
+            &context.payer.pubkey(),
+        )
+        .await?;
+        mint_tokens(
+            context,
+            &self.mint.pubkey(),
+            &self.token.pubkey(),
+            1,
+            &context.payer.pubkey(),
+            None,
+        )
+        .await
+    }
+
     pub async fn create_with_invalid_token_program(
         &self,
         context: &mut ProgramTestContext,
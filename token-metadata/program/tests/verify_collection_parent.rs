@@ -0,0 +1,391 @@
+#![cfg(feature = "test-bpf")]
+pub mod utils;
+
+use mpl_token_metadata::{
+    error::MetadataError,
+    instruction,
+    state::{Collection, CollectionDetails},
+    ID as PROGRAM_ID,
+};
+use num_traits::FromPrimitive;
+use solana_program_test::*;
+use solana_sdk::{
+    instruction::InstructionError,
+    pubkey::Pubkey,
+    signature::{Keypair, Signer},
+    transaction::{Transaction, TransactionError},
+};
+use utils::*;
+
+mod verify_collection_parent {
+    use super::*;
+
+    async fn create_collection(
+        context: &mut ProgramTestContext,
+        test_metadata: &Metadata,
+        parent: Option<Pubkey>,
+        collection_details: Option<CollectionDetails>,
+    ) -> MasterEditionV2 {
+        test_metadata
+            .create_v3(
+                context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                parent.map(|key| Collection {
+                    key,
+                    verified: false,
+                }),
+                None,
+                collection_details,
+            )
+            .await
+            .unwrap();
+
+        let master_edition = MasterEditionV2::new(test_metadata);
+        master_edition.create_v3(context, Some(0)).await.unwrap();
+        master_edition
+    }
+
+    async fn verify_parent(
+        context: &mut ProgramTestContext,
+        collection: &Metadata,
+        parent: &Metadata,
+        parent_master_edition: &MasterEditionV2,
+        ancestors: &[Pubkey],
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::verify_collection_parent(
+                PROGRAM_ID,
+                collection.pubkey,
+                context.payer.pubkey(),
+                context.payer.pubkey(),
+                parent.mint.pubkey(),
+                parent.pubkey,
+                parent_master_edition.pubkey,
+                None,
+                ancestors,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    async fn assert_ancestry(
+        context: &mut ProgramTestContext,
+        item: &Metadata,
+        ancestor: &Metadata,
+        collections: &[Pubkey],
+    ) -> Result<(), BanksClientError> {
+        let tx = Transaction::new_signed_with_payer(
+            &[instruction::assert_collection_ancestry(
+                PROGRAM_ID,
+                item.pubkey,
+                ancestor.mint.pubkey(),
+                collections,
+            )],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await
+    }
+
+    #[tokio::test]
+    async fn successfully_verify_nested_collections() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        let brand_edition =
+            create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let season = Metadata::new();
+        let season_edition = create_collection(
+            &mut context,
+            &season,
+            Some(brand.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+        let set = Metadata::new();
+        create_collection(
+            &mut context,
+            &set,
+            Some(season.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+
+        verify_parent(&mut context, &season, &brand, &brand_edition, &[])
+            .await
+            .unwrap();
+        verify_parent(
+            &mut context,
+            &set,
+            &season,
+            &season_edition,
+            &[brand.pubkey],
+        )
+        .await
+        .unwrap();
+
+        let set_metadata = set.get_data(&mut context).await;
+        assert_eq!(
+            set_metadata.collection,
+            Some(Collection {
+                key: season.mint.pubkey(),
+                verified: true,
+            })
+        );
+
+        assert_collection_size(&mut context, &brand, 1).await;
+        assert_collection_size(&mut context, &season, 1).await;
+    }
+
+    #[tokio::test]
+    async fn fail_collection_cycle() {
+        let mut context = program_test().start_with_context().await;
+
+        let first = Metadata::new();
+        let second = Metadata::new();
+        let first_edition = create_collection(
+            &mut context,
+            &first,
+            Some(second.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+        let second_edition = create_collection(
+            &mut context,
+            &second,
+            Some(first.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+
+        verify_parent(&mut context, &first, &second, &second_edition, &[])
+            .await
+            .unwrap();
+
+        let err = verify_parent(
+            &mut context,
+            &second,
+            &first,
+            &first_edition,
+            &[second.pubkey],
+        )
+        .await
+        .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CollectionHierarchyCycle);
+    }
+
+    #[tokio::test]
+    async fn fail_missing_ancestors() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        let brand_edition =
+            create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let season = Metadata::new();
+        let season_edition = create_collection(
+            &mut context,
+            &season,
+            Some(brand.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+        let set = Metadata::new();
+        create_collection(
+            &mut context,
+            &set,
+            Some(season.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+
+        verify_parent(&mut context, &season, &brand, &brand_edition, &[])
+            .await
+            .unwrap();
+
+        let err = verify_parent(&mut context, &set, &season, &season_edition, &[])
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MissingCollectionMetadata);
+    }
+
+    #[tokio::test]
+    async fn fail_child_is_not_a_collection() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        let brand_edition =
+            create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let item = Metadata::new();
+        create_collection(&mut context, &item, Some(brand.mint.pubkey()), None).await;
+
+        let err = verify_parent(&mut context, &item, &brand, &brand_edition, &[])
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::NotACollectionParent);
+    }
+
+    #[tokio::test]
+    async fn successfully_assert_collection_ancestry() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        let brand_edition =
+            create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let season = Metadata::new();
+        let season_edition = create_collection(
+            &mut context,
+            &season,
+            Some(brand.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+        let item = Metadata::new();
+        create_collection(&mut context, &item, Some(season.mint.pubkey()), None).await;
+
+        verify_parent(&mut context, &season, &brand, &brand_edition, &[])
+            .await
+            .unwrap();
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        item.verify_sized_collection_item(
+            &mut context,
+            season.pubkey,
+            &payer,
+            season.mint.pubkey(),
+            season_edition.pubkey,
+            None,
+        )
+        .await
+        .unwrap();
+
+        assert_ancestry(&mut context, &item, &season, &[season.pubkey])
+            .await
+            .unwrap();
+        assert_ancestry(&mut context, &item, &brand, &[season.pubkey, brand.pubkey])
+            .await
+            .unwrap();
+
+        context.warp_to_slot(100).unwrap();
+        let err = assert_ancestry(&mut context, &item, &brand, &[season.pubkey])
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::MissingCollectionMetadata);
+    }
+
+    #[tokio::test]
+    async fn fail_assert_collection_ancestry_not_a_member() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let other = Metadata::new();
+        let other_edition =
+            create_collection(&mut context, &other, None, DEFAULT_COLLECTION_DETAILS).await;
+        let item = Metadata::new();
+        create_collection(&mut context, &item, Some(other.mint.pubkey()), None).await;
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        item.verify_sized_collection_item(
+            &mut context,
+            other.pubkey,
+            &payer,
+            other.mint.pubkey(),
+            other_edition.pubkey,
+            None,
+        )
+        .await
+        .unwrap();
+
+        // The root of the item's hierarchy is reached without meeting the ancestor.
+        let err = assert_ancestry(&mut context, &item, &brand, &[other.pubkey])
+            .await
+            .unwrap_err();
+        assert_custom_error!(err, MetadataError::NotVerifiedMemberOfCollection);
+    }
+
+    #[tokio::test]
+    async fn fail_plain_verify_of_nested_collection() {
+        let mut context = program_test().start_with_context().await;
+
+        let brand = Metadata::new();
+        let brand_edition =
+            create_collection(&mut context, &brand, None, DEFAULT_COLLECTION_DETAILS).await;
+        let season = Metadata::new();
+        let season_edition = create_collection(
+            &mut context,
+            &season,
+            Some(brand.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+        let set = Metadata::new();
+        create_collection(
+            &mut context,
+            &set,
+            Some(season.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+
+        verify_parent(&mut context, &season, &brand, &brand_edition, &[])
+            .await
+            .unwrap();
+
+        // Without ancestor accounts the hierarchy above the season cannot be checked.
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        let err = set
+            .verify_sized_collection_item(
+                &mut context,
+                season.pubkey,
+                &payer,
+                season.mint.pubkey(),
+                season_edition.pubkey,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::MissingCollectionMetadata);
+    }
+
+    #[tokio::test]
+    async fn fail_plain_verify_into_itself() {
+        let mut context = program_test().start_with_context().await;
+
+        let collection = Metadata::new();
+        let collection_edition = create_collection(
+            &mut context,
+            &collection,
+            Some(collection.mint.pubkey()),
+            DEFAULT_COLLECTION_DETAILS,
+        )
+        .await;
+
+        let payer = Keypair::from_bytes(&context.payer.to_bytes()).unwrap();
+        let err = collection
+            .verify_sized_collection_item(
+                &mut context,
+                collection.pubkey,
+                &payer,
+                collection.mint.pubkey(),
+                collection_edition.pubkey,
+                None,
+            )
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::CollectionHierarchyCycle);
+    }
+}
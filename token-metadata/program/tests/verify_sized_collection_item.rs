@@ -130,6 +130,235 @@ mod verify_sized_collection_item {
         assert!(metadata_after.collection.unwrap().verified);
     }
 
+    #[tokio::test]
+    async fn successfully_verify_sized_collection_item_batch() {
+        let mut context = program_test().start_with_context().await;
+
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_COLLECTION_DETAILS, // Collection Parent
+            )
+            .await
+            .unwrap();
+        let collection_master_edition_account = MasterEditionV2::new(&test_collection);
+        collection_master_edition_account
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let mut test_items = vec![];
+        for _ in 0..3 {
+            let test_metadata = Metadata::new();
+            test_metadata
+                .create_v3(
+                    &mut context,
+                    "Test".to_string(),
+                    "TST".to_string(),
+                    "uri".to_string(),
+                    None,
+                    10,
+                    false,
+                    None,
+                    Some(Collection {
+                        key: test_collection.mint.pubkey(),
+                        verified: false,
+                    }),
+                    None,
+                    None, // is not collection parent NFT
+                )
+                .await
+                .unwrap();
+            test_items.push(test_metadata);
+        }
+
+        let metadata_accounts: Vec<_> = test_items.iter().map(|item| item.pubkey).collect();
+        let ix = mpl_token_metadata::instruction::verify_sized_collection_item_batch(
+            PROGRAM_ID,
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            test_collection.mint.pubkey(),
+            test_collection.pubkey,
+            collection_master_edition_account.pubkey,
+            None,
+            &metadata_accounts,
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        context.banks_client.process_transaction(tx).await.unwrap();
+
+        for test_metadata in test_items {
+            let metadata = test_metadata.get_data(&mut context).await;
+            assert!(metadata.collection.unwrap().verified);
+        }
+        assert_collection_size(&mut context, &test_collection, 3).await;
+    }
+
+    #[tokio::test]
+    async fn fail_verify_sized_collection_item_batch_duplicate() {
+        let mut context = program_test().start_with_context().await;
+
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_COLLECTION_DETAILS, // Collection Parent
+            )
+            .await
+            .unwrap();
+        let collection_master_edition_account = MasterEditionV2::new(&test_collection);
+        collection_master_edition_account
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                Some(Collection {
+                    key: test_collection.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+                None, // is not collection parent NFT
+            )
+            .await
+            .unwrap();
+
+        let ix = mpl_token_metadata::instruction::verify_sized_collection_item_batch(
+            PROGRAM_ID,
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            test_collection.mint.pubkey(),
+            test_collection.pubkey,
+            collection_master_edition_account.pubkey,
+            None,
+            &[test_metadata.pubkey, test_metadata.pubkey],
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(err, MetadataError::AlreadyVerified);
+        assert_collection_size(&mut context, &test_collection, 0).await;
+    }
+
+    #[tokio::test]
+    async fn fail_verify_sized_collection_item_batch_includes_collection() {
+        let mut context = program_test().start_with_context().await;
+
+        let test_collection = Metadata::new();
+        test_collection
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                None,
+                None,
+                DEFAULT_COLLECTION_DETAILS, // Collection Parent
+            )
+            .await
+            .unwrap();
+        let collection_master_edition_account = MasterEditionV2::new(&test_collection);
+        collection_master_edition_account
+            .create_v3(&mut context, Some(0))
+            .await
+            .unwrap();
+
+        let test_metadata = Metadata::new();
+        test_metadata
+            .create_v3(
+                &mut context,
+                "Test".to_string(),
+                "TST".to_string(),
+                "uri".to_string(),
+                None,
+                10,
+                false,
+                None,
+                Some(Collection {
+                    key: test_collection.mint.pubkey(),
+                    verified: false,
+                }),
+                None,
+                None, // is not collection parent NFT
+            )
+            .await
+            .unwrap();
+
+        let ix = mpl_token_metadata::instruction::verify_sized_collection_item_batch(
+            PROGRAM_ID,
+            context.payer.pubkey(),
+            context.payer.pubkey(),
+            test_collection.mint.pubkey(),
+            test_collection.pubkey,
+            collection_master_edition_account.pubkey,
+            None,
+            &[test_metadata.pubkey, test_collection.pubkey],
+        );
+        let tx = Transaction::new_signed_with_payer(
+            &[ix],
+            Some(&context.payer.pubkey()),
+            &[&context.payer],
+            context.last_blockhash,
+        );
+        let err = context
+            .banks_client
+            .process_transaction(tx)
+            .await
+            .unwrap_err();
+
+        assert_custom_error!(
+            err,
+            MetadataError::CollectionCannotBeVerifiedInThisInstruction
+        );
+        assert_collection_size(&mut context, &test_collection, 0).await;
+    }
+
     #[tokio::test]
     async fn fail_wrong_collection_from_authority() {
         let mut context = program_test().start_with_context().await;